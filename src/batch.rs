@@ -0,0 +1,283 @@
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::output_manager::{duration_ms, legacy_format_version, REPORT_FORMAT_VERSION};
+use crate::extractor::ExtractionReport;
+use crate::status::Stage;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::fs;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// File name of the combined report, written to the base output directory
+pub const BATCH_REPORT_FILE: &str = "batch_report.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchStatus {
+    Succeeded,
+    Failed,
+}
+
+/// Outcome of extracting one repository of a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchEntry {
+    pub source: String,
+    pub status: BatchStatus,
+    pub repository: Option<String>,
+    pub output_directory: Option<PathBuf>,
+    pub files_extracted: usize,
+    pub bytes_extracted: u64,
+    pub warnings: usize,
+    pub error: Option<String>,
+    /// Stage that hit `--max-runtime`, for runs that timed out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timed_out_stage: Option<Stage>,
+    /// The extraction panicked instead of failing with an error
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub panicked: bool,
+    #[serde(rename = "duration_ms", alias = "duration", with = "duration_ms")]
+    pub duration: Duration,
+}
+
+impl BatchEntry {
+    pub fn succeeded(source: &str, report: &ExtractionReport, duration: Duration) -> Self {
+        Self {
+            source: source.to_string(),
+            status: BatchStatus::Succeeded,
+            repository: Some(format!(
+                "{}/{}",
+                report.repository_info.owner, report.repository_info.name
+            )),
+            output_directory: Some(report.output_directory.clone()),
+            files_extracted: report.extraction_summary.total_files_processed,
+            bytes_extracted: report.extraction_summary.total_bytes_processed,
+            warnings: report.errors.len(),
+            error: None,
+            timed_out_stage: None,
+            panicked: false,
+            duration,
+        }
+    }
+
+    pub fn failed(source: &str, error: &RepoDocsError, duration: Duration) -> Self {
+        Self {
+            source: source.to_string(),
+            status: BatchStatus::Failed,
+            repository: None,
+            output_directory: None,
+            files_extracted: 0,
+            bytes_extracted: 0,
+            warnings: 0,
+            error: Some(error.to_string()),
+            timed_out_stage: match error {
+                RepoDocsError::Timeout { stage, .. } => Some(*stage),
+                _ => None,
+            },
+            panicked: false,
+            duration,
+        }
+    }
+
+    /// An extraction that panicked, caught by [`catch_panic`]
+    pub fn panicked(source: &str, message: &str, duration: Duration) -> Self {
+        Self {
+            source: source.to_string(),
+            status: BatchStatus::Failed,
+            repository: None,
+            output_directory: None,
+            files_extracted: 0,
+            bytes_extracted: 0,
+            warnings: 0,
+            error: Some(format!("Internal error: {}", message)),
+            timed_out_stage: None,
+            panicked: true,
+            duration,
+        }
+    }
+}
+
+/// Future returned by [`catch_panic`]
+pub struct CatchPanic<'a, T> {
+    future: Pin<Box<dyn Future<Output = T> + Send + 'a>>,
+}
+
+/// Run `future`, turning a panic while it is polled into an error with the
+/// panic message, so one repository cannot take the rest of a batch down.
+/// Temporary clones of the repository are removed while the panic unwinds.
+pub fn catch_panic<'a, T>(future: impl Future<Output = T> + Send + 'a) -> CatchPanic<'a, T> {
+    CatchPanic {
+        future: Box::pin(future),
+    }
+}
+
+impl<T> Future for CatchPanic<'_, T> {
+    type Output = std::result::Result<T, String>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match panic::catch_unwind(AssertUnwindSafe(|| self.future.as_mut().poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => Poll::Ready(Err(panic_message(payload.as_ref()))),
+        }
+    }
+}
+
+/// Message of a blocking task that panicked or was cancelled
+pub fn join_error_message(error: tokio::task::JoinError) -> String {
+    match error.try_into_panic() {
+        Ok(payload) => panic_message(payload.as_ref()),
+        Err(error) => error.to_string(),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panicked".to_string())
+}
+
+/// Combined report of a multi-repository run, with entries in the order
+/// the sources were given
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchReport {
+    #[serde(default = "legacy_format_version")]
+    pub format_version: u32,
+    pub started_at: DateTime<Utc>,
+    #[serde(rename = "duration_ms", alias = "duration", with = "duration_ms")]
+    pub duration: Duration,
+    pub entries: Vec<BatchEntry>,
+}
+
+impl BatchReport {
+    pub fn new() -> Self {
+        Self {
+            format_version: REPORT_FORMAT_VERSION,
+            started_at: Utc::now(),
+            duration: Duration::ZERO,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn succeeded(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| e.status == BatchStatus::Succeeded)
+            .count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.entries.len() - self.succeeded()
+    }
+
+    pub fn total_files(&self) -> usize {
+        self.entries.iter().map(|e| e.files_extracted).sum()
+    }
+
+    /// Write `batch_report.json` into the given directory
+    pub fn save(&self, dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(dir).map_err(RepoDocsError::Io)?;
+
+        let json = serde_json::to_string_pretty(self).map_err(|e| RepoDocsError::Config {
+            message: format!("Failed to serialize batch report: {}", e),
+        })?;
+        let path = dir.join(BATCH_REPORT_FILE);
+        fs::write(&path, json).map_err(RepoDocsError::Io)?;
+        Ok(path)
+    }
+}
+
+impl Default for BatchReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read repository URLs or local paths from a list file: one per line,
+/// blank lines and `#` comments are ignored
+pub fn read_source_list(path: &Path) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path).map_err(|e| RepoDocsError::Config {
+        message: format!("Failed to read repository list {}: {}", path.display(), e),
+    })?;
+
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_source_list() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("repos.txt");
+        fs::write(
+            &path,
+            "# Documentation sources\nhttps://github.com/rust-lang/book\n\n  https://gitlab.com/group/project  \n",
+        )
+        .unwrap();
+
+        let sources = read_source_list(&path).unwrap();
+        assert_eq!(
+            sources,
+            vec![
+                "https://github.com/rust-lang/book",
+                "https://gitlab.com/group/project"
+            ]
+        );
+
+        assert!(read_source_list(&temp_dir.path().join("missing.txt")).is_err());
+    }
+
+    #[test]
+    fn test_batch_report_counts_and_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut report = BatchReport::new();
+        report.entries.push(BatchEntry::failed(
+            "https://github.com/owner/missing",
+            &RepoDocsError::RepositoryNotFound {
+                url: "https://github.com/owner/missing".to_string(),
+            },
+            Duration::from_secs(1),
+        ));
+
+        assert_eq!(report.succeeded(), 0);
+        assert_eq!(report.failed(), 1);
+
+        let path = report.save(temp_dir.path()).unwrap();
+        let json = fs::read_to_string(path).unwrap();
+        assert!(json.contains("\"status\": \"failed\""));
+        assert!(!json.contains("panicked"));
+    }
+
+    #[tokio::test]
+    async fn test_catch_panic() {
+        assert_eq!(catch_panic(async { 7 }).await, Ok(7));
+
+        let caught = catch_panic(async {
+            tokio::task::yield_now().await;
+            panic!("corrupt index in {}", "owner/repo");
+        })
+        .await;
+        assert_eq!(
+            caught,
+            Err::<(), _>("corrupt index in owner/repo".to_string())
+        );
+
+        let entry = BatchEntry::panicked("owner/repo", "corrupt index", Duration::ZERO);
+        assert_eq!(entry.status, BatchStatus::Failed);
+        assert!(entry.panicked);
+    }
+}
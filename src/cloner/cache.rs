@@ -0,0 +1,134 @@
+use crate::error::{RepoDocsError, Result};
+use git2::Repository;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bare mirrors of previously cloned repositories, so later runs only
+/// fetch what changed instead of cloning from scratch
+#[derive(Debug, Clone)]
+pub struct CloneCache {
+    root: PathBuf,
+}
+
+impl CloneCache {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// `$XDG_CACHE_HOME/repodocs`, falling back to `~/.cache/repodocs`
+    pub fn default_root() -> Option<PathBuf> {
+        std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+            .map(|dir| dir.join("repodocs"))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Location of the bare repository for a URL. The readable part only
+    /// helps when browsing the cache; the hash keeps URLs apart.
+    pub fn repository_path(&self, url: &str) -> PathBuf {
+        let key = url
+            .trim()
+            .trim_end_matches('/')
+            .trim_end_matches(".git")
+            .to_lowercase();
+        let name: String = key
+            .rsplit(['/', ':'])
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let hash = blake3::hash(key.as_bytes()).to_hex();
+
+        self.root.join(format!("{}-{}.git", name, &hash[..16]))
+    }
+
+    /// Open the bare repository for a URL, creating an empty one with an
+    /// `origin` remote on first use. A repository that cannot be opened is
+    /// replaced.
+    pub fn open_or_init(&self, url: &str) -> Result<Repository> {
+        let path = self.repository_path(url);
+
+        if path.exists() {
+            match Repository::open_bare(&path) {
+                Ok(repo) => {
+                    repo.remote_set_url("origin", url)
+                        .map_err(|e| RepoDocsError::Git {
+                            message: "Failed to update cached repository remote".to_string(),
+                            source: e,
+                        })?;
+                    return Ok(repo);
+                }
+                Err(_) => fs::remove_dir_all(&path).map_err(RepoDocsError::Io)?,
+            }
+        }
+
+        fs::create_dir_all(&path).map_err(RepoDocsError::Io)?;
+        let repo = Repository::init_bare(&path).map_err(|e| RepoDocsError::Git {
+            message: format!("Failed to create cached repository at {}", path.display()),
+            source: e,
+        })?;
+        repo.remote("origin", url).map_err(|e| RepoDocsError::Git {
+            message: "Failed to configure cached repository remote".to_string(),
+            source: e,
+        })?;
+
+        Ok(repo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_repository_path() {
+        let cache = CloneCache::new("/cache");
+
+        let path = cache.repository_path("https://github.com/rust-lang/book");
+        assert!(path.starts_with("/cache"));
+        assert!(path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("book-"));
+
+        // Equivalent spellings share a cache entry, different repositories do not
+        assert_eq!(
+            path,
+            cache.repository_path("https://github.com/rust-lang/book.git/")
+        );
+        assert_ne!(
+            path,
+            cache.repository_path("https://gitlab.com/rust-lang/book")
+        );
+    }
+
+    #[test]
+    fn test_open_or_init() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = CloneCache::new(temp_dir.path());
+        let url = "https://github.com/rust-lang/book";
+
+        let repo = cache.open_or_init(url).unwrap();
+        assert!(repo.is_bare());
+        assert_eq!(repo.find_remote("origin").unwrap().url(), Some(url));
+        drop(repo);
+
+        // Reopening keeps the existing repository
+        let repo = cache.open_or_init(url).unwrap();
+        assert!(repo.is_bare());
+    }
+}
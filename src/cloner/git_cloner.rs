@@ -1,7 +1,11 @@
+use crate::cloner::cache::CloneCache;
+use crate::cloner::preflight::{self, PREFLIGHT_TIMEOUT};
+use crate::cloner::provider::GitProvider;
 use crate::error::{RepoDocsError, Result};
 use git2::{
-    build::RepoBuilder, CertificateCheckStatus, ErrorClass, ErrorCode, FetchOptions, Progress,
-    RemoteCallbacks, Repository,
+    build::{CheckoutBuilder, RepoBuilder},
+    CertificateCheckStatus, ErrorClass, ErrorCode, FetchOptions, Progress, ProxyOptions,
+    RemoteCallbacks, Repository, SubmoduleUpdateOptions,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -10,6 +14,12 @@ use std::time::{Duration, Instant};
 use tempfile::TempDir;
 use url::Url;
 
+/// Nested submodules deeper than this are left uninitialized
+const MAX_SUBMODULE_NESTING: usize = 3;
+
+/// Refspecs mirrored into cached bare repositories
+const CACHE_REFSPECS: [&str; 2] = ["+refs/heads/*:refs/heads/*", "+refs/tags/*:refs/tags/*"];
+
 #[derive(Debug, Clone)]
 pub struct CloneProgress {
     pub total_objects: u32,
@@ -38,6 +48,12 @@ pub struct SafeCloner {
     progress_callback: Option<Box<dyn Fn(CloneProgress) + Send + Sync>>,
     running: Arc<AtomicBool>,
     branch: Option<String>,
+    depth: Option<u32>,
+    submodules: bool,
+    preflight: bool,
+    sparse_paths: Vec<String>,
+    allowed_hosts: Vec<String>,
+    cache: Option<CloneCache>,
 }
 
 impl SafeCloner {
@@ -47,6 +63,12 @@ impl SafeCloner {
             progress_callback: None,
             running: Arc::new(AtomicBool::new(true)),
             branch: None,
+            depth: None,
+            submodules: false,
+            preflight: false,
+            sparse_paths: Vec::new(),
+            allowed_hosts: Vec::new(),
+            cache: None,
         }
     }
 
@@ -68,12 +90,68 @@ impl SafeCloner {
         self
     }
 
+    /// Shallow clone with only the latest `depth` commits
+    pub fn with_depth(mut self, depth: u32) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// Also check out submodules after cloning
+    pub fn with_submodules(mut self, submodules: bool) -> Self {
+        self.submodules = submodules;
+        self
+    }
+
+    /// Check DNS and TCP reachability of the host before cloning
+    pub fn with_preflight(mut self, preflight: bool) -> Self {
+        self.preflight = preflight;
+        self
+    }
+
+    /// Only materialize working tree paths matching these pathspecs
+    /// (e.g. `docs`, `*.md`); the full history is still fetched
+    pub fn with_sparse_paths(mut self, sparse_paths: Vec<String>) -> Self {
+        self.sparse_paths = sparse_paths;
+        self
+    }
+
+    /// Accept repository URLs from these hosts in addition to GitHub and GitLab
+    pub fn with_allowed_hosts(mut self, allowed_hosts: Vec<String>) -> Self {
+        self.allowed_hosts = allowed_hosts;
+        self
+    }
+
+    /// Share the flag the clone stops on, so that another thread, such as a
+    /// stall watchdog, can cancel it while it runs
+    pub fn with_running(mut self, running: Arc<AtomicBool>) -> Self {
+        self.running = running;
+        self
+    }
+
+    /// Keep a bare mirror of every repository in the cache and check out
+    /// from it; repeated runs only fetch new objects. The mirror always
+    /// holds the full history, so the clone depth does not apply.
+    pub fn with_cache(mut self, cache: CloneCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
     pub fn clone_to_temp(&self, url: &str) -> Result<(Repository, TempDir)> {
         self.validate_url(url)?;
 
+        if self.preflight {
+            let parsed = Url::parse(url).map_err(|_| RepoDocsError::InvalidUrl {
+                url: url.to_string(),
+            })?;
+            preflight::check_connectivity(&parsed, PREFLIGHT_TIMEOUT.min(self.timeout))?;
+        }
+
         let temp_dir = TempDir::new().map_err(RepoDocsError::Io)?;
 
-        let repo = self.clone_repository(url, temp_dir.path())?;
+        let repo = match &self.cache {
+            Some(cache) => self.clone_cached(cache, url, temp_dir.path())?,
+            None => self.clone_repository(url, temp_dir.path())?,
+        };
 
         Ok((repo, temp_dir))
     }
@@ -83,16 +161,27 @@ impl SafeCloner {
             url: url.to_string(),
         })?;
 
+        // Validate hosting provider domain
+        let provider = GitProvider::resolve(&parsed_url, &self.allowed_hosts).ok_or(
+            RepoDocsError::InvalidUrl {
+                url: url.to_string(),
+            },
+        )?;
+
+        // Security: Never pass embedded passwords on to libgit2
+        if parsed_url.password().is_some() {
+            return Err(RepoDocsError::InvalidUrl {
+                url: url.to_string(),
+            });
+        }
+
         // Security: Only allow specific protocols
         match parsed_url.scheme() {
             "https" => {}
             "ssh" => {}
             "git" => {
                 // Only allow git:// for github.com
-                if !parsed_url
-                    .host_str()
-                    .is_some_and(|h| h.contains("github.com"))
-                {
+                if !provider.supports_git_protocol() {
                     return Err(RepoDocsError::InvalidUrl {
                         url: url.to_string(),
                     });
@@ -105,24 +194,98 @@ impl SafeCloner {
             }
         }
 
-        // Validate GitHub domain
-        if !parsed_url
-            .host_str()
-            .is_some_and(|h| h.ends_with("github.com"))
-        {
-            return Err(RepoDocsError::InvalidUrl {
-                url: url.to_string(),
-            });
-        }
-
         Ok(())
     }
 
     fn clone_repository(&self, url: &str, path: &std::path::Path) -> Result<Repository> {
+        let mut builder = self.repo_builder();
+        builder.fetch_options(self.fetch_options(url));
+
+        // Clone the repository
+        let repo = builder
+            .clone(url, path)
+            .map_err(|e| self.handle_git_error(e, url))?;
+
+        if self.submodules {
+            self.update_submodules(&repo, MAX_SUBMODULE_NESTING)?;
+        }
+
+        Ok(repo)
+    }
+
+    /// Update the cached bare mirror of `url`, then clone the working tree
+    /// from it over the local transport
+    fn clone_cached(
+        &self,
+        cache: &CloneCache,
+        url: &str,
+        path: &std::path::Path,
+    ) -> Result<Repository> {
+        let mirror = cache.open_or_init(url)?;
+        let mut remote = mirror
+            .find_remote("origin")
+            .map_err(|e| self.handle_git_error(e, url))?;
+
+        let mut fetch_options = self.fetch_options(url);
+        fetch_options.depth(0); // The mirror keeps the full history
+        remote
+            .fetch(&CACHE_REFSPECS, Some(&mut fetch_options), None)
+            .map_err(|e| self.handle_git_error(e, url))?;
+
+        // Follow the remote's default branch, which the local clone checks out
+        if let Ok(default_branch) = remote.default_branch() {
+            if let Some(name) = default_branch.as_str() {
+                let _ = mirror.set_head(name);
+            }
+        }
+
+        let mirror_path = mirror.path().to_string_lossy().to_string();
+        let repo = self
+            .repo_builder()
+            .clone(&mirror_path, path)
+            .map_err(|e| self.handle_git_error(e, url))?;
+
+        // Point origin back at the real remote, for submodules and reporting
+        repo.remote_set_url("origin", url)
+            .map_err(|e| self.handle_git_error(e, url))?;
+
+        if self.submodules {
+            self.update_submodules(&repo, MAX_SUBMODULE_NESTING)?;
+        }
+
+        Ok(repo)
+    }
+
+    /// Builder with the requested branch and sparse checkout applied
+    fn repo_builder(&self) -> RepoBuilder<'_> {
+        let mut builder = RepoBuilder::new();
+
+        // Set specific branch if requested
+        if let Some(ref branch) = self.branch {
+            builder.branch(branch);
+        }
+
+        // Sparse checkout: write only matching paths to the working tree
+        if !self.sparse_paths.is_empty() {
+            let mut checkout = CheckoutBuilder::new();
+            for path in &self.sparse_paths {
+                checkout.path(path.as_str());
+            }
+            builder.with_checkout(checkout);
+        }
+
+        builder
+    }
+
+    fn fetch_options(&self, url: &str) -> FetchOptions<'_> {
         let mut callbacks = RemoteCallbacks::new();
         let start_time = Instant::now();
         let timeout = self.timeout;
         let running = self.running.clone();
+        let provider = Url::parse(url)
+            .ok()
+            .and_then(|u| GitProvider::resolve(&u, &self.allowed_hosts))
+            .unwrap_or_default();
 
         // Progress callback with timeout handling
         let progress_callback = self.progress_callback.as_ref().map(|cb| cb.as_ref());
@@ -154,10 +317,13 @@ impl SafeCloner {
         });
 
         // Authentication callback for private repositories
-        callbacks.credentials(|_url, username_from_url, _allowed_types| {
+        callbacks.credentials(move |_url, username_from_url, _allowed_types| {
             // For HTTPS, try token-based auth first
-            if let Ok(token) = std::env::var("GITHUB_TOKEN") {
-                return git2::Cred::userpass_plaintext(username_from_url.unwrap_or("git"), &token);
+            if let Ok(token) = std::env::var(provider.token_env_var()) {
+                return git2::Cred::userpass_plaintext(
+                    username_from_url.unwrap_or(provider.token_username()),
+                    &token,
+                );
             }
 
             // For SSH, try default SSH key
@@ -174,24 +340,90 @@ impl SafeCloner {
             git2::Cred::default()
         });
 
+        // Honor http.proxy and the HTTPS_PROXY environment variables
+        let mut proxy_options = ProxyOptions::new();
+        proxy_options.auto();
+
         let mut fetch_options = FetchOptions::new();
         fetch_options.remote_callbacks(callbacks);
+        fetch_options.proxy_options(proxy_options);
 
-        let mut builder = RepoBuilder::new();
-        builder.fetch_options(fetch_options);
+        // Shallow clone if a depth was requested
+        if let Some(depth) = self.depth {
+            fetch_options.depth(depth.min(i32::MAX as u32) as i32);
+        }
 
-        // Set specific branch if requested
-        if let Some(ref branch) = self.branch {
-            builder.branch(branch);
+        fetch_options
+    }
+
+    /// Initialize and check out submodules, recursing up to `remaining_depth`
+    /// levels. Submodules pointing at hosts that fail URL validation are
+    /// skipped rather than fetched.
+    fn update_submodules(&self, repo: &Repository, remaining_depth: usize) -> Result<()> {
+        if remaining_depth == 0 {
+            return Ok(());
         }
 
-        // Clone the repository
-        builder
-            .clone(url, path)
-            .map_err(|e| self.handle_git_error(e, url))
+        let submodules = repo.submodules().map_err(|e| RepoDocsError::Git {
+            message: "Failed to list submodules".to_string(),
+            source: e,
+        })?;
+
+        for mut submodule in submodules {
+            if !self.running.load(Ordering::SeqCst) {
+                return Err(RepoDocsError::Cancelled);
+            }
+
+            let Some(url) = submodule.url().map(str::to_string) else {
+                continue;
+            };
+
+            // Security: relative and non-allowed submodule URLs are never followed
+            if self.validate_url(&url).is_err() {
+                eprintln!(
+                    "Skipping submodule {} with unsupported URL {}",
+                    submodule.path().display(),
+                    url
+                );
+                continue;
+            }
+
+            let mut options = SubmoduleUpdateOptions::new();
+            options.fetch(self.fetch_options(&url));
+            submodule
+                .update(true, Some(&mut options))
+                .map_err(|e| self.handle_git_error(e, &url))?;
+
+            if let Ok(sub_repo) = submodule.open() {
+                self.update_submodules(&sub_repo, remaining_depth - 1)?;
+            }
+        }
+
+        Ok(())
     }
 
     fn handle_git_error(&self, error: git2::Error, url: &str) -> RepoDocsError {
+        // libgit2 reports some HTTP 404s as generic errors with the status in the message
+        let http_not_found = error.class() == ErrorClass::Http
+            && (error.code() == ErrorCode::NotFound || error.message().contains("404"));
+        if http_not_found {
+            let provider = Url::parse(url)
+                .ok()
+                .and_then(|u| GitProvider::resolve(&u, &self.allowed_hosts))
+                .unwrap_or_default();
+            let token_present = std::env::var(provider.token_env_var()).is_ok();
+            return not_found_error(url, provider, token_present);
+        }
+
+        // Rate limits and abuse detection answer with HTTP 429, whose Retry-After
+        // libgit2 does not pass on; wait a minute, as GitHub asks for
+        if error.class() == ErrorClass::Http && error.message().contains("429") {
+            return RepoDocsError::RateLimited {
+                url: url.to_string(),
+                retry_after: 60,
+            };
+        }
+
         match (error.class(), error.code()) {
             (ErrorClass::Net, ErrorCode::GenericError) => RepoDocsError::NetworkError {
                 message: format!(
@@ -202,8 +434,9 @@ impl SafeCloner {
             (ErrorClass::Http, ErrorCode::Auth) => RepoDocsError::AuthenticationFailed {
                 url: url.to_string(),
             },
-            (ErrorClass::Http, ErrorCode::NotFound) => RepoDocsError::RepositoryNotFound {
+            (ErrorClass::Ssl, _) | (_, ErrorCode::Certificate) => RepoDocsError::TlsError {
                 url: url.to_string(),
+                message: error.message().to_string(),
             },
             _ => RepoDocsError::Git {
                 message: error.message().to_string(),
@@ -221,6 +454,37 @@ impl SafeCloner {
     }
 }
 
+/// Turn scp-like remotes (`git@github.com:owner/repo.git`) into `ssh://` URLs
+pub(crate) fn normalize_scp_url(url: &str) -> String {
+    if url.contains("://") {
+        return url.to_string();
+    }
+    match url.split_once(':') {
+        Some((host, path)) if !host.contains('/') => format!("ssh://{}/{}", host, path),
+        _ => url.to_string(),
+    }
+}
+
+/// GitHub and GitLab answer 404 rather than 401 for private repositories
+/// when no credentials are sent, so without a token "not found" may really
+/// mean "needs authentication".
+pub(crate) fn not_found_error(
+    url: &str,
+    provider: GitProvider,
+    token_present: bool,
+) -> RepoDocsError {
+    if token_present {
+        RepoDocsError::RepositoryNotFound {
+            url: url.to_string(),
+        }
+    } else {
+        RepoDocsError::RepositoryNotFoundOrPrivate {
+            url: url.to_string(),
+            token_env_var: provider.token_env_var().to_string(),
+        }
+    }
+}
+
 impl Default for SafeCloner {
     fn default() -> Self {
         Self::new()
@@ -234,11 +498,120 @@ pub struct RepositoryInfo {
     pub default_branch: String,
     pub is_empty: bool,
     pub total_commits: usize,
+    /// Commit the documentation is extracted from; unknown for archives and
+    /// plain directories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
     pub url: String,
+    #[serde(default)]
+    pub provider: GitProvider,
+}
+
+/// Hash of the commit `HEAD` points at, `None` for empty repositories
+fn head_commit(repo: &Repository) -> Option<String> {
+    repo.head()
+        .ok()
+        .and_then(|head| head.target())
+        .map(|oid| oid.to_string())
 }
 
 impl RepositoryInfo {
     pub fn from_repository(repo: &Repository, original_url: &str) -> Result<Self> {
+        Self::from_repository_with(repo, original_url, true)
+    }
+
+    /// Repository information for a downloaded archive, which carries no
+    /// history: the branch is the requested one (or `HEAD`) and commits are
+    /// not counted
+    pub fn from_archive(original_url: &str, branch: Option<&str>) -> Result<Self> {
+        let (provider, owner, name) = Self::parse_repository_url(original_url)?;
+
+        Ok(RepositoryInfo {
+            name,
+            owner,
+            default_branch: branch.unwrap_or("HEAD").to_string(),
+            is_empty: false,
+            total_commits: 0,
+            commit: None,
+            url: original_url.to_string(),
+            provider,
+        })
+    }
+
+    /// Repository information for a local directory. A git checkout is read
+    /// from its `.git` (owner/name come from the `origin` remote when it
+    /// points at a known host); plain directories fall back to the
+    /// directory name with no history.
+    pub fn from_local(path: &std::path::Path, count_commits: bool) -> Result<Self> {
+        let path = path.canonicalize().map_err(RepoDocsError::Io)?;
+        let dir_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "local".to_string());
+        let location = path.display().to_string();
+
+        let Ok(repo) = Repository::open(&path) else {
+            return Ok(RepositoryInfo {
+                name: dir_name,
+                owner: "local".to_string(),
+                default_branch: String::new(),
+                is_empty: false,
+                total_commits: 0,
+                commit: None,
+                url: location,
+                provider: GitProvider::Generic,
+            });
+        };
+
+        let origin = repo
+            .find_remote("origin")
+            .ok()
+            .and_then(|remote| remote.url().map(str::to_string))
+            .map(|url| normalize_scp_url(&url))
+            .and_then(|url| Self::parse_repository_url(&url).ok().map(|p| (url, p)));
+
+        let default_branch = repo
+            .head()
+            .ok()
+            .and_then(|head| head.shorthand().map(str::to_string))
+            .unwrap_or_else(|| "HEAD".to_string());
+        let is_empty = repo.is_empty().unwrap_or(true);
+        let total_commits = if count_commits && !is_empty {
+            Self::count_commits(&repo).unwrap_or(0)
+        } else {
+            0
+        };
+        let commit = head_commit(&repo);
+
+        let (url, provider, owner, name) = match origin {
+            Some((url, (provider, owner, name))) => (url, provider, owner, name),
+            None => (
+                location,
+                GitProvider::Generic,
+                "local".to_string(),
+                dir_name,
+            ),
+        };
+
+        Ok(RepositoryInfo {
+            name,
+            owner,
+            default_branch,
+            is_empty,
+            total_commits,
+            commit,
+            url,
+            provider,
+        })
+    }
+
+    /// Like `from_repository`, optionally skipping the commit count, which
+    /// walks the full history and is slow for large repositories
+    pub fn from_repository_with(
+        repo: &Repository,
+        original_url: &str,
+        count_commits: bool,
+    ) -> Result<Self> {
         let head = repo.head().map_err(|e| RepoDocsError::Git {
             message: "Repository has no HEAD".to_string(),
             source: e,
@@ -251,9 +624,9 @@ impl RepositoryInfo {
         })?;
 
         // Extract owner/name from original URL
-        let (owner, name) = Self::parse_github_url(original_url)?;
+        let (provider, owner, name) = Self::parse_repository_url(original_url)?;
 
-        let total_commits = if !is_empty {
+        let total_commits = if count_commits && !is_empty {
             Self::count_commits(repo)?
         } else {
             0
@@ -265,37 +638,28 @@ impl RepositoryInfo {
             default_branch,
             is_empty,
             total_commits,
+            commit: head_commit(repo),
             url: original_url.to_string(),
+            provider,
         })
     }
 
-    fn parse_github_url(url: &str) -> Result<(String, String)> {
+    fn parse_repository_url(url: &str) -> Result<(GitProvider, String, String)> {
         let parsed = Url::parse(url).map_err(|_| RepoDocsError::InvalidUrl {
             url: url.to_string(),
         })?;
 
-        let path_segments: Vec<&str> = parsed
-            .path_segments()
-            .ok_or(RepoDocsError::InvalidUrl {
-                url: url.to_string(),
-            })?
-            .collect();
-
-        if path_segments.len() < 2 {
-            return Err(RepoDocsError::InvalidUrl {
-                url: url.to_string(),
-            });
-        }
-
-        let owner = path_segments[0].to_string();
-        let mut name = path_segments[1].to_string();
+        // Unknown hosts were already checked against the allow list when cloning
+        let provider = GitProvider::from_url(&parsed).unwrap_or(GitProvider::Generic);
 
-        // Remove .git suffix if present
-        if name.ends_with(".git") {
-            name = name[..name.len() - 4].to_string();
-        }
+        let (owner, name) =
+            provider
+                .parse_repository_path(&parsed)
+                .ok_or(RepoDocsError::InvalidUrl {
+                    url: url.to_string(),
+                })?;
 
-        Ok((owner, name))
+        Ok((provider, owner, name))
     }
 
     fn count_commits(repo: &Repository) -> Result<usize> {
@@ -312,6 +676,53 @@ impl RepositoryInfo {
         Ok(revwalk.count())
     }
 
+    /// Web page of the file at repository path `path` on the GitHub or
+    /// GitLab branch the documentation was extracted from; images link to
+    /// the raw file so they still display. `None` for other hosts and for
+    /// local directories without a known remote.
+    pub fn file_url(&self, path: &str) -> Option<String> {
+        self.file_url_at(&self.default_branch, path)
+    }
+
+    /// Web page of the file at repository path `path` at the extracted
+    /// commit, which keeps showing the file as it was extracted; at the
+    /// branch when the commit is unknown
+    pub fn permalink(&self, path: &str) -> Option<String> {
+        self.file_url_at(self.commit.as_deref().unwrap_or(&self.default_branch), path)
+    }
+
+    fn file_url_at(&self, revision: &str, path: &str) -> Option<String> {
+        let url = Url::parse(&self.url).ok()?;
+        if !matches!(url.scheme(), "http" | "https") || revision.is_empty() {
+            return None;
+        }
+        let base = match url.port() {
+            Some(port) => format!("{}://{}:{}", url.scheme(), url.host_str()?, port),
+            None => format!("{}://{}", url.scheme(), url.host_str()?),
+        };
+
+        let image = std::path::Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                matches!(
+                    ext.to_lowercase().as_str(),
+                    "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp"
+                )
+            });
+        let view = if image { "raw" } else { "blob" };
+        let view = match self.provider {
+            GitProvider::GitHub => view.to_string(),
+            GitProvider::GitLab => format!("-/{}", view),
+            GitProvider::Generic => return None,
+        };
+
+        Some(format!(
+            "{}/{}/{}/{}/{}/{}",
+            base, self.owner, self.name, view, revision, path
+        ))
+    }
+
     pub fn display_summary(&self) -> String {
         format!(
             "Repository: {}/{}\nBranch: {}\nCommits: {}\nEmpty: {}",
@@ -335,27 +746,78 @@ mod tests {
         assert!(cloner
             .validate_url("https://github.com/rust-lang/rust.git")
             .is_ok());
+        assert!(cloner
+            .validate_url("https://gitlab.com/group/subgroup/project.git")
+            .is_ok());
 
         // Invalid URLs
         assert!(cloner
-            .validate_url("https://gitlab.com/owner/repo")
+            .validate_url("https://bitbucket.org/owner/repo")
+            .is_err());
+        assert!(cloner
+            .validate_url("git://gitlab.com/group/project")
             .is_err());
         assert!(cloner.validate_url("http://github.com/owner/repo").is_err());
         assert!(cloner.validate_url("ftp://github.com/owner/repo").is_err());
         assert!(cloner.validate_url("not-a-url").is_err());
+
+        let cloner = SafeCloner::new().with_allowed_hosts(vec!["bitbucket.org".to_string()]);
+        assert!(cloner
+            .validate_url("https://bitbucket.org/owner/repo")
+            .is_ok());
+        assert!(cloner.validate_url("https://127.0.0.1/owner/repo").is_err());
     }
 
     #[test]
-    fn test_parse_github_url() {
-        let (owner, name) =
-            RepositoryInfo::parse_github_url("https://github.com/microsoft/vscode").unwrap();
+    fn test_parse_repository_url() {
+        let (provider, owner, name) =
+            RepositoryInfo::parse_repository_url("https://github.com/microsoft/vscode").unwrap();
+        assert_eq!(provider, GitProvider::GitHub);
         assert_eq!(owner, "microsoft");
         assert_eq!(name, "vscode");
 
-        let (owner, name) =
-            RepositoryInfo::parse_github_url("https://github.com/rust-lang/rust.git").unwrap();
+        let (_, owner, name) =
+            RepositoryInfo::parse_repository_url("https://github.com/rust-lang/rust.git").unwrap();
         assert_eq!(owner, "rust-lang");
         assert_eq!(name, "rust");
+
+        let (provider, owner, name) =
+            RepositoryInfo::parse_repository_url("https://gitlab.com/group/subgroup/project.git")
+                .unwrap();
+        assert_eq!(provider, GitProvider::GitLab);
+        assert_eq!(owner, "group/subgroup");
+        assert_eq!(name, "project");
+    }
+
+    #[test]
+    fn test_file_url() {
+        let mut info =
+            RepositoryInfo::from_archive("https://github.com/owner/repo.git", Some("main"))
+                .unwrap();
+        assert_eq!(
+            info.file_url("src/lib.rs").as_deref(),
+            Some("https://github.com/owner/repo/blob/main/src/lib.rs")
+        );
+        assert_eq!(
+            info.file_url("docs/logo.PNG").as_deref(),
+            Some("https://github.com/owner/repo/raw/main/docs/logo.PNG")
+        );
+
+        info = RepositoryInfo::from_archive("https://gitlab.com/group/sub/project", Some("dev"))
+            .unwrap();
+        assert_eq!(
+            info.file_url("README.md").as_deref(),
+            Some("https://gitlab.com/group/sub/project/-/blob/dev/README.md")
+        );
+        assert_eq!(info.permalink("README.md"), info.file_url("README.md"));
+        info.commit = Some("0123abcd".to_string());
+        assert_eq!(
+            info.permalink("README.md").as_deref(),
+            Some("https://gitlab.com/group/sub/project/-/blob/0123abcd/README.md")
+        );
+
+        info.url = "/home/user/project".to_string();
+        assert_eq!(info.file_url("README.md"), None);
     }
 
     #[test]
@@ -381,6 +843,118 @@ mod tests {
         assert!(!cloner.is_running());
     }
 
+    #[test]
+    fn test_not_found_mentions_authentication_without_token() {
+        let url = "https://gitlab.com/group/private-project";
+
+        match not_found_error(url, GitProvider::GitLab, false) {
+            RepoDocsError::RepositoryNotFoundOrPrivate { token_env_var, .. } => {
+                assert_eq!(token_env_var, "GITLAB_TOKEN")
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        assert!(matches!(
+            not_found_error(url, GitProvider::GitLab, true),
+            RepoDocsError::RepositoryNotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn test_repository_info_from_local() {
+        let dir = TempDir::new().unwrap();
+
+        // Plain directory degrades gracefully
+        let info = RepositoryInfo::from_local(dir.path(), true).unwrap();
+        assert_eq!(info.owner, "local");
+        assert_eq!(info.total_commits, 0);
+
+        // Git checkout with a GitHub origin
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.remote("origin", "git@github.com:rust-lang/book.git")
+            .unwrap();
+        let info = RepositoryInfo::from_local(dir.path(), true).unwrap();
+        assert_eq!(info.owner, "rust-lang");
+        assert_eq!(info.name, "book");
+        assert!(info.is_empty);
+    }
+
+    #[test]
+    fn test_sparse_checkout() {
+        // Build a local source repository with docs and code
+        let source = TempDir::new().unwrap();
+        let repo = Repository::init(source.path()).unwrap();
+        std::fs::create_dir_all(source.path().join("docs")).unwrap();
+        std::fs::create_dir_all(source.path().join("src")).unwrap();
+        std::fs::write(source.path().join("docs/guide.md"), "# Guide").unwrap();
+        std::fs::write(source.path().join("src/main.rs"), "fn main() {}").unwrap();
+
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+
+        let target = TempDir::new().unwrap();
+        let cloner = SafeCloner::new().with_sparse_paths(vec!["docs".to_string()]);
+        let url = Url::from_directory_path(source.path()).unwrap();
+        cloner
+            .clone_repository(url.as_str(), &target.path().join("clone"))
+            .unwrap();
+
+        assert!(target.path().join("clone/docs/guide.md").exists());
+        assert!(!target.path().join("clone/src/main.rs").exists());
+    }
+
+    #[test]
+    fn test_clone_through_cache() {
+        let source = TempDir::new().unwrap();
+        let repo = Repository::init(source.path()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let commit_file = |name: &str| {
+            std::fs::write(source.path().join(name), "# Doc").unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(std::path::Path::new(name)).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<_> = repo
+                .head()
+                .ok()
+                .and_then(|head| head.peel_to_commit().ok())
+                .into_iter()
+                .collect();
+            let parents: Vec<_> = parents.iter().collect();
+            repo.commit(Some("HEAD"), &signature, &signature, name, &tree, &parents)
+                .unwrap();
+        };
+        commit_file("README.md");
+
+        let cache_dir = TempDir::new().unwrap();
+        let cache = CloneCache::new(cache_dir.path());
+        let url = Url::from_directory_path(source.path()).unwrap();
+        let cloner = SafeCloner::new();
+
+        let target = TempDir::new().unwrap();
+        let first = cloner
+            .clone_cached(&cache, url.as_str(), &target.path().join("first"))
+            .unwrap();
+        assert!(target.path().join("first/README.md").exists());
+        assert_eq!(
+            first.find_remote("origin").unwrap().url(),
+            Some(url.as_str())
+        );
+        assert!(cache.repository_path(url.as_str()).exists());
+
+        // A later run fetches new commits into the existing mirror
+        commit_file("GUIDE.md");
+        cloner
+            .clone_cached(&cache, url.as_str(), &target.path().join("second"))
+            .unwrap();
+        assert!(target.path().join("second/GUIDE.md").exists());
+    }
+
     #[test]
     fn test_timeout_configuration() {
         let timeout = Duration::from_secs(600);
@@ -0,0 +1,426 @@
+use crate::cloner::git_cloner::normalize_scp_url;
+use crate::cloner::provider::GitProvider;
+use crate::error::{RepoDocsError, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use url::Url;
+use walkdir::WalkDir;
+
+/// First line of a Git LFS pointer file
+const POINTER_VERSION: &str = "version https://git-lfs.github.com/spec/v1";
+
+/// Pointer files are far smaller than this
+const MAX_POINTER_SIZE: u64 = 1024;
+
+/// Objects asked for per batch API request
+const BATCH_SIZE: usize = 100;
+
+/// Media type of the Git LFS batch API
+const LFS_MEDIA_TYPE: &str = "application/vnd.git-lfs+json";
+
+/// A Git LFS pointer file, checked out in place of an object kept on the
+/// LFS server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LfsPointer {
+    /// SHA-256 of the object, in hex
+    pub oid: String,
+    pub size: u64,
+}
+
+impl LfsPointer {
+    /// Parse the content of a pointer file; `None` for any other file
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut lines = content.lines();
+        if lines.next()? != POINTER_VERSION {
+            return None;
+        }
+
+        let (mut oid, mut size) = (None, None);
+        for line in lines {
+            match line.split_once(' ') {
+                Some(("oid", value)) => oid = value.strip_prefix("sha256:"),
+                Some(("size", value)) => size = value.parse().ok(),
+                _ => {}
+            }
+        }
+        let oid =
+            oid.filter(|oid| oid.len() == 64 && oid.chars().all(|c| c.is_ascii_hexdigit()))?;
+        Some(Self {
+            oid: oid.to_lowercase(),
+            size: size?,
+        })
+    }
+}
+
+/// Objects fetched for the pointer files of a checkout
+#[derive(Debug, Clone, Default)]
+pub struct LfsSummary {
+    pub fetched: usize,
+    pub bytes: u64,
+    /// Paths, relative to the checkout, still holding their pointer because
+    /// the object was too large or could not be downloaded
+    pub left_as_pointers: Vec<String>,
+}
+
+/// Replaces the Git LFS pointer files of a checkout with their objects,
+/// downloaded through the LFS batch API of the repository's host. Only
+/// files with one of the given extensions are looked at, so objects that
+/// would not be extracted are never downloaded.
+pub struct LfsFetcher {
+    timeout: Duration,
+    extensions: Vec<String>,
+    max_size: u64,
+    allowed_hosts: Vec<String>,
+}
+
+impl LfsFetcher {
+    pub fn new(extensions: Vec<String>) -> Self {
+        Self {
+            timeout: Duration::from_secs(300), // 5 minutes default
+            extensions: extensions.iter().map(|ext| ext.to_lowercase()).collect(),
+            max_size: u64::MAX,
+            allowed_hosts: Vec::new(),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Leave the pointers of objects larger than `max_size` bytes in place
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Hosts accepted besides GitHub and GitLab, for picking the token
+    pub fn with_allowed_hosts(mut self, allowed_hosts: Vec<String>) -> Self {
+        self.allowed_hosts = allowed_hosts;
+        self
+    }
+
+    /// Fetch the objects of the pointer files below `tree`, a checkout of
+    /// the repository at `url`
+    pub fn fetch(&self, url: &str, tree: &Path) -> Result<LfsSummary> {
+        let mut summary = LfsSummary::default();
+        let mut pointers = Vec::new();
+        for (path, pointer) in self.find_pointers(tree) {
+            if pointer.size > self.max_size {
+                summary.left_as_pointers.push(relative(tree, &path));
+            } else {
+                pointers.push((path, pointer));
+            }
+        }
+        if pointers.is_empty() {
+            return Ok(summary);
+        }
+
+        let parsed =
+            Url::parse(&normalize_scp_url(url)).map_err(|_| RepoDocsError::InvalidUrl {
+                url: url.to_string(),
+            })?;
+        let endpoint = batch_endpoint(&parsed)?;
+        let provider = GitProvider::resolve(&parsed, &self.allowed_hosts).unwrap_or_default();
+        let authorization = std::env::var(provider.token_env_var()).ok().map(|token| {
+            let credentials = format!("{}:{}", provider.token_username(), token);
+            format!("Basic {}", BASE64.encode(credentials))
+        });
+        let agent = ureq::AgentBuilder::new()
+            .timeout(self.timeout)
+            .try_proxy_from_env(true)
+            .build();
+
+        for batch in pointers.chunks(BATCH_SIZE) {
+            let actions = request_batch(&agent, &endpoint, authorization.as_deref(), batch)?;
+            for (path, pointer) in batch {
+                let content = actions
+                    .get(&pointer.oid)
+                    .and_then(|action| download(&agent, &endpoint, action, pointer));
+                match content {
+                    Some(content) => {
+                        fs::write(path, &content).map_err(RepoDocsError::Io)?;
+                        summary.fetched += 1;
+                        summary.bytes += content.len() as u64;
+                    }
+                    None => summary.left_as_pointers.push(relative(tree, path)),
+                }
+            }
+        }
+        summary.left_as_pointers.sort();
+        Ok(summary)
+    }
+
+    fn find_pointers(&self, tree: &Path) -> Vec<(PathBuf, LfsPointer)> {
+        WalkDir::new(tree)
+            .into_iter()
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter(|entry| {
+                entry.path().extension().is_some_and(|ext| {
+                    self.extensions
+                        .contains(&ext.to_string_lossy().to_lowercase())
+                })
+            })
+            .filter(|entry| {
+                entry
+                    .metadata()
+                    .is_ok_and(|meta| meta.len() <= MAX_POINTER_SIZE)
+            })
+            .filter_map(|entry| {
+                let content = fs::read_to_string(entry.path()).ok()?;
+                Some((entry.into_path(), LfsPointer::parse(&content)?))
+            })
+            .collect()
+    }
+}
+
+/// Batch API of the LFS server of the repository at `url`, by the Git LFS
+/// convention of `<repository>.git/info/lfs`; over HTTPS for SSH remotes
+fn batch_endpoint(url: &Url) -> Result<Url> {
+    let invalid = || RepoDocsError::InvalidUrl {
+        url: url.to_string(),
+    };
+    let base = match url.scheme() {
+        "http" | "https" => format!(
+            "{}://{}",
+            url.scheme(),
+            &url[url::Position::BeforeHost..url::Position::AfterPort]
+        ),
+        "ssh" | "git" => format!("https://{}", url.host_str().ok_or_else(invalid)?),
+        _ => return Err(invalid()),
+    };
+    let path = url.path().trim_end_matches('/').trim_end_matches(".git");
+    Url::parse(&format!("{}{}.git/info/lfs/objects/batch", base, path)).map_err(|_| invalid())
+}
+
+/// Ask the LFS server where to download `pointers` from; the download
+/// actions are keyed by object id
+fn request_batch(
+    agent: &ureq::Agent,
+    endpoint: &Url,
+    authorization: Option<&str>,
+    pointers: &[(PathBuf, LfsPointer)],
+) -> Result<HashMap<String, Value>> {
+    let objects: Vec<Value> = pointers
+        .iter()
+        .map(|(_, pointer)| json!({ "oid": pointer.oid, "size": pointer.size }))
+        .collect();
+    let body = json!({
+        "operation": "download",
+        "transfers": ["basic"],
+        "objects": objects,
+    });
+
+    let mut request = agent
+        .post(endpoint.as_str())
+        .set("Accept", LFS_MEDIA_TYPE)
+        .set("Content-Type", LFS_MEDIA_TYPE);
+    if let Some(authorization) = authorization {
+        request = request.set("Authorization", authorization);
+    }
+    let response = match request.send_string(&body.to_string()) {
+        Ok(response) => response,
+        Err(ureq::Error::Status(401 | 403, _)) => {
+            return Err(RepoDocsError::AuthenticationFailed {
+                url: endpoint.to_string(),
+            })
+        }
+        Err(e) => {
+            return Err(RepoDocsError::NetworkError {
+                message: format!("Git LFS batch request failed: {}", e),
+            })
+        }
+    };
+
+    let answer: Value = response
+        .into_string()
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .ok_or_else(|| RepoDocsError::NetworkError {
+            message: "Invalid Git LFS batch response".to_string(),
+        })?;
+    Ok(answer["objects"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|object| {
+            let download = object["actions"]["download"].clone();
+            let oid = object["oid"].as_str()?.to_lowercase();
+            (!download.is_null()).then_some((oid, download))
+        })
+        .collect())
+}
+
+/// Download an object as the batch API `action` says, checking it against
+/// its pointer. Objects are only fetched over HTTPS, or over the batch
+/// API's own scheme, and never with the repository token: the action
+/// carries the headers the server wants.
+fn download(
+    agent: &ureq::Agent,
+    endpoint: &Url,
+    action: &Value,
+    pointer: &LfsPointer,
+) -> Option<Vec<u8>> {
+    let href = Url::parse(action["href"].as_str()?).ok()?;
+    if href.scheme() != "https" && href.scheme() != endpoint.scheme() {
+        return None;
+    }
+
+    let mut request = agent.get(href.as_str());
+    if let Some(headers) = action["header"].as_object() {
+        for (name, value) in headers {
+            request = request.set(name, value.as_str()?);
+        }
+    }
+    let mut content = Vec::new();
+    request
+        .call()
+        .ok()?
+        .into_reader()
+        .take(pointer.size + 1)
+        .read_to_end(&mut content)
+        .ok()?;
+
+    let hash = format!("{:x}", Sha256::digest(&content));
+    (content.len() as u64 == pointer.size && hash == pointer.oid).then_some(content)
+}
+
+fn relative(tree: &Path, path: &Path) -> String {
+    path.strip_prefix(tree)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use tempfile::TempDir;
+
+    fn pointer_file(content: &[u8]) -> String {
+        format!(
+            "{}\noid sha256:{:x}\nsize {}\n",
+            POINTER_VERSION,
+            Sha256::digest(content),
+            content.len()
+        )
+    }
+
+    /// Answer `responses.len()` HTTP requests with the given bodies,
+    /// returning the request lines seen
+    fn serve(listener: TcpListener, responses: Vec<String>) -> thread::JoinHandle<Vec<String>> {
+        thread::spawn(move || {
+            let mut requests = Vec::new();
+            for body in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                requests.push(line.trim().to_string());
+                let mut length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(value) = header.to_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                reader.take(length).read_to_end(&mut Vec::new()).unwrap();
+                write!(
+                    &stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+            requests
+        })
+    }
+
+    #[test]
+    fn test_parse_pointer() {
+        let pointer = LfsPointer::parse(&pointer_file(b"# Guide\n")).unwrap();
+        assert_eq!(pointer.size, 8);
+        assert_eq!(pointer.oid.len(), 64);
+        assert_eq!(LfsPointer::parse("# Guide\n"), None);
+        assert_eq!(
+            LfsPointer::parse(&format!("{}\noid sha256:abc\nsize 3\n", POINTER_VERSION)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_batch_endpoint() {
+        let endpoint = |url: &str| {
+            batch_endpoint(&Url::parse(&normalize_scp_url(url)).unwrap())
+                .unwrap()
+                .to_string()
+        };
+        let expected = "https://github.com/owner/repo.git/info/lfs/objects/batch";
+        assert_eq!(endpoint("https://github.com/owner/repo"), expected);
+        assert_eq!(endpoint("https://github.com/owner/repo.git/"), expected);
+        assert_eq!(endpoint("git@github.com:owner/repo.git"), expected);
+        assert!(batch_endpoint(&Url::parse("file:///tmp/repo").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_fetch_replaces_pointers() {
+        let tree = TempDir::new().unwrap();
+        let guide = b"# Guide\n\nStored in Git LFS.\n";
+        let manual = b"# Manual, too large\n";
+        fs::write(tree.path().join("guide.md"), pointer_file(guide)).unwrap();
+        fs::write(tree.path().join("manual.md"), pointer_file(manual)).unwrap();
+        fs::write(tree.path().join("video.mp4"), pointer_file(b"frames")).unwrap();
+        fs::write(tree.path().join("README.md"), "# Project\n").unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let oid = format!("{:x}", Sha256::digest(guide));
+        let batch = json!({
+            "objects": [{
+                "oid": oid,
+                "size": guide.len(),
+                "actions": { "download": { "href": format!("{}/objects/{}", base, oid) } },
+            }],
+        });
+        let server = serve(
+            listener,
+            vec![
+                batch.to_string(),
+                String::from_utf8(guide.to_vec()).unwrap(),
+            ],
+        );
+
+        let summary = LfsFetcher::new(vec!["md".to_string()])
+            .with_max_size(guide.len() as u64)
+            .fetch(&format!("{}/owner/repo.git", base), tree.path())
+            .unwrap();
+        assert_eq!(summary.fetched, 1);
+        assert_eq!(summary.bytes, guide.len() as u64);
+        assert_eq!(summary.left_as_pointers, ["manual.md"]);
+        assert_eq!(fs::read(tree.path().join("guide.md")).unwrap(), guide);
+        assert_eq!(
+            server.join().unwrap(),
+            [
+                "POST /owner/repo.git/info/lfs/objects/batch HTTP/1.1".to_string(),
+                format!("GET /objects/{} HTTP/1.1", oid),
+            ]
+        );
+    }
+}
@@ -1,3 +1,12 @@
+pub mod cache;
 pub mod git_cloner;
+pub mod lfs;
+pub mod preflight;
+pub mod provider;
+pub mod tarball;
 
+pub use cache::CloneCache;
 pub use git_cloner::{CloneProgress, RepositoryInfo, SafeCloner};
+pub use lfs::LfsFetcher;
+pub use provider::GitProvider;
+pub use tarball::TarballFetcher;
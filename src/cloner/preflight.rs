@@ -0,0 +1,173 @@
+use crate::error::{RepoDocsError, Result};
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+use url::Url;
+
+/// Upper bound for each preflight connection attempt, independent of the
+/// (much longer) clone timeout
+pub const PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Check that the repository host (or the configured HTTPS proxy) resolves
+/// and accepts TCP connections, so that failures can be reported as DNS,
+/// proxy or firewall problems instead of a generic libgit2 network error.
+pub fn check_connectivity(url: &Url, timeout: Duration) -> Result<()> {
+    let host = url.host_str().ok_or(RepoDocsError::InvalidUrl {
+        url: url.to_string(),
+    })?;
+
+    if url.scheme() == "https" {
+        if let Some(proxy) = proxy_for_host(host, |name| std::env::var(name).ok()) {
+            return check_proxy(&proxy, timeout);
+        }
+    }
+
+    let port = default_port(url);
+    let addrs =
+        url.socket_addrs(|| Some(port))
+            .map_err(|_| RepoDocsError::DnsResolutionFailed {
+                host: host.to_string(),
+            })?;
+
+    connect_any(host, port, &addrs, timeout)
+}
+
+fn check_proxy(proxy: &Url, timeout: Duration) -> Result<()> {
+    let proxy_name = proxy.host_str().unwrap_or_default().to_string();
+    let addrs = proxy
+        .socket_addrs(|| Some(8080))
+        .map_err(|e| RepoDocsError::ProxyError {
+            proxy: proxy_name.clone(),
+            reason: format!("could not resolve proxy host ({})", e),
+        })?;
+
+    connect_any(&proxy_name, 0, &addrs, timeout).map_err(|e| RepoDocsError::ProxyError {
+        proxy: proxy_name,
+        reason: match e {
+            RepoDocsError::ConnectionFailed { reason, .. } => reason,
+            other => other.to_string(),
+        },
+    })
+}
+
+fn connect_any(host: &str, port: u16, addrs: &[SocketAddr], timeout: Duration) -> Result<()> {
+    if addrs.is_empty() {
+        return Err(RepoDocsError::DnsResolutionFailed {
+            host: host.to_string(),
+        });
+    }
+
+    let mut last_error = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(addr, timeout) {
+            Ok(_) => return Ok(()),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    let reason = match last_error {
+        Some(e) if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {
+            "connection timed out, the port may be blocked by a firewall".to_string()
+        }
+        Some(e) if e.kind() == ErrorKind::ConnectionRefused => "connection refused".to_string(),
+        Some(e) => e.to_string(),
+        None => "no addresses to connect to".to_string(),
+    };
+
+    Err(RepoDocsError::ConnectionFailed {
+        host: host.to_string(),
+        port: addrs.first().map(|a| a.port()).unwrap_or(port),
+        reason,
+    })
+}
+
+fn default_port(url: &Url) -> u16 {
+    url.port_or_known_default().unwrap_or(match url.scheme() {
+        "ssh" => 22,
+        "git" => 9418,
+        _ => 443,
+    })
+}
+
+/// Proxy from `HTTPS_PROXY`/`ALL_PROXY` (either case) unless `NO_PROXY`
+/// excludes the host
+fn proxy_for_host<F>(host: &str, env: F) -> Option<Url>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let no_proxy = env("NO_PROXY").or_else(|| env("no_proxy"));
+    if let Some(no_proxy) = no_proxy {
+        let host = host.to_lowercase();
+        let excluded = no_proxy.split(',').map(str::trim).any(|entry| {
+            let entry = entry.trim_start_matches('.').to_lowercase();
+            entry == "*"
+                || (!entry.is_empty() && (host == entry || host.ends_with(&format!(".{}", entry))))
+        });
+        if excluded {
+            return None;
+        }
+    }
+
+    let proxy = ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+        .iter()
+        .find_map(|name| env(name).filter(|value| !value.trim().is_empty()))?;
+
+    let proxy = proxy.trim();
+    if proxy.contains("://") {
+        Url::parse(proxy).ok()
+    } else {
+        Url::parse(&format!("http://{}", proxy)).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::net::TcpListener;
+
+    fn env_from(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_proxy_for_host() {
+        let env = env_from(&[("HTTPS_PROXY", "proxy.corp:3128")]);
+        let proxy = proxy_for_host("github.com", env).unwrap();
+        assert_eq!(proxy.host_str(), Some("proxy.corp"));
+        assert_eq!(proxy.port(), Some(3128));
+
+        let env = env_from(&[
+            ("https_proxy", "http://proxy.corp:3128"),
+            ("NO_PROXY", "localhost,.github.com"),
+        ]);
+        assert!(proxy_for_host("api.github.com", &env).is_none());
+        assert!(proxy_for_host("github.com", &env).is_none());
+        assert!(proxy_for_host("gitlab.com", &env).is_some());
+
+        assert!(proxy_for_host("github.com", env_from(&[])).is_none());
+    }
+
+    #[test]
+    fn test_connection_refused_is_classified() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let result = connect_any("127.0.0.1", addr.port(), &[addr], Duration::from_secs(1));
+        assert!(matches!(
+            result,
+            Err(RepoDocsError::ConnectionFailed { port, .. }) if port == addr.port()
+        ));
+
+        let result = connect_any("example.invalid", 443, &[], Duration::from_secs(1));
+        assert!(matches!(
+            result,
+            Err(RepoDocsError::DnsResolutionFailed { .. })
+        ));
+    }
+}
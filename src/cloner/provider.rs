@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use url::{Host, Url};
+
+/// Git hosting services that repository URLs are accepted from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitProvider {
+    #[default]
+    GitHub,
+    GitLab,
+    /// Any other host explicitly allowed via `git.allowed_hosts`
+    /// (Bitbucket Cloud, Gitea, Forgejo, ...)
+    Generic,
+}
+
+impl GitProvider {
+    pub fn from_host(host: &str) -> Option<Self> {
+        let host = host.to_lowercase();
+        let matches = |domain: &str| host == domain || host.ends_with(&format!(".{}", domain));
+
+        if matches("github.com") {
+            Some(GitProvider::GitHub)
+        } else if matches("gitlab.com") {
+            Some(GitProvider::GitLab)
+        } else {
+            None
+        }
+    }
+
+    pub fn from_url(url: &Url) -> Option<Self> {
+        url.host_str().and_then(Self::from_host)
+    }
+
+    /// Resolve the provider for a URL, accepting hosts from `allowed_hosts`
+    /// as generic remotes. Allowed hosts must match exactly (no subdomains),
+    /// and loopback, link-local and unspecified addresses are always refused.
+    pub fn resolve(url: &Url, allowed_hosts: &[String]) -> Option<Self> {
+        if let Some(provider) = Self::from_url(url) {
+            return Some(provider);
+        }
+
+        let host = url.host()?;
+        let forbidden = match host {
+            Host::Domain(domain) => {
+                let domain = domain.trim_end_matches('.').to_lowercase();
+                domain == "localhost" || domain.ends_with(".localhost")
+            }
+            Host::Ipv4(ip) => is_forbidden_ipv4(ip),
+            Host::Ipv6(ip) => is_forbidden_ipv6(ip),
+        };
+        if forbidden {
+            return None;
+        }
+
+        let host = url.host_str()?.trim_end_matches('.').to_lowercase();
+        allowed_hosts
+            .iter()
+            .any(|allowed| allowed.trim().trim_end_matches('.').to_lowercase() == host)
+            .then_some(GitProvider::Generic)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GitProvider::GitHub => "GitHub",
+            GitProvider::GitLab => "GitLab",
+            GitProvider::Generic => "Git",
+        }
+    }
+
+    /// Environment variable holding an access token for private repositories
+    pub fn token_env_var(&self) -> &'static str {
+        match self {
+            GitProvider::GitHub => "GITHUB_TOKEN",
+            GitProvider::GitLab => "GITLAB_TOKEN",
+            GitProvider::Generic => "GIT_TOKEN",
+        }
+    }
+
+    /// Username to pair with the access token over HTTPS
+    pub fn token_username(&self) -> &'static str {
+        match self {
+            GitProvider::GitHub | GitProvider::Generic => "git",
+            GitProvider::GitLab => "oauth2",
+        }
+    }
+
+    /// The unauthenticated `git://` protocol is only accepted for GitHub
+    pub fn supports_git_protocol(&self) -> bool {
+        matches!(self, GitProvider::GitHub)
+    }
+
+    /// Path segments naming the repository. GitHub and generic hosts use
+    /// `owner/repo`; GitLab
+    /// allows nested groups (`group/subgroup/repo`) and ends the project path
+    /// at a `-` segment (`group/repo/-/tree/main`).
+    pub fn repository_segments<'a>(&self, url: &'a Url) -> Vec<&'a str> {
+        let segments = url
+            .path_segments()
+            .map(|s| s.filter(|segment| !segment.is_empty()))
+            .into_iter()
+            .flatten();
+
+        match self {
+            GitProvider::GitHub | GitProvider::Generic => segments.take(2).collect(),
+            GitProvider::GitLab => segments.take_while(|segment| *segment != "-").collect(),
+        }
+    }
+
+    /// Split a repository URL into `(owner, name)`, where the owner of a
+    /// nested GitLab project is the full group path.
+    pub fn parse_repository_path(&self, url: &Url) -> Option<(String, String)> {
+        let segments = self.repository_segments(url);
+        if segments.len() < 2 {
+            return None;
+        }
+
+        let (name, owner) = segments.split_last()?;
+        let name = name.strip_suffix(".git").unwrap_or(name);
+
+        Some((owner.join("/"), name.to_string()))
+    }
+}
+
+fn is_forbidden_ipv4(ip: Ipv4Addr) -> bool {
+    ip.is_loopback() || ip.is_link_local() || ip.is_unspecified() || ip.is_broadcast()
+}
+
+fn is_forbidden_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(mapped) = ip.to_ipv4_mapped() {
+        return is_forbidden_ipv4(mapped);
+    }
+    // fe80::/10 is link-local
+    ip.is_loopback() || ip.is_unspecified() || (ip.segments()[0] & 0xffc0) == 0xfe80
+}
+
+impl std::fmt::Display for GitProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_detection() {
+        assert_eq!(
+            GitProvider::from_host("github.com"),
+            Some(GitProvider::GitHub)
+        );
+        assert_eq!(
+            GitProvider::from_host("GitLab.com"),
+            Some(GitProvider::GitLab)
+        );
+        assert_eq!(GitProvider::from_host("evilgithub.com"), None);
+        assert_eq!(GitProvider::from_host("bitbucket.org"), None);
+    }
+
+    #[test]
+    fn test_resolve_allowed_hosts() {
+        let allowed = vec!["bitbucket.org".to_string(), "Git.Example.com".to_string()];
+        let resolve = |url: &str| GitProvider::resolve(&Url::parse(url).unwrap(), &allowed);
+
+        assert_eq!(
+            resolve("https://github.com/owner/repo"),
+            Some(GitProvider::GitHub)
+        );
+        assert_eq!(
+            resolve("https://bitbucket.org/owner/repo"),
+            Some(GitProvider::Generic)
+        );
+        assert_eq!(
+            resolve("ssh://git@git.example.com:2222/owner/repo.git"),
+            Some(GitProvider::Generic)
+        );
+
+        // Only exact matches are allowed
+        assert_eq!(resolve("https://evil.bitbucket.org/owner/repo"), None);
+        assert_eq!(resolve("https://gitea.io/owner/repo"), None);
+
+        // Loopback and link-local targets are refused even when listed
+        let allowed = vec![
+            "localhost".to_string(),
+            "127.0.0.1".to_string(),
+            "169.254.169.254".to_string(),
+            "[::1]".to_string(),
+        ];
+        for url in [
+            "https://localhost/owner/repo",
+            "https://127.0.0.1/owner/repo",
+            "https://169.254.169.254/owner/repo",
+            "https://[::1]/owner/repo",
+        ] {
+            assert_eq!(
+                GitProvider::resolve(&Url::parse(url).unwrap(), &allowed),
+                None,
+                "Should refuse: {}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_nested_gitlab_groups() {
+        let url = Url::parse("https://gitlab.com/group/subgroup/project.git").unwrap();
+        let (owner, name) = GitProvider::GitLab.parse_repository_path(&url).unwrap();
+        assert_eq!(owner, "group/subgroup");
+        assert_eq!(name, "project");
+
+        let url = Url::parse("https://gitlab.com/group/project/-/tree/main").unwrap();
+        let (owner, name) = GitProvider::GitLab.parse_repository_path(&url).unwrap();
+        assert_eq!(owner, "group");
+        assert_eq!(name, "project");
+    }
+
+    #[test]
+    fn test_parse_github_path() {
+        let url = Url::parse("https://github.com/rust-lang/book/tree/main").unwrap();
+        let (owner, name) = GitProvider::GitHub.parse_repository_path(&url).unwrap();
+        assert_eq!(owner, "rust-lang");
+        assert_eq!(name, "book");
+
+        let url = Url::parse("https://github.com/owner").unwrap();
+        assert!(GitProvider::GitHub.parse_repository_path(&url).is_none());
+    }
+}
@@ -0,0 +1,556 @@
+use crate::cloner::git_cloner::not_found_error;
+use crate::cloner::provider::GitProvider;
+use crate::error::{RepoDocsError, Result};
+use crate::ui::GracefulShutdown;
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tar::{Archive, EntryType};
+use tempfile::TempDir;
+use url::Url;
+
+/// Refuse archives larger than this (compressed) to bound disk usage
+const MAX_ARCHIVE_SIZE: u64 = 2 * 1024 * 1024 * 1024; // 2GB
+
+type StatusCallback = Box<dyn Fn(&str) + Send + Sync>;
+
+/// Downloads a repository snapshot as a `.tar.gz` over HTTPS instead of
+/// cloning it with libgit2. Only GitHub and GitLab archives are supported.
+pub struct TarballFetcher {
+    timeout: Duration,
+    running: Arc<AtomicBool>,
+    shutdown: Option<GracefulShutdown>,
+    branch: Option<String>,
+    max_retries: u32,
+    max_retry_wait: Duration,
+    status_callback: Option<StatusCallback>,
+}
+
+impl TarballFetcher {
+    pub fn new() -> Self {
+        Self {
+            timeout: Duration::from_secs(300), // 5 minutes default
+            running: Arc::new(AtomicBool::new(true)),
+            shutdown: None,
+            branch: None,
+            max_retries: 3,
+            max_retry_wait: Duration::from_secs(60),
+            status_callback: None,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_branch<S: Into<String>>(mut self, branch: S) -> Self {
+        self.branch = Some(branch.into());
+        self
+    }
+
+    /// Retry rate-limited requests up to `max_retries` times, waiting at most
+    /// `max_wait` per attempt
+    pub fn with_retries(mut self, max_retries: u32, max_wait: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.max_retry_wait = max_wait;
+        self
+    }
+
+    /// Stop waiting for a rate limit and unpacking once `shutdown` is
+    /// requested, e.g. by Ctrl+C
+    pub fn with_shutdown(mut self, shutdown: &GracefulShutdown) -> Self {
+        self.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// Receive human-readable status updates (e.g. rate limit waits)
+    pub fn with_status<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.status_callback = Some(Box::new(callback));
+        self
+    }
+
+    pub fn fetch_to_temp(&self, url: &str) -> Result<TempDir> {
+        let (provider, owner, name) = parse_repository(url)?;
+        let archive_url = archive_url(provider, &owner, &name, self.branch.as_deref())?;
+        let reader = self.download(&archive_url, provider, url)?;
+
+        let temp_dir = TempDir::new().map_err(RepoDocsError::Io)?;
+        self.unpack(reader.take(MAX_ARCHIVE_SIZE), temp_dir.path())?;
+
+        Ok(temp_dir)
+    }
+
+    /// Download the single file at `path`, relative to the repository root,
+    /// instead of the whole archive: from `raw.githubusercontent.com` on
+    /// GitHub and the repository files API on GitLab
+    pub fn fetch_file(&self, url: &str, path: &str) -> Result<Vec<u8>> {
+        let (provider, owner, name) = parse_repository(url)?;
+        let file_url = raw_file_url(provider, &owner, &name, self.branch.as_deref(), path)?;
+        let reader = self.download(&file_url, provider, url)?;
+
+        let mut content = Vec::new();
+        reader
+            .take(MAX_ARCHIVE_SIZE)
+            .read_to_end(&mut content)
+            .map_err(RepoDocsError::Io)?;
+        Ok(content)
+    }
+
+    fn download(
+        &self,
+        archive_url: &str,
+        provider: GitProvider,
+        repo_url: &str,
+    ) -> Result<Box<dyn Read + Send + Sync>> {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(self.timeout)
+            .try_proxy_from_env(true)
+            .build();
+
+        let mut request = agent.get(archive_url);
+        let token = std::env::var(provider.token_env_var()).ok();
+        if let Some(ref token) = token {
+            request = match provider {
+                GitProvider::GitLab => request.set("PRIVATE-TOKEN", token),
+                _ => request.set("Authorization", &format!("Bearer {}", token)),
+            };
+        }
+
+        let mut attempt = 0;
+        loop {
+            let (code, response) = match request.clone().call() {
+                Ok(response) => return Ok(response.into_reader()),
+                Err(ureq::Error::Status(code, response)) => (code, response),
+                Err(ureq::Error::Transport(transport)) => {
+                    return Err(RepoDocsError::NetworkError {
+                        message: format!("Download failed: {}", transport),
+                    })
+                }
+            };
+
+            if let Some(wait) = retry_delay(
+                code,
+                response.header("retry-after"),
+                response.header("x-ratelimit-remaining"),
+                response.header("x-ratelimit-reset"),
+                Utc::now(),
+                attempt,
+            ) {
+                if attempt >= self.max_retries || wait > self.max_retry_wait {
+                    return Err(RepoDocsError::RateLimited {
+                        url: repo_url.to_string(),
+                        retry_after: wait.as_secs(),
+                    });
+                }
+
+                attempt += 1;
+                self.report_status(&format!(
+                    "Rate limited (HTTP {}), retrying in {}s (attempt {}/{})",
+                    code,
+                    wait.as_secs(),
+                    attempt,
+                    self.max_retries
+                ));
+                self.sleep(wait)?;
+                continue;
+            }
+
+            return Err(match code {
+                404 => not_found_error(repo_url, provider, token.is_some()),
+                401 | 403 => RepoDocsError::AuthenticationFailed {
+                    url: repo_url.to_string(),
+                },
+                _ => RepoDocsError::NetworkError {
+                    message: format!("Download failed with HTTP status {}", code),
+                },
+            });
+        }
+    }
+
+    fn report_status(&self, message: &str) {
+        if let Some(ref callback) = self.status_callback {
+            callback(message);
+        }
+    }
+
+    /// Sleep in short steps so cancellation is noticed while waiting
+    fn sleep(&self, duration: Duration) -> Result<()> {
+        let deadline = Instant::now() + duration;
+        while Instant::now() < deadline {
+            self.check_running()?;
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            std::thread::sleep(remaining.min(Duration::from_millis(200)));
+        }
+        Ok(())
+    }
+
+    /// Unpack regular files and directories, dropping the archive's single
+    /// top-level directory (`repo-<ref>/`). Links are skipped.
+    fn unpack<R: Read>(&self, reader: R, destination: &Path) -> Result<()> {
+        let mut archive = Archive::new(GzDecoder::new(reader));
+        let entries = archive.entries().map_err(|e| RepoDocsError::NetworkError {
+            message: format!("Failed to read repository archive: {}", e),
+        })?;
+
+        for entry in entries {
+            self.check_running()?;
+
+            let mut entry = entry.map_err(|e| RepoDocsError::NetworkError {
+                message: format!("Corrupt repository archive: {}", e),
+            })?;
+
+            let entry_type = entry.header().entry_type();
+            if !matches!(entry_type, EntryType::Regular | EntryType::Directory) {
+                continue;
+            }
+
+            let path = entry.path().map_err(RepoDocsError::Io)?.into_owned();
+            let Some(relative) = strip_archive_root(&path) else {
+                continue;
+            };
+
+            let target = destination.join(&relative);
+            if entry_type == EntryType::Directory {
+                std::fs::create_dir_all(&target).map_err(RepoDocsError::Io)?;
+            } else {
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent).map_err(RepoDocsError::Io)?;
+                }
+                let mut file = std::fs::File::create(&target).map_err(RepoDocsError::Io)?;
+                std::io::copy(&mut entry, &mut file).map_err(RepoDocsError::Io)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+
+    fn check_running(&self) -> Result<()> {
+        if !self.running.load(Ordering::SeqCst) {
+            return Err(RepoDocsError::Cancelled);
+        }
+        match &self.shutdown {
+            Some(shutdown) => shutdown.check_shutdown(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for TarballFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How long to wait before retrying a rate-limited response, or `None` if
+/// the response is not a rate limit. Honors `Retry-After` (seconds or HTTP
+/// date) and GitHub's `X-RateLimit-Reset`; a bare 429 backs off exponentially.
+fn retry_delay(
+    code: u16,
+    retry_after: Option<&str>,
+    ratelimit_remaining: Option<&str>,
+    ratelimit_reset: Option<&str>,
+    now: DateTime<Utc>,
+    attempt: u32,
+) -> Option<Duration> {
+    if code != 429 && code != 403 {
+        return None;
+    }
+
+    if let Some(value) = retry_after.map(str::trim) {
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+        if let Ok(date) = DateTime::parse_from_rfc2822(value) {
+            let seconds = (date.with_timezone(&Utc) - now).num_seconds().max(0);
+            return Some(Duration::from_secs(seconds as u64));
+        }
+    }
+
+    if ratelimit_remaining.map(str::trim) == Some("0") {
+        if let Some(reset) = ratelimit_reset.and_then(|r| r.trim().parse::<i64>().ok()) {
+            let seconds = (reset - now.timestamp()).max(0);
+            return Some(Duration::from_secs(seconds as u64));
+        }
+    }
+
+    // A 403 without rate limit headers is a permission problem
+    (code == 429).then(|| Duration::from_secs(2u64.saturating_pow(attempt + 1)))
+}
+
+fn parse_repository(url: &str) -> Result<(GitProvider, String, String)> {
+    let invalid = || RepoDocsError::InvalidUrl {
+        url: url.to_string(),
+    };
+    let parsed = Url::parse(url).map_err(|_| invalid())?;
+    let provider = GitProvider::from_url(&parsed).ok_or_else(invalid)?;
+    let (owner, name) = provider
+        .parse_repository_path(&parsed)
+        .ok_or_else(invalid)?;
+    Ok((provider, owner, name))
+}
+
+fn archive_url(
+    provider: GitProvider,
+    owner: &str,
+    name: &str,
+    reference: Option<&str>,
+) -> Result<String> {
+    match provider {
+        GitProvider::GitHub => Ok(format!(
+            "https://github.com/{}/{}/archive/{}.tar.gz",
+            owner,
+            name,
+            reference.unwrap_or("HEAD")
+        )),
+        GitProvider::GitLab => {
+            let project = format!("{}/{}", owner, name).replace('/', "%2F");
+            let mut url = format!(
+                "https://gitlab.com/api/v4/projects/{}/repository/archive.tar.gz",
+                project
+            );
+            if let Some(reference) = reference {
+                url.push_str("?sha=");
+                url.push_str(&reference.replace('/', "%2F"));
+            }
+            Ok(url)
+        }
+        GitProvider::Generic => Err(RepoDocsError::Config {
+            message: "Tarball fetch mode only supports GitHub and GitLab repositories".to_string(),
+        }),
+    }
+}
+
+/// Where the raw content of the file at `path` is served, at `reference` or
+/// the default branch
+fn raw_file_url(
+    provider: GitProvider,
+    owner: &str,
+    name: &str,
+    reference: Option<&str>,
+    path: &str,
+) -> Result<String> {
+    let reference = reference.unwrap_or("HEAD");
+    match provider {
+        GitProvider::GitHub => {
+            let mut url =
+                Url::parse("https://raw.githubusercontent.com/").expect("valid raw content URL");
+            url.path_segments_mut()
+                .expect("raw content URL has a path")
+                .pop_if_empty()
+                .extend([owner, name])
+                .extend(reference.split('/'))
+                .extend(path.split('/'));
+            Ok(url.into())
+        }
+        GitProvider::GitLab => {
+            // The project and file path are single, encoded path segments
+            let mut url =
+                Url::parse("https://gitlab.com/api/v4/projects/").expect("valid GitLab API URL");
+            url.path_segments_mut()
+                .expect("GitLab API URL has a path")
+                .pop_if_empty()
+                .extend([
+                    format!("{}/{}", owner, name).as_str(),
+                    "repository",
+                    "files",
+                    path,
+                    "raw",
+                ]);
+            url.query_pairs_mut().append_pair("ref", reference);
+            Ok(url.into())
+        }
+        GitProvider::Generic => Err(RepoDocsError::Config {
+            message: "Single file downloads only support GitHub and GitLab repositories"
+                .to_string(),
+        }),
+    }
+}
+
+/// Drop the leading directory component; reject anything that is not a
+/// plain relative path (security: no traversal out of the destination)
+fn strip_archive_root(path: &Path) -> Option<PathBuf> {
+    let mut components = path.components();
+    match components.next() {
+        Some(Component::Normal(_)) => {}
+        _ => return None,
+    }
+
+    let mut relative = PathBuf::new();
+    for component in components {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    if relative.as_os_str().is_empty() {
+        None
+    } else {
+        Some(relative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    #[test]
+    fn test_archive_url() {
+        assert_eq!(
+            archive_url(GitProvider::GitHub, "rust-lang", "book", None).unwrap(),
+            "https://github.com/rust-lang/book/archive/HEAD.tar.gz"
+        );
+        assert_eq!(
+            archive_url(GitProvider::GitLab, "group/sub", "project", Some("main")).unwrap(),
+            "https://gitlab.com/api/v4/projects/group%2Fsub%2Fproject/repository/archive.tar.gz?sha=main"
+        );
+        assert!(archive_url(GitProvider::Generic, "o", "r", None).is_err());
+    }
+
+    #[test]
+    fn test_raw_file_url() {
+        assert_eq!(
+            raw_file_url(
+                GitProvider::GitHub,
+                "rust-lang",
+                "book",
+                None,
+                "src/ch 01.md"
+            )
+            .unwrap(),
+            "https://raw.githubusercontent.com/rust-lang/book/HEAD/src/ch%2001.md"
+        );
+        assert_eq!(
+            raw_file_url(
+                GitProvider::GitHub,
+                "rust-lang",
+                "book",
+                Some("release/v2"),
+                "README.md"
+            )
+            .unwrap(),
+            "https://raw.githubusercontent.com/rust-lang/book/release/v2/README.md"
+        );
+        assert_eq!(
+            raw_file_url(
+                GitProvider::GitLab,
+                "group/sub",
+                "project",
+                Some("main"),
+                "docs/guide.md"
+            )
+            .unwrap(),
+            "https://gitlab.com/api/v4/projects/group%2Fsub%2Fproject/repository/files/docs%2Fguide.md/raw?ref=main"
+        );
+        assert!(raw_file_url(GitProvider::Generic, "o", "r", None, "README.md").is_err());
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let now = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            retry_delay(429, Some("7"), None, None, now, 0),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            retry_delay(
+                503,
+                Some("Mon, 01 Jan 2024 00:00:30 GMT"),
+                None,
+                None,
+                now,
+                0
+            ),
+            None
+        );
+        assert_eq!(
+            retry_delay(
+                429,
+                Some("Mon, 01 Jan 2024 00:00:30 GMT"),
+                None,
+                None,
+                now,
+                0
+            ),
+            Some(Duration::from_secs(30))
+        );
+
+        let reset = (now.timestamp() + 42).to_string();
+        assert_eq!(
+            retry_delay(403, None, Some("0"), Some(&reset), now, 0),
+            Some(Duration::from_secs(42))
+        );
+        assert_eq!(retry_delay(403, None, None, None, now, 0), None);
+        assert_eq!(
+            retry_delay(429, None, None, None, now, 2),
+            Some(Duration::from_secs(8))
+        );
+    }
+
+    #[test]
+    fn test_strip_archive_root() {
+        assert_eq!(
+            strip_archive_root(Path::new("book-main/src/intro.md")),
+            Some(PathBuf::from("src/intro.md"))
+        );
+        assert_eq!(strip_archive_root(Path::new("book-main/")), None);
+        assert_eq!(strip_archive_root(Path::new("book-main/../evil")), None);
+        assert_eq!(strip_archive_root(Path::new("/etc/passwd")), None);
+    }
+
+    #[test]
+    fn test_unpack() {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let content = b"# Readme";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "repo-main/docs/README.md", &content[..])
+            .unwrap();
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        TarballFetcher::new()
+            .unpack(archive.as_slice(), temp_dir.path())
+            .unwrap();
+
+        let unpacked = std::fs::read_to_string(temp_dir.path().join("docs/README.md")).unwrap();
+        assert_eq!(unpacked, "# Readme");
+
+        // A requested shutdown stops unpacking and rate limit waits
+        let shutdown = GracefulShutdown::new_for_test();
+        shutdown.request_shutdown();
+        let fetcher = TarballFetcher::new().with_shutdown(&shutdown);
+        let temp_dir = TempDir::new().unwrap();
+        assert!(matches!(
+            fetcher.unpack(archive.as_slice(), temp_dir.path()),
+            Err(RepoDocsError::Cancelled)
+        ));
+        let start = Instant::now();
+        assert!(matches!(
+            fetcher.sleep(Duration::from_secs(60)),
+            Err(RepoDocsError::Cancelled)
+        ));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
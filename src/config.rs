@@ -1,30 +1,267 @@
 use crate::error::{RepoDocsError, Result};
+use crate::extractor::archive::ArchiveFormat;
+use crate::extractor::output_manager::{self, DEFAULT_NAME_TEMPLATE};
+use crate::scanner::DocumentFile;
 use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+/// Smallest `output.max_path_length` that still leaves room for a shortened path
+const MIN_PATH_LENGTH: usize = 64;
+
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct Config {
     pub filters: FilterConfig,
     pub output: OutputConfig,
     pub git: GitConfig,
+    #[serde(default)]
+    pub politeness: PolitenessConfig,
+    #[serde(default)]
+    pub transform: TransformConfig,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct FilterConfig {
     pub extensions: Vec<String>,
     pub max_file_size: u64,
+    /// Files smaller than this many bytes are skipped
+    #[serde(default)]
+    pub min_file_size: u64,
+    /// Skip files that are empty or contain only whitespace
+    #[serde(default)]
+    pub skip_empty: bool,
     pub exclude_dirs: Vec<String>,
     pub exclude_patterns: Vec<String>,
     pub max_depth: usize,
+    /// Hidden (dot) directories to scan and hidden files to treat as documentation
+    #[serde(default = "default_hidden_allowlist")]
+    pub hidden_allowlist: Vec<String>,
+    /// Directories to scan, relative to the repository root; empty scans the whole tree
+    #[serde(default)]
+    pub scan_roots: Vec<String>,
+    /// Skip files ignored by the repository's `.gitignore` files
+    #[serde(default)]
+    pub respect_gitignore: bool,
+    /// Glob patterns (e.g. `docs/**/*.md`) a file's path must match to be
+    /// extracted; empty includes everything
+    #[serde(default)]
+    pub include_patterns: Vec<String>,
+    /// Glob patterns (e.g. `**/CHANGELOG*.md`) of files to leave out,
+    /// matched against the file's path
+    #[serde(default)]
+    pub exclude_globs: Vec<String>,
+    /// Inspect extensionless files not known by name and keep those that
+    /// read as Markdown or reStructuredText
+    #[serde(default)]
+    pub sniff_content: bool,
+    /// Extract Jupyter notebooks, copied or rendered to Markdown; unset
+    /// leaves them out
+    #[serde(default)]
+    pub notebook_mode: Option<NotebookMode>,
+    /// Treat OpenAPI/Swagger descriptions and JSON Schemas as documentation
+    /// even though YAML and JSON are not documentation extensions
+    #[serde(default)]
+    pub include_api_specs: bool,
+    /// Scan vendored and third-party directories (`vendor/`, `third_party/`,
+    /// `extern/`, bundled copies of other repositories), which are skipped
+    /// by default
+    #[serde(default)]
+    pub include_vendored: bool,
+    /// Extract only this many documents, the most relevant by their
+    /// relevance score; unset extracts every document
+    #[serde(default)]
+    pub top_n: Option<usize>,
+}
+
+fn default_hidden_allowlist() -> Vec<String> {
+    [
+        ".github",
+        ".devcontainer",
+        ".vscode",
+        ".gitlab",
+        ".env.example",
+        ".editorconfig",
+    ]
+    .iter()
+    .map(|name| name.to_string())
+    .collect()
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct OutputConfig {
     pub preserve_structure: bool,
+    /// Rewrite relative links of extracted Markdown for the output layout;
+    /// unset rewrites them when the structure is not preserved
+    #[serde(default)]
+    pub rewrite_links: Option<bool>,
+    /// Point every extracted Markdown document at the file it was extracted
+    /// from, at the extracted commit
+    #[serde(default)]
+    pub source_banner: Option<SourceBanner>,
+    /// Copy images and other assets referenced from Markdown documents
+    #[serde(default)]
+    pub with_assets: bool,
+    /// Largest asset copied with `with_assets`, in bytes
+    #[serde(default = "default_max_asset_size")]
+    pub max_asset_size: u64,
     pub create_index: bool,
+    /// Split the index into pages of at most this many files, giving
+    /// directories that do not fit in `_index.md` pages of their own
+    #[serde(default)]
+    pub index_page_size: Option<usize>,
     pub generate_report: bool,
     pub base_directory: PathBuf,
+    /// Name of the output directory, with `{owner}`, `{repo}`, `{branch}`
+    /// and `{date}` replaced for each repository
+    #[serde(default = "default_name_template")]
+    pub name_template: String,
+    #[serde(default)]
+    pub generate_graph: bool,
+    #[serde(default)]
+    pub similarity_threshold: Option<f64>,
+    #[serde(default)]
+    pub generate_glossary: bool,
+    #[serde(default)]
+    pub generate_outline: bool,
+    #[serde(default)]
+    pub max_path_length: Option<usize>,
+    #[serde(default)]
+    pub unicode_normalization: UnicodeNormalization,
+    #[serde(default)]
+    pub parallel_extraction: bool,
+    #[serde(default)]
+    pub ascii_filenames: bool,
+    #[serde(default)]
+    pub incremental: bool,
+    /// Write into an existing output directory, replacing only outputs that
+    /// changed and keeping files added or edited by hand
+    #[serde(default)]
+    pub merge: bool,
+    /// Replace an existing output directory once the new extraction is
+    /// complete instead of refusing to run
+    #[serde(default)]
+    pub force: bool,
+    /// Write WHATS_NEW.md summarizing what changed since the previous
+    /// incremental extraction
+    #[serde(default)]
+    pub whats_new: bool,
+    #[serde(default)]
+    pub readme_promotion: ReadmePromotion,
+    #[serde(default)]
+    pub sort_order: SortOrder,
+    #[serde(default)]
+    pub dedupe: DedupeMode,
+    #[serde(default)]
+    pub source_docs: bool,
+    #[serde(default)]
+    pub status_file: bool,
+    #[serde(default)]
+    pub keep_partial: bool,
+    /// Record the OS, CPU count, filesystem type and free disk space in
+    /// the extraction report
+    #[serde(default)]
+    pub capture_env: bool,
+    /// Print the files, words and estimated tokens under each directory
+    /// after scanning
+    #[serde(default)]
+    pub print_budget: bool,
+    /// Record the first heading and paragraph of each file, up to this many
+    /// characters, in the JSON report
+    #[serde(default)]
+    pub report_snippets: Option<usize>,
+    /// Time limit in seconds for the whole run, across clone, scan and
+    /// extraction
+    #[serde(default)]
+    pub max_runtime: Option<u64>,
+    /// Warn when cloning or extraction makes no progress for this many
+    /// seconds
+    #[serde(default)]
+    pub stall_timeout: Option<u64>,
+    /// Fail the run with a stall error instead of only warning
+    #[serde(default)]
+    pub abort_on_stall: bool,
+    /// Also concatenate all documents into this Markdown file; relative
+    /// paths are inside the output directory
+    #[serde(default)]
+    pub bundle: Option<PathBuf>,
+    /// Write the documents as plain text for a language model's context
+    #[serde(default)]
+    pub llm_pack: bool,
+    /// Estimated token limit of the LLM pack; documents are shortened or
+    /// left out to fit
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+    /// Split the LLM pack into files of at most this many tokens
+    #[serde(default)]
+    pub llm_chunk_tokens: Option<usize>,
+    /// Also render the extracted documents to this format
+    #[serde(default)]
+    pub render: Option<RenderFormat>,
+    /// Command converting the Markdown source to PDF for `render = "pdf"`,
+    /// with `{input}` and `{output}` placeholders; pandoc by default
+    #[serde(default)]
+    pub pdf_converter: Option<String>,
+    /// Also lay out the extracted documents for this site generator
+    #[serde(default)]
+    pub export: Option<ExportFormat>,
+    /// Also write the output directory to this `.zip` or `.tar.gz` archive;
+    /// `{repo}` is replaced by the repository name
+    #[serde(default)]
+    pub archive: Option<PathBuf>,
+    /// Remove the output directory once it is archived
+    #[serde(default)]
+    pub archive_only: bool,
+    /// Look of the pages written with `render = "html"`
+    #[serde(default)]
+    pub html: HtmlConfig,
+}
+
+/// Look of rendered HTML pages (`[output.html]`)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct HtmlConfig {
+    #[serde(default)]
+    pub theme: HtmlTheme,
+    /// Stylesheet copied next to the pages and linked after the built-in
+    /// one, so its rules take precedence
+    #[serde(default)]
+    pub custom_css: Option<PathBuf>,
+    /// Add a search box backed by an index embedded in the site
+    #[serde(default = "default_true")]
+    pub search: bool,
+    /// URL the pages are published at, for `sitemap.xml` and `robots.txt`
+    #[serde(default)]
+    pub site_url: Option<String>,
+    /// Paths of the site crawlers are asked to skip in `robots.txt`
+    #[serde(default)]
+    pub robots_disallow: Vec<String>,
+}
+
+impl Default for HtmlConfig {
+    fn default() -> Self {
+        Self {
+            theme: HtmlTheme::Light,
+            custom_css: None,
+            search: true,
+            site_url: None,
+            robots_disallow: Vec::new(),
+        }
+    }
+}
+
+/// Color scheme of rendered HTML pages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum HtmlTheme {
+    /// Dark text on a light background
+    #[default]
+    Light,
+    /// Light text on a dark background
+    Dark,
+    /// Follow the reader's system preference
+    Auto,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -32,6 +269,358 @@ pub struct GitConfig {
     pub clone_depth: Option<u32>,
     pub timeout: u64,
     pub branch: Option<String>,
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    #[serde(default)]
+    pub submodules: bool,
+    /// Replace Git LFS pointer files with their objects after fetching
+    #[serde(default)]
+    pub lfs: bool,
+    #[serde(default = "default_true")]
+    pub count_commits: bool,
+    #[serde(default = "default_true")]
+    pub preflight: bool,
+    #[serde(default)]
+    pub sparse_paths: Vec<String>,
+    #[serde(default)]
+    pub fetch_mode: FetchMode,
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    #[serde(default = "default_max_retry_wait")]
+    pub max_retry_wait: u64,
+    /// Keep bare mirrors of cloned repositories and fetch into them on later runs
+    #[serde(default)]
+    pub cache: bool,
+    /// Mirror location; defaults to `~/.cache/repodocs`
+    #[serde(default)]
+    pub cache_dir: Option<PathBuf>,
+}
+
+/// Limits on clones and downloads from one host (`[politeness]`)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PolitenessConfig {
+    /// Most clones or downloads from one host at once; unlimited by default
+    #[serde(default)]
+    pub max_clones_per_host: Option<usize>,
+    /// Seconds between the starts of clones from one host
+    #[serde(default)]
+    pub clone_delay: u64,
+    /// Longest delay in seconds that rate limit responses raise
+    /// `clone_delay` to
+    #[serde(default = "default_max_backoff")]
+    pub max_backoff: u64,
+}
+
+fn default_max_backoff() -> u64 {
+    300
+}
+
+impl Default for PolitenessConfig {
+    fn default() -> Self {
+        Self {
+            max_clones_per_host: None,
+            clone_delay: 0,
+            max_backoff: default_max_backoff(),
+        }
+    }
+}
+
+/// Normalization of extracted Markdown files (`[transform]`); every step
+/// is off by default
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TransformConfig {
+    /// Renumber headings so documents start at level 1 and never skip a level
+    #[serde(default)]
+    pub normalize_headings: bool,
+    /// Convert CRLF and CR line endings to LF
+    #[serde(default)]
+    pub normalize_line_endings: bool,
+    /// Replace tabs outside code blocks with spaces
+    #[serde(default)]
+    pub expand_tabs: bool,
+    /// Columns between tab stops for `expand_tabs`
+    #[serde(default = "default_tab_width")]
+    pub tab_width: usize,
+    /// Remove status badges (shields.io and similar images, linked or not)
+    #[serde(default)]
+    pub strip_badges: bool,
+    /// Remove all images, badges included
+    #[serde(default)]
+    pub strip_images: bool,
+    /// Remove `<!-- ... -->` comments
+    #[serde(default)]
+    pub strip_html_comments: bool,
+}
+
+fn default_tab_width() -> usize {
+    4
+}
+
+fn default_name_template() -> String {
+    DEFAULT_NAME_TEMPLATE.to_string()
+}
+
+fn default_max_asset_size() -> u64 {
+    5 * 1024 * 1024 // 5MB
+}
+
+impl Default for TransformConfig {
+    fn default() -> Self {
+        Self {
+            normalize_headings: false,
+            normalize_line_endings: false,
+            expand_tabs: false,
+            tab_width: default_tab_width(),
+            strip_badges: false,
+            strip_images: false,
+            strip_html_comments: false,
+        }
+    }
+}
+
+impl TransformConfig {
+    /// Whether any step is turned on, so Markdown files are rewritten
+    /// instead of copied
+    pub fn is_enabled(&self) -> bool {
+        self.normalize_headings
+            || self.normalize_line_endings
+            || self.expand_tabs
+            || self.strip_badges
+            || self.strip_images
+            || self.strip_html_comments
+    }
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_max_retry_wait() -> u64 {
+    60
+}
+
+/// How the repository contents are acquired
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum FetchMode {
+    /// Clone with git (libgit2)
+    #[default]
+    Git,
+    /// Download a `.tar.gz` snapshot over HTTPS (GitHub and GitLab only)
+    Tarball,
+}
+
+/// Unicode normalization form applied to output paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum UnicodeNormalization {
+    /// Composed form, as used by Windows and Linux tools
+    #[default]
+    Nfc,
+    /// Decomposed form, as historically produced by macOS
+    Nfd,
+    /// Keep paths exactly as they are in the repository
+    None,
+}
+
+/// How README files of subdirectories are named when the directory
+/// structure is not preserved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadmePromotion {
+    /// Keep `README.md`; colliding names get a `~N` suffix
+    #[default]
+    None,
+    /// Name the file after its directory: `docs/foo/README.md` becomes `foo.md`
+    Rename,
+    /// Keep the file in a folder named after its directory: `foo/README.md`
+    Directory,
+}
+
+/// How extracted Markdown points back at its source (`output.source_banner`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceBanner {
+    /// A note at the top of the page linking to the canonical source
+    Banner,
+    /// `source_url` and `source_commit` keys in the page's front matter
+    FrontMatter,
+}
+
+/// How Jupyter notebooks are extracted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum NotebookMode {
+    /// Copy the `.ipynb` file verbatim
+    Copy,
+    /// Write the notebook's markdown cells to a `.md` file
+    Markdown,
+}
+
+/// Formats extracted documents can be rendered to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderFormat {
+    /// Static HTML pages in `html/` with an `index.html` navigation page
+    Html,
+    /// One `documentation.pdf` with a chapter per file, through an external
+    /// converter
+    Pdf,
+}
+
+/// Static site generators the extracted documents can be laid out for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// An mdBook source tree in `mdbook/`: `book.toml` and `src/SUMMARY.md`
+    Mdbook,
+    /// A MkDocs project in `mkdocs/`: `mkdocs.yml` with the navigation and `docs/`
+    Mkdocs,
+    /// Docusaurus docs in `docusaurus/`: `sidebars.js` and `docs/`
+    Docusaurus,
+}
+
+/// Order of file listings in reports and the index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Alphabetically by relative path
+    #[default]
+    Path,
+    /// Largest files first
+    Size,
+    /// Least recently modified files first
+    Modified,
+    /// Grouped by extension
+    Extension,
+    /// Most relevant documents first, by `FileInfo::score`
+    Relevance,
+}
+
+impl SortOrder {
+    /// Compare two documents; ties are broken by path so listings are stable
+    pub fn compare(&self, a: &DocumentFile, b: &DocumentFile) -> Ordering {
+        let primary = match self {
+            SortOrder::Path => Ordering::Equal,
+            SortOrder::Size => Reverse(a.size).cmp(&Reverse(b.size)),
+            SortOrder::Modified => a.modified.cmp(&b.modified),
+            SortOrder::Extension => a.extension.cmp(&b.extension),
+            SortOrder::Relevance => b.score.total_cmp(&a.score),
+        };
+        primary.then_with(|| a.relative_path.cmp(&b.relative_path))
+    }
+}
+
+/// What happens to byte-identical documents, such as a LICENSE mirrored
+/// into every package
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DedupeMode {
+    /// Write every copy
+    #[default]
+    Keep,
+    /// Write the first copy and hard-link the others to it
+    Hardlink,
+    /// Write only the first copy
+    Skip,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Named bundles of clone options, applied before individual overrides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ClonePreset {
+    /// Tarball of the latest commit, no submodules, no LFS objects, no
+    /// commit count
+    Fast,
+    /// Git clone with full history, without submodules or LFS objects (the
+    /// default behavior)
+    Full,
+    /// Git clone with full history, submodules, and LFS objects
+    Archival,
+}
+
+impl ClonePreset {
+    pub fn apply(&self, git: &mut GitConfig) {
+        match self {
+            ClonePreset::Fast => {
+                git.fetch_mode = FetchMode::Tarball;
+                git.clone_depth = Some(1);
+                git.submodules = false;
+                git.lfs = false;
+                git.count_commits = false;
+            }
+            ClonePreset::Full => {
+                git.fetch_mode = FetchMode::Git;
+                git.clone_depth = None;
+                git.submodules = false;
+                git.lfs = false;
+                git.count_commits = true;
+            }
+            ClonePreset::Archival => {
+                git.fetch_mode = FetchMode::Git;
+                git.clone_depth = None;
+                git.submodules = true;
+                git.lfs = true;
+                git.count_commits = true;
+            }
+        }
+    }
+}
+
+/// Named extraction profiles for common use cases, applied before
+/// individual overrides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtractionPreset {
+    /// Plain-text corpus for language models: prose formats only, ASCII
+    /// paths, near-duplicates flagged, heading outline exported
+    Llm,
+    /// Source for a publishable site: index, link graph, outline and
+    /// glossary, with ASCII paths
+    Site,
+    /// Compliance audit: every format, full history with submodules, LFS
+    /// objects and commit counts, link graph and near-duplicate analysis
+    Audit,
+}
+
+impl ExtractionPreset {
+    pub fn apply(&self, config: &mut Config) {
+        let output = &mut config.output;
+        match self {
+            ExtractionPreset::Llm => {
+                config.filters.extensions = ["md", "markdown", "mdx", "rst", "adoc", "txt"]
+                    .iter()
+                    .map(|ext| ext.to_string())
+                    .collect();
+                config.filters.max_file_size = 1024 * 1024; // 1MB
+                output.preserve_structure = true;
+                output.create_index = true;
+                output.ascii_filenames = true;
+                output.generate_outline = true;
+                output.similarity_threshold = Some(0.9);
+            }
+            ExtractionPreset::Site => {
+                output.preserve_structure = true;
+                output.create_index = true;
+                output.ascii_filenames = true;
+                output.generate_graph = true;
+                output.generate_outline = true;
+                output.generate_glossary = true;
+            }
+            ExtractionPreset::Audit => {
+                config.filters.extensions = FilterConfig::default().extensions;
+                output.preserve_structure = true;
+                output.generate_report = true;
+                output.generate_graph = true;
+                output.similarity_threshold = Some(0.8);
+                ClonePreset::Archival.apply(&mut config.git);
+            }
+        }
+    }
 }
 
 impl Default for FilterConfig {
@@ -54,13 +643,14 @@ impl Default for FilterConfig {
                 "latex".to_string(),
             ],
             max_file_size: 10 * 1024 * 1024, // 10MB
+            min_file_size: 0,
+            skip_empty: false,
             exclude_dirs: vec![
                 "node_modules".to_string(),
                 ".git".to_string(),
                 "target".to_string(),
                 "build".to_string(),
                 "dist".to_string(),
-                "vendor".to_string(),
                 ".vscode".to_string(),
                 ".idea".to_string(),
             ],
@@ -71,6 +661,16 @@ impl Default for FilterConfig {
                 r"yarn\.lock".to_string(),
             ],
             max_depth: 10,
+            hidden_allowlist: default_hidden_allowlist(),
+            scan_roots: Vec::new(), // Scan the whole repository
+            respect_gitignore: false,
+            include_patterns: Vec::new(), // No path restriction
+            exclude_globs: Vec::new(),
+            sniff_content: false,
+            notebook_mode: None, // Notebooks are not documentation by default
+            include_api_specs: false,
+            include_vendored: false,
+            top_n: None, // Every document
         }
     }
 }
@@ -79,19 +679,77 @@ impl Default for OutputConfig {
     fn default() -> Self {
         Self {
             preserve_structure: true,
+            rewrite_links: None,
+            source_banner: None,
+            with_assets: false,
+            max_asset_size: default_max_asset_size(),
             create_index: true,
+            index_page_size: None, // A single `_index.md`
             generate_report: true,
             base_directory: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            name_template: default_name_template(),
+            generate_graph: false,
+            similarity_threshold: None, // Near-duplicate detection disabled
+            generate_glossary: false,
+            generate_outline: false,
+            max_path_length: None, // Over-long paths are an error
+            unicode_normalization: UnicodeNormalization::Nfc,
+            parallel_extraction: false,
+            ascii_filenames: false,
+            incremental: false, // Each run starts from an empty directory
+            merge: false,
+            force: false,
+            whats_new: false,
+            readme_promotion: ReadmePromotion::None,
+            sort_order: SortOrder::Path,
+            dedupe: DedupeMode::Keep,
+            source_docs: false,
+            status_file: false,
+            keep_partial: false,
+            capture_env: false,
+            print_budget: false,
+            report_snippets: None,
+            max_runtime: None,
+            stall_timeout: None,
+            abort_on_stall: false,
+            bundle: None,
+            llm_pack: false,
+            max_tokens: None,
+            llm_chunk_tokens: None,
+            render: None,
+            pdf_converter: None,
+            export: None,
+            archive: None,
+            archive_only: false,
+            html: HtmlConfig::default(),
         }
     }
 }
 
+impl OutputConfig {
+    /// Whether relative links of extracted Markdown are rewritten
+    pub fn rewrites_links(&self) -> bool {
+        self.rewrite_links.unwrap_or(!self.preserve_structure)
+    }
+}
+
 impl Default for GitConfig {
     fn default() -> Self {
         Self {
             clone_depth: None, // Full clone by default
             timeout: 300,      // 5 minutes
             branch: None,      // Default branch
+            allowed_hosts: Vec::new(),
+            submodules: false,
+            lfs: false,
+            count_commits: true,
+            preflight: true,
+            sparse_paths: Vec::new(),
+            fetch_mode: FetchMode::Git,
+            max_retries: default_max_retries(),
+            max_retry_wait: default_max_retry_wait(), // seconds
+            cache: false,
+            cache_dir: None,
         }
     }
 }
@@ -122,25 +780,37 @@ impl Config {
     }
 
     pub fn load_with_defaults<P: AsRef<Path>>(config_path: Option<P>) -> Result<Self> {
-        match config_path {
+        match Self::find_config_file(config_path) {
             Some(path) => Self::load_from_file(path),
-            None => {
-                // Try to load from default locations
-                let default_paths = ["repodocs.toml", "repodocs.config.toml", ".repodocs.toml"];
-
-                for default_path in &default_paths {
-                    if Path::new(default_path).exists() {
-                        return Self::load_from_file(default_path);
-                    }
-                }
+            // If no config file found, use defaults
+            None => Ok(Self::default()),
+        }
+    }
 
-                // If no config file found, use defaults
-                Ok(Self::default())
-            }
+    /// The configuration file a run reads: `config_path` if given, else the
+    /// first of the default locations that exists
+    pub fn find_config_file<P: AsRef<Path>>(config_path: Option<P>) -> Option<PathBuf> {
+        if let Some(path) = config_path {
+            return Some(path.as_ref().to_path_buf());
         }
+
+        let default_paths = ["repodocs.toml", "repodocs.config.toml", ".repodocs.toml"];
+        default_paths
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.exists())
     }
 
     pub fn merge_with_cli_args(&mut self, cli_args: &CliOverrides) {
+        // Presets go first so explicit options can still override them
+        if let Some(preset) = cli_args.preset {
+            preset.apply(self);
+        }
+
+        if let Some(preset) = cli_args.clone_preset {
+            preset.apply(&mut self.git);
+        }
+
         if let Some(ref formats) = cli_args.formats {
             self.filters.extensions = formats
                 .split(',')
@@ -153,25 +823,274 @@ impl Config {
             self.filters.exclude_dirs.extend(exclude.clone());
         }
 
+        if let Some(ref include_hidden) = cli_args.include_hidden {
+            self.filters.hidden_allowlist.extend(include_hidden.clone());
+        }
+
+        if let Some(ref include) = cli_args.include_patterns {
+            self.filters.include_patterns.extend(include.clone());
+        }
+
+        if let Some(ref exclude_globs) = cli_args.exclude_globs {
+            self.filters.exclude_globs.extend(exclude_globs.clone());
+        }
+
+        if let Some(include_api_specs) = cli_args.include_api_specs {
+            self.filters.include_api_specs = include_api_specs;
+        }
+
+        if let Some(include_vendored) = cli_args.include_vendored {
+            self.filters.include_vendored = include_vendored;
+        }
+
+        if let Some(top_n) = cli_args.top_n {
+            self.filters.top_n = Some(top_n);
+        }
+
+        if let Some(notebook_mode) = cli_args.notebook_mode {
+            self.filters.notebook_mode = Some(notebook_mode);
+        }
+
+        if let Some(sniff_content) = cli_args.sniff_content {
+            self.filters.sniff_content = sniff_content;
+        }
+
+        if let Some(respect_gitignore) = cli_args.respect_gitignore {
+            self.filters.respect_gitignore = respect_gitignore;
+        }
+
+        if let Some(ref scan_roots) = cli_args.scan_roots {
+            self.filters.scan_roots = scan_roots.clone();
+        }
+
         if let Some(max_size) = cli_args.max_file_size {
             self.filters.max_file_size = max_size;
         }
 
+        if let Some(skip_empty) = cli_args.skip_empty {
+            self.filters.skip_empty = skip_empty;
+        }
+
         if let Some(ref output_dir) = cli_args.output_dir {
             self.output.base_directory = output_dir.clone();
         }
 
+        if let Some(ref name_template) = cli_args.name_template {
+            self.output.name_template = name_template.clone();
+        }
+
         if let Some(preserve_structure) = cli_args.preserve_structure {
             self.output.preserve_structure = preserve_structure;
         }
 
+        if let Some(rewrite_links) = cli_args.rewrite_links {
+            self.output.rewrite_links = Some(rewrite_links);
+        }
+
+        if let Some(source_banner) = cli_args.source_banner {
+            self.output.source_banner = Some(source_banner);
+        }
+
+        if let Some(with_assets) = cli_args.with_assets {
+            self.output.with_assets = with_assets;
+        }
+
+        if let Some(max_asset_size) = cli_args.max_asset_size {
+            self.output.max_asset_size = max_asset_size;
+        }
+
+        if let Some(generate_graph) = cli_args.generate_graph {
+            self.output.generate_graph = generate_graph;
+        }
+
+        if let Some(generate_glossary) = cli_args.generate_glossary {
+            self.output.generate_glossary = generate_glossary;
+        }
+
+        if let Some(generate_outline) = cli_args.generate_outline {
+            self.output.generate_outline = generate_outline;
+        }
+
+        if let Some(max_length) = cli_args.max_path_length {
+            self.output.max_path_length = Some(max_length);
+        }
+
+        if let Some(ascii_filenames) = cli_args.ascii_filenames {
+            self.output.ascii_filenames = ascii_filenames;
+        }
+
+        if let Some(incremental) = cli_args.incremental {
+            self.output.incremental = incremental;
+        }
+
+        if let Some(merge) = cli_args.merge {
+            self.output.merge = merge;
+        }
+
+        if let Some(force) = cli_args.force {
+            self.output.force = force;
+        }
+
+        if let Some(whats_new) = cli_args.whats_new {
+            self.output.whats_new = whats_new;
+        }
+
+        if let Some(parallel) = cli_args.parallel_extraction {
+            self.output.parallel_extraction = parallel;
+        }
+
+        if let Some(status_file) = cli_args.status_file {
+            self.output.status_file = status_file;
+        }
+
+        if let Some(keep_partial) = cli_args.keep_partial {
+            self.output.keep_partial = keep_partial;
+        }
+
+        if let Some(capture_env) = cli_args.capture_env {
+            self.output.capture_env = capture_env;
+        }
+
+        if let Some(print_budget) = cli_args.print_budget {
+            self.output.print_budget = print_budget;
+        }
+
+        if let Some(report_snippets) = cli_args.report_snippets {
+            self.output.report_snippets = Some(report_snippets);
+        }
+
+        if let Some(max_runtime) = cli_args.max_runtime {
+            self.output.max_runtime = Some(max_runtime);
+        }
+
+        if let Some(stall_timeout) = cli_args.stall_timeout {
+            self.output.stall_timeout = Some(stall_timeout);
+        }
+
+        if let Some(abort_on_stall) = cli_args.abort_on_stall {
+            self.output.abort_on_stall = abort_on_stall;
+        }
+
+        if let Some(ref bundle) = cli_args.bundle {
+            self.output.bundle = Some(bundle.clone());
+        }
+
+        if let Some(llm_pack) = cli_args.llm_pack {
+            self.output.llm_pack = llm_pack;
+        }
+
+        if let Some(max_tokens) = cli_args.max_tokens {
+            self.output.max_tokens = Some(max_tokens);
+        }
+
+        if let Some(chunk_tokens) = cli_args.llm_chunk_tokens {
+            self.output.llm_chunk_tokens = Some(chunk_tokens);
+        }
+
+        if let Some(render) = cli_args.render {
+            self.output.render = Some(render);
+        }
+
+        if let Some(ref converter) = cli_args.pdf_converter {
+            self.output.pdf_converter = Some(converter.clone());
+        }
+
+        if let Some(theme) = cli_args.html_theme {
+            self.output.html.theme = theme;
+        }
+
+        if let Some(ref custom_css) = cli_args.custom_css {
+            self.output.html.custom_css = Some(custom_css.clone());
+        }
+
+        if let Some(search) = cli_args.html_search {
+            self.output.html.search = search;
+        }
+
+        if let Some(ref site_url) = cli_args.site_url {
+            self.output.html.site_url = Some(site_url.clone());
+        }
+
+        if let Some(ref robots_disallow) = cli_args.robots_disallow {
+            self.output.html.robots_disallow = robots_disallow.clone();
+        }
+
+        if let Some(export) = cli_args.export {
+            self.output.export = Some(export);
+        }
+
+        if let Some(ref archive) = cli_args.archive {
+            self.output.archive = Some(archive.clone());
+        }
+
+        if let Some(archive_only) = cli_args.archive_only {
+            self.output.archive_only = archive_only;
+        }
+
+        if let Some(source_docs) = cli_args.source_docs {
+            self.output.source_docs = source_docs;
+        }
+
+        if let Some(sort_order) = cli_args.sort_order {
+            self.output.sort_order = sort_order;
+        }
+
+        if let Some(index_page_size) = cli_args.index_page_size {
+            self.output.index_page_size = Some(index_page_size);
+        }
+
+        if let Some(dedupe) = cli_args.dedupe {
+            self.output.dedupe = dedupe;
+        }
+
+        if let Some(promotion) = cli_args.readme_promotion {
+            self.output.readme_promotion = promotion;
+        }
+
+        if let Some(normalization) = cli_args.unicode_normalization {
+            self.output.unicode_normalization = normalization;
+        }
+
+        if let Some(threshold) = cli_args.similarity_threshold {
+            self.output.similarity_threshold = Some(threshold);
+        }
+
         if let Some(timeout) = cli_args.timeout {
             self.git.timeout = timeout;
         }
 
+        if let Some(depth) = cli_args.clone_depth {
+            self.git.clone_depth = Some(depth);
+        }
+
         if let Some(ref branch) = cli_args.branch {
             self.git.branch = Some(branch.clone());
         }
+
+        if let Some(ref sparse_paths) = cli_args.sparse_paths {
+            self.git.sparse_paths = sparse_paths.clone();
+        }
+
+        if let Some(cache) = cli_args.cache {
+            self.git.cache = cache;
+        }
+
+        if let Some(ref cache_dir) = cli_args.cache_dir {
+            self.git.cache = true;
+            self.git.cache_dir = Some(cache_dir.clone());
+        }
+
+        if let Some(fetch_mode) = cli_args.fetch_mode {
+            self.git.fetch_mode = fetch_mode;
+        }
+
+        if let Some(preflight) = cli_args.preflight {
+            self.git.preflight = preflight;
+        }
+
+        if let Some(ref allowed_hosts) = cli_args.allowed_hosts {
+            self.git.allowed_hosts.extend(allowed_hosts.clone());
+        }
     }
 
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
@@ -202,51 +1121,450 @@ impl Config {
             });
         }
 
-        // Validate timeout
-        if self.git.timeout == 0 {
+        if self.filters.min_file_size > self.filters.max_file_size {
             return Err(RepoDocsError::Config {
-                message: "Git timeout must be greater than 0".to_string(),
+                message: format!(
+                    "Minimum file size ({} bytes) exceeds the maximum file size ({} bytes)",
+                    self.filters.min_file_size, self.filters.max_file_size
+                ),
             });
         }
 
-        // Validate max depth
-        if self.filters.max_depth == 0 {
+        if self.output.max_runtime == Some(0) {
             return Err(RepoDocsError::Config {
-                message: "Maximum directory depth must be greater than 0".to_string(),
+                message: "Maximum runtime must be greater than 0".to_string(),
             });
         }
 
-        // Validate output directory
-        if let Some(parent) = self.output.base_directory.parent() {
-            if !parent.exists() {
+        if self.output.max_tokens == Some(0) || self.output.llm_chunk_tokens == Some(0) {
+            return Err(RepoDocsError::Config {
+                message: "LLM pack token limits must be greater than 0".to_string(),
+            });
+        }
+
+        output_manager::validate_name_template(&self.output.name_template)?;
+
+        if let Some(site_url) = &self.output.html.site_url {
+            let valid =
+                url::Url::parse(site_url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+            if !valid {
                 return Err(RepoDocsError::Config {
-                    message: format!("Parent directory does not exist: {}", parent.display()),
+                    message: format!(
+                        "output.html.site_url must be an http or https URL, got '{}'",
+                        site_url
+                    ),
                 });
             }
         }
+        if let Some(path) = self
+            .output
+            .html
+            .robots_disallow
+            .iter()
+            .find(|path| !path.starts_with('/'))
+        {
+            return Err(RepoDocsError::Config {
+                message: format!(
+                    "output.html.robots_disallow paths must start with '/', got '{}'",
+                    path
+                ),
+            });
+        }
 
-        Ok(())
-    }
-
-    pub fn git_timeout_duration(&self) -> Duration {
-        Duration::from_secs(self.git.timeout)
-    }
+        if let Some(archive) = &self.output.archive {
+            if ArchiveFormat::from_path(archive).is_none() {
+                return Err(RepoDocsError::Config {
+                    message: format!(
+                        "Unsupported archive format: {} (use .zip, .tar.gz or .tgz)",
+                        archive.display()
+                    ),
+                });
+            }
+        }
 
-    pub fn create_sample_config() -> String {
-        let sample_config = Self::default();
-        toml::to_string_pretty(&sample_config).unwrap_or_else(|_| String::new())
-    }
-}
+        if self.output.archive_only && self.output.archive.is_none() {
+            return Err(RepoDocsError::Config {
+                message: "archive_only requires an archive path".to_string(),
+            });
+        }
 
-#[derive(Debug, Default)]
+        if self.output.archive_only && (self.output.incremental || self.output.merge) {
+            return Err(RepoDocsError::Config {
+                message: "archive_only cannot be combined with incremental updates or merges, which need the output directory".to_string(),
+            });
+        }
+
+        if self.output.merge && self.output.incremental {
+            return Err(RepoDocsError::Config {
+                message: "merge cannot be combined with incremental updates, which remove outputs of deleted documents".to_string(),
+            });
+        }
+
+        if self.filters.top_n == Some(0) {
+            return Err(RepoDocsError::Config {
+                message: "filters.top_n must be greater than 0".to_string(),
+            });
+        }
+
+        if self.output.index_page_size == Some(0) {
+            return Err(RepoDocsError::Config {
+                message: "output.index_page_size must be greater than 0".to_string(),
+            });
+        }
+
+        if self.output.report_snippets == Some(0) {
+            return Err(RepoDocsError::Config {
+                message: "output.report_snippets must be greater than 0".to_string(),
+            });
+        }
+
+        if self.output.stall_timeout == Some(0) {
+            return Err(RepoDocsError::Config {
+                message: "Stall timeout must be greater than 0".to_string(),
+            });
+        }
+
+        if self.output.with_assets && self.output.max_asset_size == 0 {
+            return Err(RepoDocsError::Config {
+                message: "output.max_asset_size must be greater than 0".to_string(),
+            });
+        }
+
+        if self.transform.expand_tabs && self.transform.tab_width == 0 {
+            return Err(RepoDocsError::Config {
+                message: "transform.tab_width must be greater than 0".to_string(),
+            });
+        }
+
+        if self.politeness.max_clones_per_host == Some(0) {
+            return Err(RepoDocsError::Config {
+                message: "politeness.max_clones_per_host must be greater than 0".to_string(),
+            });
+        }
+
+        // Validate timeout
+        if self.git.timeout == 0 {
+            return Err(RepoDocsError::Config {
+                message: "Git timeout must be greater than 0".to_string(),
+            });
+        }
+
+        // Validate clone depth
+        if self.git.clone_depth == Some(0) {
+            return Err(RepoDocsError::Config {
+                message: "Clone depth must be greater than 0".to_string(),
+            });
+        }
+
+        for pattern in &self.filters.include_patterns {
+            if let Err(e) = globset::Glob::new(pattern) {
+                return Err(RepoDocsError::Config {
+                    message: format!("Invalid include pattern '{}': {}", pattern, e),
+                });
+            }
+        }
+
+        for pattern in &self.filters.exclude_globs {
+            if let Err(e) = globset::Glob::new(pattern) {
+                return Err(RepoDocsError::Config {
+                    message: format!("Invalid exclude glob '{}': {}", pattern, e),
+                });
+            }
+        }
+
+        // Validate scan roots (relative, no parent traversal)
+        for root in &self.filters.scan_roots {
+            let invalid = root.trim().is_empty()
+                || Path::new(root).is_absolute()
+                || root.split(['/', '\\']).any(|part| part == "..");
+            if invalid {
+                return Err(RepoDocsError::Config {
+                    message: format!(
+                        "Invalid scan root '{}': use a directory relative to the repository root",
+                        root
+                    ),
+                });
+            }
+        }
+
+        // Validate sparse checkout paths (relative, no parent traversal)
+        for path in &self.git.sparse_paths {
+            let invalid = path.trim().is_empty()
+                || Path::new(path).is_absolute()
+                || path.split(['/', '\\']).any(|part| part == "..");
+            if invalid {
+                return Err(RepoDocsError::Config {
+                    message: format!(
+                        "Invalid sparse path '{}': use a path relative to the repository root",
+                        path
+                    ),
+                });
+            }
+        }
+
+        // Validate max depth
+        if self.filters.max_depth == 0 {
+            return Err(RepoDocsError::Config {
+                message: "Maximum directory depth must be greater than 0".to_string(),
+            });
+        }
+
+        // Validate similarity threshold
+        if let Some(threshold) = self.output.similarity_threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(RepoDocsError::Config {
+                    message: "Similarity threshold must be between 0.0 and 1.0".to_string(),
+                });
+            }
+        }
+
+        // Validate path length limit (must leave room for a shortened path)
+        if let Some(max_length) = self.output.max_path_length {
+            if max_length < MIN_PATH_LENGTH {
+                return Err(RepoDocsError::Config {
+                    message: format!(
+                        "Maximum path length must be at least {} characters",
+                        MIN_PATH_LENGTH
+                    ),
+                });
+            }
+        }
+
+        // Validate allowed hosts (bare hostnames only)
+        for host in &self.git.allowed_hosts {
+            let host = host.trim();
+            if host.is_empty() || host.contains(['/', '@', '*', ' ']) {
+                return Err(RepoDocsError::Config {
+                    message: format!(
+                        "Invalid allowed host '{}': use a bare hostname such as git.example.com",
+                        host
+                    ),
+                });
+            }
+        }
+
+        // Validate output directory
+        if let Some(parent) = self.output.base_directory.parent() {
+            if !parent.exists() {
+                return Err(RepoDocsError::Config {
+                    message: format!("Parent directory does not exist: {}", parent.display()),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn git_timeout_duration(&self) -> Duration {
+        Duration::from_secs(self.git.timeout)
+    }
+
+    /// The default configuration as TOML, every setting described
+    pub fn create_sample_config() -> String {
+        crate::sample_config::generate(&Self::default())
+    }
+}
+
+/// Where a setting of the effective configuration came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    File(PathBuf),
+    /// `--preset` or `--clone-preset`
+    Preset,
+    CommandLine,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::File(path) => write!(f, "{}", path.display()),
+            ConfigSource::Preset => write!(f, "preset"),
+            ConfigSource::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+/// The configuration a run would use, with the source of every setting
+/// (`--print-config`). Settings are merged from the defaults, the
+/// configuration file, presets and command-line options, later ones
+/// winning; a setting is attributed to the last of them that changed it.
+#[derive(Debug, Clone)]
+pub struct EffectiveConfig {
+    pub config: Config,
+    /// Sources by dotted key, e.g. `output.bundle`
+    pub sources: BTreeMap<String, ConfigSource>,
+}
+
+impl EffectiveConfig {
+    pub fn resolve(config_path: Option<&Path>, overrides: &CliOverrides) -> Result<Self> {
+        let defaults = Config::default();
+        let file = Config::find_config_file(config_path);
+        let from_file = match &file {
+            Some(path) => Config::load_from_file(path)?,
+            None => defaults.clone(),
+        };
+
+        let mut with_presets = from_file.clone();
+        with_presets.merge_with_cli_args(
+            &CliOverrides::new()
+                .with_preset(overrides.preset)
+                .with_clone_preset(overrides.clone_preset),
+        );
+
+        let mut config = from_file.clone();
+        config.merge_with_cli_args(overrides);
+        config.validate()?;
+
+        let layers = [
+            (ConfigSource::Default, &defaults),
+            (
+                file.map_or(ConfigSource::Default, ConfigSource::File),
+                &from_file,
+            ),
+            (ConfigSource::Preset, &with_presets),
+            (ConfigSource::CommandLine, &config),
+        ];
+        let mut sources = BTreeMap::new();
+        let mut previous = BTreeMap::new();
+        for (source, layer) in layers {
+            let values = flatten(layer)?;
+            for (key, value) in &values {
+                if previous.get(key) != Some(value) {
+                    sources.insert(key.clone(), source.clone());
+                }
+            }
+            previous = values;
+        }
+        sources.retain(|key, _| previous.contains_key(key));
+
+        Ok(Self { config, sources })
+    }
+
+    /// The configuration as TOML, each setting followed by a comment naming
+    /// its source
+    pub fn to_annotated_toml(&self) -> Result<String> {
+        let mut out = String::new();
+        let mut section = "";
+        let values = flatten(&self.config)?;
+        for (key, value) in &values {
+            let (table, name) = key.split_once('.').unwrap_or(("", key));
+            if table != section {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                out.push_str(&format!("[{}]\n", table));
+                section = table;
+            }
+            let source = self.sources.get(key).unwrap_or(&ConfigSource::Default);
+            out.push_str(&format!("{} = {}  # {}\n", name, value, source));
+        }
+        Ok(out)
+    }
+}
+
+/// Settings of `config` by dotted key
+pub(crate) fn flatten(config: &Config) -> Result<BTreeMap<String, toml::Value>> {
+    fn flatten_into(prefix: &str, value: &toml::Value, values: &mut BTreeMap<String, toml::Value>) {
+        match value {
+            toml::Value::Table(table) => {
+                for (key, value) in table {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    flatten_into(&key, value, values);
+                }
+            }
+            value => {
+                values.insert(prefix.to_string(), value.clone());
+            }
+        }
+    }
+
+    let value = toml::Value::try_from(config).map_err(|e| RepoDocsError::Config {
+        message: format!("Failed to serialize configuration: {}", e),
+    })?;
+    let mut values = BTreeMap::new();
+    flatten_into("", &value, &mut values);
+    Ok(values)
+}
+
+#[derive(Debug, Default)]
 pub struct CliOverrides {
     pub formats: Option<String>,
     pub exclude: Option<Vec<String>>,
+    pub include_hidden: Option<Vec<String>>,
+    pub scan_roots: Option<Vec<String>>,
+    pub respect_gitignore: Option<bool>,
+    pub include_patterns: Option<Vec<String>>,
+    pub exclude_globs: Option<Vec<String>>,
+    pub sniff_content: Option<bool>,
+    pub notebook_mode: Option<NotebookMode>,
+    pub include_api_specs: Option<bool>,
+    pub include_vendored: Option<bool>,
+    pub top_n: Option<usize>,
     pub max_file_size: Option<u64>,
+    pub skip_empty: Option<bool>,
     pub output_dir: Option<PathBuf>,
     pub preserve_structure: Option<bool>,
+    pub rewrite_links: Option<bool>,
+    pub source_banner: Option<SourceBanner>,
+    pub with_assets: Option<bool>,
+    pub max_asset_size: Option<u64>,
+    pub generate_graph: Option<bool>,
+    pub similarity_threshold: Option<f64>,
+    pub generate_glossary: Option<bool>,
+    pub generate_outline: Option<bool>,
+    pub max_path_length: Option<usize>,
+    pub unicode_normalization: Option<UnicodeNormalization>,
+    pub readme_promotion: Option<ReadmePromotion>,
+    pub sort_order: Option<SortOrder>,
+    pub index_page_size: Option<usize>,
+    pub name_template: Option<String>,
+    pub dedupe: Option<DedupeMode>,
+    pub source_docs: Option<bool>,
+    pub bundle: Option<PathBuf>,
+    pub llm_pack: Option<bool>,
+    pub max_tokens: Option<usize>,
+    pub llm_chunk_tokens: Option<usize>,
+    pub render: Option<RenderFormat>,
+    pub pdf_converter: Option<String>,
+    pub html_theme: Option<HtmlTheme>,
+    pub custom_css: Option<PathBuf>,
+    pub html_search: Option<bool>,
+    pub site_url: Option<String>,
+    pub robots_disallow: Option<Vec<String>>,
+    pub export: Option<ExportFormat>,
+    pub archive: Option<PathBuf>,
+    pub archive_only: Option<bool>,
+    pub status_file: Option<bool>,
+    pub keep_partial: Option<bool>,
+    pub capture_env: Option<bool>,
+    pub print_budget: Option<bool>,
+    pub report_snippets: Option<usize>,
+    pub max_runtime: Option<u64>,
+    pub stall_timeout: Option<u64>,
+    pub abort_on_stall: Option<bool>,
+    pub parallel_extraction: Option<bool>,
+    pub ascii_filenames: Option<bool>,
+    pub incremental: Option<bool>,
+    pub merge: Option<bool>,
+    pub force: Option<bool>,
+    pub whats_new: Option<bool>,
     pub timeout: Option<u64>,
+    pub clone_depth: Option<u32>,
+    pub clone_preset: Option<ClonePreset>,
+    pub preset: Option<ExtractionPreset>,
     pub branch: Option<String>,
+    pub allowed_hosts: Option<Vec<String>>,
+    pub preflight: Option<bool>,
+    pub sparse_paths: Option<Vec<String>>,
+    pub fetch_mode: Option<FetchMode>,
+    pub cache: Option<bool>,
+    pub cache_dir: Option<PathBuf>,
 }
 
 impl CliOverrides {
@@ -264,11 +1582,66 @@ impl CliOverrides {
         self
     }
 
+    pub fn with_include_patterns(mut self, include_patterns: Option<Vec<String>>) -> Self {
+        self.include_patterns = include_patterns;
+        self
+    }
+
+    pub fn with_exclude_globs(mut self, exclude_globs: Option<Vec<String>>) -> Self {
+        self.exclude_globs = exclude_globs;
+        self
+    }
+
+    pub fn with_include_api_specs(mut self, include_api_specs: Option<bool>) -> Self {
+        self.include_api_specs = include_api_specs;
+        self
+    }
+
+    pub fn with_include_vendored(mut self, include_vendored: Option<bool>) -> Self {
+        self.include_vendored = include_vendored;
+        self
+    }
+
+    pub fn with_top_n(mut self, top_n: Option<usize>) -> Self {
+        self.top_n = top_n;
+        self
+    }
+
+    pub fn with_notebook_mode(mut self, notebook_mode: Option<NotebookMode>) -> Self {
+        self.notebook_mode = notebook_mode;
+        self
+    }
+
+    pub fn with_sniff_content(mut self, sniff_content: Option<bool>) -> Self {
+        self.sniff_content = sniff_content;
+        self
+    }
+
+    pub fn with_respect_gitignore(mut self, respect_gitignore: Option<bool>) -> Self {
+        self.respect_gitignore = respect_gitignore;
+        self
+    }
+
+    pub fn with_scan_roots(mut self, scan_roots: Option<Vec<String>>) -> Self {
+        self.scan_roots = scan_roots;
+        self
+    }
+
+    pub fn with_include_hidden(mut self, include_hidden: Option<Vec<String>>) -> Self {
+        self.include_hidden = include_hidden;
+        self
+    }
+
     pub fn with_max_file_size(mut self, max_size: Option<u64>) -> Self {
         self.max_file_size = max_size;
         self
     }
 
+    pub fn with_skip_empty(mut self, skip_empty: Option<bool>) -> Self {
+        self.skip_empty = skip_empty;
+        self
+    }
+
     pub fn with_output_dir(mut self, output_dir: Option<PathBuf>) -> Self {
         self.output_dir = output_dir;
         self
@@ -279,21 +1652,289 @@ impl CliOverrides {
         self
     }
 
+    pub fn with_rewrite_links(mut self, rewrite_links: Option<bool>) -> Self {
+        self.rewrite_links = rewrite_links;
+        self
+    }
+
+    pub fn with_source_banner(mut self, source_banner: Option<SourceBanner>) -> Self {
+        self.source_banner = source_banner;
+        self
+    }
+
+    pub fn with_assets(mut self, with_assets: Option<bool>) -> Self {
+        self.with_assets = with_assets;
+        self
+    }
+
+    pub fn with_max_asset_size(mut self, max_asset_size: Option<u64>) -> Self {
+        self.max_asset_size = max_asset_size;
+        self
+    }
+
+    pub fn with_generate_graph(mut self, generate_graph: Option<bool>) -> Self {
+        self.generate_graph = generate_graph;
+        self
+    }
+
+    pub fn with_max_path_length(mut self, max_length: Option<usize>) -> Self {
+        self.max_path_length = max_length;
+        self
+    }
+
+    pub fn with_unicode_normalization(
+        mut self,
+        normalization: Option<UnicodeNormalization>,
+    ) -> Self {
+        self.unicode_normalization = normalization;
+        self
+    }
+
+    pub fn with_parallel_extraction(mut self, parallel: Option<bool>) -> Self {
+        self.parallel_extraction = parallel;
+        self
+    }
+
+    pub fn with_ascii_filenames(mut self, ascii_filenames: Option<bool>) -> Self {
+        self.ascii_filenames = ascii_filenames;
+        self
+    }
+
+    pub fn with_status_file(mut self, status_file: Option<bool>) -> Self {
+        self.status_file = status_file;
+        self
+    }
+
+    pub fn with_keep_partial(mut self, keep_partial: Option<bool>) -> Self {
+        self.keep_partial = keep_partial;
+        self
+    }
+
+    pub fn with_capture_env(mut self, capture_env: Option<bool>) -> Self {
+        self.capture_env = capture_env;
+        self
+    }
+
+    pub fn with_print_budget(mut self, print_budget: Option<bool>) -> Self {
+        self.print_budget = print_budget;
+        self
+    }
+
+    pub fn with_report_snippets(mut self, report_snippets: Option<usize>) -> Self {
+        self.report_snippets = report_snippets;
+        self
+    }
+
+    pub fn with_max_runtime(mut self, max_runtime: Option<u64>) -> Self {
+        self.max_runtime = max_runtime;
+        self
+    }
+
+    pub fn with_stall_timeout(mut self, stall_timeout: Option<u64>) -> Self {
+        self.stall_timeout = stall_timeout;
+        self
+    }
+
+    pub fn with_abort_on_stall(mut self, abort_on_stall: Option<bool>) -> Self {
+        self.abort_on_stall = abort_on_stall;
+        self
+    }
+
+    pub fn with_source_docs(mut self, source_docs: Option<bool>) -> Self {
+        self.source_docs = source_docs;
+        self
+    }
+
+    pub fn with_bundle(mut self, bundle: Option<PathBuf>) -> Self {
+        self.bundle = bundle;
+        self
+    }
+
+    pub fn with_llm_pack(mut self, llm_pack: Option<bool>) -> Self {
+        self.llm_pack = llm_pack;
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: Option<usize>) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn with_llm_chunk_tokens(mut self, chunk_tokens: Option<usize>) -> Self {
+        self.llm_chunk_tokens = chunk_tokens;
+        self
+    }
+
+    pub fn with_render(mut self, render: Option<RenderFormat>) -> Self {
+        self.render = render;
+        self
+    }
+
+    pub fn with_pdf_converter(mut self, converter: Option<String>) -> Self {
+        self.pdf_converter = converter;
+        self
+    }
+
+    pub fn with_html_theme(mut self, theme: Option<HtmlTheme>) -> Self {
+        self.html_theme = theme;
+        self
+    }
+
+    pub fn with_custom_css(mut self, custom_css: Option<PathBuf>) -> Self {
+        self.custom_css = custom_css;
+        self
+    }
+
+    pub fn with_html_search(mut self, search: Option<bool>) -> Self {
+        self.html_search = search;
+        self
+    }
+
+    pub fn with_site_url(mut self, site_url: Option<String>) -> Self {
+        self.site_url = site_url;
+        self
+    }
+
+    pub fn with_robots_disallow(mut self, robots_disallow: Option<Vec<String>>) -> Self {
+        self.robots_disallow = robots_disallow;
+        self
+    }
+
+    pub fn with_export(mut self, export: Option<ExportFormat>) -> Self {
+        self.export = export;
+        self
+    }
+
+    pub fn with_archive(mut self, archive: Option<PathBuf>) -> Self {
+        self.archive = archive;
+        self
+    }
+
+    pub fn with_archive_only(mut self, archive_only: Option<bool>) -> Self {
+        self.archive_only = archive_only;
+        self
+    }
+
+    pub fn with_sort_order(mut self, sort_order: Option<SortOrder>) -> Self {
+        self.sort_order = sort_order;
+        self
+    }
+
+    pub fn with_index_page_size(mut self, index_page_size: Option<usize>) -> Self {
+        self.index_page_size = index_page_size;
+        self
+    }
+
+    pub fn with_name_template(mut self, name_template: Option<String>) -> Self {
+        self.name_template = name_template;
+        self
+    }
+
+    pub fn with_dedupe(mut self, dedupe: Option<DedupeMode>) -> Self {
+        self.dedupe = dedupe;
+        self
+    }
+
+    pub fn with_readme_promotion(mut self, promotion: Option<ReadmePromotion>) -> Self {
+        self.readme_promotion = promotion;
+        self
+    }
+
+    pub fn with_incremental(mut self, incremental: Option<bool>) -> Self {
+        self.incremental = incremental;
+        self
+    }
+
+    pub fn with_merge(mut self, merge: Option<bool>) -> Self {
+        self.merge = merge;
+        self
+    }
+
+    pub fn with_force(mut self, force: Option<bool>) -> Self {
+        self.force = force;
+        self
+    }
+
+    pub fn with_whats_new(mut self, whats_new: Option<bool>) -> Self {
+        self.whats_new = whats_new;
+        self
+    }
+
+    pub fn with_similarity_threshold(mut self, threshold: Option<f64>) -> Self {
+        self.similarity_threshold = threshold;
+        self
+    }
+
+    pub fn with_generate_glossary(mut self, generate_glossary: Option<bool>) -> Self {
+        self.generate_glossary = generate_glossary;
+        self
+    }
+
+    pub fn with_generate_outline(mut self, generate_outline: Option<bool>) -> Self {
+        self.generate_outline = generate_outline;
+        self
+    }
+
     pub fn with_timeout(mut self, timeout: Option<u64>) -> Self {
         self.timeout = timeout;
         self
     }
 
+    pub fn with_clone_depth(mut self, depth: Option<u32>) -> Self {
+        self.clone_depth = depth;
+        self
+    }
+
+    pub fn with_preset(mut self, preset: Option<ExtractionPreset>) -> Self {
+        self.preset = preset;
+        self
+    }
+
+    pub fn with_clone_preset(mut self, preset: Option<ClonePreset>) -> Self {
+        self.clone_preset = preset;
+        self
+    }
+
     pub fn with_branch(mut self, branch: Option<String>) -> Self {
         self.branch = branch;
         self
     }
+
+    pub fn with_preflight(mut self, preflight: Option<bool>) -> Self {
+        self.preflight = preflight;
+        self
+    }
+
+    pub fn with_sparse_paths(mut self, sparse_paths: Option<Vec<String>>) -> Self {
+        self.sparse_paths = sparse_paths;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: Option<bool>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: Option<PathBuf>) -> Self {
+        self.cache_dir = cache_dir;
+        self
+    }
+
+    pub fn with_fetch_mode(mut self, fetch_mode: Option<FetchMode>) -> Self {
+        self.fetch_mode = fetch_mode;
+        self
+    }
+
+    pub fn with_allowed_hosts(mut self, allowed_hosts: Option<Vec<String>>) -> Self {
+        self.allowed_hosts = allowed_hosts;
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::NamedTempFile;
+    use tempfile::{NamedTempFile, TempDir};
 
     #[test]
     fn test_default_config() {
@@ -310,6 +1951,54 @@ mod tests {
 
         config.filters.extensions.clear();
         assert!(config.validate().is_err());
+
+        let mut config = Config::default();
+        config.git.clone_depth = Some(0);
+        assert!(config.validate().is_err());
+
+        let mut config = Config::default();
+        config.git.sparse_paths = vec!["docs".to_string(), "*.md".to_string()];
+        assert!(config.validate().is_ok());
+        config.git.sparse_paths.push("../outside".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_similarity_threshold_validation() {
+        let mut config = Config::default();
+
+        config.output.similarity_threshold = Some(0.9);
+        assert!(config.validate().is_ok());
+
+        config.output.similarity_threshold = Some(1.5);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_max_path_length_validation() {
+        let mut config = Config::default();
+
+        config.output.max_path_length = Some(260);
+        assert!(config.validate().is_ok());
+
+        config.output.max_path_length = Some(10);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_archive_validation() {
+        let mut config = Config::default();
+
+        config.output.archive = Some(PathBuf::from("dist/{repo}.tar.gz"));
+        config.output.archive_only = true;
+        assert!(config.validate().is_ok());
+
+        config.output.incremental = true;
+        assert!(config.validate().is_err());
+
+        config.output.incremental = false;
+        config.output.archive = Some(PathBuf::from("docs.rar"));
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -332,15 +2021,104 @@ mod tests {
 
         let overrides = CliOverrides::new()
             .with_timeout(Some(600))
+            .with_clone_depth(Some(1))
             .with_formats(Some("md,txt".to_string()));
 
         config.merge_with_cli_args(&overrides);
 
         assert_eq!(config.git.timeout, 600);
+        assert_eq!(config.git.clone_depth, Some(1));
         assert_ne!(config.git.timeout, original_timeout);
         assert_eq!(config.filters.extensions, vec!["md", "txt"]);
     }
 
+    #[test]
+    fn test_clone_preset_overrides() {
+        let mut config = Config::default();
+        config.merge_with_cli_args(&CliOverrides::new().with_clone_preset(Some(ClonePreset::Fast)));
+        assert_eq!(config.git.fetch_mode, FetchMode::Tarball);
+        assert_eq!(config.git.clone_depth, Some(1));
+        assert!(!config.git.lfs);
+        assert!(!config.git.count_commits);
+
+        // Explicit options win over the preset
+        let mut config = Config::default();
+        config.merge_with_cli_args(
+            &CliOverrides::new()
+                .with_clone_preset(Some(ClonePreset::Archival))
+                .with_clone_depth(Some(5)),
+        );
+        assert_eq!(config.git.fetch_mode, FetchMode::Git);
+        assert_eq!(config.git.clone_depth, Some(5));
+        assert!(config.git.submodules);
+        assert!(config.git.lfs);
+
+        let mut config = Config::default();
+        config.merge_with_cli_args(
+            &CliOverrides::new()
+                .with_clone_preset(Some(ClonePreset::Fast))
+                .with_fetch_mode(Some(FetchMode::Git)),
+        );
+        assert_eq!(config.git.fetch_mode, FetchMode::Git);
+    }
+
+    #[test]
+    fn test_extraction_preset_overrides() {
+        let mut config = Config::default();
+        config.merge_with_cli_args(&CliOverrides::new().with_preset(Some(ExtractionPreset::Llm)));
+        assert!(config.filters.extensions.contains(&"mdx".to_string()));
+        assert!(!config.filters.extensions.contains(&"tex".to_string()));
+        assert_eq!(config.output.similarity_threshold, Some(0.9));
+
+        // Explicit options win over the preset
+        let mut config = Config::default();
+        config.merge_with_cli_args(
+            &CliOverrides::new()
+                .with_preset(Some(ExtractionPreset::Audit))
+                .with_formats(Some("md".to_string()))
+                .with_clone_depth(Some(1)),
+        );
+        assert_eq!(config.filters.extensions, vec!["md"]);
+        assert_eq!(config.git.clone_depth, Some(1));
+        assert!(config.git.submodules);
+        assert!(config.output.generate_graph);
+    }
+
+    #[test]
+    fn test_effective_config_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("repodocs.toml");
+        let mut file_config = Config::default();
+        file_config.git.timeout = 60;
+        file_config.filters.max_depth = 5;
+        file_config.save_to_file(&path).unwrap();
+
+        let overrides = CliOverrides::new()
+            .with_preset(Some(ExtractionPreset::Llm))
+            .with_timeout(Some(120));
+        let effective = EffectiveConfig::resolve(Some(&path), &overrides).unwrap();
+
+        assert_eq!(effective.config.git.timeout, 120);
+        assert_eq!(effective.sources["git.timeout"], ConfigSource::CommandLine);
+        assert_eq!(
+            effective.sources["filters.max_depth"],
+            ConfigSource::File(path.clone())
+        );
+        assert_eq!(
+            effective.sources["output.generate_outline"],
+            ConfigSource::Preset
+        );
+        assert_eq!(
+            effective.sources["output.keep_partial"],
+            ConfigSource::Default
+        );
+
+        let toml = effective.to_annotated_toml().unwrap();
+        assert!(toml.contains("[git]\n"));
+        assert!(toml.contains("\ntimeout = 120  # command line\n"));
+        assert!(toml.contains("\ngenerate_outline = true  # preset\n"));
+    }
+
     #[test]
     fn test_sample_config_generation() {
         let sample = Config::create_sample_config();
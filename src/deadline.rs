@@ -0,0 +1,79 @@
+use crate::error::{RepoDocsError, Result};
+use crate::status::Stage;
+use std::time::{Duration, Instant};
+
+/// Time limit for a whole run (`--max-runtime`). It is checked between
+/// stages and inside the clone, scan and copy loops, so a run stops soon
+/// after the limit whichever stage it is in.
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline {
+    limit: Duration,
+    /// `None` for a limit too far out for the clock, which never expires
+    expires: Option<Instant>,
+}
+
+impl Deadline {
+    /// A deadline `limit` from now
+    pub fn after(limit: Duration) -> Self {
+        Self {
+            limit,
+            expires: Instant::now().checked_add(limit),
+        }
+    }
+
+    pub fn limit(&self) -> Duration {
+        self.limit
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.expires.map_or(Duration::MAX, |expires| {
+            expires.saturating_duration_since(Instant::now())
+        })
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires
+            .is_some_and(|expires| Instant::now() >= expires)
+    }
+
+    /// Fail with a timeout naming `stage` once the deadline has passed
+    pub fn check(&self, stage: Stage) -> Result<()> {
+        if self.is_expired() {
+            return Err(self.timeout(stage));
+        }
+        Ok(())
+    }
+
+    pub fn timeout(&self, stage: Stage) -> RepoDocsError {
+        RepoDocsError::Timeout {
+            seconds: self.limit.as_secs(),
+            stage,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline_check() {
+        let deadline = Deadline::after(Duration::from_secs(60));
+        assert!(deadline.check(Stage::Scanning).is_ok());
+        assert!(deadline.remaining() > Duration::from_secs(59));
+
+        let expired = Deadline::after(Duration::ZERO);
+        assert!(expired.is_expired());
+        match expired.check(Stage::Extracting) {
+            Err(RepoDocsError::Timeout { seconds, stage }) => {
+                assert_eq!(seconds, 0);
+                assert_eq!(stage, Stage::Extracting);
+            }
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+
+        let distant = Deadline::after(Duration::from_secs(u64::MAX));
+        assert!(!distant.is_expired());
+        assert_eq!(distant.remaining(), Duration::MAX);
+    }
+}
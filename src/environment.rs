@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The machine a run happened on, recorded in the extraction report with
+/// `--capture-env` to help triage platform-specific performance problems.
+/// Nothing identifying is captured: no host or user names and no paths.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvironmentInfo {
+    /// Operating system, e.g. `linux`, `macos` or `windows`
+    pub os: String,
+    /// Kernel release, where the platform reports one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os_release: Option<String>,
+    pub arch: String,
+    /// Logical CPUs
+    pub cpu_count: usize,
+    /// Type of the filesystem holding the output, e.g. `ext4` or `apfs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filesystem: Option<String>,
+    /// Free bytes available to the user on that filesystem
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub available_disk: Option<u64>,
+}
+
+impl EnvironmentInfo {
+    /// Capture the environment, with filesystem details for `output_dir`.
+    /// Details the platform does not provide are left out.
+    pub fn capture(output_dir: &Path) -> Self {
+        Self {
+            os: std::env::consts::OS.to_string(),
+            os_release: os_release(),
+            arch: std::env::consts::ARCH.to_string(),
+            cpu_count: num_cpus::get(),
+            filesystem: filesystem_type(output_dir),
+            available_disk: available_disk(output_dir),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn os_release() -> Option<String> {
+    // SAFETY: uname only fills in the zeroed struct it is given
+    let mut name: libc::utsname = unsafe { std::mem::zeroed() };
+    if unsafe { libc::uname(&mut name) } != 0 {
+        return None;
+    }
+    // SAFETY: uname null-terminates the fields it fills in
+    let release = unsafe { std::ffi::CStr::from_ptr(name.release.as_ptr()) };
+    Some(release.to_string_lossy().into_owned())
+}
+
+#[cfg(not(unix))]
+fn os_release() -> Option<String> {
+    None
+}
+
+/// Filesystem of the longest mount point containing `path`
+#[cfg(target_os = "linux")]
+fn filesystem_type(path: &Path) -> Option<String> {
+    let path = std::fs::canonicalize(path).ok()?;
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = fields.nth(1)?.replace("\\040", " ");
+            let filesystem = fields.next()?;
+            path.starts_with(&mount_point)
+                .then(|| (mount_point.len(), filesystem.to_string()))
+        })
+        .max_by_key(|(length, _)| *length)
+        .map(|(_, filesystem)| filesystem)
+}
+
+#[cfg(target_os = "macos")]
+fn filesystem_type(path: &Path) -> Option<String> {
+    let stats = statfs(path)?;
+    // SAFETY: statfs null-terminates f_fstypename
+    let name = unsafe { std::ffi::CStr::from_ptr(stats.f_fstypename.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+#[cfg(target_os = "macos")]
+fn statfs(path: &Path) -> Option<libc::statfs> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statfs only fills in the zeroed struct it is given
+    let mut stats: libc::statfs = unsafe { std::mem::zeroed() };
+    (unsafe { libc::statfs(path.as_ptr(), &mut stats) } == 0).then_some(stats)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn filesystem_type(_path: &Path) -> Option<String> {
+    None
+}
+
+#[cfg(unix)]
+fn available_disk(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statvfs only fills in the zeroed struct it is given
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stats) } != 0 {
+        return None;
+    }
+    #[allow(clippy::unnecessary_cast)]
+    Some(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn available_disk(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_capture_environment() {
+        let temp_dir = TempDir::new().unwrap();
+        let environment = EnvironmentInfo::capture(temp_dir.path());
+
+        assert_eq!(environment.os, std::env::consts::OS);
+        assert!(environment.cpu_count >= 1);
+        if cfg!(unix) {
+            assert!(environment.available_disk.is_some());
+        }
+        if cfg!(target_os = "linux") {
+            assert!(environment.filesystem.is_some());
+        }
+
+        let json = serde_json::to_string(&environment).unwrap();
+        assert!(!json.contains(&*temp_dir.path().to_string_lossy()));
+    }
+}
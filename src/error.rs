@@ -1,3 +1,4 @@
+use crate::status::Stage;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -18,12 +19,34 @@ pub enum RepoDocsError {
     #[error("Repository not found or inaccessible: {url}")]
     RepositoryNotFound { url: String },
 
+    #[error("Repository not found or private: {url}")]
+    RepositoryNotFoundOrPrivate { url: String, token_env_var: String },
+
     #[error("Authentication failed for repository: {url}")]
     AuthenticationFailed { url: String },
 
     #[error("Network error occurred")]
     NetworkError { message: String },
 
+    #[error("Rate limited by {url}, retry after {retry_after} seconds")]
+    RateLimited { url: String, retry_after: u64 },
+
+    #[error("Could not resolve host: {host}")]
+    DnsResolutionFailed { host: String },
+
+    #[error("Could not connect to {host}:{port}: {reason}")]
+    ConnectionFailed {
+        host: String,
+        port: u16,
+        reason: String,
+    },
+
+    #[error("Proxy {proxy} is unreachable: {reason}")]
+    ProxyError { proxy: String, reason: String },
+
+    #[error("TLS handshake failed for {url}: {message}")]
+    TlsError { url: String, message: String },
+
     #[error("No documentation files found in repository")]
     NoDocumentationFound { searched_extensions: Vec<String> },
 
@@ -36,8 +59,11 @@ pub enum RepoDocsError {
     #[error("Operation was cancelled by user")]
     Cancelled,
 
-    #[error("Operation timed out after {seconds} seconds")]
-    Timeout { seconds: u64 },
+    #[error("Operation timed out after {seconds} seconds while {stage}")]
+    Timeout { seconds: u64, stage: Stage },
+
+    #[error("No progress for {seconds} seconds while {stage}")]
+    Stalled { stage: Stage, seconds: u64 },
 
     #[error("File too large: {size} bytes (max: {max_size} bytes)")]
     FileTooLarge { size: u64, max_size: u64 },
@@ -66,12 +92,36 @@ impl UserFriendlyError for RepoDocsError {
             RepoDocsError::RepositoryNotFound { url } => {
                 format!("Repository not found: {}", url)
             }
+            RepoDocsError::RepositoryNotFoundOrPrivate { url, .. } => {
+                format!(
+                    "Repository not found, or it is private and requires authentication: {}",
+                    url
+                )
+            }
             RepoDocsError::AuthenticationFailed { url } => {
                 format!("Authentication failed for: {}", url)
             }
             RepoDocsError::NetworkError { message } => {
                 format!("Network error: {}", message)
             }
+            RepoDocsError::RateLimited { url, retry_after } => {
+                format!(
+                    "Rate limit exceeded for {} (retry allowed in {} seconds)",
+                    url, retry_after
+                )
+            }
+            RepoDocsError::DnsResolutionFailed { host } => {
+                format!("Could not resolve host name: {}", host)
+            }
+            RepoDocsError::ConnectionFailed { host, port, reason } => {
+                format!("Could not connect to {}:{} ({})", host, port, reason)
+            }
+            RepoDocsError::ProxyError { proxy, reason } => {
+                format!("Proxy {} is unreachable ({})", proxy, reason)
+            }
+            RepoDocsError::TlsError { url, message } => {
+                format!("Secure connection to {} failed: {}", url, message)
+            }
             RepoDocsError::NoDocumentationFound {
                 searched_extensions,
             } => {
@@ -87,8 +137,14 @@ impl UserFriendlyError for RepoDocsError {
                 format!("Permission denied accessing: {}", path)
             }
             RepoDocsError::Cancelled => "Operation was cancelled by user".to_string(),
-            RepoDocsError::Timeout { seconds } => {
-                format!("Operation timed out after {} seconds", seconds)
+            RepoDocsError::Timeout { seconds, stage } => {
+                format!(
+                    "Operation timed out after {} seconds while {}",
+                    seconds, stage
+                )
+            }
+            RepoDocsError::Stalled { seconds, stage } => {
+                format!("No progress for {} seconds while {}", seconds, stage)
             }
             RepoDocsError::FileTooLarge { size, max_size } => {
                 format!(
@@ -110,17 +166,37 @@ impl UserFriendlyError for RepoDocsError {
     fn suggestion(&self) -> Option<String> {
         match self {
             RepoDocsError::InvalidUrl { .. } => Some(
-                "Please check that the URL is a valid GitHub repository URL (e.g., https://github.com/owner/repo)".to_string()
+                "Please check that the URL is a valid GitHub or GitLab repository URL (e.g., https://github.com/owner/repo). Other hosts must be allowed with --allow-host".to_string()
             ),
             RepoDocsError::RepositoryNotFound { .. } => Some(
-                "Verify the repository exists and you have access to it. For private repositories, set the GITHUB_TOKEN environment variable.".to_string()
+                "Verify the repository exists and you have access to it. For private repositories, set the GITHUB_TOKEN (or GITLAB_TOKEN) environment variable.".to_string()
             ),
+            RepoDocsError::RepositoryNotFoundOrPrivate { token_env_var, .. } => Some(format!(
+                "Hosting services answer \"not found\" for private repositories when no credentials are sent, and {} is not set. If the repository is private, set {} to an access token with read access; otherwise check the owner and repository name.",
+                token_env_var, token_env_var
+            )),
             RepoDocsError::AuthenticationFailed { .. } => Some(
-                "Set the GITHUB_TOKEN environment variable with a valid personal access token for private repositories.".to_string()
+                "Set the GITHUB_TOKEN (or GITLAB_TOKEN) environment variable with a valid personal access token for private repositories.".to_string()
             ),
             RepoDocsError::NetworkError { .. } => Some(
                 "Check your internet connection and try again. If the problem persists, the repository server might be temporarily unavailable.".to_string()
             ),
+            RepoDocsError::RateLimited { .. } => Some(
+                "Wait and try again, set GITHUB_TOKEN/GITLAB_TOKEN for higher rate limits, or raise git.max_retries and git.max_retry_wait in the configuration.".to_string()
+            ),
+            RepoDocsError::DnsResolutionFailed { .. } => Some(
+                "Check the host name for typos and verify your DNS settings (e.g., try `nslookup <host>`). If you are offline, reconnect and try again.".to_string()
+            ),
+            RepoDocsError::ConnectionFailed { port, .. } => Some(format!(
+                "The host resolved but port {} did not accept a connection. A firewall may be blocking it; for SSH URLs try HTTPS instead, or configure HTTPS_PROXY if your network requires a proxy.",
+                port
+            )),
+            RepoDocsError::ProxyError { .. } => Some(
+                "Verify the HTTPS_PROXY/ALL_PROXY environment variables point to a reachable proxy, or unset them to connect directly.".to_string()
+            ),
+            RepoDocsError::TlsError { .. } => Some(
+                "The server certificate could not be verified. Check your system clock and CA certificates, or whether a corporate proxy intercepts TLS traffic.".to_string()
+            ),
             RepoDocsError::NoDocumentationFound { .. } => Some(
                 "Try using different file extensions with --formats (e.g., --formats md,rst,txt,adoc) or check if the repository contains documentation files.".to_string()
             ),
@@ -131,7 +207,10 @@ impl UserFriendlyError for RepoDocsError {
                 "Ensure you have the necessary read/write permissions for the target directory.".to_string()
             ),
             RepoDocsError::Timeout { .. } => Some(
-                "The operation took longer than expected. Try again or increase the timeout with --timeout.".to_string()
+                "The run took longer than allowed. Raise the limit with --max-runtime, or narrow the run with --paths or --include.".to_string()
+            ),
+            RepoDocsError::Stalled { .. } => Some(
+                "Check your network connection and disk, then try again. Raise --stall-timeout if the stage is just slow.".to_string()
             ),
             RepoDocsError::FileTooLarge { .. } => Some(
                 "Increase the maximum file size limit with --max-size or exclude large files.".to_string()
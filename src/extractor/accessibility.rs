@@ -0,0 +1,187 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Accessibility rules rendered pages are checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AccessibilityRule {
+    /// An image without alternative text, which screen readers cannot describe
+    MissingAltText,
+    /// A heading more than one level below the previous one, which breaks
+    /// navigation by heading
+    HeadingLevelSkip,
+    /// A link without text, which screen readers announce by its URL only
+    EmptyLink,
+}
+
+impl AccessibilityRule {
+    pub(crate) const ALL: [AccessibilityRule; 3] = [
+        AccessibilityRule::MissingAltText,
+        AccessibilityRule::HeadingLevelSkip,
+        AccessibilityRule::EmptyLink,
+    ];
+
+    pub(crate) fn description(&self) -> &'static str {
+        match self {
+            AccessibilityRule::MissingAltText => "Images need alternative text",
+            AccessibilityRule::HeadingLevelSkip => "Headings must not skip levels",
+            AccessibilityRule::EmptyLink => "Links need text",
+        }
+    }
+}
+
+impl fmt::Display for AccessibilityRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AccessibilityRule::MissingAltText => "missing-alt-text",
+            AccessibilityRule::HeadingLevelSkip => "heading-level-skip",
+            AccessibilityRule::EmptyLink => "empty-link",
+        })
+    }
+}
+
+/// A violation of an accessibility rule on a rendered page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessibilityFinding {
+    /// Path relative to the `html/` directory
+    pub page: String,
+    /// Path of the document the page was rendered from, relative to the
+    /// repository root
+    #[serde(default)]
+    pub source: String,
+    pub rule: AccessibilityRule,
+    /// The offending element, shortened
+    pub element: String,
+}
+
+/// Longest element quoted in a finding, in characters
+const ELEMENT_CHARS: usize = 80;
+
+fn image_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?is)<img\b[^>]*>").expect("valid image regex"))
+}
+
+fn alt_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"(?is)\salt\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#)
+            .expect("valid alt regex")
+    })
+}
+
+fn heading_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?is)<h([1-6])\b[^>]*>").expect("valid heading regex"))
+}
+
+fn link_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?is)<a\b([^>]*)>(.*?)</a\s*>").expect("valid link regex"))
+}
+
+fn tag_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?s)<[^>]*>").expect("valid tag regex"))
+}
+
+/// Check `body`, the HTML rendered for `page`, against the accessibility
+/// rules. Only images without an `alt` attribute miss alternative text: an
+/// empty one marks the image as decorative, which screen readers skip.
+pub fn check(page: &str, source: &str, body: &str) -> Vec<AccessibilityFinding> {
+    let mut findings = Vec::new();
+    let mut finding = |rule, element: &str| {
+        findings.push(AccessibilityFinding {
+            page: page.to_string(),
+            source: source.to_string(),
+            rule,
+            element: shorten(element),
+        })
+    };
+
+    for image in image_regex().find_iter(body) {
+        if alt_text(image.as_str()).is_none() {
+            finding(AccessibilityRule::MissingAltText, image.as_str());
+        }
+    }
+
+    let mut previous_level = None;
+    for heading in heading_regex().captures_iter(body) {
+        let level: usize = heading[1].parse().unwrap_or(1);
+        if previous_level.is_some_and(|previous| level > previous + 1) {
+            finding(AccessibilityRule::HeadingLevelSkip, &heading[0]);
+        }
+        previous_level = Some(level);
+    }
+
+    for link in link_regex().captures_iter(body) {
+        let (attributes, content) = (&link[1], &link[2]);
+        let labelled = attributes.to_lowercase().contains("aria-label");
+        let text = tag_regex().replace_all(content, "");
+        let described_image = image_regex()
+            .find_iter(content)
+            .any(|image| alt_text(image.as_str()).is_some_and(|alt| !alt.trim().is_empty()));
+        if !labelled && text.trim().is_empty() && !described_image {
+            finding(AccessibilityRule::EmptyLink, &link[0]);
+        }
+    }
+
+    findings
+}
+
+fn alt_text(image: &str) -> Option<&str> {
+    let alt = alt_regex().captures(image)?;
+    alt.get(1)
+        .or_else(|| alt.get(2))
+        .or_else(|| alt.get(3))
+        .map(|value| value.as_str())
+}
+
+fn shorten(element: &str) -> String {
+    let element = element.split_whitespace().collect::<Vec<_>>().join(" ");
+    match element.char_indices().nth(ELEMENT_CHARS) {
+        Some((end, _)) => format!("{}…", &element[..end]),
+        None => element,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accessibility_checks() {
+        let body = "<h1>Guide</h1>\n\
+                    <p><img src=\"logo.png\" alt=\"Logo\" /> <img src=\"chart.png\" alt=\"\" />\
+                    <img src=\"raw.png\"></p>\n\
+                    <h3>Deep</h3>\n<h2>Back</h2>\n<h3>Fine</h3>\n\
+                    <p><a href=\"a.html\">A</a> <a href=\"b.html\"></a> \
+                    <a href=\"c.html\"><img src=\"c.png\" alt=\"C\"></a> \
+                    <a href=\"d.html\" aria-label=\"D\"><span></span></a> \
+                    <a href=\"e.html\"><img src=\"e.png\" alt=\"\"></a></p>\n";
+
+        let findings = check("docs/guide.html", "docs/guide.md", body);
+        let found: Vec<(AccessibilityRule, &str)> = findings
+            .iter()
+            .map(|finding| (finding.rule, finding.element.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (AccessibilityRule::MissingAltText, "<img src=\"raw.png\">"),
+                (AccessibilityRule::HeadingLevelSkip, "<h3>"),
+                (AccessibilityRule::EmptyLink, "<a href=\"b.html\"></a>"),
+                (
+                    AccessibilityRule::EmptyLink,
+                    "<a href=\"e.html\"><img src=\"e.png\" alt=\"\"></a>"
+                ),
+            ]
+        );
+        // Decorative images, with an empty `alt`, pass
+        assert!(!findings.iter().any(|f| f.element.contains("alt=\"\" />")));
+        assert!(findings.iter().all(|f| f.page == "docs/guide.html"));
+        assert_eq!(shorten(&"x".repeat(100)).chars().count(), ELEMENT_CHARS + 1);
+    }
+}
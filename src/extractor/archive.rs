@@ -0,0 +1,227 @@
+use crate::error::{RepoDocsError, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Placeholder in archive paths replaced by the repository name, so a batch
+/// writes one archive per repository
+pub const REPO_PLACEHOLDER: &str = "{repo}";
+
+/// Archive formats, chosen by the file extension of the archive path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+/// What was written to an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveSummary {
+    pub path: PathBuf,
+    pub files: usize,
+    /// Uncompressed size of the archived files
+    pub bytes: u64,
+}
+
+/// Path of the archive for `repo`, with [`REPO_PLACEHOLDER`] replaced
+pub fn archive_path(template: &Path, repo: &str) -> PathBuf {
+    PathBuf::from(template.to_string_lossy().replace(REPO_PLACEHOLDER, repo))
+}
+
+/// Write the output directory, `.repodocs` metadata included, to a ZIP or
+/// gzipped tar archive at `archive`. Entries are under `name`, the name the
+/// output directory has once committed, and are compressed as they are
+/// read, one file at a time.
+pub fn write_archive(output_dir: &Path, name: &str, archive: &Path) -> Result<ArchiveSummary> {
+    let format = ArchiveFormat::from_path(archive).ok_or_else(|| RepoDocsError::Config {
+        message: format!(
+            "Unsupported archive format: {} (use .zip, .tar.gz or .tgz)",
+            archive.display()
+        ),
+    })?;
+
+    if let Some(parent) = archive.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(RepoDocsError::Io)?;
+    }
+    let root = PathBuf::from(name);
+
+    // Files in a stable order; an archive written into the output directory
+    // is not added to itself
+    let archive_file = fs::canonicalize(archive.parent().unwrap_or(Path::new(".")))
+        .ok()
+        .zip(archive.file_name())
+        .map(|(dir, name)| dir.join(name));
+    let files: Vec<PathBuf> = WalkDir::new(output_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| fs::canonicalize(path).ok() != archive_file)
+        .collect();
+
+    let writer = BufWriter::new(File::create(archive).map_err(RepoDocsError::Io)?);
+    let mut summary = ArchiveSummary {
+        path: archive.to_path_buf(),
+        files: 0,
+        bytes: 0,
+    };
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = ZipWriter::new(writer);
+            let options = FileOptions::default()
+                .compression_method(CompressionMethod::Deflated)
+                .large_file(true);
+            for path in &files {
+                let name = entry_name(&root, output_dir, path);
+                zip.start_file(name, options).map_err(zip_error)?;
+                let mut file = File::open(path).map_err(RepoDocsError::Io)?;
+                summary.bytes += io::copy(&mut file, &mut zip).map_err(RepoDocsError::Io)?;
+                summary.files += 1;
+            }
+            zip.finish().map_err(zip_error)?.flush()?;
+        }
+        ArchiveFormat::TarGz => {
+            let mut tar = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+            for path in &files {
+                let name = entry_name(&root, output_dir, path);
+                tar.append_path_with_name(path, name)
+                    .map_err(RepoDocsError::Io)?;
+                summary.bytes += fs::metadata(path).map_or(0, |meta| meta.len());
+                summary.files += 1;
+            }
+            tar.into_inner()
+                .and_then(GzEncoder::finish)
+                .and_then(|mut writer| writer.flush())
+                .map_err(RepoDocsError::Io)?;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Name of a file in the archive: its path under the output directory's
+/// name, with forward slashes on every platform
+fn entry_name(root: &Path, output_dir: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(output_dir).unwrap_or(path);
+    root.join(relative).to_string_lossy().replace('\\', "/")
+}
+
+fn zip_error(error: zip::result::ZipError) -> RepoDocsError {
+    match error {
+        zip::result::ZipError::Io(error) => RepoDocsError::Io(error),
+        error => RepoDocsError::Io(io::Error::other(error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    fn create_output(temp_dir: &Path) -> PathBuf {
+        let output_dir = temp_dir.join("owner-repo");
+        fs::create_dir_all(output_dir.join("docs")).unwrap();
+        fs::create_dir_all(output_dir.join(".repodocs")).unwrap();
+        fs::write(output_dir.join("README.md"), "# Project\n").unwrap();
+        fs::write(output_dir.join("docs/guide.md"), "# Guide\n").unwrap();
+        fs::write(output_dir.join(".repodocs/manifest.json"), "{}").unwrap();
+        output_dir
+    }
+
+    #[test]
+    fn test_archive_format_from_path() {
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("out.zip")),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("dist/out.TAR.GZ")),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::from_path(Path::new("out.tgz")),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(ArchiveFormat::from_path(Path::new("out.rar")), None);
+        assert_eq!(
+            archive_path(Path::new("dist/{repo}.zip"), "owner-repo"),
+            PathBuf::from("dist/owner-repo.zip")
+        );
+    }
+
+    #[test]
+    fn test_write_zip_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = create_output(temp_dir.path());
+        let archive = temp_dir.path().join("out.zip");
+
+        let summary = write_archive(&output_dir, "owner-repo", &archive).unwrap();
+        assert_eq!(summary.files, 3);
+        assert_eq!(summary.bytes, 20);
+
+        let mut zip = zip::ZipArchive::new(File::open(&archive).unwrap()).unwrap();
+        let mut names: Vec<&str> = zip.file_names().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "owner-repo/.repodocs/manifest.json",
+                "owner-repo/README.md",
+                "owner-repo/docs/guide.md"
+            ]
+        );
+        let mut content = String::new();
+        zip.by_name("owner-repo/docs/guide.md")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "# Guide\n");
+    }
+
+    #[test]
+    fn test_write_tar_gz_archive_inside_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_dir = create_output(temp_dir.path());
+        let archive = output_dir.join("docs.tar.gz");
+
+        let summary = write_archive(&output_dir, "owner-repo", &archive).unwrap();
+        assert_eq!(summary.files, 3);
+
+        let mut tar = tar::Archive::new(GzDecoder::new(File::open(&archive).unwrap()));
+        let names: Vec<String> = tar
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "owner-repo/.repodocs/manifest.json",
+                "owner-repo/README.md",
+                "owner-repo/docs/guide.md"
+            ]
+        );
+    }
+}
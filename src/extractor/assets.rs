@@ -0,0 +1,159 @@
+use crate::extractor::links::repository_root;
+use crate::extractor::markdown;
+use crate::scanner::DocumentFile;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Extensions of referenced files copied along with the documents
+pub const ASSET_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "webp", "avif", "bmp", "ico", "pdf", "mp4", "webm",
+];
+
+/// Files referenced from extracted Markdown that are not documents
+/// themselves, such as the images of a guide
+#[derive(Debug, Default)]
+pub struct Assets {
+    /// Assets to copy, sorted by repository path
+    pub files: Vec<DocumentFile>,
+    /// Repository paths of referenced assets larger than the size limit
+    pub oversized: Vec<String>,
+}
+
+/// Find the assets that relative links and images of the Markdown
+/// `documents` point at. Only files inside the repository with an asset
+/// extension are taken, and those larger than `max_size` bytes are listed
+/// as oversized instead.
+pub fn find(documents: &[DocumentFile], max_size: u64) -> Assets {
+    let extracted: HashSet<&Path> = documents
+        .iter()
+        .map(|document| document.relative_path.as_path())
+        .collect();
+    let mut found: BTreeMap<PathBuf, DocumentFile> = BTreeMap::new();
+    let mut oversized: BTreeSet<PathBuf> = BTreeSet::new();
+
+    for document in documents
+        .iter()
+        .filter(|document| markdown::is_markdown_path(&document.relative_path.to_string_lossy()))
+    {
+        let Some(root) = repository_root(document) else {
+            continue;
+        };
+        let Ok(real_root) = fs::canonicalize(root) else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(&document.source_path) else {
+            continue;
+        };
+
+        for link in markdown::extract_links(&content) {
+            if link.is_external() || link.is_anchor_only() {
+                continue;
+            }
+            let Some(path) =
+                markdown::resolve_relative_link(&document.relative_path, link.path_part())
+            else {
+                continue;
+            };
+            if !is_asset_path(&path)
+                || extracted.contains(path.as_path())
+                || found.contains_key(&path)
+                || oversized.contains(&path)
+            {
+                continue;
+            }
+
+            // Symlinks could lead out of the repository
+            let source = root.join(&path);
+            let inside = fs::canonicalize(&source).is_ok_and(|real| real.starts_with(&real_root));
+            let Ok(metadata) = fs::symlink_metadata(&source) else {
+                continue;
+            };
+            if !inside || !metadata.is_file() {
+                continue;
+            }
+            if metadata.len() > max_size {
+                oversized.insert(path);
+                continue;
+            }
+
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            let asset = DocumentFile::new(source, path.clone(), metadata.len(), modified);
+            found.insert(path, asset);
+        }
+    }
+
+    Assets {
+        files: found.into_values().collect(),
+        oversized: oversized
+            .iter()
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+            .collect(),
+    }
+}
+
+fn is_asset_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ASSET_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_assets() {
+        let repo = TempDir::new().unwrap();
+        let files: &[(&str, usize)] = &[
+            ("docs/guide.md", 0),
+            ("docs/images/setup.png", 10),
+            ("docs/images/huge.gif", 100),
+            ("logo.svg", 10),
+            ("src/main.rs", 10),
+            ("docs/api.md", 0),
+        ];
+        for (path, size) in files {
+            let path = repo.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, vec![b'x'; *size]).unwrap();
+        }
+        fs::write(
+            repo.path().join("docs/guide.md"),
+            "![Setup](images/setup.png#top) ![Huge](./images/huge.gif)\n\
+             [Logo](../logo.svg) [Again](/logo.svg) [Source](../src/main.rs)\n\
+             [API](api.md) ![Gone](images/gone.png) ![Remote](https://example.com/a.png)\n",
+        )
+        .unwrap();
+
+        let documents: Vec<DocumentFile> = ["docs/guide.md", "docs/api.md"]
+            .into_iter()
+            .map(|path| {
+                DocumentFile::new(
+                    repo.path().join(path),
+                    PathBuf::from(path),
+                    0,
+                    SystemTime::UNIX_EPOCH,
+                )
+            })
+            .collect();
+
+        let assets = find(&documents, 50);
+        let copied: Vec<&Path> = assets
+            .files
+            .iter()
+            .map(|asset| asset.relative_path.as_path())
+            .collect();
+        assert_eq!(
+            copied,
+            [Path::new("docs/images/setup.png"), Path::new("logo.svg")]
+        );
+        assert_eq!(
+            assets.files[0].source_path,
+            repo.path().join("docs/images/setup.png")
+        );
+        assert_eq!(assets.oversized, ["docs/images/huge.gif"]);
+    }
+}
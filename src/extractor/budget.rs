@@ -0,0 +1,151 @@
+use crate::extractor::llm_pack::estimate_tokens;
+use crate::scanner::DocumentFile;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Name of the repository root in budgets
+const ROOT: &str = ".";
+
+/// Size of the documents under a directory, its subdirectories included,
+/// for deciding which subtrees fit in an LLM prompt
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirectoryBudget {
+    /// Path relative to the repository root, `.` for the root
+    pub directory: String,
+    pub files: usize,
+    pub words: usize,
+    /// Estimated tokens, at the rate of the LLM pack
+    pub tokens: usize,
+}
+
+/// Word and token totals of every directory holding documents, directly or
+/// below, ordered by path so that subdirectories follow their parent.
+/// Documents that cannot be read count as files without words.
+pub fn directory_budgets(documents: &[DocumentFile]) -> Vec<DirectoryBudget> {
+    let mut budgets: BTreeMap<String, DirectoryBudget> = BTreeMap::new();
+    for document in documents {
+        let text = fs::read(&document.source_path)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_default();
+        let words = text.split_whitespace().count();
+        let tokens = estimate_tokens(&text);
+
+        let path = document.relative_path.to_string_lossy().replace('\\', "/");
+        let mut directories = vec![ROOT.to_string()];
+        if let Some((parents, _)) = path.rsplit_once('/') {
+            let mut prefix = String::new();
+            for component in parents.split('/') {
+                if !prefix.is_empty() {
+                    prefix.push('/');
+                }
+                prefix.push_str(component);
+                directories.push(prefix.clone());
+            }
+        }
+
+        for directory in directories {
+            let budget = budgets
+                .entry(directory.clone())
+                .or_insert_with(|| DirectoryBudget {
+                    directory,
+                    ..DirectoryBudget::default()
+                });
+            budget.files += 1;
+            budget.words += words;
+            budget.tokens += tokens;
+        }
+    }
+
+    // Sort by path components, so `docs/api` comes before `docs-old`
+    let mut budgets: Vec<DirectoryBudget> = budgets.into_values().collect();
+    budgets.sort_by(|a, b| {
+        let components = |budget: &DirectoryBudget| -> Vec<String> {
+            match budget.directory.as_str() {
+                ROOT => Vec::new(),
+                directory => directory.split('/').map(str::to_string).collect(),
+            }
+        };
+        components(a).cmp(&components(b))
+    });
+    budgets
+}
+
+/// Budgets as an aligned table, subdirectories indented below their parent
+pub fn budget_table(budgets: &[DirectoryBudget]) -> String {
+    let label = |budget: &DirectoryBudget| match budget.directory.as_str() {
+        ROOT => ROOT.to_string(),
+        directory => {
+            let depth = directory.matches('/').count() + 1;
+            let name = directory.rsplit('/').next().unwrap_or(directory);
+            format!("{}{}/", "  ".repeat(depth), name)
+        }
+    };
+    let width = budgets
+        .iter()
+        .map(|budget| label(budget).chars().count())
+        .chain(["Directory".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut table = format!(
+        "{:<width$}  {:>7}  {:>10}  {:>10}\n",
+        "Directory", "Files", "Words", "Tokens"
+    );
+    for budget in budgets {
+        table.push_str(&format!(
+            "{:<width$}  {:>7}  {:>10}  {:>10}\n",
+            label(budget),
+            budget.files,
+            budget.words,
+            budget.tokens
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::write_test_documents;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_directory_budgets() {
+        let temp_dir = TempDir::new().unwrap();
+        let documents = write_test_documents(
+            temp_dir.path(),
+            &[
+                ("README.md", "# Project\n\nOne two three."),
+                ("docs/guide.md", "Five words in this guide."),
+                ("docs/api/endpoints.md", "Two words"),
+                ("docs-old/notes.md", "Old"),
+            ],
+        );
+
+        let budgets = directory_budgets(&documents);
+        let rows: Vec<(&str, usize, usize)> = budgets
+            .iter()
+            .map(|budget| (budget.directory.as_str(), budget.files, budget.words))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (".", 4, 13),
+                ("docs", 2, 7),
+                ("docs/api", 1, 2),
+                ("docs-old", 1, 1)
+            ]
+        );
+        // Four characters per token: 25 and 9 characters
+        assert_eq!(budgets[1].tokens, 7 + 3);
+
+        assert_eq!(
+            budget_table(&budgets[..3]),
+            "Directory    Files       Words      Tokens\n\
+             .                4          13          18\n  \
+             docs/          2           7          10\n    \
+             api/         1           2           3\n"
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use crate::config::SortOrder;
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::markdown::{self, is_markdown_extension};
+use crate::scanner::front_matter;
+use crate::scanner::notebook::{self, Notebook};
+use crate::scanner::DocumentFile;
+use regex::Regex;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Levels document headings are pushed down by: the bundle title is `#`
+/// and each file is a `##` section
+const HEADING_SHIFT: usize = 2;
+
+/// One extracted document in the bundle
+#[derive(Debug, Clone)]
+pub struct BundleSection {
+    pub path: String,
+    pub title: String,
+    pub anchor: String,
+    pub content: String,
+}
+
+/// All documents concatenated into one Markdown file (`--bundle`), with a
+/// table of contents and a section per file
+#[derive(Debug, Clone, Default)]
+pub struct Bundle {
+    pub sections: Vec<BundleSection>,
+}
+
+fn atx_heading_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(\s{0,3})(#{1,6})(\s+.*)?$").expect("valid ATX heading regex"))
+}
+
+impl Bundle {
+    pub fn build(documents: &[DocumentFile], sort_order: SortOrder) -> Self {
+        Self::with_heading_shift(documents, sort_order, HEADING_SHIFT)
+    }
+
+    /// Build the sections with document headings pushed down by
+    /// `heading_shift` levels
+    pub(crate) fn with_heading_shift(
+        documents: &[DocumentFile],
+        sort_order: SortOrder,
+        heading_shift: usize,
+    ) -> Self {
+        let mut sorted: Vec<&DocumentFile> = documents.iter().collect();
+        sorted.sort_by(|a, b| sort_order.compare(a, b));
+
+        let mut anchors = HashSet::new();
+        let sections = sorted
+            .into_iter()
+            .filter_map(|doc| {
+                let path = doc.relative_path.to_string_lossy().replace('\\', "/");
+                let content = section_content(doc, heading_shift)?;
+                let base = format!("file-{}", markdown::slugify(&path.replace(['/', '.'], " ")));
+                let mut anchor = base.clone();
+                let mut n = 1;
+                while !anchors.insert(anchor.clone()) {
+                    anchor = format!("{}-{}", base, n);
+                    n += 1;
+                }
+
+                Some(BundleSection {
+                    path,
+                    title: doc.title().to_string(),
+                    anchor,
+                    content,
+                })
+            })
+            .collect();
+
+        Self { sections }
+    }
+
+    /// Write the bundle to `path`, creating its parent directory if needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(RepoDocsError::Io)?;
+        }
+        let mut file = BufWriter::new(fs::File::create(path).map_err(RepoDocsError::Io)?);
+
+        writeln!(file, "# Documentation Bundle")?;
+        writeln!(file)?;
+        writeln!(
+            file,
+            "Generated by RepoDocs on {} from {} documents.",
+            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+            self.sections.len()
+        )?;
+        writeln!(file)?;
+
+        writeln!(file, "## Contents")?;
+        writeln!(file)?;
+        for section in &self.sections {
+            writeln!(
+                file,
+                "- [{}](#{}) (`{}`)",
+                section.title, section.anchor, section.path
+            )?;
+        }
+        writeln!(file)?;
+
+        for section in &self.sections {
+            writeln!(file, "---")?;
+            writeln!(file)?;
+            writeln!(file, "<a id=\"{}\"></a>", section.anchor)?;
+            writeln!(file)?;
+            writeln!(file, "## {}", section.title)?;
+            writeln!(file)?;
+            writeln!(file, "_Source: `{}`_", section.path)?;
+            writeln!(file)?;
+            writeln!(file, "{}", section.content.trim_end())?;
+            writeln!(file)?;
+        }
+
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// Body of a document's section: Markdown (and notebooks, through their
+/// markdown cells) with headings pushed down and front matter removed,
+/// anything else inside a code fence. Binary files are left out.
+fn section_content(doc: &DocumentFile, heading_shift: usize) -> Option<String> {
+    if notebook::has_notebook_extension(&doc.relative_path) {
+        let markdown = Notebook::load(&doc.source_path).ok()?.to_markdown();
+        return Some(shift_headings(&markdown, heading_shift));
+    }
+
+    let bytes = fs::read(&doc.source_path).ok()?;
+    let content = String::from_utf8(bytes).ok()?;
+    if is_markdown_extension(&doc.extension) || doc.extension == "mdx" {
+        return Some(shift_headings(front_matter::strip(&content), heading_shift));
+    }
+
+    // A fence longer than any backtick run in the content cannot be closed early
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    Some(format!(
+        "{}{}\n{}\n{}",
+        fence,
+        doc.extension,
+        content.trim_end(),
+        fence
+    ))
+}
+
+/// Push ATX and setext headings down by `shift` levels (at most to `######`),
+/// leaving fenced code blocks alone. Setext headings become ATX headings.
+fn shift_headings(content: &str, shift: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_fence = false;
+    // Whether the last line pushed could be the text of a setext heading
+    let mut paragraph_line = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            paragraph_line = false;
+            lines.push(line.to_string());
+            continue;
+        }
+        if in_fence {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        if let Some(caps) = atx_heading_regex().captures(line) {
+            let level = (caps[2].len() + shift).min(6);
+            lines.push(format!(
+                "{}{}{}",
+                &caps[1],
+                "#".repeat(level),
+                caps.get(3).map_or("", |rest| rest.as_str())
+            ));
+            paragraph_line = false;
+            continue;
+        }
+
+        let underline = !trimmed.is_empty()
+            && (trimmed.chars().all(|c| c == '=') || trimmed.chars().all(|c| c == '-'));
+        if paragraph_line && underline {
+            let text = lines.pop().unwrap_or_default();
+            let level = if trimmed.starts_with('=') { 1 } else { 2 };
+            lines.push(format!(
+                "{} {}",
+                "#".repeat((level + shift).min(6)),
+                text.trim()
+            ));
+            paragraph_line = false;
+            continue;
+        }
+
+        paragraph_line = !trimmed.is_empty() && !line.starts_with([' ', '\t']);
+        lines.push(line.to_string());
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::write_test_documents;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_shift_headings() {
+        let content =
+            "# Guide\n\nIntro\n\nSetup\n-----\n\n```sh\n# not a heading\n```\n\n##### Deep\n";
+        assert_eq!(
+            shift_headings(content, 2),
+            "### Guide\n\nIntro\n\n#### Setup\n\n```sh\n# not a heading\n```\n\n###### Deep"
+        );
+        // A line of dashes after a blank line is a thematic break
+        assert_eq!(shift_headings("Text\n\n---\n", 2), "Text\n\n---");
+    }
+
+    #[test]
+    fn test_bundle_build_and_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = [
+            ("README.md", &b"---\ntitle: Project\n---\n# Project\n"[..]),
+            ("docs/notes.txt", b"Plain ``` notes"),
+            ("logo.md", &[0x89, 0x50, 0xff]),
+        ];
+        let documents = write_test_documents(temp_dir.path(), &files);
+
+        let bundle = Bundle::build(&documents, SortOrder::Path);
+        assert_eq!(bundle.sections.len(), 2);
+        assert_eq!(bundle.sections[0].anchor, "file-readme-md");
+        assert_eq!(bundle.sections[0].title, "Project");
+        assert_eq!(bundle.sections[0].content, "### Project");
+        assert_eq!(bundle.sections[1].content, "````txt\nPlain ``` notes\n````");
+
+        let path = temp_dir.path().join("out/merged.md");
+        bundle.save(&path).unwrap();
+        let merged = fs::read_to_string(path).unwrap();
+        assert!(merged.contains("- [Project](#file-readme-md) (`README.md`)"));
+        assert!(merged.contains("<a id=\"file-docs-notes-txt\"></a>\n\n## notes.txt\n"));
+    }
+}
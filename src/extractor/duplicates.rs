@@ -0,0 +1,203 @@
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::manifest::{self, Manifest, ManifestEntry};
+use crate::scanner::DocumentFile;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Byte-identical files, such as a LICENSE mirrored into every package
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    /// BLAKE3 hash of the shared content
+    pub hash: String,
+    pub size: u64,
+    /// Repository path of the copy that is kept, the first in path order
+    pub kept: String,
+    /// Repository paths of the other copies
+    pub duplicates: Vec<String>,
+}
+
+/// Group the files of an extraction by content hash. Empty files are left
+/// out, as are files only extracted once.
+pub fn find(entries: &[ManifestEntry]) -> Vec<DuplicateGroup> {
+    group(
+        entries
+            .iter()
+            .map(|entry| (entry.path.clone(), entry.size, entry.hash.clone())),
+    )
+}
+
+/// Drop all but the first of each set of byte-identical `documents`,
+/// returning the documents to extract and the duplicates left out
+pub fn remove(documents: Vec<DocumentFile>) -> Result<(Vec<DocumentFile>, Vec<DuplicateGroup>)> {
+    let mut hashed = Vec::with_capacity(documents.len());
+    for document in &documents {
+        if document.size > 0 {
+            hashed.push((
+                repository_path(document),
+                document.size,
+                manifest::hash_file(&document.source_path)?,
+            ));
+        }
+    }
+
+    let groups = group(hashed.into_iter());
+    let dropped: HashSet<&str> = groups
+        .iter()
+        .flat_map(|group| group.duplicates.iter().map(String::as_str))
+        .collect();
+    let kept = documents
+        .into_iter()
+        .filter(|document| !dropped.contains(repository_path(document).as_str()))
+        .collect();
+    Ok((kept, groups))
+}
+
+/// Replace the outputs of duplicates with hard links to the output of the
+/// copy that is kept, where both outputs are still identical; rewritten
+/// links can make them differ. Returns the number of bytes saved.
+pub fn hardlink(groups: &[DuplicateGroup], manifest: &Manifest, output_root: &Path) -> Result<u64> {
+    let outputs = manifest.by_path();
+    let mut saved = 0;
+
+    for group in groups {
+        let Some(kept) = outputs.get(group.kept.as_str()) else {
+            continue;
+        };
+        let kept_path = output_root.join(&kept.extracted);
+        let Ok(kept_content) = fs::read(&kept_path) else {
+            continue;
+        };
+
+        for duplicate in &group.duplicates {
+            let Some(entry) = outputs.get(duplicate.as_str()) else {
+                continue;
+            };
+            let path = output_root.join(&entry.extracted);
+            if fs::read(&path).ok().as_ref() != Some(&kept_content) {
+                continue;
+            }
+            fs::remove_file(&path).map_err(RepoDocsError::Io)?;
+            if fs::hard_link(&kept_path, &path).is_err() {
+                // Filesystems without hard links get the copy back
+                fs::write(&path, &kept_content).map_err(RepoDocsError::Io)?;
+                continue;
+            }
+            saved += kept_content.len() as u64;
+        }
+    }
+
+    Ok(saved)
+}
+
+fn group(files: impl Iterator<Item = (String, u64, String)>) -> Vec<DuplicateGroup> {
+    let mut by_hash: BTreeMap<String, (u64, Vec<String>)> = BTreeMap::new();
+    for (path, size, hash) in files.filter(|(_, size, _)| *size > 0) {
+        by_hash
+            .entry(hash)
+            .or_insert((size, Vec::new()))
+            .1
+            .push(path);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_iter()
+        .filter(|(_, (_, paths))| paths.len() > 1)
+        .map(|(hash, (size, mut paths))| {
+            paths.sort();
+            let kept = paths.remove(0);
+            DuplicateGroup {
+                hash,
+                size,
+                kept,
+                duplicates: paths,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| a.kept.cmp(&b.kept));
+    groups
+}
+
+fn repository_path(document: &DocumentFile) -> String {
+    document.relative_path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::write_test_documents;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_remove_duplicates() {
+        let repo = TempDir::new().unwrap();
+        let documents = write_test_documents(
+            repo.path(),
+            &[
+                ("packages/b/LICENSE.md", "MIT"),
+                ("LICENSE.md", "MIT"),
+                ("packages/a/LICENSE.md", "MIT"),
+                ("README.md", "# Project"),
+                ("empty.md", ""),
+                ("blank.md", ""),
+            ],
+        );
+
+        let (kept, groups) = remove(documents).unwrap();
+        let kept: Vec<&Path> = kept.iter().map(|d| d.relative_path.as_path()).collect();
+        assert_eq!(
+            kept,
+            [
+                Path::new("LICENSE.md"),
+                Path::new("README.md"),
+                Path::new("empty.md"),
+                Path::new("blank.md"),
+            ]
+        );
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].kept, "LICENSE.md");
+        assert_eq!(
+            groups[0].duplicates,
+            ["packages/a/LICENSE.md", "packages/b/LICENSE.md"]
+        );
+        assert_eq!(
+            groups[0].hash,
+            manifest::hash_file(&repo.path().join("LICENSE.md")).unwrap()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hardlink_identical_outputs() {
+        use std::os::unix::fs::MetadataExt;
+
+        let output = TempDir::new().unwrap();
+        let documents = write_test_documents(
+            output.path(),
+            &[("a.md", "same"), ("b.md", "same"), ("c.md", "same")],
+        );
+        let manifest = Manifest {
+            commit: None,
+            settings: None,
+            entries: documents
+                .iter()
+                .map(|d| ManifestEntry::for_document(d, repository_path(d), None).unwrap())
+                .collect(),
+        };
+        // Output of c.md differs, as if its links had been rewritten
+        fs::write(output.path().join("c.md"), "changed").unwrap();
+
+        let groups = find(&manifest.entries);
+        assert_eq!(groups[0].duplicates, ["b.md", "c.md"]);
+        assert_eq!(hardlink(&groups, &manifest, output.path()).unwrap(), 4);
+
+        let inode = |name: &str| fs::metadata(output.path().join(name)).unwrap().ino();
+        assert_eq!(inode("a.md"), inode("b.md"));
+        assert_ne!(inode("a.md"), inode("c.md"));
+        assert_eq!(
+            fs::read_to_string(output.path().join("b.md")).unwrap(),
+            "same"
+        );
+    }
+}
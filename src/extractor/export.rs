@@ -0,0 +1,510 @@
+use crate::config::ExportFormat;
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::manifest::Manifest;
+use crate::extractor::markdown;
+use crate::scanner::front_matter::{self, yaml_string};
+use crate::scanner::DocumentFile;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A document laid out for a site generator
+#[derive(Debug, Clone)]
+pub struct Chapter {
+    /// Path relative to the repository root
+    pub source: PathBuf,
+    /// Path relative to the generator's content directory, always `.md`
+    pub path: PathBuf,
+    pub title: String,
+    pub content: String,
+}
+
+impl Chapter {
+    fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    }
+
+    /// Whether the chapter introduces its directory (`README.md` or `index.md`)
+    fn is_directory_index(&self) -> bool {
+        matches!(self.file_name().as_str(), "readme.md" | "index.md")
+    }
+}
+
+/// Chapters grouped by directory, as site generators nest their navigation
+#[derive(Debug, Default)]
+struct ChapterTree<'a> {
+    /// The directory's `README.md` or `index.md`
+    index: Option<&'a Chapter>,
+    chapters: Vec<&'a Chapter>,
+    directories: BTreeMap<String, ChapterTree<'a>>,
+}
+
+impl<'a> ChapterTree<'a> {
+    fn build(chapters: &'a [Chapter]) -> Self {
+        let mut root = ChapterTree::default();
+        for chapter in chapters {
+            let mut tree = &mut root;
+            if let Some(parent) = chapter.path.parent() {
+                for component in parent.components() {
+                    let name = component.as_os_str().to_string_lossy().to_string();
+                    tree = tree.directories.entry(name).or_default();
+                }
+            }
+            if chapter.is_directory_index() && tree.index.is_none() {
+                tree.index = Some(chapter);
+            } else {
+                tree.chapters.push(chapter);
+            }
+        }
+        root
+    }
+}
+
+/// The extracted documents laid out as the source tree of a static site
+/// generator (`--export`), in a directory of the output named after it
+#[derive(Debug, Clone)]
+pub struct SiteExport {
+    pub format: ExportFormat,
+    pub chapters: Vec<Chapter>,
+}
+
+impl SiteExport {
+    /// Chapters for the extracted files listed in `manifest`, read from
+    /// `output_dir`. Markdown files get an `.md` extension; their front
+    /// matter gives the chapter title and is kept as YAML for generators
+    /// that read it; other text files become a chapter holding them in a
+    /// code block. Links between documents follow the new names.
+    pub fn build(
+        format: ExportFormat,
+        documents: &[DocumentFile],
+        manifest: &Manifest,
+        output_dir: &Path,
+    ) -> Self {
+        let titles: HashMap<String, &str> = documents
+            .iter()
+            .filter_map(|doc| {
+                let title = doc.front_matter.title.as_deref()?;
+                Some((
+                    doc.relative_path.to_string_lossy().replace('\\', "/"),
+                    title,
+                ))
+            })
+            .collect();
+
+        // mdBook shows front matter as text; the others read it
+        let keep_front_matter = format != ExportFormat::Mdbook;
+
+        let paths: HashMap<PathBuf, PathBuf> = manifest
+            .entries
+            .iter()
+            .map(|entry| (PathBuf::from(&entry.path), chapter_path(&entry.extracted)))
+            .collect();
+
+        let mut chapters: Vec<Chapter> = manifest
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let content = fs::read_to_string(output_dir.join(&entry.extracted)).ok()?;
+                let source = PathBuf::from(&entry.path);
+                let path = chapter_path(&entry.extracted);
+                let is_markdown = markdown::is_markdown_path(&entry.extracted);
+                let body = if is_markdown {
+                    front_matter::strip(&content)
+                } else {
+                    ""
+                };
+                // The front matter title, else the first top-level heading
+                let title = titles
+                    .get(&entry.path)
+                    .map(|title| title.to_string())
+                    .or_else(|| {
+                        markdown::extract_headings(body)
+                            .into_iter()
+                            .find(|heading| heading.level == 1)
+                            .map(|heading| heading.text)
+                    })
+                    .unwrap_or_else(|| file_name(&entry.path));
+
+                let content = if is_markdown {
+                    let body = markdown::rewrite_links(body, |target| {
+                        markdown::relink(target, &source, &path, &paths)
+                    });
+                    if keep_front_matter {
+                        format!("{}{}", yaml_front_matter(&content), body)
+                    } else {
+                        body
+                    }
+                } else {
+                    let extension = Path::new(&entry.extracted)
+                        .extension()
+                        .map(|ext| ext.to_string_lossy().to_lowercase())
+                        .unwrap_or_default();
+                    format!("# {}\n\n{}", title, fenced(&content, &extension))
+                };
+
+                Some(Chapter {
+                    source,
+                    path,
+                    title,
+                    content,
+                })
+            })
+            .collect();
+
+        chapters.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { format, chapters }
+    }
+
+    /// Directory of the output the export is written to
+    pub fn directory(&self, output_dir: &Path) -> PathBuf {
+        output_dir.join(match self.format {
+            ExportFormat::Mdbook => "mdbook",
+            ExportFormat::Mkdocs => "mkdocs",
+            ExportFormat::Docusaurus => "docusaurus",
+        })
+    }
+
+    /// Write the export into `output_dir`, titling the site `title`.
+    /// Returns the directory written to.
+    pub fn save(&self, output_dir: &Path, title: &str) -> Result<PathBuf> {
+        let root = self.directory(output_dir);
+        match self.format {
+            ExportFormat::Mdbook => self.save_mdbook(&root, title)?,
+            ExportFormat::Mkdocs => self.save_mkdocs(&root, title)?,
+            ExportFormat::Docusaurus => self.save_docusaurus(&root)?,
+        }
+        Ok(root)
+    }
+
+    fn write_chapters(&self, content_dir: &Path) -> Result<()> {
+        for chapter in &self.chapters {
+            let destination = content_dir.join(&chapter.path);
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent).map_err(RepoDocsError::Io)?;
+            }
+            fs::write(&destination, &chapter.content).map_err(RepoDocsError::Io)?;
+        }
+        Ok(())
+    }
+
+    /// `book.toml` and `src/` with a `SUMMARY.md` mirroring the directory
+    /// tree, ready for `mdbook build`
+    fn save_mdbook(&self, root: &Path, title: &str) -> Result<()> {
+        let src = root.join("src");
+        fs::create_dir_all(&src).map_err(RepoDocsError::Io)?;
+        self.write_chapters(&src)?;
+
+        let book = format!(
+            "[book]\ntitle = {}\nlanguage = \"en\"\nsrc = \"src\"\n\n[build]\ncreate-missing = false\n",
+            toml::Value::String(title.to_string())
+        );
+        fs::write(root.join("book.toml"), book).map_err(RepoDocsError::Io)?;
+
+        let tree = ChapterTree::build(&self.chapters);
+        let mut summary = String::from("# Summary\n\n");
+        // The root README is the unnumbered introduction
+        if let Some(index) = tree.index {
+            summary.push_str(&format!("{}\n\n", summary_link(index)));
+        }
+        write_summary_entries(&tree, 0, &mut summary);
+        fs::write(src.join("SUMMARY.md"), summary).map_err(RepoDocsError::Io)?;
+        Ok(())
+    }
+
+    /// `mkdocs.yml` with a navigation mirroring the directory tree, and `docs/`
+    fn save_mkdocs(&self, root: &Path, title: &str) -> Result<()> {
+        let docs = root.join("docs");
+        fs::create_dir_all(&docs).map_err(RepoDocsError::Io)?;
+        self.write_chapters(&docs)?;
+
+        let mut config = format!("site_name: {}\ndocs_dir: docs\nnav:\n", yaml_string(title));
+        write_mkdocs_nav(&ChapterTree::build(&self.chapters), 1, &mut config);
+        fs::write(root.join("mkdocs.yml"), config).map_err(RepoDocsError::Io)?;
+        Ok(())
+    }
+
+    /// `docs/` and a `sidebars.js` with a category per directory, to copy
+    /// into a Docusaurus site
+    fn save_docusaurus(&self, root: &Path) -> Result<()> {
+        let docs = root.join("docs");
+        fs::create_dir_all(&docs).map_err(RepoDocsError::Io)?;
+        self.write_chapters(&docs)?;
+
+        let tree = ChapterTree::build(&self.chapters);
+        let mut items: Vec<serde_json::Value> = tree.index.map(doc_id).into_iter().collect();
+        items.extend(sidebar_items(&tree));
+        let sidebar =
+            serde_json::to_string_pretty(&serde_json::json!({ "docs": items })).map_err(|e| {
+                RepoDocsError::Config {
+                    message: format!("Failed to serialize Docusaurus sidebar: {}", e),
+                }
+            })?;
+
+        let script = format!(
+            "// Generated by RepoDocs\n\
+             /** @type {{import('@docusaurus/plugin-content-docs').SidebarsConfig}} */\n\
+             const sidebars = {};\n\nmodule.exports = sidebars;\n",
+            sidebar
+        );
+        fs::write(root.join("sidebars.js"), script).map_err(RepoDocsError::Io)?;
+        Ok(())
+    }
+}
+
+fn write_mkdocs_nav(tree: &ChapterTree, depth: usize, config: &mut String) {
+    let indent = "  ".repeat(depth);
+    let entry = |chapter: &Chapter| {
+        format!(
+            "{}- {}: {}\n",
+            indent,
+            yaml_string(&chapter.title),
+            yaml_string(&chapter.path.to_string_lossy().replace('\\', "/"))
+        )
+    };
+
+    if let Some(index) = tree.index {
+        config.push_str(&entry(index));
+    }
+    for chapter in &tree.chapters {
+        config.push_str(&entry(chapter));
+    }
+    for (name, directory) in &tree.directories {
+        config.push_str(&format!("{}- {}:\n", indent, yaml_string(name)));
+        write_mkdocs_nav(directory, depth + 2, config);
+    }
+}
+
+fn sidebar_items(tree: &ChapterTree) -> Vec<serde_json::Value> {
+    let mut items: Vec<serde_json::Value> = tree.chapters.iter().map(|c| doc_id(c)).collect();
+    for (name, directory) in &tree.directories {
+        let mut category = serde_json::json!({
+            "type": "category",
+            "label": name,
+            "items": sidebar_items(directory),
+        });
+        if let Some(index) = directory.index {
+            category["link"] = serde_json::json!({ "type": "doc", "id": doc_id(index) });
+        }
+        items.push(category);
+    }
+    items
+}
+
+/// Docusaurus document ID: the path in `docs/` without the `.md` extension
+fn doc_id(chapter: &Chapter) -> serde_json::Value {
+    let path = chapter.path.with_extension("");
+    serde_json::Value::String(path.to_string_lossy().replace('\\', "/"))
+}
+
+/// The front matter at the start of `content` as a YAML block: kept as
+/// written when it is YAML, rebuilt from its fields when it is TOML
+fn yaml_front_matter(content: &str) -> String {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let block = &content[..content.len() - front_matter::strip(content).len()];
+    if block.starts_with("+++") {
+        let parsed = front_matter::FrontMatter::parse(block);
+        if parsed.is_empty() {
+            return String::new();
+        }
+        return parsed.to_yaml();
+    }
+    block.to_string()
+}
+
+fn write_summary_entries(tree: &ChapterTree, depth: usize, summary: &mut String) {
+    let indent = "  ".repeat(depth);
+    for chapter in &tree.chapters {
+        summary.push_str(&format!("{}- {}\n", indent, summary_link(chapter)));
+    }
+    for (name, directory) in &tree.directories {
+        match directory.index {
+            Some(index) => summary.push_str(&format!("{}- {}\n", indent, summary_link(index))),
+            // A draft chapter: listed, but without a page of its own
+            None => summary.push_str(&format!("{}- [{}]()\n", indent, escape_link_text(name))),
+        }
+        write_summary_entries(directory, depth + 1, summary);
+    }
+}
+
+fn summary_link(chapter: &Chapter) -> String {
+    let path = chapter.path.to_string_lossy().replace('\\', "/");
+    let target = if path.contains(' ') {
+        format!("<{}>", path)
+    } else {
+        path
+    };
+    format!("[{}]({})", escape_link_text(&chapter.title), target)
+}
+
+fn escape_link_text(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]")
+}
+
+/// Chapter of an extracted file: Markdown files get an `.md` extension,
+/// other files have it appended so `guide.md` and `guide.rst` stay apart
+fn chapter_path(extracted: &str) -> PathBuf {
+    if markdown::is_markdown_path(extracted) {
+        Path::new(extracted).with_extension("md")
+    } else {
+        PathBuf::from(format!("{}.md", extracted))
+    }
+}
+
+fn file_name(path: &str) -> String {
+    path.rsplit('/').next().unwrap_or(path).to_string()
+}
+
+/// `content` in a code block whose fence cannot be closed early by it
+fn fenced(content: &str, language: &str) -> String {
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{}{}\n{}\n{}\n", fence, language, content.trim_end(), fence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::write_test_documents;
+    use tempfile::TempDir;
+
+    fn extract(output_dir: &Path, files: &[(&str, &str)]) -> (Vec<DocumentFile>, Manifest) {
+        let documents = write_test_documents(output_dir, files);
+        let manifest = Manifest::for_test_documents(&documents);
+        (documents, manifest)
+    }
+
+    #[test]
+    fn test_mdbook_export() {
+        let output_dir = TempDir::new().unwrap();
+        let (documents, manifest) = extract(
+            output_dir.path(),
+            &[
+                (
+                    "README.md",
+                    "# Project\n\nSee the [guide](docs/guide.markdown).\n",
+                ),
+                ("CHANGELOG.md", "# Changelog\n"),
+                (
+                    "docs/guide.markdown",
+                    "---\ntitle: User Guide\n---\n# Guide\n\n[Home](../README.md)\n",
+                ),
+                ("docs/api/README.md", "# API\n"),
+                ("docs/api/notes.rst", "Notes\n=====\n"),
+            ],
+        );
+
+        let export = SiteExport::build(
+            ExportFormat::Mdbook,
+            &documents,
+            &manifest,
+            output_dir.path(),
+        );
+        let root = export.save(output_dir.path(), "Project \"Docs\"").unwrap();
+        assert_eq!(root, output_dir.path().join("mdbook"));
+
+        let book = fs::read_to_string(root.join("book.toml")).unwrap();
+        assert!(book.contains("title = 'Project \"Docs\"'"));
+
+        let summary = fs::read_to_string(root.join("src/SUMMARY.md")).unwrap();
+        assert_eq!(
+            summary,
+            "# Summary\n\n\
+             [Project](README.md)\n\n\
+             - [Changelog](CHANGELOG.md)\n\
+             - [docs]()\n  \
+               - [User Guide](docs/guide.md)\n  \
+               - [API](docs/api/README.md)\n    \
+                 - [notes.rst](docs/api/notes.rst.md)\n"
+        );
+
+        let readme = fs::read_to_string(root.join("src/README.md")).unwrap();
+        assert!(readme.contains("[guide](docs/guide.md)"));
+        let guide = fs::read_to_string(root.join("src/docs/guide.md")).unwrap();
+        assert_eq!(guide, "# Guide\n\n[Home](../README.md)\n");
+        let notes = fs::read_to_string(root.join("src/docs/api/notes.rst.md")).unwrap();
+        assert_eq!(notes, "# notes.rst\n\n```rst\nNotes\n=====\n```\n");
+    }
+
+    #[test]
+    fn test_mkdocs_and_docusaurus_export() {
+        let output_dir = TempDir::new().unwrap();
+        let (documents, manifest) = extract(
+            output_dir.path(),
+            &[
+                (
+                    "README.md",
+                    "---\ntitle: Project\nslug: /\n---\n# Project\n",
+                ),
+                (
+                    "docs/guide.md",
+                    "+++\ntitle = \"Guide\"\n+++\n[Home](../README.md)\n",
+                ),
+                ("docs/api/index.md", "# API\n"),
+            ],
+        );
+
+        let mkdocs = SiteExport::build(
+            ExportFormat::Mkdocs,
+            &documents,
+            &manifest,
+            output_dir.path(),
+        );
+        let root = mkdocs.save(output_dir.path(), "Project Docs").unwrap();
+        let config = fs::read_to_string(root.join("mkdocs.yml")).unwrap();
+        assert_eq!(
+            config,
+            "site_name: \"Project Docs\"\ndocs_dir: docs\nnav:\n  \
+             - \"Project\": \"README.md\"\n  \
+             - \"docs\":\n      \
+               - \"Guide\": \"docs/guide.md\"\n      \
+               - \"api\":\n          \
+                 - \"API\": \"docs/api/index.md\"\n"
+        );
+        // Front matter is kept, TOML front matter as YAML
+        let readme = fs::read_to_string(root.join("docs/README.md")).unwrap();
+        assert!(readme.starts_with("---\ntitle: Project\nslug: /\n---\n# Project"));
+        let guide = fs::read_to_string(root.join("docs/docs/guide.md")).unwrap();
+        assert_eq!(guide, "---\ntitle: \"Guide\"\n---\n[Home](../README.md)\n");
+
+        let docusaurus = SiteExport::build(
+            ExportFormat::Docusaurus,
+            &documents,
+            &manifest,
+            output_dir.path(),
+        );
+        let root = docusaurus.save(output_dir.path(), "Project Docs").unwrap();
+        assert_eq!(root, output_dir.path().join("docusaurus"));
+        assert!(root.join("docs/docs/api/index.md").exists());
+        let sidebars = fs::read_to_string(root.join("sidebars.js")).unwrap();
+        let json = sidebars
+            .split_once("const sidebars = ")
+            .and_then(|(_, rest)| rest.split_once(";\n"))
+            .map(|(json, _)| json)
+            .unwrap();
+        let sidebar: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            sidebar,
+            serde_json::json!({
+                "docs": [
+                    "README",
+                    {
+                        "type": "category",
+                        "label": "docs",
+                        "items": [
+                            "docs/guide",
+                            {
+                                "type": "category",
+                                "label": "api",
+                                "items": [],
+                                "link": { "type": "doc", "id": "docs/api/index" }
+                            }
+                        ]
+                    }
+                ]
+            })
+        );
+    }
+}
@@ -1,9 +1,44 @@
+use crate::cloner::RepositoryInfo;
+use crate::config::{
+    NotebookMode, ReadmePromotion, SortOrder, SourceBanner, TransformConfig, UnicodeNormalization,
+};
+use crate::deadline::Deadline;
 use crate::error::{RepoDocsError, Result};
+use crate::extractor::assets;
+use crate::extractor::links::{LinkRewriter, UnresolvedLink};
+use crate::extractor::manifest::{
+    self, ChangeSummary, Manifest, ManifestEntry, MergeConflict, MergeSummary,
+};
+use crate::extractor::markdown;
+use crate::extractor::output_manager::format_bytes;
+use crate::extractor::renderer::escape_html;
+use crate::extractor::transform;
+use crate::scanner::front_matter;
+use crate::scanner::notebook::{self, Notebook};
 use crate::scanner::DocumentFile;
+use crate::status::Stage;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::io::{BufReader, BufWriter, Read, Write};
-use std::path::{Component, Path};
-use std::time::{Duration, Instant};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use unicode_normalization::UnicodeNormalization as _;
+
+/// Directory of the output holding the index pages of directories when the
+/// index is split into pages
+pub const INDEX_PAGES_DIR: &str = "_index";
+
+/// A document written somewhere other than its repository-relative path
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathMapping {
+    pub original: String,
+    pub extracted: String,
+}
 
 #[derive(Debug, Clone)]
 pub struct ExtractionProgress {
@@ -14,6 +49,76 @@ pub struct ExtractionProgress {
     pub current_file: Option<String>,
     pub start_time: Instant,
     pub errors: Vec<String>,
+    pub shortened_paths: Vec<PathMapping>,
+    pub case_collisions: Vec<PathMapping>,
+    pub normalized_paths: Vec<PathMapping>,
+    pub transliterated_paths: Vec<PathMapping>,
+    /// Relative links, found while rewriting links, whose targets do not exist
+    pub unresolved_links: Vec<UnresolvedLink>,
+    /// Repository paths of the assets copied along with the documents
+    pub assets: Vec<String>,
+    /// Repository paths of referenced assets over the size limit
+    pub oversized_assets: Vec<String>,
+    /// Content fingerprints of the extracted documents
+    pub manifest: Manifest,
+    /// Differences to the previous extraction, in incremental mode
+    pub changes: Option<ChangeSummary>,
+    /// What a merge into an existing output directory wrote and kept
+    pub merge: Option<MergeSummary>,
+    throughput: Throughput,
+}
+
+/// Shortest span over which a throughput sample is taken; copies of small
+/// files finish in microseconds and would make per-file rates meaningless
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Weight of the newest sample in the moving average
+const SMOOTHING: f64 = 0.3;
+
+/// Exponentially weighted moving averages of the copy rate, so the ETA
+/// follows the current pace instead of the run's overall average
+#[derive(Debug, Clone)]
+struct Throughput {
+    sample_start: Instant,
+    sample_bytes: u64,
+    sample_files: usize,
+    bytes_per_second: Option<f64>,
+    files_per_second: Option<f64>,
+}
+
+impl Throughput {
+    fn new(now: Instant) -> Self {
+        Self {
+            sample_start: now,
+            sample_bytes: 0,
+            sample_files: 0,
+            bytes_per_second: None,
+            files_per_second: None,
+        }
+    }
+
+    fn record(&mut self, bytes: u64, now: Instant) {
+        self.sample_bytes += bytes;
+        self.sample_files += 1;
+
+        let span = now.duration_since(self.sample_start);
+        if span < SAMPLE_INTERVAL {
+            return;
+        }
+
+        let seconds = span.as_secs_f64();
+        let smooth = |average: Option<f64>, sample: f64| {
+            Some(average.map_or(sample, |average| {
+                SMOOTHING * sample + (1.0 - SMOOTHING) * average
+            }))
+        };
+        self.bytes_per_second = smooth(self.bytes_per_second, self.sample_bytes as f64 / seconds);
+        self.files_per_second = smooth(self.files_per_second, self.sample_files as f64 / seconds);
+
+        self.sample_start = now;
+        self.sample_bytes = 0;
+        self.sample_files = 0;
+    }
 }
 
 impl ExtractionProgress {
@@ -26,6 +131,17 @@ impl ExtractionProgress {
             current_file: None,
             start_time: Instant::now(),
             errors: Vec::new(),
+            shortened_paths: Vec::new(),
+            case_collisions: Vec::new(),
+            normalized_paths: Vec::new(),
+            transliterated_paths: Vec::new(),
+            unresolved_links: Vec::new(),
+            assets: Vec::new(),
+            oversized_assets: Vec::new(),
+            manifest: Manifest::default(),
+            changes: None,
+            merge: None,
+            throughput: Throughput::new(Instant::now()),
         }
     }
 
@@ -33,6 +149,7 @@ impl ExtractionProgress {
         self.files_processed += 1;
         self.bytes_processed += bytes;
         self.current_file = Some(filename);
+        self.throughput.record(bytes, Instant::now());
     }
 
     pub fn add_error<S: Into<String>>(&mut self, error: S) {
@@ -51,20 +168,52 @@ impl ExtractionProgress {
         self.start_time.elapsed()
     }
 
+    /// Current copy rate in bytes per second, smoothed over recent samples;
+    /// the overall average until the first sample is complete
+    pub fn bytes_per_second(&self) -> f64 {
+        self.throughput
+            .bytes_per_second
+            .unwrap_or_else(|| average_rate(self.bytes_processed as f64, self.elapsed()))
+    }
+
+    /// Current copy rate in files per second, smoothed like `bytes_per_second`
+    pub fn files_per_second(&self) -> f64 {
+        self.throughput
+            .files_per_second
+            .unwrap_or_else(|| average_rate(self.files_processed as f64, self.elapsed()))
+    }
+
+    /// Time left at the current rate. Based on the bytes still to copy, so a
+    /// few large files at the end are not estimated like small ones; by
+    /// file count when sizes are unknown.
     pub fn estimated_remaining(&self) -> Duration {
         if self.files_processed == 0 {
             return Duration::from_secs(0);
         }
 
-        let elapsed = self.elapsed();
-        let rate = self.files_processed as f64 / elapsed.as_secs_f64();
-        let remaining_files = self.total_files - self.files_processed;
+        let remaining_bytes = self.total_bytes.saturating_sub(self.bytes_processed);
+        let remaining_files = self.total_files.saturating_sub(self.files_processed);
+        let byte_rate = self.bytes_per_second();
+        let file_rate = self.files_per_second();
 
-        if rate > 0.0 {
-            Duration::from_secs_f64(remaining_files as f64 / rate)
+        let seconds = if self.total_bytes > 0 && byte_rate > 0.0 {
+            remaining_bytes as f64 / byte_rate
+        } else if file_rate > 0.0 {
+            remaining_files as f64 / file_rate
         } else {
-            Duration::from_secs(0)
-        }
+            0.0
+        };
+
+        Duration::try_from_secs_f64(seconds).unwrap_or(Duration::ZERO)
+    }
+}
+
+fn average_rate(amount: f64, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds > 0.0 {
+        amount / seconds
+    } else {
+        0.0
     }
 }
 
@@ -72,6 +221,24 @@ pub struct FileOperations {
     preserve_structure: bool,
     force_overwrite: bool,
     buffer_size: usize,
+    max_path_length: Option<usize>,
+    unicode_normalization: UnicodeNormalization,
+    parallel: bool,
+    transliterate: bool,
+    previous_manifest: Option<Manifest>,
+    merge: bool,
+    readme_promotion: ReadmePromotion,
+    sort_order: SortOrder,
+    notebook_mode: Option<NotebookMode>,
+    transform: TransformConfig,
+    link_repository: Option<RepositoryInfo>,
+    /// Style of the note pointing Markdown documents back at their files
+    /// on the repository's web view
+    source_banner: Option<(SourceBanner, RepositoryInfo)>,
+    max_asset_size: Option<u64>,
+    index_page_size: Option<usize>,
+    deadline: Option<Deadline>,
+    running: Option<Arc<AtomicBool>>,
 }
 
 impl FileOperations {
@@ -80,6 +247,22 @@ impl FileOperations {
             preserve_structure: true,
             force_overwrite: false,
             buffer_size: 64 * 1024, // 64KB buffer
+            max_path_length: None,
+            unicode_normalization: UnicodeNormalization::Nfc,
+            parallel: false,
+            transliterate: false,
+            previous_manifest: None,
+            merge: false,
+            readme_promotion: ReadmePromotion::None,
+            sort_order: SortOrder::Path,
+            notebook_mode: None,
+            transform: TransformConfig::default(),
+            link_repository: None,
+            source_banner: None,
+            max_asset_size: None,
+            index_page_size: None,
+            deadline: None,
+            running: None,
         }
     }
 
@@ -98,54 +281,750 @@ impl FileOperations {
         self
     }
 
+    /// Shorten intermediate directory names of destination paths longer than
+    /// `max_length` characters instead of failing on them
+    pub fn with_max_path_length(mut self, max_length: Option<usize>) -> Self {
+        self.max_path_length = max_length;
+        self
+    }
+
+    /// Unicode normalization form applied to destination paths
+    pub fn with_unicode_normalization(mut self, normalization: UnicodeNormalization) -> Self {
+        self.unicode_normalization = normalization;
+        self
+    }
+
+    /// Transliterate non-ASCII path components to ASCII (`Ünïcödé` becomes
+    /// `Unicode`) for consumers that cannot handle Unicode paths
+    pub fn with_transliteration(mut self, transliterate: bool) -> Self {
+        self.transliterate = transliterate;
+        self
+    }
+
+    /// Naming of README files from subdirectories when the structure is
+    /// flattened, so they do not all collide on `README.md`
+    pub fn with_readme_promotion(mut self, promotion: ReadmePromotion) -> Self {
+        self.readme_promotion = promotion;
+        self
+    }
+
+    /// Order of the files listed under each directory of the index
+    pub fn with_sort_order(mut self, sort_order: SortOrder) -> Self {
+        self.sort_order = sort_order;
+        self
+    }
+
+    /// With `NotebookMode::Markdown`, notebooks are written as `.md` files
+    /// holding their markdown cells instead of being copied
+    pub fn with_notebook_mode(mut self, notebook_mode: Option<NotebookMode>) -> Self {
+        self.notebook_mode = notebook_mode;
+        self
+    }
+
+    /// Normalize Markdown files, rendered notebooks included, as they are
+    /// written instead of copying them unchanged
+    pub fn with_transform(mut self, transform: TransformConfig) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Rewrite relative links of Markdown files for the output layout:
+    /// links to extracted documents point at where they were written, links
+    /// to other files at `repository`'s web view, when it has one, and links
+    /// to missing files are recorded in the progress
+    pub fn with_link_rewriting(mut self, repository: Option<RepositoryInfo>) -> Self {
+        self.link_repository = repository;
+        self
+    }
+
+    /// Mark Markdown documents as copies of their files at `repository`'s
+    /// extracted commit, where it has a web view
+    pub fn with_source_banner(
+        mut self,
+        style: Option<SourceBanner>,
+        repository: &RepositoryInfo,
+    ) -> Self {
+        self.source_banner = style.map(|style| (style, repository.clone()));
+        self
+    }
+
+    /// Also copy the images and other assets that Markdown documents
+    /// reference, up to `max_size` bytes each, to where their links expect
+    /// them; `None` copies documents only
+    pub fn with_assets(mut self, max_size: Option<u64>) -> Self {
+        self.max_asset_size = max_size;
+        self
+    }
+
+    /// Split the index into pages of at most `page_size` files: directories
+    /// that do not fit in `_index.md` get pages of their own under
+    /// `_index/`; `None` writes a single `_index.md`
+    pub fn with_index_page_size(mut self, page_size: Option<usize>) -> Self {
+        self.index_page_size = page_size;
+        self
+    }
+
+    /// Stop copying and fail with a timeout once the deadline passes
+    pub fn with_deadline(mut self, deadline: Option<Deadline>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Stop copying and fail as cancelled once `running` is cleared, as a
+    /// stall watchdog does
+    pub fn with_running(mut self, running: Option<Arc<AtomicBool>>) -> Self {
+        self.running = running;
+        self
+    }
+
+    /// Hash of the settings that shape the extracted files beyond their
+    /// sources, recorded in the manifest so that incremental runs rewrite
+    /// every file when they change
+    fn settings_fingerprint(&self) -> String {
+        let settings = serde_json::json!({
+            "transform": self.transform,
+            // Rewritten links point at the web view of the branch
+            "link_repository": self
+                .link_repository
+                .as_ref()
+                .map(|repository| (&repository.url, &repository.default_branch)),
+            // Banners link to the file at the extracted commit
+            "source_banner": self.source_banner.as_ref().map(|(style, repository)| {
+                (style, &repository.url, &repository.default_branch, &repository.commit)
+            }),
+        });
+        blake3::hash(settings.to_string().as_bytes())
+            .to_hex()
+            .to_string()
+    }
+
+    fn cancelled(&self) -> bool {
+        self.running
+            .as_ref()
+            .is_some_and(|running| !running.load(Ordering::SeqCst))
+    }
+
+    /// Whether copying should stop before the next document
+    fn should_stop(&self) -> bool {
+        self.cancelled() || self.deadline.is_some_and(|deadline| deadline.is_expired())
+    }
+
+    /// Copy files on the rayon thread pool. Requires the `parallel` feature;
+    /// without it files are copied sequentially.
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Extract incrementally against the manifest of a previous extraction
+    /// into the same output directory: unchanged documents are skipped,
+    /// changed ones replaced and outputs of removed ones deleted
+    pub fn with_previous_manifest(mut self, manifest: Option<Manifest>) -> Self {
+        self.previous_manifest = manifest;
+        self
+    }
+
+    /// Merge into an existing output directory instead: outputs the previous
+    /// extraction wrote, if any, are replaced when their document changed,
+    /// other files are never overwritten or removed
+    pub fn with_merge(mut self, merge: bool) -> Self {
+        self.merge = merge;
+        self
+    }
+
     pub fn extract_files(
         &self,
         documents: &[DocumentFile],
         output_root: &Path,
         progress_callback: Option<&dyn Fn(&ExtractionProgress)>,
     ) -> Result<ExtractionProgress> {
+        // Assets are planned, copied and fingerprinted like documents
+        let assets = self
+            .max_asset_size
+            .map(|max_size| assets::find(documents, max_size))
+            .unwrap_or_default();
+        let with_assets;
+        let documents = if assets.files.is_empty() {
+            documents
+        } else {
+            with_assets = [documents, assets.files.as_slice()].concat();
+            with_assets.as_slice()
+        };
+
         let total_bytes = documents.iter().map(|d| d.size).sum();
         let mut progress = ExtractionProgress::new(documents.len(), total_bytes);
+        progress.assets = assets
+            .files
+            .iter()
+            .map(|asset| asset.relative_path.to_string_lossy().replace('\\', "/"))
+            .collect();
+        progress.oversized_assets = assets.oversized;
 
         // Create output directory if it doesn't exist
         if !output_root.exists() {
             fs::create_dir_all(output_root).map_err(RepoDocsError::Io)?;
         }
 
-        for document in documents {
+        let destinations = self.plan_destinations(documents, output_root);
+        let fingerprints = self.fingerprint_documents(documents, &destinations);
+        let links = self.link_repository.as_ref().map(|repository| {
+            let written = documents
+                .iter()
+                .zip(&destinations)
+                .map(|(document, destination)| {
+                    (document.relative_path.clone(), destination.path.clone())
+                })
+                .collect();
+            LinkRewriter::new(written, repository)
+        });
+
+        let mut conflicted = HashSet::new();
+        let pending = match &self.previous_manifest {
+            previous if self.merge => self.apply_merge(
+                previous.as_ref(),
+                documents,
+                &destinations,
+                &fingerprints,
+                output_root,
+                &mut progress,
+                &mut conflicted,
+            ),
+            Some(previous) => self.apply_previous_manifest(
+                previous,
+                documents,
+                &destinations,
+                &fingerprints,
+                output_root,
+                &mut progress,
+            )?,
+            None => (0..documents.len()).collect(),
+        };
+
+        let plan = CopyPlan {
+            destinations: &destinations,
+            output_root,
+            links: links.as_ref(),
+        };
+
+        #[cfg(feature = "parallel")]
+        if self.parallel {
+            self.copy_parallel(documents, &plan, &pending, &mut progress, progress_callback);
+        } else {
+            self.copy_sequential(documents, &plan, &pending, &mut progress, progress_callback);
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        self.copy_sequential(documents, &plan, &pending, &mut progress, progress_callback);
+
+        if let Some(deadline) = &self.deadline {
+            deadline.check(Stage::Extracting)?;
+        }
+        if self.cancelled() {
+            return Err(RepoDocsError::Cancelled);
+        }
+
+        // Outputs kept in a merge are not the documents', so are left out
+        progress.manifest.entries = fingerprints
+            .into_iter()
+            .zip(&destinations)
+            .enumerate()
+            .filter_map(|(index, (entry, destination))| {
+                let output = output_root.join(&destination.path);
+                let mut entry =
+                    entry.filter(|_| output.is_file() && !conflicted.contains(&index))?;
+                entry.sha256 = manifest::sha256_file(&output).ok();
+                Some(entry)
+            })
+            .collect();
+        progress.manifest.settings = Some(self.settings_fingerprint());
+
+        // Final progress update
+        if let Some(callback) = progress_callback {
+            callback(&progress);
+        }
+
+        Ok(progress)
+    }
+
+    fn copy_sequential(
+        &self,
+        documents: &[DocumentFile],
+        plan: &CopyPlan,
+        pending: &[usize],
+        progress: &mut ExtractionProgress,
+        progress_callback: Option<&dyn Fn(&ExtractionProgress)>,
+    ) {
+        for &index in pending {
+            if self.should_stop() {
+                break;
+            }
             if let Some(callback) = progress_callback {
-                callback(&progress);
+                callback(progress);
+            }
+
+            let (document, destination) = (&documents[index], &plan.destinations[index]);
+            let result =
+                self.copy_document(document, plan.output_root, &destination.path, plan.links);
+            record_copy(progress, document, destination, result);
+        }
+    }
+
+    /// Copy on the rayon pool while this thread aggregates results into
+    /// `progress` and drives the callback, which need not be thread-safe
+    #[cfg(feature = "parallel")]
+    fn copy_parallel(
+        &self,
+        documents: &[DocumentFile],
+        plan: &CopyPlan,
+        pending: &[usize],
+        progress: &mut ExtractionProgress,
+        progress_callback: Option<&dyn Fn(&ExtractionProgress)>,
+    ) {
+        use rayon::prelude::*;
+        use std::sync::mpsc;
+
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                pending.par_iter().for_each_with(sender, |sender, &index| {
+                    if self.should_stop() {
+                        return;
+                    }
+                    let result = self.copy_document(
+                        &documents[index],
+                        plan.output_root,
+                        &plan.destinations[index].path,
+                        plan.links,
+                    );
+                    // The receiver outlives all senders
+                    let _ = sender.send((index, result));
+                });
+            });
+
+            for (index, result) in receiver {
+                record_copy(
+                    progress,
+                    &documents[index],
+                    &plan.destinations[index],
+                    result,
+                );
+                if let Some(callback) = progress_callback {
+                    callback(progress);
+                }
             }
+        });
+
+        // Results arrive in completion order; keep the report deterministic
+        progress
+            .normalized_paths
+            .sort_by(|a, b| a.original.cmp(&b.original));
+        progress
+            .shortened_paths
+            .sort_by(|a, b| a.original.cmp(&b.original));
+        progress
+            .case_collisions
+            .sort_by(|a, b| a.original.cmp(&b.original));
+        progress
+            .transliterated_paths
+            .sort_by(|a, b| a.original.cmp(&b.original));
+        progress
+            .unresolved_links
+            .sort_by(|a, b| a.document.cmp(&b.document));
+        progress.errors.sort();
+    }
+
+    /// Work out where each document is written, relative to the output root.
+    /// Paths are Unicode-normalized, optionally transliterated to ASCII and
+    /// shortened when they exceed the length limit. Paths that differ only by
+    /// case or normalization clobber each other on macOS and Windows, so they
+    /// are renamed deterministically: the first path in sorted order keeps
+    /// its name, the others get a `~N` suffix.
+    fn plan_destinations(
+        &self,
+        documents: &[DocumentFile],
+        output_root: &Path,
+    ) -> Vec<PlannedDestination> {
+        let mut destinations: Vec<PlannedDestination> = documents
+            .iter()
+            .map(|document| {
+                let mut path = if self.preserve_structure {
+                    document.relative_path.clone()
+                } else {
+                    self.flattened_path(document)
+                };
+                if self.renders_notebook(document) {
+                    path.set_extension("md");
+                }
 
-            match self.copy_document(document, output_root) {
-                Ok(bytes_copied) => {
-                    progress.update_file(document.filename.clone(), bytes_copied);
+                let mut destination = match normalize_path(&path, self.unicode_normalization) {
+                    Some(normalized) => PlannedDestination {
+                        normalized: true,
+                        ..PlannedDestination::new(normalized)
+                    },
+                    None => PlannedDestination::new(path),
+                };
+
+                if self.transliterate {
+                    if let Some(ascii) = transliterate_path(&destination.path) {
+                        destination.path = ascii;
+                        destination.transliterated = true;
+                    }
+                }
+
+                if self.preserve_structure {
+                    if let Some(shortened) = self.max_path_length.and_then(|max_length| {
+                        shorten_path(output_root, &destination.path, max_length)
+                    }) {
+                        destination.path = shortened;
+                        destination.shortened = true;
+                    }
                 }
-                Err(e) => {
-                    let error_msg =
-                        format!("Failed to copy {}: {}", document.source_path.display(), e);
-                    progress.add_error(error_msg);
-                    // Continue with other files instead of failing completely
+
+                destination
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..documents.len()).collect();
+        order.sort_by(|&a, &b| documents[a].relative_path.cmp(&documents[b].relative_path));
+
+        // Every path claims its own name first, so renames never take a
+        // name that exists in the repository
+        let mut taken: HashMap<String, usize> = HashMap::new();
+        let mut colliding = Vec::new();
+        for index in order {
+            match taken.entry(case_key(&destinations[index].path)) {
+                Entry::Occupied(_) => colliding.push(index),
+                Entry::Vacant(entry) => {
+                    entry.insert(1);
                 }
             }
         }
 
-        // Final progress update
-        if let Some(callback) = progress_callback {
-            callback(&progress);
+        for index in colliding {
+            let original = destinations[index].path.clone();
+            let mut n = taken[&case_key(&original)];
+            let renamed = loop {
+                n += 1;
+                let candidate = with_suffix(&original, n);
+                if !taken.contains_key(&case_key(&candidate)) {
+                    break candidate;
+                }
+            };
+
+            taken.insert(case_key(&original), n);
+            taken.insert(case_key(&renamed), 1);
+            destinations[index].path = renamed;
+            destinations[index].renamed = true;
         }
 
-        Ok(progress)
+        destinations
+    }
+
+    /// Destination of a document when the structure is flattened: its file
+    /// name, or for READMEs below the root a name derived from the
+    /// directory they describe
+    fn flattened_path(&self, document: &DocumentFile) -> PathBuf {
+        let filename = PathBuf::from(&document.filename);
+        let is_readme = filename
+            .file_stem()
+            .is_some_and(|stem| stem.eq_ignore_ascii_case("readme"));
+        let Some(dir_name) = document
+            .relative_path
+            .parent()
+            .and_then(|parent| parent.file_name())
+            .filter(|_| is_readme)
+        else {
+            return filename;
+        };
+
+        match self.readme_promotion {
+            ReadmePromotion::None => filename,
+            ReadmePromotion::Rename => {
+                // Appended rather than set, as directory names may contain dots
+                let mut promoted = dir_name.to_os_string();
+                if let Some(extension) = filename.extension() {
+                    promoted.push(".");
+                    promoted.push(extension);
+                }
+                PathBuf::from(promoted)
+            }
+            ReadmePromotion::Directory => Path::new(dir_name).join(filename),
+        }
+    }
+
+    /// Content fingerprints for the manifest. Documents that cannot be read
+    /// get `None`; copying them reports the error.
+    fn fingerprint_documents(
+        &self,
+        documents: &[DocumentFile],
+        destinations: &[PlannedDestination],
+    ) -> Vec<Option<ManifestEntry>> {
+        let previous = self.previous_manifest.as_ref().map(Manifest::by_path);
+
+        documents
+            .iter()
+            .zip(destinations)
+            .map(|(document, destination)| {
+                let path = document.relative_path.to_string_lossy().replace('\\', "/");
+                let previous_entry = previous
+                    .as_ref()
+                    .and_then(|entries| entries.get(path.as_str()).copied());
+                ManifestEntry::for_document(
+                    document,
+                    destination.path.to_string_lossy().replace('\\', "/"),
+                    previous_entry,
+                )
+                .ok()
+            })
+            .collect()
+    }
+
+    /// Compare against the previous manifest: count unchanged documents as
+    /// extracted, delete stale outputs of modified and removed documents and
+    /// return the indices of the documents that still need copying
+    fn apply_previous_manifest(
+        &self,
+        previous: &Manifest,
+        documents: &[DocumentFile],
+        destinations: &[PlannedDestination],
+        fingerprints: &[Option<ManifestEntry>],
+        output_root: &Path,
+        progress: &mut ExtractionProgress,
+    ) -> Result<Vec<usize>> {
+        let previous_entries = previous.by_path();
+        // Files written with other settings are stale even if their
+        // sources are not
+        let settings_changed = previous.settings != Some(self.settings_fingerprint());
+        let mut changes = ChangeSummary::default();
+        let mut pending = Vec::new();
+        let mut kept = HashSet::new();
+        let mut stale = Vec::new();
+
+        for (index, document) in documents.iter().enumerate() {
+            let path = document.relative_path.to_string_lossy().replace('\\', "/");
+            let Some(entry) = previous_entries.get(path.as_str()) else {
+                changes.added.push(path);
+                pending.push(index);
+                continue;
+            };
+
+            let unchanged = !settings_changed
+                && fingerprints[index].as_ref().is_some_and(|current| {
+                    current.hash == entry.hash && current.extracted == entry.extracted
+                })
+                && output_root.join(&destinations[index].path).is_file();
+
+            if unchanged {
+                changes.unchanged += 1;
+                kept.insert(entry.extracted.as_str());
+                let copied = Copied {
+                    bytes: document.size,
+                    unresolved_links: Vec::new(),
+                };
+                record_copy(progress, document, &destinations[index], Ok(copied));
+            } else {
+                if markdown::is_markdown_path(&entry.extracted) {
+                    if let Ok(content) = fs::read_to_string(output_root.join(&entry.extracted)) {
+                        changes.previous_headings.insert(
+                            path.clone(),
+                            markdown::extract_headings(front_matter::strip(&content)),
+                        );
+                    }
+                }
+                changes.modified.push(path);
+                stale.push(entry.extracted.as_str());
+                pending.push(index);
+            }
+        }
+
+        let current: HashSet<String> = documents
+            .iter()
+            .map(|d| d.relative_path.to_string_lossy().replace('\\', "/"))
+            .collect();
+        for entry in &previous.entries {
+            if !current.contains(&entry.path) {
+                changes.removed.push(entry.path.clone());
+                stale.push(entry.extracted.as_str());
+            }
+        }
+
+        for extracted in stale {
+            if !kept.contains(extracted) {
+                remove_output(output_root, extracted)?;
+            }
+        }
+
+        changes.added.sort();
+        changes.modified.sort();
+        changes.removed.sort();
+        progress.changes = Some(changes);
+
+        Ok(pending)
+    }
+
+    /// Plan a merge into `output_root`, returning the documents to copy and
+    /// adding those whose outputs were added or edited by hand to `conflicted`
+    #[allow(clippy::too_many_arguments)]
+    fn apply_merge(
+        &self,
+        previous: Option<&Manifest>,
+        documents: &[DocumentFile],
+        destinations: &[PlannedDestination],
+        fingerprints: &[Option<ManifestEntry>],
+        output_root: &Path,
+        progress: &mut ExtractionProgress,
+        conflicted: &mut HashSet<usize>,
+    ) -> Vec<usize> {
+        let previous_outputs: HashMap<&str, &ManifestEntry> = previous
+            .map(|previous| {
+                previous
+                    .entries
+                    .iter()
+                    .map(|entry| (entry.extracted.as_str(), entry))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut summary = MergeSummary::default();
+        let mut pending = Vec::new();
+
+        for (index, document) in documents.iter().enumerate() {
+            let path = document.relative_path.to_string_lossy().replace('\\', "/");
+            let extracted = destinations[index]
+                .path
+                .to_string_lossy()
+                .replace('\\', "/");
+            let output = output_root.join(&destinations[index].path);
+            if !output.is_file() {
+                summary.written.push(path);
+                pending.push(index);
+                continue;
+            }
+
+            let current = fingerprints[index].as_ref();
+            let previous = previous_outputs.get(extracted.as_str());
+            // Outputs still as the previous extraction wrote them are its own
+            let written_before = previous
+                .and_then(|entry| entry.sha256.as_ref())
+                .is_some_and(|sha256| manifest::sha256_file(&output).ok().as_ref() == Some(sha256));
+            let unchanged = if written_before {
+                previous.zip(current).is_some_and(|(previous, current)| {
+                    previous.path == current.path && previous.hash == current.hash
+                })
+            } else {
+                current.is_some_and(|current| {
+                    manifest::hash_file(&output).ok().as_ref() == Some(&current.hash)
+                })
+            };
+
+            if unchanged {
+                summary.unchanged += 1;
+                let copied = Copied {
+                    bytes: document.size,
+                    unresolved_links: Vec::new(),
+                };
+                record_copy(progress, document, &destinations[index], Ok(copied));
+            } else if written_before {
+                summary.written.push(path);
+                pending.push(index);
+            } else {
+                summary.conflicts.push(MergeConflict {
+                    document: path,
+                    output: extracted,
+                });
+                conflicted.insert(index);
+            }
+        }
+
+        summary.written.sort();
+        summary.conflicts.sort_by(|a, b| a.output.cmp(&b.output));
+        progress.merge = Some(summary);
+        pending
     }
 
-    fn copy_document(&self, document: &DocumentFile, output_root: &Path) -> Result<u64> {
-        let _dest_path = if self.preserve_structure {
-            output_root.join(&document.relative_path)
+    fn copy_document(
+        &self,
+        document: &DocumentFile,
+        output_root: &Path,
+        destination: &Path,
+        links: Option<&LinkRewriter>,
+    ) -> Result<Copied> {
+        let dest_path = output_root.join(destination);
+
+        if let Some(parent) = dest_path.parent() {
+            fs::create_dir_all(parent).map_err(RepoDocsError::Io)?;
+        }
+
+        // Notebooks rendered as Markdown hold their markdown cells; Markdown
+        // files are read when they are transformed or their links rewritten,
+        // unless they are not UTF-8, and are otherwise copied as they are
+        let markdown = if self.renders_notebook(document) {
+            Some(Notebook::load(&document.source_path)?.to_markdown())
+        } else if (self.transform.is_enabled() || links.is_some() || self.source_banner.is_some())
+            && markdown::is_markdown_path(&document.relative_path.to_string_lossy())
+        {
+            fs::read_to_string(&document.source_path).ok()
         } else {
-            output_root.join(&document.filename)
+            None
+        };
+        let Some(mut markdown) = markdown else {
+            let bytes = self.secure_copy(&document.source_path, &dest_path)?;
+            return Ok(Copied {
+                bytes,
+                unresolved_links: Vec::new(),
+            });
         };
 
-        self.copy_preserving_structure(&document.source_path, output_root, &document.relative_path)
+        if self.transform.is_enabled() {
+            markdown = transform::apply(&markdown, &self.transform);
+        }
+        let mut unresolved_links = Vec::new();
+        if let Some(links) = links {
+            markdown = links.rewrite(document, destination, &markdown, &mut unresolved_links);
+        }
+        if let Some((style, repository)) = &self.source_banner {
+            let path = document.relative_path.to_string_lossy().replace('\\', "/");
+            if let Some(url) = repository.permalink(&path) {
+                markdown = transform::add_source_banner(
+                    &markdown,
+                    *style,
+                    &path,
+                    &url,
+                    repository.commit.as_deref(),
+                );
+            }
+        }
+
+        let bytes = self.write_markdown(&document.source_path, &dest_path, &markdown)?;
+        Ok(Copied {
+            bytes,
+            unresolved_links,
+        })
+    }
+
+    fn renders_notebook(&self, document: &DocumentFile) -> bool {
+        self.notebook_mode == Some(NotebookMode::Markdown)
+            && notebook::has_notebook_extension(&document.relative_path)
+    }
+
+    /// Write Markdown generated from `source` to `dest`, returning the
+    /// number of bytes written
+    fn write_markdown(&self, source: &Path, dest: &Path, markdown: &str) -> Result<u64> {
+        self.validate_destination_path(dest)?;
+        if dest.exists() && !self.force_overwrite && self.previous_manifest.is_none() {
+            return Err(RepoDocsError::OutputDirectoryExists {
+                path: dest.display().to_string(),
+            });
+        }
+
+        fs::write(dest, markdown).map_err(RepoDocsError::Io)?;
+
+        if let Ok(modified_time) = fs::metadata(source).and_then(|m| m.modified()) {
+            let _ =
+                filetime::set_file_mtime(dest, filetime::FileTime::from_system_time(modified_time));
+        }
+
+        Ok(markdown.len() as u64)
     }
 
     pub fn copy_preserving_structure(
@@ -191,43 +1070,17 @@ impl FileOperations {
         // Validate destination path
         self.validate_destination_path(dest)?;
 
-        // Security: Prevent overwriting existing files unless force is enabled
-        if dest.exists() && !self.force_overwrite {
+        // Security: Prevent overwriting existing files unless force is enabled;
+        // incremental runs and merges replace outputs of changed documents
+        if dest.exists() && !self.force_overwrite && self.previous_manifest.is_none() && !self.merge
+        {
             return Err(RepoDocsError::OutputDirectoryExists {
                 path: dest.display().to_string(),
             });
         }
 
         // Perform the copy operation
-        self.copy_file_with_buffer(source, dest)
-    }
-
-    fn copy_file_with_buffer(&self, source: &Path, dest: &Path) -> Result<u64> {
-        let source_file = fs::File::open(source).map_err(RepoDocsError::Io)?;
-
-        let dest_file = fs::File::create(dest).map_err(RepoDocsError::Io)?;
-
-        let mut reader = BufReader::with_capacity(self.buffer_size, source_file);
-        let mut writer = BufWriter::with_capacity(self.buffer_size, dest_file);
-
-        let mut total_bytes = 0u64;
-        let mut buffer = vec![0u8; 8192]; // 8KB chunks
-
-        loop {
-            let bytes_read = reader.read(&mut buffer).map_err(RepoDocsError::Io)?;
-
-            if bytes_read == 0 {
-                break; // End of file
-            }
-
-            writer
-                .write_all(&buffer[..bytes_read])
-                .map_err(RepoDocsError::Io)?;
-
-            total_bytes += bytes_read as u64;
-        }
-
-        writer.flush().map_err(RepoDocsError::Io)?;
+        let bytes_copied = self.copy_file(source, dest)?;
 
         // Set file modification time to match source
         if let Ok(source_metadata) = fs::metadata(source) {
@@ -239,17 +1092,45 @@ impl FileOperations {
             }
         }
 
-        Ok(total_bytes)
+        Ok(bytes_copied)
     }
 
-    fn validate_destination_path(&self, path: &Path) -> Result<()> {
-        let path_str = path.to_string_lossy();
-
-        // Security: Prevent directory traversal
-        if path.components().any(|c| matches!(c, Component::ParentDir)) {
-            return Err(RepoDocsError::InvalidPath {
-                path: format!("Directory traversal not allowed: {}", path_str),
-            });
+    /// Copy with the platform's accelerated copy (`copy_file_range` or
+    /// `sendfile` on Linux, `fclonefileat`/`fcopyfile` on macOS,
+    /// `CopyFileEx` on Windows), falling back to buffered IO where it is
+    /// unsupported, e.g. across some network filesystems
+    fn copy_file(&self, source: &Path, dest: &Path) -> Result<u64> {
+        match fs::copy(source, dest) {
+            Ok(bytes_copied) => Ok(bytes_copied),
+            Err(e) if matches!(e.kind(), ErrorKind::NotFound | ErrorKind::PermissionDenied) => {
+                Err(RepoDocsError::Io(e))
+            }
+            Err(_) => self.copy_file_with_buffer(source, dest),
+        }
+    }
+
+    fn copy_file_with_buffer(&self, source: &Path, dest: &Path) -> Result<u64> {
+        let source_file = fs::File::open(source).map_err(RepoDocsError::Io)?;
+
+        let dest_file = fs::File::create(dest).map_err(RepoDocsError::Io)?;
+
+        let mut reader = BufReader::with_capacity(self.buffer_size, source_file);
+        let mut writer = BufWriter::with_capacity(self.buffer_size, dest_file);
+
+        let total_bytes = io::copy(&mut reader, &mut writer).map_err(RepoDocsError::Io)?;
+        writer.flush().map_err(RepoDocsError::Io)?;
+
+        Ok(total_bytes)
+    }
+
+    fn validate_destination_path(&self, path: &Path) -> Result<()> {
+        let path_str = path.to_string_lossy();
+
+        // Security: Prevent directory traversal
+        if path.components().any(|c| matches!(c, Component::ParentDir)) {
+            return Err(RepoDocsError::InvalidPath {
+                path: format!("Directory traversal not allowed: {}", path_str),
+            });
         }
 
         // Security: Prevent very long paths
@@ -313,70 +1194,292 @@ impl FileOperations {
     }
 
     pub fn create_index_file(&self, documents: &[DocumentFile], output_dir: &Path) -> Result<()> {
-        let index_path = output_dir.join("_index.md");
-        let mut index_file = fs::File::create(&index_path).map_err(RepoDocsError::Io)?;
-
-        writeln!(index_file, "# Documentation Index")?;
-        writeln!(index_file)?;
-        writeln!(
-            index_file,
-            "Generated by RepoDocs on {}",
-            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
-        )?;
-        writeln!(index_file)?;
-
-        // Group files by directory
-        let mut files_by_dir: std::collections::BTreeMap<String, Vec<&DocumentFile>> =
-            std::collections::BTreeMap::new();
-
-        for doc in documents {
-            let dir = doc
+        let destinations = self.plan_destinations(documents, output_dir);
+        let mut links: HashMap<&Path, &Path> = HashMap::new();
+        let mut root = IndexSection::default();
+
+        for (doc, destination) in documents.iter().zip(&destinations) {
+            let directories: Vec<String> = doc
                 .relative_path
                 .parent()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|| ".".to_string());
+                .into_iter()
+                .flat_map(|parent| parent.components())
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .collect();
 
-            files_by_dir.entry(dir).or_default().push(doc);
+            root.insert(&directories, doc);
+            links.insert(&doc.relative_path, &destination.path);
         }
 
-        for (dir, files) in files_by_dir {
-            if dir != "." {
-                writeln!(index_file, "## {}/", dir)?;
-            } else {
-                writeln!(index_file, "## Root Directory")?;
+        let page_size = self.index_page_size.unwrap_or(usize::MAX);
+        self.write_index_page(output_dir, &[], &root, &links, page_size)
+    }
+
+    /// Write the index of the directory `directories`, listing at most
+    /// `page_size` files per page. Files of the directory itself continue on
+    /// numbered pages, and subdirectories that do not fit on the first page
+    /// get index pages of their own, linked from it.
+    fn write_index_page(
+        &self,
+        output_dir: &Path,
+        directories: &[&str],
+        section: &IndexSection,
+        links: &HashMap<&Path, &Path>,
+        page_size: usize,
+    ) -> Result<()> {
+        let mut files = section.files.clone();
+        files.sort_by(|a, b| self.sort_order.compare(a, b));
+        let chunks: Vec<&[&DocumentFile]> = if files.is_empty() {
+            vec![&[]]
+        } else {
+            files.chunks(page_size).collect()
+        };
+
+        // Subdirectories are listed in full while they fit on the first page
+        let mut room = page_size - chunks[0].len();
+        let mut subdirectories = Vec::new();
+        for (name, subsection) in self.ordered_sections(section) {
+            let paged = subsection.file_count > room;
+            if !paged {
+                room -= subsection.file_count;
             }
-            writeln!(index_file)?;
+            subdirectories.push((name.as_str(), subsection, paged));
+        }
 
-            for file in files {
-                let link_path = if self.preserve_structure {
-                    file.relative_path.to_string_lossy()
-                } else {
-                    file.filename.as_str().into()
-                };
+        for (number, chunk) in (1..).zip(&chunks) {
+            let page = index_page(directories, number);
+            let path = output_dir.join(&page);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(RepoDocsError::Io)?;
+            }
+            let mut out = BufWriter::new(fs::File::create(&path).map_err(RepoDocsError::Io)?);
 
+            if directories.is_empty() {
+                writeln!(out, "# Documentation Index")?;
+                writeln!(out)?;
                 writeln!(
-                    index_file,
-                    "- [{}]({}) ({} bytes)",
-                    file.filename,
-                    link_path.replace('\\', "/"), // Use forward slashes for markdown links
-                    file.size
+                    out,
+                    "Generated by RepoDocs on {}",
+                    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
                 )?;
+            } else {
+                writeln!(out, "# {}/", directories.join("/"))?;
+                writeln!(out)?;
+                writeln!(out, "{}", index_breadcrumb(directories, &page))?;
+            }
+            writeln!(out)?;
+
+            if chunks.len() > 1 {
+                let pages: Vec<String> = (1..=chunks.len())
+                    .map(|other| match other == number {
+                        true => other.to_string(),
+                        false => format!(
+                            "[{}]({})",
+                            other,
+                            markdown::relative_href(&page, &index_page(directories, other))
+                        ),
+                    })
+                    .collect();
+                writeln!(out, "Pages: {}", pages.join(" | "))?;
+                writeln!(out)?;
+            }
+
+            // Files of the directory come first, then one collapsible block
+            // per subdirectory holding a nested list of everything below it
+            if !chunk.is_empty() {
+                self.write_index_files(&mut out, chunk, &page, links, 0)?;
+                writeln!(out)?;
+            }
+
+            if number == 1 {
+                for (name, subsection, paged) in &subdirectories {
+                    if *paged {
+                        let subpage = index_page(&[directories, &[name]].concat(), 1);
+                        writeln!(
+                            out,
+                            "- [**{}/**]({}) ({})",
+                            name,
+                            markdown::relative_href(&page, &subpage),
+                            subsection.describe()
+                        )?;
+                        writeln!(out)?;
+                        continue;
+                    }
+                    writeln!(out, "<details open>")?;
+                    writeln!(
+                        out,
+                        "<summary><strong>{}/</strong> ({})</summary>",
+                        escape_html(name),
+                        subsection.describe()
+                    )?;
+                    writeln!(out)?;
+                    self.write_index_section(&mut out, subsection, &page, links, 0)?;
+                    writeln!(out)?;
+                    writeln!(out, "</details>")?;
+                    writeln!(out)?;
+                }
             }
-            writeln!(index_file)?;
+
+            writeln!(out, "---")?;
+            writeln!(out, "Total files: {}", section.file_count)?;
+            writeln!(out, "Total size: {} bytes", section.total_size)?;
+            out.flush()?;
         }
 
-        writeln!(index_file, "---")?;
-        writeln!(index_file, "Total files: {}", documents.len())?;
-        writeln!(
-            index_file,
-            "Total size: {} bytes",
-            documents.iter().map(|d| d.size).sum::<u64>()
-        )?;
+        for (name, subsection, paged) in subdirectories {
+            if paged {
+                let nested = [directories, &[name]].concat();
+                self.write_index_page(output_dir, &nested, subsection, links, page_size)?;
+            }
+        }
+        Ok(())
+    }
 
+    /// Subdirectories of `section` in the configured order; directories
+    /// are compared by their totals, falling back to their names
+    fn ordered_sections<'s, 'a>(
+        &self,
+        section: &'s IndexSection<'a>,
+    ) -> Vec<(&'s String, &'s IndexSection<'a>)> {
+        let mut sections: Vec<_> = section.sections.iter().collect();
+        match self.sort_order {
+            SortOrder::Size => sections.sort_by_key(|(_, section)| Reverse(section.total_size)),
+            SortOrder::Modified => sections.sort_by_key(|(_, section)| section.oldest),
+            SortOrder::Relevance => {
+                sections.sort_by(|(_, a), (_, b)| b.top_score.total_cmp(&a.top_score))
+            }
+            SortOrder::Path | SortOrder::Extension => {}
+        }
+        sections
+    }
+
+    fn write_index_section(
+        &self,
+        out: &mut impl Write,
+        section: &IndexSection,
+        page: &Path,
+        links: &HashMap<&Path, &Path>,
+        depth: usize,
+    ) -> io::Result<()> {
+        let mut files = section.files.clone();
+        files.sort_by(|a, b| self.sort_order.compare(a, b));
+        self.write_index_files(out, &files, page, links, depth)?;
+
+        let indent = "  ".repeat(depth);
+        for (name, subsection) in self.ordered_sections(section) {
+            writeln!(out, "{}- **{}/** ({})", indent, name, subsection.describe())?;
+            self.write_index_section(out, subsection, page, links, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    fn write_index_files(
+        &self,
+        out: &mut impl Write,
+        files: &[&DocumentFile],
+        page: &Path,
+        links: &HashMap<&Path, &Path>,
+        depth: usize,
+    ) -> io::Result<()> {
+        let indent = "  ".repeat(depth);
+        for file in files {
+            // Link to where extraction actually wrote the file
+            let destination = links
+                .get(file.relative_path.as_path())
+                .copied()
+                .unwrap_or(file.relative_path.as_path());
+
+            writeln!(
+                out,
+                "{}- [{}]({}) ({})",
+                indent,
+                file.title(),
+                markdown::relative_href(page, destination),
+                format_bytes(file.size)
+            )?;
+        }
         Ok(())
     }
 }
 
+/// Output path of page `number` of the index of `directories`: the root
+/// index is `_index.md`, everything else is under `_index/`
+fn index_page(directories: &[&str], number: usize) -> PathBuf {
+    if directories.is_empty() && number == 1 {
+        return PathBuf::from("_index.md");
+    }
+    let mut path = PathBuf::from(INDEX_PAGES_DIR);
+    path.extend(directories);
+    match number {
+        1 => path.join("_index.md"),
+        _ => path.join(format!("_index-{}.md", number)),
+    }
+}
+
+/// Links from `page` up to the index pages of the parent directories
+fn index_breadcrumb(directories: &[&str], page: &Path) -> String {
+    let mut crumbs = vec![format!(
+        "[Documentation Index]({})",
+        markdown::relative_href(page, &index_page(&[], 1))
+    )];
+    for depth in 1..directories.len() {
+        crumbs.push(format!(
+            "[{}]({})",
+            directories[depth - 1],
+            markdown::relative_href(page, &index_page(&directories[..depth], 1))
+        ));
+    }
+    crumbs.push(directories[directories.len() - 1].to_string());
+    crumbs.join(" / ")
+}
+
+/// A directory in the index, with totals covering everything below it
+#[derive(Default)]
+struct IndexSection<'a> {
+    files: Vec<&'a DocumentFile>,
+    sections: BTreeMap<String, IndexSection<'a>>,
+    file_count: usize,
+    total_size: u64,
+    oldest: Option<SystemTime>,
+    /// Score of the most relevant document in the section
+    top_score: f64,
+}
+
+impl<'a> IndexSection<'a> {
+    /// Add `document`, found under the nested `directories` of this section
+    fn insert(&mut self, directories: &[String], document: &'a DocumentFile) {
+        self.file_count += 1;
+        self.total_size += document.size;
+        self.oldest = Some(
+            self.oldest
+                .map_or(document.modified, |oldest| oldest.min(document.modified)),
+        );
+        self.top_score = self.top_score.max(document.score);
+
+        match directories.split_first() {
+            Some((directory, rest)) => self
+                .sections
+                .entry(directory.clone())
+                .or_default()
+                .insert(rest, document),
+            None => self.files.push(document),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} {}, {}",
+            self.file_count,
+            if self.file_count == 1 {
+                "file"
+            } else {
+                "files"
+            },
+            format_bytes(self.total_size)
+        )
+    }
+}
+
 impl Default for FileOperations {
     fn default() -> Self {
         Self::new()
@@ -411,6 +1514,231 @@ pub fn sanitize_filename(name: &str) -> String {
     }
 }
 
+/// Delete a stale output file recorded in a previous manifest, along with
+/// directories it leaves empty
+fn remove_output(output_root: &Path, extracted: &str) -> Result<()> {
+    let relative = Path::new(extracted);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(RepoDocsError::InvalidPath {
+            path: format!("Invalid path in extraction manifest: {}", extracted),
+        });
+    }
+
+    let path = output_root.join(relative);
+    match fs::remove_file(&path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(RepoDocsError::Io(e)),
+    }
+
+    let mut parent = path.parent();
+    while let Some(dir) = parent.filter(|dir| *dir != output_root) {
+        // Fails, and stops, at the first directory that is not empty
+        if fs::remove_dir(dir).is_err() {
+            break;
+        }
+        parent = dir.parent();
+    }
+
+    Ok(())
+}
+
+/// Account for one copied (or failed) document in the progress
+fn record_copy(
+    progress: &mut ExtractionProgress,
+    document: &DocumentFile,
+    destination: &PlannedDestination,
+    result: Result<Copied>,
+) {
+    match result {
+        Ok(copied) => {
+            let mapping = || PathMapping {
+                original: document.relative_path.to_string_lossy().replace('\\', "/"),
+                extracted: destination.path.to_string_lossy().replace('\\', "/"),
+            };
+            if destination.normalized {
+                progress.normalized_paths.push(mapping());
+            }
+            if destination.transliterated {
+                progress.transliterated_paths.push(mapping());
+            }
+            if destination.shortened {
+                progress.shortened_paths.push(mapping());
+            }
+            if destination.renamed {
+                progress.case_collisions.push(mapping());
+            }
+            progress.unresolved_links.extend(copied.unresolved_links);
+            progress.update_file(document.filename.clone(), copied.bytes);
+        }
+        Err(e) => {
+            let error_msg = format!("Failed to copy {}: {}", document.source_path.display(), e);
+            progress.add_error(error_msg);
+            // Continue with other files instead of failing completely
+        }
+    }
+}
+
+/// Where and how the documents of one extraction are written
+struct CopyPlan<'a> {
+    destinations: &'a [PlannedDestination],
+    output_root: &'a Path,
+    links: Option<&'a LinkRewriter<'a>>,
+}
+
+/// What writing one document produced
+struct Copied {
+    bytes: u64,
+    unresolved_links: Vec<UnresolvedLink>,
+}
+
+struct PlannedDestination {
+    path: PathBuf,
+    normalized: bool,
+    transliterated: bool,
+    shortened: bool,
+    renamed: bool,
+}
+
+impl PlannedDestination {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            normalized: false,
+            transliterated: false,
+            shortened: false,
+            renamed: false,
+        }
+    }
+}
+
+/// Comparison key of a path on a case- and normalization-insensitive
+/// filesystem
+fn case_key(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('\\', "/")
+        .nfc()
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// ASCII transliteration of every non-ASCII path component, or `None` if
+/// the path is already ASCII
+fn transliterate_path(path: &Path) -> Option<PathBuf> {
+    let original = path.to_str()?;
+    if original.is_ascii() {
+        return None;
+    }
+
+    let transliterated = path
+        .components()
+        .map(|component| {
+            let name = component.as_os_str().to_string_lossy();
+            if name.is_ascii() {
+                name.into_owned()
+            } else {
+                sanitize_filename(deunicode::deunicode(&name).trim())
+            }
+        })
+        .collect();
+    Some(transliterated)
+}
+
+/// Path in the given normalization form, or `None` if it already is
+fn normalize_path(path: &Path, form: UnicodeNormalization) -> Option<PathBuf> {
+    let original = path.to_str()?;
+    let normalized: String = match form {
+        UnicodeNormalization::Nfc => original.nfc().collect(),
+        UnicodeNormalization::Nfd => original.nfd().collect(),
+        UnicodeNormalization::None => return None,
+    };
+
+    (normalized != original).then(|| PathBuf::from(normalized))
+}
+
+/// `dir/name~N.ext` for `dir/name.ext`
+fn with_suffix(path: &Path, n: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{}~{}.{}", stem, n, ext.to_string_lossy()),
+        None => format!("{}~{}", stem, n),
+    };
+    path.with_file_name(name)
+}
+
+/// Deterministically shorten `relative` so that `root/relative` fits in
+/// `max_length` characters, keeping the filename intact. Directory names are
+/// replaced longest-first by `<prefix>~<hash>`; if that is not enough, all
+/// directories collapse into a single hashed directory. Returns `None` when
+/// the path already fits.
+pub fn shorten_path(root: &Path, relative: &Path, max_length: usize) -> Option<PathBuf> {
+    let length = |path: &Path| root.join(path).to_string_lossy().chars().count();
+    if length(relative) <= max_length {
+        return None;
+    }
+
+    let file_name = relative.file_name()?;
+    let mut directories: Vec<String> = relative
+        .parent()
+        .map(|parent| {
+            parent
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let rebuild = |directories: &[String]| -> PathBuf {
+        let mut path: PathBuf = directories.iter().collect();
+        path.push(file_name);
+        path
+    };
+
+    // Shorten the longest directory names first; the hash covers the full
+    // path prefix so equal names in different places stay distinct
+    let mut order: Vec<usize> = (0..directories.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(directories[i].chars().count()));
+    for index in order {
+        if directories[index].chars().count() <= SHORT_DIR_LEN {
+            continue;
+        }
+        let prefix: String = directories[index].chars().take(6).collect();
+        let full_prefix = directories[..=index].join("/");
+        directories[index] = format!("{}~{:08x}", prefix, path_hash(&full_prefix) as u32);
+
+        let candidate = rebuild(&directories);
+        if length(&candidate) <= max_length {
+            return Some(candidate);
+        }
+    }
+
+    // Collapse the whole directory chain
+    let parent = relative
+        .parent()
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_default();
+    let mut collapsed = PathBuf::from("_long");
+    collapsed.push(format!("{:012x}", path_hash(&parent) & 0xffff_ffff_ffff));
+    collapsed.push(file_name);
+    Some(collapsed)
+}
+
+/// Shortened directory names are `<6 chars>~<8 hex digits>`
+const SHORT_DIR_LEN: usize = 15;
+
+fn path_hash(text: &str) -> u64 {
+    // FNV-1a: stable across platforms and Rust versions
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
 // Check if path exceeds platform limits
 pub fn check_path_length(path: &Path) -> Result<()> {
     let path_str = path.to_string_lossy();
@@ -442,6 +1770,19 @@ mod tests {
     use std::time::SystemTime;
     use tempfile::TempDir;
 
+    fn github_repository(commit: &str) -> RepositoryInfo {
+        RepositoryInfo {
+            name: "repo".to_string(),
+            owner: "owner".to_string(),
+            default_branch: "main".to_string(),
+            is_empty: false,
+            total_commits: 0,
+            commit: Some(commit.to_string()),
+            url: "https://github.com/owner/repo".to_string(),
+            provider: crate::cloner::GitProvider::GitHub,
+        }
+    }
+
     fn create_test_document(name: &str, content: &str, temp_dir: &Path) -> DocumentFile {
         let file_path = temp_dir.join(name);
         fs::write(&file_path, content).unwrap();
@@ -505,6 +1846,147 @@ mod tests {
         assert!(dest_dir.path().join("docs").join("nested.md").exists());
     }
 
+    #[test]
+    fn test_readme_promotion() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+        fs::create_dir_all(source_dir.path().join("docs/foo")).unwrap();
+        fs::create_dir_all(source_dir.path().join("docs/v1.2")).unwrap();
+
+        let documents = vec![
+            create_test_document("README.md", "# Root", source_dir.path()),
+            create_test_document("docs/foo/README.md", "# Foo", source_dir.path()),
+            create_test_document("docs/v1.2/readme.rst", "Version", source_dir.path()),
+        ];
+
+        let planned = |promotion| {
+            FileOperations::new()
+                .with_preserve_structure(false)
+                .with_readme_promotion(promotion)
+                .plan_destinations(&documents, dest_dir.path())
+                .into_iter()
+                .map(|destination| destination.path)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            planned(ReadmePromotion::None),
+            vec![
+                PathBuf::from("README.md"),
+                PathBuf::from("README~2.md"),
+                PathBuf::from("readme.rst"),
+            ]
+        );
+        assert_eq!(
+            planned(ReadmePromotion::Rename),
+            vec![
+                PathBuf::from("README.md"),
+                PathBuf::from("foo.md"),
+                PathBuf::from("v1.2.rst"),
+            ]
+        );
+        assert_eq!(
+            planned(ReadmePromotion::Directory),
+            vec![
+                PathBuf::from("README.md"),
+                PathBuf::from("foo/README.md"),
+                PathBuf::from("v1.2/readme.rst"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_notebooks_rendered_as_markdown() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+        let notebook = create_test_document(
+            "analysis.ipynb",
+            r##"{"cells": [{"cell_type": "markdown", "source": ["# Analysis"]},
+                {"cell_type": "code", "source": "print(1)"}]}"##,
+            source_dir.path(),
+        );
+
+        let progress = FileOperations::new()
+            .with_notebook_mode(Some(NotebookMode::Markdown))
+            .extract_files(&[notebook], dest_dir.path(), None)
+            .unwrap();
+
+        assert_eq!(progress.files_processed, 1);
+        assert!(!dest_dir.path().join("analysis.ipynb").exists());
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("analysis.md")).unwrap(),
+            "# Analysis\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_transformed_on_extraction() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+        let documents = vec![
+            create_test_document(
+                "README.md",
+                "## Test\r\n<!-- draft -->\r\n",
+                source_dir.path(),
+            ),
+            create_test_document("notes.txt", "## Notes\r\n", source_dir.path()),
+        ];
+        let transform = TransformConfig {
+            normalize_headings: true,
+            normalize_line_endings: true,
+            strip_html_comments: true,
+            ..TransformConfig::default()
+        };
+
+        FileOperations::new()
+            .with_transform(transform)
+            .extract_files(&documents, dest_dir.path(), None)
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("README.md")).unwrap(),
+            "# Test\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("notes.txt")).unwrap(),
+            "## Notes\r\n"
+        );
+    }
+
+    #[test]
+    fn test_extraction_stops_at_deadline() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+        let document = create_test_document("README.md", "# Test", source_dir.path());
+
+        let result = FileOperations::new()
+            .with_deadline(Some(Deadline::after(Duration::ZERO)))
+            .extract_files(&[document], dest_dir.path(), None);
+
+        assert!(matches!(
+            result,
+            Err(RepoDocsError::Timeout {
+                stage: Stage::Extracting,
+                ..
+            })
+        ));
+        assert!(!dest_dir.path().join("README.md").exists());
+    }
+
+    #[test]
+    fn test_extraction_stops_when_cancelled() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+        let document = create_test_document("README.md", "# Test", source_dir.path());
+
+        let result = FileOperations::new()
+            .with_running(Some(Arc::new(AtomicBool::new(false))))
+            .extract_files(&[document], dest_dir.path(), None);
+
+        assert!(matches!(result, Err(RepoDocsError::Cancelled)));
+        assert!(!dest_dir.path().join("README.md").exists());
+    }
+
     #[test]
     fn test_filename_sanitization() {
         assert_eq!(sanitize_filename("normal_file.txt"), "normal_file.txt");
@@ -535,6 +2017,33 @@ mod tests {
         assert_eq!(progress.errors.len(), 1);
     }
 
+    #[test]
+    fn test_eta_follows_recent_byte_rate() {
+        let start = Instant::now();
+        let mut progress = ExtractionProgress::new(4, 4000);
+        progress.start_time = start;
+        progress.throughput = Throughput::new(start);
+
+        // 1000 bytes per second at first, then 2000 bytes per second
+        progress.files_processed = 2;
+        progress.bytes_processed = 2000;
+        progress
+            .throughput
+            .record(1000, start + Duration::from_secs(1));
+        assert_eq!(progress.throughput.bytes_per_second, Some(1000.0));
+        assert_eq!(progress.throughput.files_per_second, Some(1.0));
+
+        progress
+            .throughput
+            .record(1000, start + Duration::from_millis(1500));
+        let rate = progress.bytes_per_second();
+        assert!((rate - 1300.0).abs() < 1e-6, "rate was {}", rate);
+
+        // 2000 bytes left at the smoothed rate, regardless of file count
+        let eta = progress.estimated_remaining().as_secs_f64();
+        assert!((eta - 2000.0 / 1300.0).abs() < 1e-3, "eta was {}", eta);
+    }
+
     #[test]
     fn test_index_file_creation() {
         let temp_dir = TempDir::new().unwrap();
@@ -552,4 +2061,469 @@ mod tests {
         assert!(content.contains("# Documentation Index"));
         assert!(content.contains("README.md"));
     }
+
+    #[test]
+    fn test_index_mirrors_directory_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("docs/api")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("guides")).unwrap();
+        let documents = vec![
+            create_test_document("README.md", "# Test", temp_dir.path()),
+            create_test_document("docs/intro.md", "intro", temp_dir.path()),
+            create_test_document("docs/api/endpoints.md", "endpoints", temp_dir.path()),
+            create_test_document("guides/setup.md", &"x".repeat(2048), temp_dir.path()),
+        ];
+        let output_dir = TempDir::new().unwrap();
+
+        FileOperations::new()
+            .create_index_file(&documents, output_dir.path())
+            .unwrap();
+        let index = fs::read_to_string(output_dir.path().join("_index.md")).unwrap();
+        assert!(index.contains("- [README.md](README.md) (6 B)"));
+        assert!(index.contains("<summary><strong>docs/</strong> (2 files, 14 B)</summary>"));
+        assert!(index.contains("- [intro.md](docs/intro.md) (5 B)\n- **api/** (1 file, 9 B)\n  - [endpoints.md](docs/api/endpoints.md) (9 B)\n"));
+
+        // Directories follow the listing order too: largest first
+        let by_size = FileOperations::new().with_sort_order(SortOrder::Size);
+        by_size
+            .create_index_file(&documents, output_dir.path())
+            .unwrap();
+        let index = fs::read_to_string(output_dir.path().join("_index.md")).unwrap();
+        assert!(index.find("guides/").unwrap() < index.find("docs/").unwrap());
+    }
+
+    #[test]
+    fn test_paginated_index() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("docs/api")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("guides")).unwrap();
+        let documents: Vec<DocumentFile> = [
+            "CHANGELOG.md",
+            "README.md",
+            "docs/intro.md",
+            "docs/api/auth.md",
+            "docs/api/endpoints.md",
+            "docs/api/errors.md",
+            "docs/api/limits.md",
+            "guides/setup.md",
+        ]
+        .into_iter()
+        .map(|path| create_test_document(path, "text", temp_dir.path()))
+        .collect();
+        let output_dir = TempDir::new().unwrap();
+        let read = |page: &str| fs::read_to_string(output_dir.path().join(page)).unwrap();
+
+        FileOperations::new()
+            .with_index_page_size(Some(3))
+            .create_index_file(&documents, output_dir.path())
+            .unwrap();
+
+        // docs/ does not fit next to the root files, guides/ still does
+        let index = read("_index.md");
+        assert!(index.contains("- [README.md](README.md) (4 B)"));
+        assert!(index.contains("- [**docs/**](_index/docs/_index.md) (5 files, 20 B)"));
+        assert!(index.contains("<summary><strong>guides/</strong> (1 file, 4 B)</summary>"));
+        assert!(index.contains("Total files: 8"));
+
+        let docs = read("_index/docs/_index.md");
+        assert!(docs.starts_with("# docs/\n\n[Documentation Index](../../_index.md) / docs\n"));
+        assert!(docs.contains("- [intro.md](../../docs/intro.md) (4 B)"));
+        assert!(docs.contains("- [**api/**](api/_index.md) (4 files, 16 B)"));
+
+        // Files over the page size continue on numbered pages
+        let api = read("_index/docs/api/_index.md");
+        assert!(
+            api.contains("[Documentation Index](../../../_index.md) / [docs](../_index.md) / api")
+        );
+        assert!(api.contains("Pages: 1 | [2](_index-2.md)"));
+        assert!(api.contains("- [auth.md](../../../docs/api/auth.md) (4 B)"));
+        assert!(!api.contains("limits.md"));
+        let api = read("_index/docs/api/_index-2.md");
+        assert!(api.contains("Pages: [1](_index.md) | 2"));
+        assert!(api.contains("- [limits.md](../../../docs/api/limits.md) (4 B)"));
+    }
+
+    #[test]
+    fn test_shorten_path() {
+        let root = Path::new("/out");
+        let relative = Path::new(
+            "src/main/java/org/example/verylongpackagename/anotherverylongpackagename/README.md",
+        );
+
+        assert_eq!(shorten_path(root, relative, 4096), None);
+
+        let shortened = shorten_path(root, relative, 75).unwrap();
+        assert!(root.join(&shortened).to_string_lossy().len() <= 75);
+        assert_eq!(shortened.file_name(), relative.file_name());
+        assert!(shortened.to_string_lossy().contains('~'));
+
+        // Deterministic
+        assert_eq!(shorten_path(root, relative, 75), Some(shortened));
+
+        // Falls back to a single hashed directory
+        let collapsed = shorten_path(root, relative, 35).unwrap();
+        assert!(collapsed.starts_with("_long"));
+        assert_eq!(collapsed.file_name(), relative.file_name());
+    }
+
+    #[test]
+    fn test_extraction_records_shortened_paths() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+
+        let relative =
+            PathBuf::from("averyveryverylongdirectoryname/anotherlongdirectoryname/doc.md");
+        let source = source_dir.path().join(&relative);
+        fs::create_dir_all(source.parent().unwrap()).unwrap();
+        fs::write(&source, "# Doc").unwrap();
+        let document = DocumentFile::new(source, relative, 5, SystemTime::UNIX_EPOCH);
+
+        let max_length = dest_dir.path().to_string_lossy().len() + 40;
+        let progress = FileOperations::new()
+            .with_max_path_length(Some(max_length))
+            .extract_files(&[document], dest_dir.path(), None)
+            .unwrap();
+
+        assert!(progress.errors.is_empty());
+        assert_eq!(progress.shortened_paths.len(), 1);
+        let extracted = dest_dir.path().join(&progress.shortened_paths[0].extracted);
+        assert_eq!(fs::read_to_string(extracted).unwrap(), "# Doc");
+    }
+
+    #[test]
+    fn test_case_collisions_are_renamed() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+
+        // Separate source directories, so the fixture also works on
+        // case-insensitive filesystems
+        let documents: Vec<DocumentFile> = ["Readme.md", "README.md", "readme~2.md"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let source = source_dir.path().join(i.to_string()).join(name);
+                fs::create_dir_all(source.parent().unwrap()).unwrap();
+                fs::write(&source, name).unwrap();
+                DocumentFile::new(source, PathBuf::from(name), 0, SystemTime::UNIX_EPOCH)
+            })
+            .collect();
+
+        let file_ops = FileOperations::new();
+        let progress = file_ops
+            .extract_files(&documents, dest_dir.path(), None)
+            .unwrap();
+
+        assert!(progress.errors.is_empty());
+        assert_eq!(progress.case_collisions.len(), 1);
+        assert_eq!(progress.case_collisions[0].original, "Readme.md");
+        assert_eq!(progress.case_collisions[0].extracted, "Readme~3.md");
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("Readme~3.md")).unwrap(),
+            "Readme.md"
+        );
+
+        file_ops
+            .create_index_file(&documents, dest_dir.path())
+            .unwrap();
+        let index = fs::read_to_string(dest_dir.path().join("_index.md")).unwrap();
+        assert!(index.contains("[Readme.md](Readme~3.md)"));
+    }
+
+    #[test]
+    fn test_unicode_normalization_of_paths() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+
+        // "café.md" decomposed (e + combining acute accent)
+        let nfd_name = "cafe\u{301}.md";
+        let document = create_test_document(nfd_name, "# Café", source_dir.path());
+
+        let progress = FileOperations::new()
+            .extract_files(std::slice::from_ref(&document), dest_dir.path(), None)
+            .unwrap();
+
+        assert_eq!(progress.normalized_paths.len(), 1);
+        assert_eq!(progress.normalized_paths[0].original, nfd_name);
+        assert_eq!(progress.normalized_paths[0].extracted, "caf\u{e9}.md");
+        assert!(dest_dir.path().join("caf\u{e9}.md").exists());
+
+        let progress = FileOperations::new()
+            .with_unicode_normalization(UnicodeNormalization::None)
+            .with_force_overwrite(true)
+            .extract_files(&[document], dest_dir.path(), None)
+            .unwrap();
+        assert!(progress.normalized_paths.is_empty());
+    }
+
+    #[test]
+    fn test_parallel_extraction() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+
+        let documents: Vec<DocumentFile> = (0..50)
+            .map(|i| create_test_document(&format!("doc{}.md", i), "# Doc", source_dir.path()))
+            .collect();
+
+        let progress = FileOperations::new()
+            .with_parallel(true)
+            .extract_files(&documents, dest_dir.path(), None)
+            .unwrap();
+
+        assert!(progress.errors.is_empty());
+        assert_eq!(progress.files_processed, 50);
+        assert_eq!(progress.bytes_processed, 50 * 5);
+        assert!(dest_dir.path().join("doc49.md").exists());
+    }
+
+    #[test]
+    fn test_transliterated_filenames() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+
+        let source = source_dir.path().join("guía.md");
+        fs::write(&source, "# Guía").unwrap();
+        let document = DocumentFile::new(
+            source,
+            PathBuf::from("docs/español/guía.md"),
+            7,
+            SystemTime::UNIX_EPOCH,
+        );
+
+        let file_ops = FileOperations::new().with_transliteration(true);
+        let progress = file_ops
+            .extract_files(std::slice::from_ref(&document), dest_dir.path(), None)
+            .unwrap();
+
+        assert_eq!(progress.transliterated_paths.len(), 1);
+        assert_eq!(
+            progress.transliterated_paths[0].extracted,
+            "docs/espanol/guia.md"
+        );
+        assert!(dest_dir.path().join("docs/espanol/guia.md").exists());
+
+        file_ops
+            .create_index_file(&[document], dest_dir.path())
+            .unwrap();
+        let index = fs::read_to_string(dest_dir.path().join("_index.md")).unwrap();
+        assert!(index.contains("(docs/espanol/guia.md)"));
+    }
+
+    #[test]
+    fn test_large_file_copy_preserves_content_and_mtime() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+
+        let content: Vec<u8> = (0..3 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        let source = source_dir.path().join("big.txt");
+        fs::write(&source, &content).unwrap();
+        let mtime = filetime::FileTime::from_unix_time(1_600_000_000, 0);
+        filetime::set_file_mtime(&source, mtime).unwrap();
+
+        let file_ops = FileOperations::new();
+        let dest = dest_dir.path().join("big.txt");
+        assert_eq!(
+            file_ops.secure_copy(&source, &dest).unwrap(),
+            content.len() as u64
+        );
+        assert_eq!(fs::read(&dest).unwrap(), content);
+        let copied = fs::metadata(&dest).unwrap();
+        assert_eq!(
+            filetime::FileTime::from_last_modification_time(&copied),
+            mtime
+        );
+
+        // The buffered fallback produces the same result
+        let fallback = dest_dir.path().join("fallback.txt");
+        assert_eq!(
+            file_ops.copy_file_with_buffer(&source, &fallback).unwrap(),
+            content.len() as u64
+        );
+        assert_eq!(fs::read(&fallback).unwrap(), content);
+    }
+
+    #[test]
+    fn test_incremental_extraction() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+        fs::create_dir(source_dir.path().join("guide")).unwrap();
+
+        let documents = vec![
+            create_test_document("a.md", "# A", source_dir.path()),
+            create_test_document("b.md", "# B", source_dir.path()),
+            create_test_document("guide/c.md", "# C", source_dir.path()),
+        ];
+        let first = FileOperations::new()
+            .extract_files(&documents, dest_dir.path(), None)
+            .unwrap();
+        assert!(first.changes.is_none());
+        assert_eq!(first.manifest.entries.len(), 3);
+
+        fs::remove_file(source_dir.path().join("guide/c.md")).unwrap();
+        let documents = vec![
+            create_test_document("a.md", "# A, revised", source_dir.path()),
+            create_test_document("b.md", "# B", source_dir.path()),
+            create_test_document("d.md", "# D", source_dir.path()),
+        ];
+        let second = FileOperations::new()
+            .with_previous_manifest(Some(first.manifest))
+            .extract_files(&documents, dest_dir.path(), None)
+            .unwrap();
+
+        assert!(second.errors.is_empty());
+        assert_eq!(second.files_processed, 3);
+        let changes = second.changes.unwrap();
+        assert_eq!(changes.added, vec!["d.md"]);
+        assert_eq!(changes.modified, vec!["a.md"]);
+        assert_eq!(changes.removed, vec!["guide/c.md"]);
+        assert_eq!(changes.unchanged, 1);
+
+        assert_eq!(
+            fs::read_to_string(dest_dir.path().join("a.md")).unwrap(),
+            "# A, revised"
+        );
+        assert!(dest_dir.path().join("d.md").exists());
+        assert!(!dest_dir.path().join("guide").exists());
+        assert_eq!(second.manifest.entries.len(), 3);
+    }
+
+    #[test]
+    fn test_incremental_extraction_after_settings_change() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+        let documents = vec![
+            create_test_document("a.md", "# A\n\n<!-- draft -->\n", source_dir.path()),
+            create_test_document("b.md", "# B\n", source_dir.path()),
+        ];
+        let first = FileOperations::new()
+            .extract_files(&documents, dest_dir.path(), None)
+            .unwrap();
+
+        let transform = TransformConfig {
+            strip_html_comments: true,
+            ..TransformConfig::default()
+        };
+        let extract = |previous: Manifest| {
+            FileOperations::new()
+                .with_transform(transform.clone())
+                .with_previous_manifest(Some(previous))
+                .extract_files(&documents, dest_dir.path(), None)
+                .unwrap()
+        };
+        let second = extract(first.manifest);
+        let changes = second.changes.unwrap();
+        assert_eq!(changes.modified, ["a.md", "b.md"]);
+        assert_eq!(changes.unchanged, 0);
+        assert!(!fs::read_to_string(dest_dir.path().join("a.md"))
+            .unwrap()
+            .contains("draft"));
+
+        let third = extract(second.manifest);
+        assert_eq!(third.changes.unwrap().unchanged, 2);
+    }
+
+    #[test]
+    fn test_incremental_extraction_after_enabling_link_rewriting() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+        fs::create_dir(source_dir.path().join("docs")).unwrap();
+        let documents = vec![
+            create_test_document("README.md", "[Guide](docs/guide.md)\n", source_dir.path()),
+            create_test_document("docs/guide.md", "# Guide\n", source_dir.path()),
+        ];
+        let extract = |rewrite: bool, previous: Option<Manifest>| {
+            FileOperations::new()
+                .with_preserve_structure(false)
+                .with_link_rewriting(rewrite.then(|| github_repository("1111111")))
+                .with_previous_manifest(previous)
+                .extract_files(&documents, dest_dir.path(), None)
+                .unwrap()
+        };
+
+        let first = extract(false, None);
+        let readme = || fs::read_to_string(dest_dir.path().join("README.md")).unwrap();
+        assert_eq!(readme(), "[Guide](docs/guide.md)\n");
+
+        let second = extract(true, Some(first.manifest));
+        assert_eq!(second.changes.unwrap().unchanged, 0);
+        assert_eq!(readme(), "[Guide](guide.md)\n");
+    }
+
+    #[test]
+    fn test_incremental_banners_follow_the_commit() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+        let documents = vec![create_test_document("a.md", "# A\n", source_dir.path())];
+        let extract = |commit: &str, previous: Option<Manifest>| {
+            FileOperations::new()
+                .with_source_banner(Some(SourceBanner::Banner), &github_repository(commit))
+                .with_previous_manifest(previous)
+                .extract_files(&documents, dest_dir.path(), None)
+                .unwrap()
+        };
+
+        let first = extract("1111111", None);
+        let second = extract("2222222", Some(first.manifest));
+        assert_eq!(second.changes.unwrap().modified, ["a.md"]);
+        let banner = fs::read_to_string(dest_dir.path().join("a.md")).unwrap();
+        assert!(banner.contains("/blob/2222222/a.md"));
+
+        let third = extract("2222222", Some(second.manifest));
+        assert_eq!(third.changes.unwrap().unchanged, 1);
+    }
+
+    #[test]
+    fn test_merge_extraction() {
+        let source_dir = TempDir::new().unwrap();
+        let dest_dir = TempDir::new().unwrap();
+
+        let documents = vec![
+            create_test_document("a.md", "# A", source_dir.path()),
+            create_test_document("b.md", "# B", source_dir.path()),
+            create_test_document("c.md", "# C", source_dir.path()),
+        ];
+        let first = FileOperations::new()
+            .extract_files(&documents, dest_dir.path(), None)
+            .unwrap();
+
+        // Edited, added over a document's output, identical, and unrelated
+        fs::write(dest_dir.path().join("b.md"), "# B, annotated").unwrap();
+        fs::write(dest_dir.path().join("e.md"), "# Local notes").unwrap();
+        fs::write(dest_dir.path().join("f.md"), "# F").unwrap();
+        fs::write(dest_dir.path().join("notes.md"), "# Notes").unwrap();
+
+        let documents = vec![
+            create_test_document("a.md", "# A, revised", source_dir.path()),
+            create_test_document("b.md", "# B, revised", source_dir.path()),
+            create_test_document("c.md", "# C", source_dir.path()),
+            create_test_document("d.md", "# D", source_dir.path()),
+            create_test_document("e.md", "# E", source_dir.path()),
+            create_test_document("f.md", "# F", source_dir.path()),
+        ];
+        let second = FileOperations::new()
+            .with_previous_manifest(Some(first.manifest))
+            .with_merge(true)
+            .extract_files(&documents, dest_dir.path(), None)
+            .unwrap();
+
+        assert!(second.errors.is_empty());
+        assert!(second.changes.is_none());
+        let merge = second.merge.unwrap();
+        assert_eq!(merge.written, ["a.md", "d.md"]);
+        assert_eq!(merge.unchanged, 2);
+        let conflicts: Vec<&str> = merge.conflicts.iter().map(|c| c.output.as_str()).collect();
+        assert_eq!(conflicts, ["b.md", "e.md"]);
+
+        let read = |name: &str| fs::read_to_string(dest_dir.path().join(name)).unwrap();
+        assert_eq!(read("a.md"), "# A, revised");
+        assert_eq!(read("b.md"), "# B, annotated");
+        assert_eq!(read("e.md"), "# Local notes");
+        assert_eq!(read("notes.md"), "# Notes");
+        let manifested: Vec<&str> = second
+            .manifest
+            .entries
+            .iter()
+            .map(|entry| entry.path.as_str())
+            .collect();
+        assert_eq!(manifested, ["a.md", "c.md", "d.md", "f.md"]);
+    }
 }
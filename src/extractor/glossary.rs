@@ -0,0 +1,243 @@
+use crate::error::{RepoDocsError, Result};
+use crate::scanner::DocumentFile;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Terms must appear at least this often across all documents to be listed
+const MIN_TERM_FREQUENCY: usize = 3;
+const MAX_FREQUENT_TERMS: usize = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlossaryEntry {
+    pub term: String,
+    pub definition: String,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Glossary {
+    /// Explicit definitions, keyed by lowercase term (first definition wins)
+    pub definitions: BTreeMap<String, GlossaryEntry>,
+    /// Capitalized terms and how often they occur, most frequent first
+    pub frequent_terms: Vec<(String, usize)>,
+}
+
+fn bold_definition_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^\s*(?:[-*+]\s+)?\*\*([^*]{1,60})\*\*\s*(?::|-|–|—)\s*(.+)$")
+            .expect("valid bold definition regex")
+    })
+}
+
+fn capitalized_term_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\b([A-Z][a-z0-9]+(?:[A-Z][a-z0-9]+)+|[A-Z]{2,}[a-z]*|[A-Z][a-z]+(?:\s[A-Z][a-z]+)+)\b")
+            .expect("valid capitalized term regex")
+    })
+}
+
+impl Glossary {
+    pub fn build(documents: &[DocumentFile]) -> Self {
+        let mut glossary = Glossary::default();
+        let mut frequencies: HashMap<String, usize> = HashMap::new();
+
+        for doc in documents {
+            let content = match fs::read(&doc.source_path) {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(_) => continue,
+            };
+            let source = doc.relative_path.to_string_lossy().replace('\\', "/");
+
+            let lines = prose_lines(&content);
+            for entry in find_definitions(&lines, &source) {
+                glossary
+                    .definitions
+                    .entry(entry.term.to_lowercase())
+                    .or_insert(entry);
+            }
+
+            for line in &lines {
+                if line.trim_start().starts_with('#') {
+                    continue; // Headings are title-cased and skew counts
+                }
+                for caps in capitalized_term_regex().captures_iter(line) {
+                    *frequencies.entry(caps[1].to_string()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut frequent_terms: Vec<(String, usize)> = frequencies
+            .into_iter()
+            .filter(|(_, count)| *count >= MIN_TERM_FREQUENCY)
+            .collect();
+        frequent_terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        frequent_terms.truncate(MAX_FREQUENT_TERMS);
+        glossary.frequent_terms = frequent_terms;
+
+        glossary
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty() && self.frequent_terms.is_empty()
+    }
+
+    pub fn save(&self, output_dir: &Path) -> Result<()> {
+        let path = output_dir.join("GLOSSARY.md");
+        let mut file = fs::File::create(&path).map_err(RepoDocsError::Io)?;
+
+        writeln!(file, "# Glossary")?;
+        writeln!(file)?;
+        writeln!(
+            file,
+            "Generated by RepoDocs from definition lists and term usage across the extracted documentation."
+        )?;
+        writeln!(file)?;
+
+        if !self.definitions.is_empty() {
+            writeln!(file, "## Definitions")?;
+            writeln!(file)?;
+            for entry in self.definitions.values() {
+                writeln!(file, "**{}**", entry.term)?;
+                writeln!(file, ": {} _({})_", entry.definition, entry.source)?;
+                writeln!(file)?;
+            }
+        }
+
+        if !self.frequent_terms.is_empty() {
+            writeln!(file, "## Frequent Terms")?;
+            writeln!(file)?;
+            writeln!(file, "| Term | Occurrences |")?;
+            writeln!(file, "|------|-------------|")?;
+            for (term, count) in &self.frequent_terms {
+                writeln!(file, "| {} | {} |", term, count)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Lines outside fenced code blocks
+fn prose_lines(content: &str) -> Vec<&str> {
+    let mut in_fence = false;
+    content
+        .lines()
+        .filter(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+                return false;
+            }
+            !in_fence
+        })
+        .collect()
+}
+
+fn find_definitions(lines: &[&str], source: &str) -> Vec<GlossaryEntry> {
+    let mut entries = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        // `**Term**: definition` and `- **Term** - definition`
+        if let Some(caps) = bold_definition_regex().captures(line) {
+            entries.push(GlossaryEntry {
+                term: caps[1].trim().to_string(),
+                definition: caps[2].trim().to_string(),
+                source: source.to_string(),
+            });
+            continue;
+        }
+
+        let Some(next) = lines.get(index + 1) else {
+            continue;
+        };
+        let term = line.trim();
+        if term.is_empty() || term.len() > 60 || term.starts_with(['#', '-', '*', '>', '|']) {
+            continue;
+        }
+
+        // Markdown extra definition lists: `Term` followed by `: definition`
+        if let Some(definition) = next.trim_start().strip_prefix(": ") {
+            entries.push(GlossaryEntry {
+                term: term.to_string(),
+                definition: definition.trim().to_string(),
+                source: source.to_string(),
+            });
+            continue;
+        }
+
+        // AsciiDoc/RST style: `Term::` followed by an indented definition
+        if let Some(stripped) = term.strip_suffix("::") {
+            let definition = next.trim();
+            if !stripped.is_empty() && !definition.is_empty() && next.starts_with([' ', '\t']) {
+                entries.push(GlossaryEntry {
+                    term: stripped.trim().to_string(),
+                    definition: definition.to_string(),
+                    source: source.to_string(),
+                });
+            }
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_definitions() {
+        let content = "**Crate**: A compilation unit.\n\
+                       - **Workspace** - A set of crates.\n\
+                       Manifest\n: The Cargo.toml file.\n\
+                       Registry::\n  Where crates are published.\n\
+                       ```\n**NotATerm**: inside code\n```\n";
+        let lines = prose_lines(content);
+        let entries = find_definitions(&lines, "guide.md");
+        let terms: Vec<&str> = entries.iter().map(|e| e.term.as_str()).collect();
+
+        assert_eq!(terms, vec!["Crate", "Workspace", "Manifest", "Registry"]);
+        assert_eq!(entries[2].definition, "The Cargo.toml file.");
+    }
+
+    #[test]
+    fn test_glossary_build_and_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("guide.md");
+        fs::write(
+            &path,
+            "**Extractor**: Copies documentation.\n\
+             The RepoDocs tool uses GitHub. RepoDocs clones GitHub repos.\n\
+             Then RepoDocs writes files fetched from GitHub.\n",
+        )
+        .unwrap();
+
+        let documents = vec![DocumentFile::new(
+            path,
+            PathBuf::from("guide.md"),
+            0,
+            SystemTime::UNIX_EPOCH,
+        )];
+
+        let glossary = Glossary::build(&documents);
+        assert!(glossary.definitions.contains_key("extractor"));
+        assert!(glossary
+            .frequent_terms
+            .iter()
+            .any(|(term, count)| term == "RepoDocs" && *count == 3));
+
+        glossary.save(temp_dir.path()).unwrap();
+        let content = fs::read_to_string(temp_dir.path().join("GLOSSARY.md")).unwrap();
+        assert!(content.contains("**Extractor**"));
+        assert!(content.contains("| GitHub | 3 |"));
+    }
+}
@@ -0,0 +1,332 @@
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::markdown::{self, is_markdown_extension};
+use crate::scanner::DocumentFile;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Document,
+    Directory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    Contains,
+    Link,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub kind: NodeKind,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub kind: EdgeKind,
+}
+
+/// Documents and directories as nodes, with directory containment and
+/// intra-repository Markdown links as edges.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocumentGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// Documents that no other document links to
+    pub orphans: Vec<String>,
+    /// Groups of documents connected by links (two or more members)
+    pub clusters: Vec<Vec<String>>,
+}
+
+const ROOT_DIR_ID: &str = "dir:.";
+
+impl DocumentGraph {
+    pub fn build(documents: &[DocumentFile]) -> Self {
+        let doc_ids: BTreeSet<String> = documents.iter().map(document_id).collect();
+        let mut graph = DocumentGraph::default();
+        let mut directories = BTreeSet::new();
+
+        graph.nodes.push(GraphNode {
+            id: ROOT_DIR_ID.to_string(),
+            kind: NodeKind::Directory,
+            label: ".".to_string(),
+        });
+
+        for doc in documents {
+            let id = document_id(doc);
+            graph.nodes.push(GraphNode {
+                id: id.clone(),
+                kind: NodeKind::Document,
+                label: doc.filename.clone(),
+            });
+
+            // Directory containment chain from the root down to the document
+            let mut parent_id = ROOT_DIR_ID.to_string();
+            if let Some(parent) = doc.relative_path.parent() {
+                let mut current = String::new();
+                for component in parent.components() {
+                    let name = component.as_os_str().to_string_lossy();
+                    if !current.is_empty() {
+                        current.push('/');
+                    }
+                    current.push_str(&name);
+
+                    let dir_id = format!("dir:{}", current);
+                    if directories.insert(dir_id.clone()) {
+                        graph.nodes.push(GraphNode {
+                            id: dir_id.clone(),
+                            kind: NodeKind::Directory,
+                            label: name.to_string(),
+                        });
+                        graph.edges.push(GraphEdge {
+                            source: parent_id.clone(),
+                            target: dir_id.clone(),
+                            kind: EdgeKind::Contains,
+                        });
+                    }
+                    parent_id = dir_id;
+                }
+            }
+
+            graph.edges.push(GraphEdge {
+                source: parent_id,
+                target: id,
+                kind: EdgeKind::Contains,
+            });
+        }
+
+        let link_edges = link_edges(documents);
+        for (source, target) in &link_edges {
+            graph.edges.push(GraphEdge {
+                source: source.clone(),
+                target: target.clone(),
+                kind: EdgeKind::Link,
+            });
+        }
+
+        graph.orphans = find_orphans(&doc_ids, &link_edges);
+        graph.clusters = find_clusters(&doc_ids, &link_edges);
+        graph
+    }
+
+    pub fn link_count(&self) -> usize {
+        self.edges
+            .iter()
+            .filter(|e| e.kind == EdgeKind::Link)
+            .count()
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        xml.push_str("  <key id=\"kind\" for=\"all\" attr.name=\"kind\" attr.type=\"string\"/>\n");
+        xml.push_str(
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+        );
+        xml.push_str("  <graph id=\"documentation\" edgedefault=\"directed\">\n");
+
+        for node in &self.nodes {
+            xml.push_str(&format!(
+                "    <node id=\"{}\">\n      <data key=\"kind\">{}</data>\n      <data key=\"label\">{}</data>\n    </node>\n",
+                escape_xml(&node.id),
+                node_kind_name(node.kind),
+                escape_xml(&node.label)
+            ));
+        }
+
+        for (index, edge) in self.edges.iter().enumerate() {
+            xml.push_str(&format!(
+                "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n      <data key=\"kind\">{}</data>\n    </edge>\n",
+                index,
+                escape_xml(&edge.source),
+                escape_xml(&edge.target),
+                edge_kind_name(edge.kind)
+            ));
+        }
+
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    /// Write `graph.json` and `graph.graphml` into the given directory
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| RepoDocsError::Config {
+            message: format!("Failed to serialize document graph: {}", e),
+        })?;
+        fs::write(dir.join("graph.json"), json).map_err(RepoDocsError::Io)?;
+
+        let mut file = fs::File::create(dir.join("graph.graphml")).map_err(RepoDocsError::Io)?;
+        file.write_all(self.to_graphml().as_bytes())?;
+
+        Ok(())
+    }
+}
+
+/// Links between documents, as pairs of the linking and the linked
+/// document's relative path. Each pair appears once, however many times the
+/// link is repeated, and links of a document to itself are left out.
+pub fn link_edges(documents: &[DocumentFile]) -> BTreeSet<(String, String)> {
+    let doc_ids: BTreeSet<String> = documents.iter().map(document_id).collect();
+    let mut link_edges = BTreeSet::new();
+    for doc in documents {
+        if !is_markdown_extension(&doc.extension) {
+            continue;
+        }
+
+        let content = match fs::read(&doc.source_path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(_) => continue,
+        };
+
+        let source_id = document_id(doc);
+        for link in markdown::extract_links(&content) {
+            if link.is_external() || link.is_anchor_only() {
+                continue;
+            }
+
+            let Some(resolved) =
+                markdown::resolve_relative_link(&doc.relative_path, link.path_part())
+            else {
+                continue;
+            };
+
+            let target_id = path_id(&resolved);
+            if target_id != source_id && doc_ids.contains(&target_id) {
+                link_edges.insert((source_id.clone(), target_id));
+            }
+        }
+    }
+    link_edges
+}
+
+fn document_id(doc: &DocumentFile) -> String {
+    path_id(&doc.relative_path)
+}
+
+fn path_id(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn find_orphans(doc_ids: &BTreeSet<String>, links: &BTreeSet<(String, String)>) -> Vec<String> {
+    let linked: BTreeSet<&String> = links.iter().map(|(_, target)| target).collect();
+    doc_ids
+        .iter()
+        .filter(|id| !linked.contains(id))
+        .cloned()
+        .collect()
+}
+
+fn find_clusters(
+    doc_ids: &BTreeSet<String>,
+    links: &BTreeSet<(String, String)>,
+) -> Vec<Vec<String>> {
+    let mut adjacency: BTreeMap<&String, Vec<&String>> = BTreeMap::new();
+    for (source, target) in links {
+        adjacency.entry(source).or_default().push(target);
+        adjacency.entry(target).or_default().push(source);
+    }
+
+    let mut visited = BTreeSet::new();
+    let mut clusters = Vec::new();
+
+    for id in doc_ids {
+        if visited.contains(id) || !adjacency.contains_key(id) {
+            continue;
+        }
+
+        let mut cluster = Vec::new();
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+            cluster.push(current.clone());
+            if let Some(neighbours) = adjacency.get(current) {
+                stack.extend(neighbours.iter().copied());
+            }
+        }
+
+        cluster.sort();
+        clusters.push(cluster);
+    }
+
+    clusters
+}
+
+fn node_kind_name(kind: NodeKind) -> &'static str {
+    match kind {
+        NodeKind::Document => "document",
+        NodeKind::Directory => "directory",
+    }
+}
+
+fn edge_kind_name(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Contains => "contains",
+        EdgeKind::Link => "link",
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::write_test_documents;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_graph_links_and_containment() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        let documents = write_test_documents(
+            root,
+            &[
+                ("README.md", "See [guide](docs/guide.md)."),
+                ("docs/guide.md", "Back to [readme](../README.md)."),
+                ("docs/orphan.md", "Nobody links here."),
+            ],
+        );
+
+        let graph = DocumentGraph::build(&documents);
+
+        assert_eq!(graph.link_count(), 2);
+        assert!(graph.nodes.iter().any(|n| n.id == "dir:docs"));
+        assert!(graph
+            .edges
+            .iter()
+            .any(|e| e.source == "dir:docs" && e.target == "docs/orphan.md"));
+        assert_eq!(graph.orphans, vec!["docs/orphan.md".to_string()]);
+        assert_eq!(graph.clusters.len(), 1);
+        assert_eq!(graph.clusters[0].len(), 2);
+    }
+
+    #[test]
+    fn test_graph_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let documents = write_test_documents(temp_dir.path(), &[("a&b.md", "# A")]);
+
+        let graph = DocumentGraph::build(&documents);
+        graph.save(temp_dir.path()).unwrap();
+
+        assert!(temp_dir.path().join("graph.json").exists());
+        let graphml = fs::read_to_string(temp_dir.path().join("graph.graphml")).unwrap();
+        assert!(graphml.contains("a&amp;b.md"));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::cloner::RepositoryInfo;
+use crate::extractor::markdown::{self, MarkdownLink};
+use crate::scanner::DocumentFile;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A relative link whose target is not in the repository
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnresolvedLink {
+    /// Repository path of the document holding the link
+    pub document: String,
+    pub target: String,
+}
+
+/// Rewrites relative links of extracted Markdown files for the output
+/// layout: links to extracted documents point at where those were written,
+/// links to other files of the repository at its web view, and links to
+/// files the repository does not have are reported.
+#[derive(Debug, Clone)]
+pub struct LinkRewriter<'a> {
+    /// Output path by repository path of every extracted document
+    destinations: HashMap<PathBuf, PathBuf>,
+    repository: &'a RepositoryInfo,
+}
+
+impl<'a> LinkRewriter<'a> {
+    pub fn new(destinations: HashMap<PathBuf, PathBuf>, repository: &'a RepositoryInfo) -> Self {
+        Self {
+            destinations,
+            repository,
+        }
+    }
+
+    /// Rewrite the links of `content`, the Markdown of `document` written
+    /// to `destination`, adding links that resolve to nothing to `unresolved`
+    pub fn rewrite(
+        &self,
+        document: &DocumentFile,
+        destination: &Path,
+        content: &str,
+        unresolved: &mut Vec<UnresolvedLink>,
+    ) -> String {
+        let source = document.relative_path.as_path();
+        let root = repository_root(document);
+
+        markdown::rewrite_links(content, |target| {
+            let link = MarkdownLink {
+                text: String::new(),
+                target: target.to_string(),
+            };
+            if link.is_external() || link.is_anchor_only() || target.contains(':') {
+                return None;
+            }
+
+            let suffix = &target[link.path_part().len()..];
+            let resolved = markdown::resolve_relative_link(source, link.path_part());
+            let exists = resolved
+                .as_ref()
+                .zip(root)
+                .is_some_and(|(path, root)| root.join(path).exists());
+            let Some(resolved) = resolved.filter(|_| exists) else {
+                unresolved.push(UnresolvedLink {
+                    document: source.to_string_lossy().replace('\\', "/"),
+                    target: target.to_string(),
+                });
+                return None;
+            };
+
+            let href = match self.destinations.get(&resolved) {
+                Some(linked) => {
+                    format!("{}{}", markdown::relative_href(destination, linked), suffix)
+                }
+                None => format!(
+                    "{}{}",
+                    self.repository
+                        .file_url(&resolved.to_string_lossy().replace('\\', "/"))?,
+                    suffix
+                ),
+            };
+            (href != target).then_some(href)
+        })
+    }
+}
+
+/// Directory the document's repository path is relative to
+pub(crate) fn repository_root(document: &DocumentFile) -> Option<&Path> {
+    document
+        .source_path
+        .ancestors()
+        .nth(document.relative_path.components().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cloner::GitProvider;
+    use std::fs;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rewrite_links_for_flat_output() {
+        let repo = TempDir::new().unwrap();
+        for path in ["docs/guide/setup.md", "docs/api.md", "src/lib.rs"] {
+            let path = repo.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        let document = DocumentFile::new(
+            repo.path().join("docs/guide/setup.md"),
+            PathBuf::from("docs/guide/setup.md"),
+            0,
+            SystemTime::UNIX_EPOCH,
+        );
+        let destinations = HashMap::from([
+            (
+                PathBuf::from("docs/guide/setup.md"),
+                PathBuf::from("setup.md"),
+            ),
+            (PathBuf::from("docs/api.md"), PathBuf::from("api.md")),
+        ]);
+        let repository = RepositoryInfo {
+            name: "repo".to_string(),
+            owner: "owner".to_string(),
+            default_branch: "main".to_string(),
+            is_empty: false,
+            total_commits: 0,
+            commit: None,
+            url: "https://github.com/owner/repo".to_string(),
+            provider: GitProvider::GitHub,
+        };
+
+        let content = "[API](../api.md#errors), [lib](../../src/lib.rs), \
+                       [gone](missing.md), [top](#setup), [site](https://example.com)\n\
+                       ```\n[code](../api.md)\n```\n";
+        let mut unresolved = Vec::new();
+        let rewritten = LinkRewriter::new(destinations, &repository).rewrite(
+            &document,
+            Path::new("setup.md"),
+            content,
+            &mut unresolved,
+        );
+
+        assert_eq!(
+            rewritten,
+            "[API](api.md#errors), [lib](https://github.com/owner/repo/blob/main/src/lib.rs), \
+             [gone](missing.md), [top](#setup), [site](https://example.com)\n\
+             ```\n[code](../api.md)\n```\n"
+        );
+        assert_eq!(
+            unresolved,
+            vec![UnresolvedLink {
+                document: "docs/guide/setup.md".to_string(),
+                target: "missing.md".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,389 @@
+use crate::error::{RepoDocsError, Result};
+use crate::scanner::front_matter;
+use crate::scanner::notebook::{self, Notebook};
+use crate::scanner::DocumentFile;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Characters per token used for estimates. There is no tokenizer
+/// dependency; four characters per token is close for English prose and
+/// code with the common LLM tokenizers, and errs on the generous side
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Documents that would get less than this under a token limit are left
+/// out instead of being cut to a few lines
+const MIN_DOCUMENT_TOKENS: usize = 64;
+
+const TRUNCATION_MARKER: &str = "\n[... truncated]";
+
+const CLOSING_TAG: &str = "\n</document>\n\n";
+
+/// Estimated number of tokens in `text`
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// A document as it appears in the pack
+#[derive(Debug, Clone)]
+pub struct PackedDocument {
+    pub path: String,
+    pub text: String,
+    pub truncated: bool,
+}
+
+impl PackedDocument {
+    fn opening_tag(&self, truncated: bool) -> String {
+        if truncated {
+            format!("<document path=\"{}\" truncated=\"true\">\n", self.path)
+        } else {
+            format!("<document path=\"{}\">\n", self.path)
+        }
+    }
+
+    fn render(&self) -> String {
+        format!(
+            "{}{}{}",
+            self.opening_tag(self.truncated),
+            self.text,
+            CLOSING_TAG
+        )
+    }
+
+    /// Most tokens taken by everything but the text, whether or not the
+    /// document ends up truncated
+    fn overhead(&self) -> usize {
+        estimate_tokens(&self.opening_tag(true)) + estimate_tokens(CLOSING_TAG)
+    }
+}
+
+/// The documentation as plain text to paste into a language model's context
+/// (`--llm-pack`): each document between `<document path="...">` tags, with
+/// front matter, images, badges and HTML comments removed
+#[derive(Debug, Clone, Default)]
+pub struct LlmPack {
+    pub documents: Vec<PackedDocument>,
+    /// Documents left out to stay within the token limit
+    pub omitted: Vec<String>,
+}
+
+fn image_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        // Linked images (badges) first, then bare inline and reference images
+        Regex::new(
+            r"\[!\[[^\]]*\](?:\([^)]*\)|\[[^\]]*\])\](?:\([^)]*\)|\[[^\]]*\])|!\[[^\]]*\](?:\([^)]*\)|\[[^\]]*\])",
+        )
+        .expect("valid image regex")
+    })
+}
+
+fn html_noise_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?s)<!--.*?-->|<img\b[^>]*>|</?(?:picture|source)\b[^>]*>")
+            .expect("valid HTML noise regex")
+    })
+}
+
+fn blank_lines_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\n{3,}").expect("valid blank lines regex"))
+}
+
+impl LlmPack {
+    /// Pack `documents`, the root README first and the rest by path. With
+    /// `max_tokens`, documents are shortened to fit: short ones are kept
+    /// whole and the longest ones share what is left.
+    pub fn build(documents: &[DocumentFile], max_tokens: Option<usize>) -> Self {
+        let mut sorted: Vec<&DocumentFile> = documents.iter().collect();
+        sorted.sort_by_key(|doc| {
+            let is_root_readme = doc.relative_path.parent() == Some(Path::new(""))
+                && doc.filename.to_lowercase().starts_with("readme");
+            (!is_root_readme, doc.relative_path.clone())
+        });
+
+        let documents = sorted
+            .into_iter()
+            .filter_map(|doc| {
+                let text = clean(&document_text(doc)?);
+                (!text.is_empty()).then(|| PackedDocument {
+                    path: doc.relative_path.to_string_lossy().replace('\\', "/"),
+                    text,
+                    truncated: false,
+                })
+            })
+            .collect();
+
+        let mut pack = Self {
+            documents,
+            omitted: Vec::new(),
+        };
+        if let Some(max_tokens) = max_tokens {
+            pack.fit(max_tokens);
+        }
+        pack
+    }
+
+    pub fn total_tokens(&self) -> usize {
+        self.documents
+            .iter()
+            .map(|doc| estimate_tokens(&doc.render()))
+            .sum()
+    }
+
+    fn fit(&mut self, max_tokens: usize) {
+        loop {
+            let overhead: usize = self.documents.iter().map(PackedDocument::overhead).sum();
+            let available = max_tokens.saturating_sub(overhead);
+            let sizes: Vec<usize> = self
+                .documents
+                .iter()
+                .map(|doc| estimate_tokens(&doc.text))
+                .collect();
+            let allocation = share_budget(&sizes, available);
+
+            let starved = sizes
+                .iter()
+                .zip(&allocation)
+                .any(|(&size, &allowed)| allowed < size && allowed < MIN_DOCUMENT_TOKENS);
+            if starved && self.documents.len() > 1 {
+                // Drop the last document and share the budget again
+                if let Some(doc) = self.documents.pop() {
+                    self.omitted.push(doc.path);
+                }
+                continue;
+            }
+
+            for ((doc, size), allowed) in self.documents.iter_mut().zip(sizes).zip(allocation) {
+                if allowed < size {
+                    doc.text = truncate(&doc.text, allowed);
+                    doc.truncated = true;
+                }
+            }
+            self.omitted.reverse();
+            return;
+        }
+    }
+
+    /// Write the pack into `output_dir` as `llm_pack.txt`, or with
+    /// `chunk_tokens` as `llm_pack_001.txt`, `llm_pack_002.txt`, ... of at
+    /// most that many tokens each. Returns the files written.
+    pub fn save(&self, output_dir: &Path, chunk_tokens: Option<usize>) -> Result<Vec<PathBuf>> {
+        let Some(chunk_tokens) = chunk_tokens else {
+            let path = output_dir.join("llm_pack.txt");
+            let text: String = self.documents.iter().map(PackedDocument::render).collect();
+            fs::write(&path, text).map_err(RepoDocsError::Io)?;
+            return Ok(vec![path]);
+        };
+
+        let mut paths = Vec::new();
+        for (n, chunk) in self.chunks(chunk_tokens).iter().enumerate() {
+            let path = output_dir.join(format!("llm_pack_{:03}.txt", n + 1));
+            fs::write(&path, chunk).map_err(RepoDocsError::Io)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Group the documents into chunks of at most `chunk_tokens` tokens.
+    /// Documents longer than a chunk are split at paragraphs, each part
+    /// repeating the document's tag.
+    fn chunks(&self, chunk_tokens: usize) -> Vec<String> {
+        let mut chunks = Vec::new();
+        let mut current = String::new();
+        let mut current_tokens = 0;
+
+        for doc in &self.documents {
+            let room = chunk_tokens.saturating_sub(doc.overhead()).max(1);
+            for text in split_text(&doc.text, room) {
+                let part = PackedDocument {
+                    path: doc.path.clone(),
+                    text,
+                    truncated: doc.truncated,
+                }
+                .render();
+                let tokens = estimate_tokens(&part);
+                if current_tokens + tokens > chunk_tokens && !current.is_empty() {
+                    chunks.push(std::mem::take(&mut current));
+                    current_tokens = 0;
+                }
+                current.push_str(&part);
+                current_tokens += tokens;
+            }
+        }
+
+        if !current.is_empty() {
+            chunks.push(current);
+        }
+        chunks
+    }
+}
+
+/// Text of a document: the markdown cells of notebooks, the content of
+/// anything else. Binary files have none.
+fn document_text(doc: &DocumentFile) -> Option<String> {
+    if notebook::has_notebook_extension(&doc.relative_path) {
+        return Notebook::load(&doc.source_path)
+            .ok()
+            .map(|notebook| notebook.to_markdown());
+    }
+    String::from_utf8(fs::read(&doc.source_path).ok()?).ok()
+}
+
+/// Remove what costs tokens without telling a model anything: front
+/// matter, images and badges, HTML comments and runs of blank lines
+fn clean(content: &str) -> String {
+    let content = front_matter::strip(content);
+    let content = image_regex().replace_all(content, "");
+    let content = html_noise_regex().replace_all(&content, "");
+    let lines: Vec<&str> = content.lines().map(str::trim_end).collect();
+    blank_lines_regex()
+        .replace_all(&lines.join("\n"), "\n\n")
+        .trim()
+        .to_string()
+}
+
+/// Split `available` tokens between documents of the given sizes: every
+/// document gets an equal share, and what short documents do not need is
+/// shared among the longer ones
+fn share_budget(sizes: &[usize], available: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| sizes[i]);
+
+    let mut allocation = vec![0; sizes.len()];
+    let mut remaining = available;
+    for (placed, &i) in order.iter().enumerate() {
+        let share = remaining / (sizes.len() - placed);
+        allocation[i] = sizes[i].min(share);
+        remaining -= allocation[i];
+    }
+    allocation
+}
+
+/// Shorten `text` to about `tokens` tokens, ending at a paragraph or line
+/// break when there is one in the second half of what is kept
+fn truncate(text: &str, tokens: usize) -> String {
+    let keep_chars = (tokens * CHARS_PER_TOKEN).saturating_sub(TRUNCATION_MARKER.len());
+    let end = text
+        .char_indices()
+        .nth(keep_chars)
+        .map_or(text.len(), |(index, _)| index);
+    let kept = &text[..end];
+
+    let cut = kept
+        .rfind("\n\n")
+        .or_else(|| kept.rfind('\n'))
+        .filter(|&cut| cut >= kept.len() / 2)
+        .unwrap_or(kept.len());
+    format!("{}{}", kept[..cut].trim_end(), TRUNCATION_MARKER)
+}
+
+/// Split `text` into parts of at most `tokens` tokens, preferring
+/// paragraph boundaries and cutting long paragraphs where needed
+fn split_text(text: &str, tokens: usize) -> Vec<String> {
+    let max_chars = tokens * CHARS_PER_TOKEN;
+    let mut parts = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n") {
+        let mut paragraph = paragraph;
+        while paragraph.chars().count() > max_chars {
+            if !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+            let end = paragraph
+                .char_indices()
+                .nth(max_chars)
+                .map_or(paragraph.len(), |(index, _)| index);
+            parts.push(paragraph[..end].to_string());
+            paragraph = &paragraph[end..];
+        }
+
+        let joined = current.chars().count() + 2 + paragraph.chars().count();
+        if !current.is_empty() && joined > max_chars {
+            parts.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(paragraph);
+    }
+
+    if !current.is_empty() || parts.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::write_test_documents;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_clean() {
+        let content = "---\ntitle: A\n---\n# Project [![CI](https://ci/badge.svg)](https://ci)\n\n\n\n<!-- toc -->\n![logo][logo]\nText with a [link](docs/a.md).   \n<img src=\"x.png\" alt=\"x\">\n";
+        assert_eq!(
+            clean(content),
+            "# Project\n\nText with a [link](docs/a.md)."
+        );
+    }
+
+    #[test]
+    fn test_share_budget() {
+        // The short document is kept whole, the two long ones split the rest
+        assert_eq!(share_budget(&[10, 500, 300], 310), vec![10, 150, 150]);
+        assert_eq!(share_budget(&[10, 20], 100), vec![10, 20]);
+    }
+
+    #[test]
+    fn test_build_with_token_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        let long = "Paragraph of documentation text.\n\n".repeat(200);
+        let documents = write_test_documents(
+            temp_dir.path(),
+            &[
+                ("docs/guide.md", long.as_str()),
+                ("README.md", "# Project\n\nShort introduction."),
+                ("docs/logo.md", "![logo](logo.png)"),
+            ],
+        );
+
+        let pack = LlmPack::build(&documents, None);
+        assert_eq!(pack.documents.len(), 2);
+        assert_eq!(pack.documents[0].path, "README.md");
+
+        let limited = LlmPack::build(&documents, Some(500));
+        assert!(limited.total_tokens() <= 500);
+        assert!(!limited.documents[0].truncated);
+        assert!(limited.documents[1].truncated);
+        assert!(limited.documents[1].text.ends_with(TRUNCATION_MARKER));
+
+        let tight = LlmPack::build(&documents, Some(60));
+        assert_eq!(tight.documents.len(), 1);
+        assert_eq!(tight.omitted, vec!["docs/guide.md"]);
+    }
+
+    #[test]
+    fn test_save_in_chunks() {
+        let temp_dir = TempDir::new().unwrap();
+        let long = "Paragraph of documentation text.\n\n".repeat(100);
+        let documents = write_test_documents(temp_dir.path(), &[("guide.md", long)]);
+        let pack = LlmPack::build(&documents, None);
+
+        let output_dir = TempDir::new().unwrap();
+        let paths = pack.save(output_dir.path(), Some(300)).unwrap();
+        assert!(paths.len() > 1);
+        for path in &paths {
+            let chunk = fs::read_to_string(path).unwrap();
+            assert!(estimate_tokens(&chunk) <= 300);
+            assert!(chunk.starts_with("<document path=\"guide.md\">\n"));
+        }
+
+        let single = pack.save(output_dir.path(), None).unwrap();
+        assert_eq!(single, vec![output_dir.path().join("llm_pack.txt")]);
+    }
+}
@@ -0,0 +1,318 @@
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::markdown::Heading;
+use crate::scanner::DocumentFile;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// One extracted document as recorded in `.repodocs/manifest.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Path relative to the output directory
+    pub extracted: String,
+    pub size: u64,
+    /// Source modification time in seconds since the Unix epoch
+    #[serde(default)]
+    pub modified: Option<u64>,
+    /// BLAKE3 hash of the content
+    pub hash: String,
+    /// SHA-256 of the extracted file as written, which differs from the
+    /// source when Markdown is transformed or its links rewritten
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+impl ManifestEntry {
+    /// Fingerprint a document. The hash of the previous entry is reused when
+    /// size and modification time are unchanged, so unchanged local trees
+    /// are not read again; fresh clones always get hashed.
+    pub fn for_document(
+        document: &DocumentFile,
+        extracted: String,
+        previous: Option<&ManifestEntry>,
+    ) -> Result<Self> {
+        let modified = document
+            .modified
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs());
+
+        let hash = match previous {
+            Some(entry)
+                if entry.size == document.size
+                    && entry.modified.is_some()
+                    && entry.modified == modified =>
+            {
+                entry.hash.clone()
+            }
+            _ => hash_file(&document.source_path)?,
+        };
+
+        Ok(Self {
+            path: document.relative_path.to_string_lossy().replace('\\', "/"),
+            extracted,
+            size: document.size,
+            modified,
+            hash,
+            sha256: None,
+        })
+    }
+}
+
+/// Content hashes of an extraction, used by incremental mode and to verify
+/// the output with `--verify`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Commit the documentation was extracted from, when the source is a
+    /// git repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Hash of the settings that shape the extracted files beyond their
+    /// sources, such as `[transform]`, link rewriting and source banners
+    /// with their commit;
+    /// incremental runs rewrite every file
+    /// when it changes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settings: Option<String>,
+    pub entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Manifest of `documents` extracted to their repository paths, without
+    /// hashes
+    #[cfg(test)]
+    pub fn for_test_documents(documents: &[DocumentFile]) -> Self {
+        let entries = documents
+            .iter()
+            .map(|document| {
+                let path = document.relative_path.to_string_lossy().replace('\\', "/");
+                ManifestEntry {
+                    extracted: path.clone(),
+                    path,
+                    size: document.size,
+                    modified: None,
+                    hash: String::new(),
+                    sha256: None,
+                }
+            })
+            .collect();
+        Manifest {
+            commit: None,
+            settings: None,
+            entries,
+        }
+    }
+
+    /// Load `manifest.json` from the metadata directory, if present
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let content = match fs::read_to_string(dir.join(MANIFEST_FILE)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(RepoDocsError::Io(e)),
+        };
+
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| RepoDocsError::Config {
+                message: format!("Failed to parse extraction manifest: {}", e),
+            })
+    }
+
+    /// Write `manifest.json` into the metadata directory
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| RepoDocsError::Config {
+            message: format!("Failed to serialize extraction manifest: {}", e),
+        })?;
+        fs::write(dir.join(MANIFEST_FILE), json).map_err(RepoDocsError::Io)?;
+        Ok(())
+    }
+
+    /// Entries keyed by repository path
+    pub fn by_path(&self) -> HashMap<&str, &ManifestEntry> {
+        self.entries.iter().map(|e| (e.path.as_str(), e)).collect()
+    }
+
+    /// Re-hash the extracted files under `output_dir` and compare them with
+    /// the recorded checksums
+    pub fn verify(&self, output_dir: &Path) -> Result<Verification> {
+        let mut verification = Verification::default();
+        for entry in &self.entries {
+            let Some(expected) = &entry.sha256 else {
+                verification.unchecked.push(entry.extracted.clone());
+                continue;
+            };
+            match sha256_file(&output_dir.join(&entry.extracted)) {
+                Ok(actual) if &actual == expected => verification.verified += 1,
+                Ok(_) => verification.modified.push(entry.extracted.clone()),
+                Err(RepoDocsError::Io(e)) if e.kind() == io::ErrorKind::NotFound => {
+                    verification.missing.push(entry.extracted.clone())
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(verification)
+    }
+}
+
+/// Outcome of `Manifest::verify`, by output path
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Verification {
+    pub verified: usize,
+    /// Files that are gone, as after a partial extraction
+    pub missing: Vec<String>,
+    /// Files whose content changed after extraction
+    pub modified: Vec<String>,
+    /// Files recorded by a version that did not write checksums
+    pub unchecked: Vec<String>,
+}
+
+impl Verification {
+    pub fn is_intact(&self) -> bool {
+        self.missing.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// An output a merge kept rather than overwrite, since it is not what the
+/// previous extraction wrote there
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeConflict {
+    /// Repository path of the document that was not written
+    pub document: String,
+    /// Path relative to the output directory
+    pub output: String,
+}
+
+/// Outcome of merging an extraction into an existing output directory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeSummary {
+    /// Repository paths of documents written, as new or changed outputs
+    pub written: Vec<String>,
+    pub unchanged: usize,
+    /// Outputs added or edited by hand, left alone
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Differences between an incremental extraction and the previous one
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChangeSummary {
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged: usize,
+    /// Headings of modified Markdown documents before they changed, by
+    /// repository path
+    #[serde(skip)]
+    pub previous_headings: BTreeMap<String, Vec<Heading>>,
+}
+
+impl ChangeSummary {
+    pub fn has_changes(&self) -> bool {
+        !self.added.is_empty() || !self.modified.is_empty() || !self.removed.is_empty()
+    }
+}
+
+pub fn hash_file(path: &Path) -> Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    let mut file = fs::File::open(path).map_err(RepoDocsError::Io)?;
+    io::copy(&mut file, &mut hasher).map_err(RepoDocsError::Io)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    let mut file = fs::File::open(path).map_err(RepoDocsError::Io)?;
+    io::copy(&mut file, &mut hasher).map_err(RepoDocsError::Io)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(Manifest::load(temp_dir.path()).unwrap().is_none());
+
+        let file = temp_dir.path().join("README.md");
+        fs::write(&file, "# Hello").unwrap();
+        let hash = hash_file(&file).unwrap();
+        assert_eq!(hash.len(), 64);
+
+        let manifest = Manifest {
+            commit: None,
+            settings: None,
+            entries: vec![ManifestEntry {
+                path: "README.md".to_string(),
+                extracted: "README.md".to_string(),
+                size: 7,
+                modified: None,
+                hash: hash.clone(),
+                sha256: None,
+            }],
+        };
+        manifest.save(temp_dir.path()).unwrap();
+
+        let loaded = Manifest::load(temp_dir.path()).unwrap().unwrap();
+        assert_eq!(loaded.entries, manifest.entries);
+        assert_eq!(loaded.by_path()["README.md"].hash, hash);
+    }
+
+    #[test]
+    fn test_verify_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let entry = |name: &str, content: &str| {
+            let path = temp_dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            ManifestEntry {
+                path: name.to_string(),
+                extracted: name.to_string(),
+                size: content.len() as u64,
+                modified: None,
+                hash: hash_file(&path).unwrap(),
+                sha256: Some(sha256_file(&path).unwrap()),
+            }
+        };
+        let mut manifest = Manifest {
+            commit: Some("0123abcd".to_string()),
+            settings: None,
+            entries: vec![entry("a.md", "a"), entry("b.md", "b"), entry("c.md", "c")],
+        };
+        manifest.entries.push(ManifestEntry {
+            sha256: None,
+            ..entry("old.md", "old")
+        });
+        assert_eq!(
+            manifest.entries[0].sha256.as_deref(),
+            Some("ca978112ca1bbdcafac231b39a23dc4da786eff8147c4e72b9807785afee48bb")
+        );
+
+        fs::write(temp_dir.path().join("b.md"), "tampered").unwrap();
+        fs::remove_file(temp_dir.path().join("c.md")).unwrap();
+
+        let verification = manifest.verify(temp_dir.path()).unwrap();
+        assert_eq!(
+            verification,
+            Verification {
+                verified: 1,
+                missing: vec!["c.md".to_string()],
+                modified: vec!["b.md".to_string()],
+                unchecked: vec!["old.md".to_string()],
+            }
+        );
+        assert!(!verification.is_intact());
+    }
+}
@@ -0,0 +1,395 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkdownLink {
+    pub text: String,
+    pub target: String,
+}
+
+impl MarkdownLink {
+    pub fn is_external(&self) -> bool {
+        let target = self.target.trim();
+        target.contains("://") || target.starts_with("mailto:") || target.starts_with("//")
+    }
+
+    pub fn is_anchor_only(&self) -> bool {
+        self.target.starts_with('#')
+    }
+
+    /// Path part of the target, without `#fragment` or `?query`
+    pub fn path_part(&self) -> &str {
+        let end = self.target.find(['#', '?']).unwrap_or(self.target.len());
+        &self.target[..end]
+    }
+}
+
+fn inline_link_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"!?\[([^\]]*)\]\(\s*<?([^)\s>]+)>?(?:\s+"[^"]*")?\s*\)"#)
+            .expect("valid inline link regex")
+    })
+}
+
+fn reference_link_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?m)^\s{0,3}\[([^\]]+)\]:\s*<?(\S+?)>?(?:\s+.*)?$")
+            .expect("valid reference link regex")
+    })
+}
+
+/// Extract inline (`[text](target)`) and reference-style (`[id]: target`)
+/// links from Markdown content. Image links are included.
+pub fn extract_links(content: &str) -> Vec<MarkdownLink> {
+    let mut links = Vec::new();
+
+    for caps in inline_link_regex().captures_iter(content) {
+        links.push(MarkdownLink {
+            text: caps[1].to_string(),
+            target: caps[2].to_string(),
+        });
+    }
+
+    for caps in reference_link_regex().captures_iter(content) {
+        links.push(MarkdownLink {
+            text: caps[1].to_string(),
+            target: caps[2].to_string(),
+        });
+    }
+
+    links
+}
+
+/// Resolve a link target found in `from` (a repository-relative path) to a
+/// repository-relative path. Returns `None` for targets escaping the root.
+pub fn resolve_relative_link(from: &Path, target: &str) -> Option<PathBuf> {
+    if target.is_empty() {
+        return None;
+    }
+
+    let joined = if let Some(stripped) = target.strip_prefix('/') {
+        PathBuf::from(stripped)
+    } else {
+        from.parent()
+            .map(|p| p.join(target))
+            .unwrap_or_else(|| PathBuf::from(target))
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::ParentDir => {
+                // Security: never resolve above the repository root
+                if !resolved.pop() {
+                    return None;
+                }
+            }
+            Component::CurDir => {}
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    if resolved.as_os_str().is_empty() {
+        None
+    } else {
+        Some(resolved)
+    }
+}
+
+/// Point a relative link `target`, found in the document at repository path
+/// `source`, at the file another document was written to. `destinations`
+/// maps repository paths to output paths, and `from` is the output path of
+/// the file holding the link. Returns `None` for links that stay as they are.
+pub fn relink(
+    target: &str,
+    source: &Path,
+    from: &Path,
+    destinations: &HashMap<PathBuf, PathBuf>,
+) -> Option<String> {
+    let link = MarkdownLink {
+        text: String::new(),
+        target: target.to_string(),
+    };
+    if link.is_external() || link.is_anchor_only() {
+        return None;
+    }
+
+    let linked = resolve_relative_link(source, link.path_part())?;
+    let destination = destinations.get(&linked)?;
+    let suffix = &target[link.path_part().len()..];
+    Some(format!("{}{}", relative_href(from, destination), suffix))
+}
+
+/// Link from the file at `from` to the file at `to`, both relative to the
+/// same directory
+pub fn relative_href(from: &Path, to: &Path) -> String {
+    let from_dir: Vec<Component> = from
+        .parent()
+        .map(|parent| parent.components().collect())
+        .unwrap_or_default();
+    let to_components: Vec<Component> = to.components().collect();
+
+    let common = from_dir
+        .iter()
+        .zip(&to_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); from_dir.len() - common];
+    parts.extend(
+        to_components[common..]
+            .iter()
+            .map(|component| component.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
+
+/// Replace the targets of inline and reference-style links in Markdown
+/// `content` with what `rewrite` returns for them, leaving fenced code
+/// blocks and links it returns `None` for untouched
+pub fn rewrite_links(content: &str, mut rewrite: impl FnMut(&str) -> Option<String>) -> String {
+    let mut rewritten = String::with_capacity(content.len());
+    let mut in_fence = false;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if in_fence || !(line.contains("](") || line.contains("]:")) {
+            rewritten.push_str(line);
+            continue;
+        }
+
+        let mut last = 0;
+        for regex in [inline_link_regex(), reference_link_regex()] {
+            for caps in regex.captures_iter(line) {
+                let target = caps.get(2).expect("link target group");
+                if target.start() < last {
+                    continue;
+                }
+                if let Some(new_target) = rewrite(target.as_str()) {
+                    rewritten.push_str(&line[last..target.start()]);
+                    rewritten.push_str(&new_target);
+                    last = target.end();
+                }
+            }
+        }
+        rewritten.push_str(&line[last..]);
+    }
+
+    rewritten
+}
+
+pub fn is_markdown_extension(extension: &str) -> bool {
+    matches!(extension, "md" | "markdown" | "mdown")
+}
+
+/// Whether a file is Markdown, MDX included, judging by its extension
+pub fn is_markdown_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            let ext = ext.to_lowercase();
+            is_markdown_extension(&ext) || ext == "mdx"
+        })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+}
+
+fn atx_heading_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^\s{0,3}(#{1,6})\s+(.+?)(?:\s+#+)?\s*$").expect("valid ATX heading regex")
+    })
+}
+
+/// Extract ATX (`## Title`) and setext (`Title` + `===`/`---`) headings,
+/// ignoring anything inside fenced code blocks.
+pub fn extract_headings(content: &str) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut in_fence = false;
+    let mut previous: Option<&str> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            previous = None;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        if let Some(caps) = atx_heading_regex().captures(line) {
+            headings.push(Heading {
+                level: caps[1].len() as u8,
+                text: caps[2].trim().to_string(),
+            });
+            previous = None;
+            continue;
+        }
+
+        if let Some(text) = previous {
+            let underline = !trimmed.is_empty()
+                && (trimmed.chars().all(|c| c == '=') || trimmed.chars().all(|c| c == '-'));
+            if underline {
+                headings.push(Heading {
+                    level: if trimmed.starts_with('=') { 1 } else { 2 },
+                    text: text.to_string(),
+                });
+                previous = None;
+                continue;
+            }
+        }
+
+        previous = if trimmed.is_empty() || line.starts_with([' ', '\t']) {
+            None
+        } else {
+            Some(trimmed)
+        };
+    }
+
+    headings
+}
+
+/// GitHub-style heading anchor: lowercase, punctuation removed, spaces
+/// replaced with hyphens.
+pub fn slugify(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                Some(c)
+            } else if c.is_whitespace() {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_links() {
+        let content = "See [guide](docs/guide.md) and ![logo](img/logo.png \"Logo\").\n\
+                       \n[ref]: ../other.md#section\n\
+                       Visit [site](https://example.com).";
+        let links = extract_links(content);
+
+        let targets: Vec<&str> = links.iter().map(|l| l.target.as_str()).collect();
+        assert!(targets.contains(&"docs/guide.md"));
+        assert!(targets.contains(&"img/logo.png"));
+        assert!(targets.contains(&"../other.md#section"));
+        assert!(targets.contains(&"https://example.com"));
+
+        let external = links
+            .iter()
+            .find(|l| l.target.starts_with("https"))
+            .unwrap();
+        assert!(external.is_external());
+    }
+
+    #[test]
+    fn test_resolve_relative_link() {
+        let from = Path::new("docs/guide/intro.md");
+        assert_eq!(
+            resolve_relative_link(from, "setup.md"),
+            Some(PathBuf::from("docs/guide/setup.md"))
+        );
+        assert_eq!(
+            resolve_relative_link(from, "../../README.md"),
+            Some(PathBuf::from("README.md"))
+        );
+        assert_eq!(
+            resolve_relative_link(from, "/CONTRIBUTING.md"),
+            Some(PathBuf::from("CONTRIBUTING.md"))
+        );
+        assert_eq!(resolve_relative_link(from, "../../../escape.md"), None);
+    }
+
+    #[test]
+    fn test_relative_href() {
+        let href = |from: &str, to: &str| relative_href(Path::new(from), Path::new(to));
+        assert_eq!(href("README.html", "docs/guide.html"), "docs/guide.html");
+        assert_eq!(href("docs/guide.html", "README.html"), "../README.html");
+        assert_eq!(href("docs/a/b.html", "docs/c.html"), "../c.html");
+        assert_eq!(href("docs/guide.html", "docs/api.html"), "api.html");
+    }
+
+    #[test]
+    fn test_rewrite_links() {
+        let content = "See [docs/a.md](docs/a.md#top) and [site](https://x.io).\n\
+                       ```\n[code](docs/a.md)\n```\n\
+                       [ref]: docs/a.md\n";
+        let rewritten = rewrite_links(content, |target| {
+            target
+                .starts_with("docs/a.md")
+                .then(|| target.replace("a.md", "a.html"))
+        });
+        assert_eq!(
+            rewritten,
+            "See [docs/a.md](docs/a.html#top) and [site](https://x.io).\n\
+             ```\n[code](docs/a.md)\n```\n\
+             [ref]: docs/a.html\n"
+        );
+    }
+
+    #[test]
+    fn test_extract_headings() {
+        let content =
+            "# Title\n\nIntro\n\nSetup\n-----\n\n```\n# not a heading\n```\n### Deep ###\n";
+        let headings = extract_headings(content);
+
+        assert_eq!(
+            headings,
+            vec![
+                Heading {
+                    level: 1,
+                    text: "Title".to_string()
+                },
+                Heading {
+                    level: 2,
+                    text: "Setup".to_string()
+                },
+                Heading {
+                    level: 3,
+                    text: "Deep".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Getting Started"), "getting-started");
+        assert_eq!(slugify("What's new in v2.0?"), "whats-new-in-v20");
+        assert_eq!(slugify("snake_case and-dash"), "snake_case-and-dash");
+    }
+
+    #[test]
+    fn test_link_path_part() {
+        let link = MarkdownLink {
+            text: "x".to_string(),
+            target: "guide.md#install?x=1".to_string(),
+        };
+        assert_eq!(link.path_part(), "guide.md");
+        assert!(!link.is_anchor_only());
+    }
+}
@@ -1,5 +1,41 @@
+pub mod accessibility;
+pub mod archive;
+pub mod assets;
+pub mod budget;
+pub mod bundle;
+pub mod duplicates;
+pub mod export;
 pub mod file_extractor;
+pub mod glossary;
+pub mod graph;
+pub mod links;
+pub mod llm_pack;
+pub mod manifest;
+pub mod markdown;
+pub mod outline;
 pub mod output_manager;
+pub mod pdf;
+pub mod relevance;
+pub mod renderer;
+pub mod sarif;
+pub mod search;
+pub mod similarity;
+pub mod sitemap;
+pub mod source_docs;
+pub mod transform;
+pub mod whats_new;
 
-pub use file_extractor::{ExtractionProgress, FileOperations};
-pub use output_manager::{ConfigSnapshot, ExtractionReport, OutputManager};
+pub use bundle::Bundle;
+pub use export::SiteExport;
+pub use file_extractor::{ExtractionProgress, FileOperations, PathMapping};
+pub use glossary::Glossary;
+pub use graph::DocumentGraph;
+pub use llm_pack::LlmPack;
+pub use manifest::{ChangeSummary, Manifest, ManifestEntry, MergeConflict, MergeSummary};
+pub use outline::Outline;
+pub use output_manager::{ConfigSnapshot, ExtractionReport, OutputManager, RemovedOutput};
+pub use pdf::PdfDocument;
+pub use renderer::HtmlSite;
+pub use similarity::NearDuplicateGroup;
+pub use source_docs::SourceDocs;
+pub use whats_new::WhatsNew;
@@ -0,0 +1,157 @@
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::markdown::{self, is_markdown_extension};
+use crate::scanner::DocumentFile;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutlineHeading {
+    pub level: u8,
+    pub text: String,
+    pub anchor: String,
+    pub file: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<OutlineHeading>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentOutline {
+    pub file: String,
+    pub headings: Vec<OutlineHeading>,
+}
+
+/// Heading tree of every Markdown document, written as `outline.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Outline {
+    pub documents: Vec<DocumentOutline>,
+}
+
+impl Outline {
+    pub fn build(documents: &[DocumentFile]) -> Self {
+        let mut outline = Outline::default();
+
+        for doc in documents {
+            if !is_markdown_extension(&doc.extension) {
+                continue;
+            }
+
+            let content = match fs::read(&doc.source_path) {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(_) => continue,
+            };
+
+            let file = doc.relative_path.to_string_lossy().replace('\\', "/");
+            let headings = build_tree(&content, &file);
+            if !headings.is_empty() {
+                outline.documents.push(DocumentOutline { file, headings });
+            }
+        }
+
+        outline.documents.sort_by(|a, b| a.file.cmp(&b.file));
+        outline
+    }
+
+    pub fn heading_count(&self) -> usize {
+        fn count(headings: &[OutlineHeading]) -> usize {
+            headings.iter().map(|h| 1 + count(&h.children)).sum()
+        }
+
+        self.documents.iter().map(|d| count(&d.headings)).sum()
+    }
+
+    /// Write `outline.json` into the given directory
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| RepoDocsError::Config {
+            message: format!("Failed to serialize outline: {}", e),
+        })?;
+        fs::write(dir.join("outline.json"), json).map_err(RepoDocsError::Io)?;
+        Ok(())
+    }
+}
+
+/// Nest the flat heading list by level, assigning unique anchors per file
+fn build_tree(content: &str, file: &str) -> Vec<OutlineHeading> {
+    let mut used_anchors: HashMap<String, usize> = HashMap::new();
+    let mut roots: Vec<OutlineHeading> = Vec::new();
+    let mut stack: Vec<OutlineHeading> = Vec::new();
+
+    for heading in markdown::extract_headings(content) {
+        let base = markdown::slugify(&heading.text);
+        let seen = used_anchors.entry(base.clone()).or_insert(0);
+        let anchor = if *seen == 0 {
+            base
+        } else {
+            format!("{}-{}", base, seen)
+        };
+        *seen += 1;
+
+        let node = OutlineHeading {
+            level: heading.level,
+            text: heading.text,
+            anchor,
+            file: file.to_string(),
+            children: Vec::new(),
+        };
+
+        while stack.last().is_some_and(|top| top.level >= node.level) {
+            attach(stack.pop().unwrap(), &mut stack, &mut roots);
+        }
+        stack.push(node);
+    }
+
+    while let Some(node) = stack.pop() {
+        attach(node, &mut stack, &mut roots);
+    }
+
+    roots
+}
+
+fn attach(node: OutlineHeading, stack: &mut [OutlineHeading], roots: &mut Vec<OutlineHeading>) {
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(node),
+        None => roots.push(node),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_build_tree() {
+        let content = "# Guide\n## Install\n### Linux\n## Usage\n## Install\n# Appendix\n";
+        let tree = build_tree(content, "guide.md");
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].children.len(), 3);
+        assert_eq!(tree[0].children[0].children[0].text, "Linux");
+        assert_eq!(tree[0].children[2].anchor, "install-1");
+        assert_eq!(tree[1].file, "guide.md");
+    }
+
+    #[test]
+    fn test_outline_build_and_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("README.md");
+        fs::write(&path, "# Project\n\n## Getting Started\n").unwrap();
+
+        let documents = vec![DocumentFile::new(
+            path,
+            PathBuf::from("README.md"),
+            0,
+            SystemTime::UNIX_EPOCH,
+        )];
+
+        let outline = Outline::build(&documents);
+        assert_eq!(outline.heading_count(), 2);
+
+        outline.save(temp_dir.path()).unwrap();
+        let json = fs::read_to_string(temp_dir.path().join("outline.json")).unwrap();
+        assert!(json.contains("\"anchor\": \"getting-started\""));
+    }
+}
@@ -1,30 +1,109 @@
 use crate::cloner::RepositoryInfo;
+use crate::config::SortOrder;
+use crate::environment::EnvironmentInfo;
 use crate::error::{RepoDocsError, Result};
-use crate::extractor::ExtractionProgress;
-use crate::scanner::DocumentFile;
+use crate::extractor::accessibility::AccessibilityFinding;
+use crate::extractor::budget::{budget_table, DirectoryBudget};
+use crate::extractor::duplicates::{self, DuplicateGroup};
+use crate::extractor::links::UnresolvedLink;
+use crate::extractor::{
+    ChangeSummary, ExtractionProgress, MergeSummary, NearDuplicateGroup, PathMapping,
+};
+use crate::scanner::notebook::{self, Notebook};
+use crate::scanner::{front_matter, DocumentFile, FrontMatter, SkipStatistics};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::time::{Duration, SystemTime};
+use std::time::Duration;
+use walkdir::WalkDir;
 
+/// Version of the report formats, raised when a field is removed, renamed
+/// or changes its type; new fields are added without raising it
+pub const REPORT_FORMAT_VERSION: u32 = 2;
+
+/// Output directory name template used unless one is configured
+pub const DEFAULT_NAME_TEMPLATE: &str = "docs_{repo}";
+
+/// Placeholders an output directory name template can hold
+pub const NAME_PLACEHOLDERS: [&str; 4] = ["{owner}", "{repo}", "{branch}", "{date}"];
+
+/// Reports written before the version was recorded, which had durations and
+/// file times as `{secs, nanos}` objects
+pub(crate) fn legacy_format_version() -> u32 {
+    1
+}
+
+/// Extraction report, `.repodocs/extraction_report.json`. Fields are
+/// written in declaration order, maps with sorted keys and lists in a
+/// stable order, so reports of identical runs differ only in times.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractionReport {
+    #[serde(default = "legacy_format_version")]
+    pub format_version: u32,
     pub repository_info: RepositoryInfo,
     pub extraction_summary: ExtractionSummary,
     pub files: Vec<FileInfo>,
     pub extraction_time: DateTime<Utc>,
     pub errors: Vec<String>,
     pub config_used: ConfigSnapshot,
+    #[serde(default)]
+    pub output_directory: PathBuf,
+    #[serde(default)]
+    pub near_duplicates: Vec<NearDuplicateGroup>,
+    #[serde(default)]
+    pub shortened_paths: Vec<PathMapping>,
+    #[serde(default)]
+    pub case_collisions: Vec<PathMapping>,
+    #[serde(default)]
+    pub normalized_paths: Vec<PathMapping>,
+    #[serde(default)]
+    pub transliterated_paths: Vec<PathMapping>,
+    #[serde(default)]
+    pub changes: Option<ChangeSummary>,
+    /// Outputs written and kept by `output.merge`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge: Option<MergeSummary>,
+    #[serde(default)]
+    pub skipped: SkipStatistics,
+    /// Relative links to files that do not exist, when links are rewritten
+    #[serde(default)]
+    pub unresolved_links: Vec<UnresolvedLink>,
+    /// Groups of byte-identical files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<DuplicateGroup>,
+    /// Assets copied along with the documents, with `output.with_assets`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assets: Vec<String>,
+    /// Referenced assets that were not copied for exceeding
+    /// `output.max_asset_size`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub oversized_assets: Vec<String>,
+    /// Accessibility problems of the pages rendered with `render = "html"`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accessibility: Vec<AccessibilityFinding>,
+    /// Files, words and estimated tokens under each directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub budget: Vec<DirectoryBudget>,
+    /// Machine the run happened on, recorded with `output.capture_env`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<EnvironmentInfo>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractionSummary {
     pub total_files_processed: usize,
     pub total_bytes_processed: u64,
+    #[serde(
+        rename = "extraction_duration_ms",
+        alias = "extraction_duration",
+        with = "duration_ms"
+    )]
     pub extraction_duration: Duration,
-    pub files_by_extension: std::collections::HashMap<String, usize>,
+    pub files_by_extension: BTreeMap<String, usize>,
     pub largest_file: Option<FileInfo>,
     pub average_file_size: u64,
 }
@@ -35,7 +114,23 @@ pub struct FileInfo {
     pub relative_path: String,
     pub extension: String,
     pub size: u64,
-    pub modified: SystemTime,
+    #[serde(deserialize_with = "timestamp::deserialize")]
+    pub modified: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scan_root: Option<String>,
+    /// BLAKE3 hash of the content, for files that were extracted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// First heading and paragraph, with `output.report_snippets`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    /// Relevance among the extracted documents, from their depth, file
+    /// name, inbound links and size; higher is more important
+    #[serde(default)]
+    pub score: f64,
+    /// Title, tags and date from Markdown front matter
+    #[serde(flatten, default)]
+    pub front_matter: FrontMatter,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +139,8 @@ pub struct ConfigSnapshot {
     pub max_file_size: u64,
     pub exclude_dirs: Vec<String>,
     pub preserve_structure: bool,
+    #[serde(default)]
+    pub scan_roots: Vec<String>,
 }
 
 impl From<&DocumentFile> for FileInfo {
@@ -53,17 +150,50 @@ impl From<&DocumentFile> for FileInfo {
             relative_path: doc.relative_path.to_string_lossy().to_string(),
             extension: doc.extension.clone(),
             size: doc.size,
-            modified: doc.modified,
+            modified: doc.modified.into(),
+            scan_root: doc.scan_root.clone(),
+            hash: None,
+            snippet: None,
+            score: doc.score,
+            front_matter: doc.front_matter.clone(),
         }
     }
 }
 
+/// Output of a cancelled run that was removed
+#[derive(Debug, Clone)]
+pub struct RemovedOutput {
+    pub path: PathBuf,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+impl fmt::Display for RemovedOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({} files, {})",
+            self.path.display(),
+            self.files,
+            format_bytes(self.bytes)
+        )
+    }
+}
+
 pub struct OutputManager {
     base_path: PathBuf,
-    #[allow(dead_code)]
     repo_name: String,
     output_directory: PathBuf,
     force_overwrite: bool,
+    incremental: bool,
+    sort_order: SortOrder,
+    snippet_length: Option<usize>,
+    /// The directory existed before `initialize` and was kept for an
+    /// incremental update
+    kept_existing: bool,
+    /// Directory a fresh extraction is written to until `commit` moves it
+    /// to the output directory
+    staging_directory: Option<PathBuf>,
 }
 
 impl OutputManager {
@@ -75,6 +205,11 @@ impl OutputManager {
             repo_name,
             output_directory,
             force_overwrite: false,
+            incremental: false,
+            sort_order: SortOrder::Path,
+            snippet_length: None,
+            kept_existing: false,
+            staging_directory: None,
         };
 
         manager.validate_paths()?;
@@ -86,38 +221,131 @@ impl OutputManager {
         self
     }
 
+    /// Keep an existing output directory so it can be updated in place
+    pub fn with_incremental(mut self, incremental: bool) -> Self {
+        self.incremental = incremental;
+        self
+    }
+
+    /// Order of the file listings in the reports
+    pub fn with_sort_order(mut self, sort_order: SortOrder) -> Self {
+        self.sort_order = sort_order;
+        self
+    }
+
+    /// Record a preview of up to `length` characters for each file in the
+    /// JSON report
+    pub fn with_snippets(mut self, length: Option<usize>) -> Self {
+        self.snippet_length = length;
+        self
+    }
+
     pub fn with_custom_output_name<S: Into<String>>(mut self, name: S) -> Self {
         let name = sanitize_repo_name(&name.into());
         self.output_directory = self.base_path.join(name);
         self
     }
 
-    pub fn initialize(&self) -> Result<()> {
-        if self.output_directory.exists() {
-            if !self.force_overwrite {
-                return Err(RepoDocsError::OutputDirectoryExists {
-                    path: self.output_directory.display().to_string(),
-                });
-            } else {
-                // Remove existing directory
-                fs::remove_dir_all(&self.output_directory).map_err(RepoDocsError::Io)?;
-            }
+    /// Name the output directory after `template`, see [`expand_name_template`]
+    pub fn with_name_template(mut self, template: &str, owner: &str, branch: &str) -> Self {
+        let name = expand_name_template(template, owner, &self.repo_name, branch, Utc::now());
+        self.output_directory = self.base_path.join(name);
+        self
+    }
+
+    /// Create the directory the extraction is written to. A fresh
+    /// extraction goes to a staging directory next to the output directory,
+    /// so an interrupted run never leaves half an extraction in its place;
+    /// an existing extraction kept for an incremental update is written to
+    /// directly.
+    pub fn initialize(&mut self) -> Result<()> {
+        self.kept_existing = self.output_directory.exists() && self.incremental;
+
+        if self.output_directory.exists() && !self.incremental && !self.force_overwrite {
+            return Err(RepoDocsError::OutputDirectoryExists {
+                path: self.output_directory.display().to_string(),
+            });
+        }
+
+        if !self.kept_existing && self.staging_directory.is_none() {
+            self.staging_directory = Some(self.sibling_directory("staging")?);
         }
 
         // Create output directory
-        fs::create_dir_all(&self.output_directory).map_err(RepoDocsError::Io)?;
+        fs::create_dir_all(self.get_output_directory()).map_err(RepoDocsError::Io)?;
 
         // Create .repodocs metadata directory
-        let metadata_dir = self.output_directory.join(".repodocs");
-        fs::create_dir_all(&metadata_dir).map_err(RepoDocsError::Io)?;
+        fs::create_dir_all(self.get_metadata_dir()).map_err(RepoDocsError::Io)?;
 
         Ok(())
     }
 
+    /// Directory the extraction is written to: the staging directory until
+    /// `commit`, the output directory after it
     pub fn get_output_directory(&self) -> &Path {
+        self.staging_directory
+            .as_deref()
+            .unwrap_or(&self.output_directory)
+    }
+
+    /// Output directory the extraction ends up in once committed
+    pub fn destination(&self) -> &Path {
         &self.output_directory
     }
 
+    /// Move a completed extraction from the staging directory to the output
+    /// directory, replacing an earlier extraction there with `force_overwrite`.
+    /// The earlier extraction is only removed once the new one is in place.
+    pub fn commit(&mut self) -> Result<()> {
+        let Some(staging) = self.staging_directory.take() else {
+            return Ok(());
+        };
+
+        let replaced = if self.output_directory.exists() {
+            let holder = self.sibling_directory("replaced")?;
+            let replaced = holder.join("previous");
+            if let Err(e) = fs::rename(&self.output_directory, &replaced) {
+                let _ = fs::remove_dir_all(&holder);
+                self.staging_directory = Some(staging);
+                return Err(RepoDocsError::Io(e));
+            }
+            Some((holder, replaced))
+        } else {
+            None
+        };
+
+        if let Err(e) = fs::rename(&staging, &self.output_directory) {
+            if let Some((holder, replaced)) = &replaced {
+                let _ = fs::rename(replaced, &self.output_directory);
+                let _ = fs::remove_dir_all(holder);
+            }
+            self.staging_directory = Some(staging);
+            return Err(RepoDocsError::Io(e));
+        }
+
+        if let Some((holder, _)) = replaced {
+            fs::remove_dir_all(&holder).map_err(RepoDocsError::Io)?;
+        }
+        Ok(())
+    }
+
+    /// Create a hidden directory next to the output directory. Its name is
+    /// unique to this manager, so concurrent jobs writing outputs with the
+    /// same name never share or remove each other's directories.
+    fn sibling_directory(&self, purpose: &str) -> Result<PathBuf> {
+        let name = self
+            .output_directory
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        fs::create_dir_all(&self.base_path).map_err(RepoDocsError::Io)?;
+        tempfile::Builder::new()
+            .prefix(&format!(".{}.{}-", name, purpose))
+            .tempdir_in(&self.base_path)
+            .map(|dir| dir.keep())
+            .map_err(RepoDocsError::Io)
+    }
+
     pub fn create_extraction_report(
         &self,
         repository_info: &RepositoryInfo,
@@ -125,24 +353,73 @@ impl OutputManager {
         progress: &ExtractionProgress,
         config: &ConfigSnapshot,
     ) -> Result<ExtractionReport> {
-        let extraction_summary = self.create_extraction_summary(documents, progress);
-        let file_infos: Vec<FileInfo> = documents.iter().map(FileInfo::from).collect();
+        let report = self.build_extraction_report(repository_info, documents, progress, config);
+        self.save_reports(&report)?;
+        Ok(report)
+    }
 
-        let report = ExtractionReport {
+    /// Assemble the report without writing it, so analysis passes can
+    /// attach their results before `save_reports`.
+    pub fn build_extraction_report(
+        &self,
+        repository_info: &RepositoryInfo,
+        documents: &[DocumentFile],
+        progress: &ExtractionProgress,
+        config: &ConfigSnapshot,
+    ) -> ExtractionReport {
+        let extraction_summary = self.create_extraction_summary(documents, progress);
+        let mut sorted: Vec<&DocumentFile> = documents.iter().collect();
+        sorted.sort_by(|a, b| self.sort_order.compare(a, b));
+        let hashes = progress.manifest.by_path();
+        let file_infos: Vec<FileInfo> = sorted
+            .into_iter()
+            .map(|doc| FileInfo {
+                hash: hashes
+                    .get(
+                        doc.relative_path
+                            .to_string_lossy()
+                            .replace('\\', "/")
+                            .as_str(),
+                    )
+                    .map(|entry| entry.hash.clone()),
+                snippet: self.snippet_length.and_then(|length| snippet(doc, length)),
+                ..FileInfo::from(doc)
+            })
+            .collect();
+
+        ExtractionReport {
+            format_version: REPORT_FORMAT_VERSION,
             repository_info: repository_info.clone(),
             extraction_summary,
             files: file_infos,
             extraction_time: Utc::now(),
             errors: progress.errors.clone(),
             config_used: config.clone(),
-        };
+            output_directory: self.output_directory.clone(),
+            near_duplicates: Vec::new(),
+            shortened_paths: progress.shortened_paths.clone(),
+            case_collisions: progress.case_collisions.clone(),
+            normalized_paths: progress.normalized_paths.clone(),
+            transliterated_paths: progress.transliterated_paths.clone(),
+            changes: progress.changes.clone(),
+            merge: progress.merge.clone(),
+            skipped: SkipStatistics::default(),
+            unresolved_links: progress.unresolved_links.clone(),
+            duplicates: duplicates::find(&progress.manifest.entries),
+            assets: progress.assets.clone(),
+            oversized_assets: progress.oversized_assets.clone(),
+            accessibility: Vec::new(),
+            budget: Vec::new(),
+            environment: None,
+        }
+    }
 
+    pub fn save_reports(&self, report: &ExtractionReport) -> Result<()> {
         // Save report in multiple formats
-        self.save_report_json(&report)?;
-        self.save_report_text(&report)?;
-        self.create_summary_file(&report)?;
-
-        Ok(report)
+        self.save_report_json(report)?;
+        self.save_report_text(report)?;
+        self.create_summary_file(report)?;
+        Ok(())
     }
 
     fn create_extraction_summary(
@@ -150,8 +427,7 @@ impl OutputManager {
         documents: &[DocumentFile],
         progress: &ExtractionProgress,
     ) -> ExtractionSummary {
-        let mut files_by_extension: std::collections::HashMap<String, usize> =
-            std::collections::HashMap::new();
+        let mut files_by_extension: BTreeMap<String, usize> = BTreeMap::new();
         let mut largest_file: Option<&DocumentFile> = None;
 
         for doc in documents {
@@ -186,10 +462,7 @@ impl OutputManager {
     }
 
     fn save_report_json(&self, report: &ExtractionReport) -> Result<()> {
-        let report_path = self
-            .output_directory
-            .join(".repodocs")
-            .join("extraction_report.json");
+        let report_path = self.get_metadata_dir().join("extraction_report.json");
         let json_content =
             serde_json::to_string_pretty(report).map_err(|e| RepoDocsError::Config {
                 message: format!("Failed to serialize report to JSON: {}", e),
@@ -201,10 +474,7 @@ impl OutputManager {
     }
 
     fn save_report_text(&self, report: &ExtractionReport) -> Result<()> {
-        let report_path = self
-            .output_directory
-            .join(".repodocs")
-            .join("extraction_report.txt");
+        let report_path = self.get_metadata_dir().join("extraction_report.txt");
         let mut file = fs::File::create(&report_path).map_err(RepoDocsError::Io)?;
 
         writeln!(file, "RepoDocs Extraction Report")?;
@@ -219,6 +489,9 @@ impl OutputManager {
         )?;
         writeln!(file, "URL: {}", report.repository_info.url)?;
         writeln!(file, "Branch: {}", report.repository_info.default_branch)?;
+        if let Some(commit) = &report.repository_info.commit {
+            writeln!(file, "Commit: {}", commit)?;
+        }
         writeln!(
             file,
             "Total commits: {}",
@@ -315,8 +588,198 @@ impl OutputManager {
             "  Preserve structure: {}",
             report.config_used.preserve_structure
         )?;
+        if !report.config_used.scan_roots.is_empty() {
+            writeln!(
+                file,
+                "  Scan roots: {}",
+                report.config_used.scan_roots.join(", ")
+            )?;
+        }
         writeln!(file)?;
 
+        if report.skipped.total() > 0 {
+            writeln!(file, "Skipped: {}", report.skipped.summary())?;
+            writeln!(file)?;
+        }
+
+        if !report.budget.is_empty() {
+            writeln!(file, "Budget by directory:")?;
+            for line in budget_table(&report.budget).lines() {
+                writeln!(file, "  {}", line)?;
+            }
+            writeln!(file)?;
+        }
+
+        if !report.unresolved_links.is_empty() {
+            writeln!(file, "Unresolved links: {}", report.unresolved_links.len())?;
+            for link in &report.unresolved_links {
+                writeln!(file, "  {}: {}", link.document, link.target)?;
+            }
+            writeln!(file)?;
+        }
+
+        if !report.duplicates.is_empty() {
+            writeln!(file, "Duplicate groups: {}", report.duplicates.len())?;
+            for group in &report.duplicates {
+                writeln!(
+                    file,
+                    "  {} ({}): {}",
+                    group.kept,
+                    format_bytes(group.size),
+                    group.duplicates.join(", ")
+                )?;
+            }
+            writeln!(file)?;
+        }
+
+        if !report.assets.is_empty() {
+            writeln!(file, "Assets: {}", report.assets.len())?;
+            for asset in &report.assets {
+                writeln!(file, "  {}", asset)?;
+            }
+            writeln!(file)?;
+        }
+
+        if !report.oversized_assets.is_empty() {
+            writeln!(
+                file,
+                "Assets over the size limit: {}",
+                report.oversized_assets.len()
+            )?;
+            for asset in &report.oversized_assets {
+                writeln!(file, "  {}", asset)?;
+            }
+            writeln!(file)?;
+        }
+
+        if !report.accessibility.is_empty() {
+            writeln!(
+                file,
+                "Accessibility problems in rendered pages: {}",
+                report.accessibility.len()
+            )?;
+            for finding in &report.accessibility {
+                writeln!(
+                    file,
+                    "  {}: {}: {}",
+                    finding.page, finding.rule, finding.element
+                )?;
+            }
+            writeln!(file)?;
+        }
+
+        if let Some(environment) = &report.environment {
+            writeln!(file, "Environment:")?;
+            match &environment.os_release {
+                Some(release) => writeln!(
+                    file,
+                    "  OS: {} {} ({})",
+                    environment.os, release, environment.arch
+                )?,
+                None => writeln!(file, "  OS: {} ({})", environment.os, environment.arch)?,
+            }
+            writeln!(file, "  CPUs: {}", environment.cpu_count)?;
+            if let Some(filesystem) = &environment.filesystem {
+                writeln!(file, "  Filesystem: {}", filesystem)?;
+            }
+            if let Some(available) = environment.available_disk {
+                writeln!(file, "  Available disk: {}", format_bytes(available))?;
+            }
+            writeln!(file)?;
+        }
+
+        // Changes since the previous extraction
+        if let Some(changes) = &report.changes {
+            writeln!(
+                file,
+                "Changes since previous extraction: {} added, {} modified, {} removed, {} unchanged",
+                changes.added.len(),
+                changes.modified.len(),
+                changes.removed.len(),
+                changes.unchanged
+            )?;
+            for path in &changes.added {
+                writeln!(file, "  + {}", path)?;
+            }
+            for path in &changes.modified {
+                writeln!(file, "  ~ {}", path)?;
+            }
+            for path in &changes.removed {
+                writeln!(file, "  - {}", path)?;
+            }
+            writeln!(file)?;
+        }
+
+        if let Some(merge) = &report.merge {
+            writeln!(
+                file,
+                "Merged into existing output: {} written, {} unchanged, {} conflicts",
+                merge.written.len(),
+                merge.unchanged,
+                merge.conflicts.len()
+            )?;
+            for path in &merge.written {
+                writeln!(file, "  + {}", path)?;
+            }
+            for conflict in &merge.conflicts {
+                match conflict.output == conflict.document {
+                    true => writeln!(file, "  ! {}", conflict.output)?,
+                    false => writeln!(file, "  ! {} ({})", conflict.output, conflict.document)?,
+                }
+            }
+            writeln!(file)?;
+        }
+
+        // Near-duplicate documents
+        if !report.near_duplicates.is_empty() {
+            writeln!(file, "Near-duplicate documents:")?;
+            for group in &report.near_duplicates {
+                writeln!(
+                    file,
+                    "  - {} (similarity >= {:.2})",
+                    group.files.join(", "),
+                    group.min_similarity
+                )?;
+            }
+            writeln!(file)?;
+        }
+
+        // Paths shortened to fit the length limit
+        if !report.shortened_paths.is_empty() {
+            writeln!(file, "Shortened paths:")?;
+            for mapping in &report.shortened_paths {
+                writeln!(file, "  - {} -> {}", mapping.original, mapping.extracted)?;
+            }
+            writeln!(file)?;
+        }
+
+        // Paths rewritten by Unicode normalization
+        if !report.normalized_paths.is_empty() {
+            writeln!(file, "Unicode-normalized paths:")?;
+            for mapping in &report.normalized_paths {
+                writeln!(file, "  - {} -> {}", mapping.original, mapping.extracted)?;
+            }
+            writeln!(file)?;
+        }
+
+        // Paths transliterated to ASCII
+        if !report.transliterated_paths.is_empty() {
+            writeln!(file, "Transliterated paths:")?;
+            for mapping in &report.transliterated_paths {
+                writeln!(file, "  - {} -> {}", mapping.original, mapping.extracted)?;
+            }
+            writeln!(file)?;
+        }
+
+        // Paths renamed because they differ from another only by case
+        if !report.case_collisions.is_empty() {
+            writeln!(file, "Case collisions (renamed):")?;
+            for mapping in &report.case_collisions {
+                writeln!(file, "  - {} -> {}", mapping.original, mapping.extracted)?;
+            }
+            writeln!(file)?;
+        }
+
         // Errors (if any)
         if !report.errors.is_empty() {
             writeln!(file, "Errors encountered:")?;
@@ -342,7 +805,7 @@ impl OutputManager {
     }
 
     fn create_summary_file(&self, report: &ExtractionReport) -> Result<()> {
-        let summary_path = self.output_directory.join("EXTRACTION_SUMMARY.md");
+        let summary_path = self.get_output_directory().join("EXTRACTION_SUMMARY.md");
         let mut file = fs::File::create(&summary_path).map_err(RepoDocsError::Io)?;
 
         writeln!(file, "# Documentation Extraction Summary")?;
@@ -404,6 +867,91 @@ impl OutputManager {
             writeln!(file)?;
         }
 
+        if let Some(changes) = report.changes.as_ref().filter(|c| c.has_changes()) {
+            writeln!(file, "## Changes")?;
+            writeln!(file)?;
+            for (label, paths) in [
+                ("Added", &changes.added),
+                ("Modified", &changes.modified),
+                ("Removed", &changes.removed),
+            ] {
+                for path in paths {
+                    writeln!(file, "- {}: `{}`", label, path)?;
+                }
+            }
+            writeln!(file)?;
+        }
+
+        if let Some(merge) = report.merge.as_ref().filter(|m| !m.conflicts.is_empty()) {
+            writeln!(file, "## Merge Conflicts")?;
+            writeln!(file)?;
+            writeln!(
+                file,
+                "Files added or edited by hand were kept instead of these documents:"
+            )?;
+            writeln!(file)?;
+            for conflict in &merge.conflicts {
+                writeln!(file, "- `{}`: `{}`", conflict.output, conflict.document)?;
+            }
+            writeln!(file)?;
+        }
+
+        if !report.near_duplicates.is_empty() {
+            writeln!(file, "## Near-Duplicate Documents")?;
+            writeln!(file)?;
+            for group in &report.near_duplicates {
+                let files: Vec<String> = group.files.iter().map(|f| format!("`{}`", f)).collect();
+                writeln!(
+                    file,
+                    "- {} (similarity >= {:.2})",
+                    files.join(", "),
+                    group.min_similarity
+                )?;
+            }
+            writeln!(file)?;
+        }
+
+        if !report.shortened_paths.is_empty() {
+            writeln!(file, "## Shortened Paths")?;
+            writeln!(file)?;
+            for mapping in &report.shortened_paths {
+                writeln!(file, "- `{}` -> `{}`", mapping.original, mapping.extracted)?;
+            }
+            writeln!(file)?;
+        }
+
+        if !report.normalized_paths.is_empty() {
+            writeln!(file, "## Unicode-Normalized Paths")?;
+            writeln!(file)?;
+            for mapping in &report.normalized_paths {
+                writeln!(file, "- `{}` -> `{}`", mapping.original, mapping.extracted)?;
+            }
+            writeln!(file)?;
+        }
+
+        if !report.transliterated_paths.is_empty() {
+            writeln!(file, "## Transliterated Paths")?;
+            writeln!(file)?;
+            for mapping in &report.transliterated_paths {
+                writeln!(file, "- `{}` -> `{}`", mapping.original, mapping.extracted)?;
+            }
+            writeln!(file)?;
+        }
+
+        if !report.case_collisions.is_empty() {
+            writeln!(file, "## Case Collisions")?;
+            writeln!(file)?;
+            writeln!(
+                file,
+                "These paths differ only by letter case and were renamed so they do not overwrite each other on case-insensitive filesystems:"
+            )?;
+            writeln!(file)?;
+            for mapping in &report.case_collisions {
+                writeln!(file, "- `{}` -> `{}`", mapping.original, mapping.extracted)?;
+            }
+            writeln!(file)?;
+        }
+
         if !report.errors.is_empty() {
             writeln!(file, "## Issues Encountered")?;
             writeln!(file)?;
@@ -413,6 +961,31 @@ impl OutputManager {
             writeln!(file)?;
         }
 
+        if !report.files.is_empty() {
+            writeln!(file, "## Files")?;
+            writeln!(file)?;
+            for file_info in &report.files {
+                // Files ranked by relevance show what they are ranked by
+                let details = match self.sort_order {
+                    SortOrder::Relevance => format!(
+                        "{}, score {:.2}",
+                        format_bytes(file_info.size),
+                        file_info.score
+                    ),
+                    _ => format_bytes(file_info.size),
+                };
+                match &file_info.front_matter.title {
+                    Some(title) => writeln!(
+                        file,
+                        "- {} (`{}`, {})",
+                        title, file_info.relative_path, details
+                    )?,
+                    None => writeln!(file, "- `{}` ({})", file_info.relative_path, details)?,
+                }
+            }
+            writeln!(file)?;
+        }
+
         writeln!(file, "---")?;
         writeln!(file, "*Generated by RepoDocs*")?;
 
@@ -451,18 +1024,173 @@ impl OutputManager {
         Ok(())
     }
 
+    /// Remove a completed extraction from the staging directory instead of
+    /// committing it, when only its archive is kept. The output directory,
+    /// and an earlier extraction in it, are left as they are.
+    pub fn discard_staging(&mut self) -> Result<()> {
+        match self.staging_directory.take() {
+            Some(staging) => fs::remove_dir_all(staging).map_err(RepoDocsError::Io),
+            None => Ok(()),
+        }
+    }
+
     pub fn cleanup_on_error(&self) -> Result<()> {
-        if self.output_directory.exists() {
-            fs::remove_dir_all(&self.output_directory).map_err(RepoDocsError::Io)?;
+        let directory = self.get_output_directory();
+        if directory.exists() {
+            fs::remove_dir_all(directory).map_err(RepoDocsError::Io)?;
         }
         Ok(())
     }
 
+    /// Remove the output of a failed or cancelled run, which is still in
+    /// the staging directory, leaving an earlier extraction in the output
+    /// directory untouched. A directory kept from an earlier run for an
+    /// incremental update is left alone too, as removing it would lose that
+    /// extraction, and `None` is returned.
+    pub fn rollback(&self) -> Result<Option<RemovedOutput>> {
+        if self.kept_existing {
+            return Ok(None);
+        }
+
+        let mut removed = RemovedOutput {
+            path: self.get_output_directory().to_path_buf(),
+            files: 0,
+            bytes: 0,
+        };
+        for entry in WalkDir::new(self.get_output_directory())
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+        {
+            removed.files += 1;
+            removed.bytes += entry.metadata().map(|meta| meta.len()).unwrap_or(0);
+        }
+
+        self.cleanup_on_error()?;
+        Ok(Some(removed))
+    }
+
     pub fn get_metadata_dir(&self) -> PathBuf {
-        self.output_directory.join(".repodocs")
+        self.get_output_directory().join(".repodocs")
     }
 }
 
+/// Preview of a document: its first heading and paragraph, without front
+/// matter, cut to `max_chars` characters
+fn snippet(document: &DocumentFile, max_chars: usize) -> Option<String> {
+    let content = if notebook::has_notebook_extension(&document.relative_path) {
+        Notebook::load(&document.source_path).ok()?.to_markdown()
+    } else {
+        fs::read_to_string(&document.source_path).ok()?
+    };
+    let text = preview(front_matter::strip(&content), max_chars);
+    (!text.is_empty()).then_some(text)
+}
+
+/// Leading headings and the first paragraph after them, lines of a
+/// paragraph joined by spaces and blocks by a blank line
+fn preview(content: &str, max_chars: usize) -> String {
+    let mut blocks: Vec<String> = Vec::new();
+    let mut paragraph = String::new();
+
+    for line in content.lines().map(str::trim).chain([""]) {
+        let heading = line.starts_with('#');
+        if !line.is_empty() && !heading {
+            if !paragraph.is_empty() {
+                paragraph.push(' ');
+            }
+            paragraph.push_str(line);
+            continue;
+        }
+        if !paragraph.is_empty() {
+            blocks.push(std::mem::take(&mut paragraph));
+            break;
+        }
+        if heading {
+            blocks.push(line.to_string());
+        }
+    }
+
+    let text = blocks.join("\n\n");
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => text[..end].trim_end().to_string(),
+        None => text,
+    }
+}
+
+/// Check that `template` names a single directory and only uses the
+/// placeholders [`expand_name_template`] knows
+pub fn validate_name_template(template: &str) -> Result<()> {
+    let invalid = |reason: &str| {
+        Err(RepoDocsError::Config {
+            message: format!("Invalid output name template '{}': {}", template, reason),
+        })
+    };
+
+    if template.trim().is_empty() || matches!(template, "." | "..") {
+        return invalid("it does not name a directory");
+    }
+    if let Some(c) = template.chars().find(|c| {
+        matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*' | '/' | '\\') || c.is_control()
+    }) {
+        return invalid(&format!(
+            "'{}' is not allowed in a directory name",
+            c.escape_default()
+        ));
+    }
+
+    let mut literal = template.to_string();
+    for placeholder in NAME_PLACEHOLDERS {
+        literal = literal.replace(placeholder, "");
+    }
+    if literal.contains(['{', '}']) {
+        return invalid(&format!(
+            "unknown placeholder, expected one of {}",
+            NAME_PLACEHOLDERS.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Name of the output directory for `template`, replacing `{owner}`,
+/// `{repo}` and `{branch}` with the repository's, made safe for a file
+/// name, and `{date}` with the UTC date of `now` as `YYYY-MM-DD`. Text
+/// around the placeholders is kept as written.
+pub fn expand_name_template(
+    template: &str,
+    owner: &str,
+    repo: &str,
+    branch: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let value = |value: &str| {
+        if value.trim().is_empty() {
+            "unknown".to_string()
+        } else {
+            sanitize_repo_name(value)
+        }
+    };
+
+    let mut name = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        name.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map_or(rest.len(), |end| start + end + 1);
+        match &rest[start..end] {
+            "{owner}" => name.push_str(&value(owner)),
+            "{repo}" => name.push_str(&value(repo)),
+            "{branch}" => name.push_str(&value(branch)),
+            "{date}" => name.push_str(&now.format("%Y-%m-%d").to_string()),
+            other => name.push_str(other),
+        }
+        rest = &rest[end..];
+    }
+    name.push_str(rest);
+    name
+}
+
 fn sanitize_repo_name(name: &str) -> String {
     let mut sanitized = String::new();
 
@@ -483,14 +1211,12 @@ fn sanitize_repo_name(name: &str) -> String {
     // Ensure it's not empty and not too long
     if sanitized.is_empty() {
         "unnamed_repo".to_string()
-    } else if sanitized.len() > 100 {
-        sanitized[..100].to_string()
     } else {
-        sanitized.to_string()
+        sanitized.chars().take(100).collect()
     }
 }
 
-fn format_bytes(bytes: u64) -> String {
+pub(crate) fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
     let mut size = bytes as f64;
     let mut unit_index = 0;
@@ -507,9 +1233,61 @@ fn format_bytes(bytes: u64) -> String {
     }
 }
 
+/// Serde helper writing a `Duration` as whole milliseconds, for
+/// `#[serde(with = "duration_ms")]`. The `{secs, nanos}` objects of
+/// version 1 reports are read too.
+pub mod duration_ms {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Millis(u64),
+        Legacy(Duration),
+    }
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis().try_into().unwrap_or(u64::MAX))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(match Stored::deserialize(deserializer)? {
+            Stored::Millis(millis) => Duration::from_millis(millis),
+            Stored::Legacy(duration) => duration,
+        })
+    }
+}
+
+/// Serde helper reading a timestamp written as RFC 3339, or as the
+/// `{secs_since_epoch, nanos_since_epoch}` object of version 1 reports,
+/// for `#[serde(deserialize_with = "timestamp::deserialize")]`
+pub mod timestamp {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer};
+    use std::time::SystemTime;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Rfc3339(DateTime<Utc>),
+        Legacy(SystemTime),
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        Ok(match Stored::deserialize(deserializer)? {
+            Stored::Rfc3339(time) => time,
+            Stored::Legacy(time) => time.into(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cloner::GitProvider;
     use std::time::SystemTime;
     use tempfile::TempDir;
 
@@ -520,7 +1298,9 @@ mod tests {
             default_branch: "main".to_string(),
             is_empty: false,
             total_commits: 42,
+            commit: None,
             url: "https://github.com/test-owner/test-repo".to_string(),
+            provider: GitProvider::GitHub,
         }
     }
 
@@ -540,6 +1320,7 @@ mod tests {
             max_file_size: 1024 * 1024,
             exclude_dirs: vec![".git".to_string()],
             preserve_structure: true,
+            scan_roots: vec![],
         }
     }
 
@@ -559,7 +1340,7 @@ mod tests {
     #[test]
     fn test_output_directory_initialization() {
         let temp_dir = TempDir::new().unwrap();
-        let manager =
+        let mut manager =
             OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string()).unwrap();
 
         manager.initialize().unwrap();
@@ -571,7 +1352,7 @@ mod tests {
     #[test]
     fn test_extraction_report_creation() {
         let temp_dir = TempDir::new().unwrap();
-        let manager =
+        let mut manager =
             OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string()).unwrap();
 
         manager.initialize().unwrap();
@@ -611,6 +1392,144 @@ mod tests {
             .exists());
     }
 
+    #[test]
+    fn test_report_sort_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let documents = vec![
+            create_test_document("b.md", 100),
+            create_test_document("a.txt", 300),
+            create_test_document("c.md", 200),
+        ];
+        let progress = ExtractionProgress::new(3, 600);
+
+        let listed = |sort_order| {
+            OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string())
+                .unwrap()
+                .with_sort_order(sort_order)
+                .build_extraction_report(
+                    &create_test_repo_info(),
+                    &documents,
+                    &progress,
+                    &create_test_config(),
+                )
+                .files
+                .into_iter()
+                .map(|file| file.relative_path)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(listed(SortOrder::Path), ["a.txt", "b.md", "c.md"]);
+        assert_eq!(listed(SortOrder::Size), ["a.txt", "c.md", "b.md"]);
+        assert_eq!(listed(SortOrder::Extension), ["b.md", "c.md", "a.txt"]);
+
+        let mut documents = documents;
+        documents[2].score = 4.5;
+        documents[0].score = 2.0;
+        let listed: Vec<(String, f64)> =
+            OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string())
+                .unwrap()
+                .with_sort_order(SortOrder::Relevance)
+                .build_extraction_report(
+                    &create_test_repo_info(),
+                    &documents,
+                    &progress,
+                    &create_test_config(),
+                )
+                .files
+                .into_iter()
+                .map(|file| (file.relative_path, file.score))
+                .collect();
+        assert_eq!(
+            listed,
+            [
+                ("c.md".to_string(), 4.5),
+                ("b.md".to_string(), 2.0),
+                ("a.txt".to_string(), 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_json_is_stable() {
+        let temp_dir = TempDir::new().unwrap();
+        let documents = vec![
+            create_test_document("z.txt", 100),
+            create_test_document("b.md", 300),
+            create_test_document("a.rst", 200),
+        ];
+        let progress = ExtractionProgress::new(3, 600);
+        let mut report = OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string())
+            .unwrap()
+            .build_extraction_report(
+                &create_test_repo_info(),
+                &documents,
+                &progress,
+                &create_test_config(),
+            );
+        report.extraction_summary.extraction_duration = Duration::from_micros(1_500_700);
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.starts_with(&format!("{{\"format_version\":{}", REPORT_FORMAT_VERSION)));
+        assert!(json.contains("\"extraction_duration_ms\":1500,"));
+        assert!(json.contains("\"files_by_extension\":{\"md\":1,\"rst\":1,\"txt\":1}"));
+
+        let parsed: ExtractionReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed.extraction_summary.extraction_duration,
+            Duration::from_millis(1500)
+        );
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn test_preview() {
+        let content = "# Project\n## Overview\nA tool that\nextracts docs.\n\nMore text.\n";
+        assert_eq!(
+            preview(content, 100),
+            "# Project\n\n## Overview\n\nA tool that extracts docs."
+        );
+        assert_eq!(preview(content, 11), "# Project");
+        assert_eq!(preview("Plain text without heading\n", 5), "Plain");
+        assert_eq!(preview("\n\n", 10), "");
+    }
+
+    #[test]
+    fn test_read_legacy_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let report = OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string())
+            .unwrap()
+            .build_extraction_report(
+                &create_test_repo_info(),
+                &[create_test_document("README.md", 100)],
+                &ExtractionProgress::new(1, 100),
+                &create_test_config(),
+            );
+
+        // Rewrite the report the way version 1 serialized it
+        let mut json = serde_json::to_value(&report).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("format_version");
+        let summary = object["extraction_summary"].as_object_mut().unwrap();
+        summary.remove("extraction_duration_ms");
+        summary.insert(
+            "extraction_duration".to_string(),
+            serde_json::json!({"secs": 2, "nanos": 500_000_000}),
+        );
+        object["files"][0]["modified"] =
+            serde_json::json!({"secs_since_epoch": 86_400, "nanos_since_epoch": 0});
+
+        let parsed: ExtractionReport = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.format_version, 1);
+        assert_eq!(
+            parsed.extraction_summary.extraction_duration,
+            Duration::from_millis(2500)
+        );
+        assert_eq!(
+            parsed.files[0].modified.to_rfc3339(),
+            "1970-01-02T00:00:00+00:00"
+        );
+    }
+
     #[test]
     fn test_repo_name_sanitization() {
         assert_eq!(sanitize_repo_name("normal-repo"), "normal-repo");
@@ -622,12 +1541,56 @@ mod tests {
         let long_name = "a".repeat(150);
         let sanitized = sanitize_repo_name(&long_name);
         assert_eq!(sanitized.len(), 100);
+
+        // Truncated on a character boundary
+        let sanitized = sanitize_repo_name(&format!("a{}", "é".repeat(150)));
+        assert_eq!(sanitized.chars().count(), 100);
+    }
+
+    #[test]
+    fn test_name_template() {
+        use chrono::TimeZone;
+
+        let now = Utc.with_ymd_and_hms(2026, 3, 14, 23, 59, 0).unwrap();
+        let expand = |template| expand_name_template(template, "acme", "widgets", "feature/x", now);
+        assert_eq!(expand(DEFAULT_NAME_TEMPLATE), "docs_widgets");
+        assert_eq!(
+            expand("{owner}__{repo}@{branch}-{date}"),
+            "acme__widgets@feature_x-2026-03-14"
+        );
+        assert_eq!(
+            expand_name_template("{owner}-{repo}", "", "widgets", "", now),
+            "unknown-widgets"
+        );
+
+        assert!(validate_name_template(DEFAULT_NAME_TEMPLATE).is_ok());
+        assert!(validate_name_template("{owner}__{repo}@{branch}-{date}").is_ok());
+        for invalid in [
+            "",
+            " ",
+            "..",
+            "docs/{repo}",
+            "docs_{name}",
+            "docs_{repo",
+            "docs}",
+        ] {
+            assert!(validate_name_template(invalid).is_err(), "{}", invalid);
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let manager = OutputManager::new(temp_dir.path().to_path_buf(), "widgets".to_string())
+            .unwrap()
+            .with_name_template("{owner}_{repo}@{branch}", "acme", "main");
+        assert_eq!(
+            manager.destination(),
+            temp_dir.path().join("acme_widgets@main")
+        );
     }
 
     #[test]
     fn test_force_overwrite() {
         let temp_dir = TempDir::new().unwrap();
-        let manager =
+        let mut manager =
             OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string()).unwrap();
 
         // Create initial directory
@@ -636,18 +1599,138 @@ mod tests {
 
         // Create a file in the directory
         fs::write(manager.get_output_directory().join("test.txt"), "test").unwrap();
+        manager.commit().unwrap();
+        assert!(manager.destination().join("test.txt").exists());
 
         // Try to initialize again without force - should fail
         assert!(manager.initialize().is_err());
 
         // Try with force overwrite - should succeed
-        let manager_with_force = manager.with_force_overwrite(true);
+        let mut manager_with_force = manager.with_force_overwrite(true);
         manager_with_force.initialize().unwrap();
         assert!(manager_with_force.get_output_directory().exists());
         assert!(!manager_with_force
             .get_output_directory()
             .join("test.txt")
             .exists());
+
+        // The earlier extraction is replaced once the new one is committed
+        assert!(manager_with_force.destination().join("test.txt").exists());
+        manager_with_force.commit().unwrap();
+        assert!(!manager_with_force.destination().join("test.txt").exists());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_commit_replaces_existing_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let previous = temp_dir.path().join("docs_test-repo");
+        fs::create_dir_all(&previous).unwrap();
+        fs::write(previous.join("OLD.md"), "# Old").unwrap();
+
+        // Concurrent jobs with the same output name stage separately
+        let mut first = OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string())
+            .unwrap()
+            .with_force_overwrite(true);
+        let mut second = OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string())
+            .unwrap()
+            .with_force_overwrite(true);
+        first.initialize().unwrap();
+        second.initialize().unwrap();
+        assert_ne!(first.get_output_directory(), second.get_output_directory());
+
+        fs::write(first.get_output_directory().join("NEW.md"), "# New").unwrap();
+        first.commit().unwrap();
+        assert!(!previous.join("OLD.md").exists());
+        assert_eq!(
+            fs::read_to_string(previous.join("NEW.md")).unwrap(),
+            "# New"
+        );
+        assert!(second.get_output_directory().exists());
+
+        second.rollback().unwrap();
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_discard_staging_keeps_existing_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let previous = temp_dir.path().join("docs_test-repo");
+        fs::create_dir_all(&previous).unwrap();
+        fs::write(previous.join("OLD.md"), "# Old").unwrap();
+
+        for incremental in [false, true] {
+            let mut manager =
+                OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string())
+                    .unwrap()
+                    .with_force_overwrite(!incremental)
+                    .with_incremental(incremental);
+            manager.initialize().unwrap();
+            fs::write(manager.get_output_directory().join("NEW.md"), "# New").unwrap();
+
+            manager.discard_staging().unwrap();
+            manager.commit().unwrap();
+            assert!(previous.join("OLD.md").exists());
+            // Written in place when incremental, staged and removed otherwise
+            assert_eq!(previous.join("NEW.md").exists(), incremental);
+            assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_discard_partial_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager =
+            OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string()).unwrap();
+        manager.initialize().unwrap();
+        fs::write(
+            manager.get_output_directory().join("README.md"),
+            "# Partial",
+        )
+        .unwrap();
+
+        let removed = manager.rollback().unwrap().unwrap();
+        assert_eq!(removed.files, 1);
+        assert_eq!(removed.bytes, 9);
+        assert!(!manager.get_output_directory().exists());
+        assert!(!manager.destination().exists());
+
+        // An earlier extraction updated in place is kept
+        fs::create_dir_all(temp_dir.path().join("docs_test-repo")).unwrap();
+        let mut incremental =
+            OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string())
+                .unwrap()
+                .with_incremental(true);
+        incremental.initialize().unwrap();
+        assert!(incremental.rollback().unwrap().is_none());
+        assert!(incremental.get_output_directory().exists());
+        assert_eq!(
+            incremental.get_output_directory(),
+            incremental.destination()
+        );
+    }
+
+    #[test]
+    fn test_rollback_keeps_previous_extraction() {
+        let temp_dir = TempDir::new().unwrap();
+        let previous = temp_dir.path().join("docs_test-repo");
+        fs::create_dir_all(&previous).unwrap();
+        fs::write(previous.join("README.md"), "# Previous").unwrap();
+
+        let mut manager =
+            OutputManager::new(temp_dir.path().to_path_buf(), "test-repo".to_string())
+                .unwrap()
+                .with_force_overwrite(true);
+        manager.initialize().unwrap();
+        assert_ne!(manager.get_output_directory(), previous);
+        fs::write(manager.get_output_directory().join("README.md"), "# Half").unwrap();
+
+        manager.rollback().unwrap();
+        assert_eq!(
+            fs::read_to_string(previous.join("README.md")).unwrap(),
+            "# Previous"
+        );
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
     }
 
     #[test]
@@ -0,0 +1,241 @@
+use crate::cloner::RepositoryInfo;
+use crate::config::SortOrder;
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::bundle::Bundle;
+use crate::scanner::front_matter::yaml_string;
+use crate::scanner::DocumentFile;
+use std::fs;
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// File of the output the PDF is written to
+pub const PDF_FILE: &str = "documentation.pdf";
+
+/// Markdown source of the PDF, kept in the metadata directory so a failed
+/// conversion can be retried by hand
+pub const PDF_SOURCE_FILE: &str = "documentation.md";
+
+/// Converter used when `output.pdf_converter` is not set
+pub const DEFAULT_PDF_CONVERTER: &str = "pandoc {input} --output {output} --toc --standalone";
+
+/// Result of `--render pdf`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PdfOutcome {
+    Rendered(PathBuf),
+    /// The converter is missing or failed; the Markdown source is left for
+    /// converting later
+    Fallback {
+        source: PathBuf,
+        reason: String,
+    },
+}
+
+/// The documents as one PDF (`--render pdf`): a title page with the
+/// repository's details and a chapter per file. The chapters are written
+/// to one Markdown file, which an external converter, pandoc unless
+/// `output.pdf_converter` names another, turns into the PDF.
+#[derive(Debug, Clone)]
+pub struct PdfDocument {
+    pub title: String,
+    pub bundle: Bundle,
+}
+
+impl PdfDocument {
+    pub fn build(documents: &[DocumentFile], sort_order: SortOrder, title: &str) -> Self {
+        // Files are level-1 chapters, so their own headings move down one level
+        Self {
+            title: title.to_string(),
+            bundle: Bundle::with_heading_shift(documents, sort_order, 1),
+        }
+    }
+
+    /// Write the Markdown source: a pandoc metadata block for the title
+    /// page, then the chapters
+    pub fn write_source(&self, repo_info: &RepositoryInfo, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(RepoDocsError::Io)?;
+        }
+        let mut file = BufWriter::new(fs::File::create(path).map_err(RepoDocsError::Io)?);
+
+        writeln!(file, "---")?;
+        writeln!(file, "title: {}", yaml_string(&self.title))?;
+        writeln!(file, "subtitle: {}", yaml_string(&repo_info.url))?;
+        if !repo_info.owner.is_empty() {
+            writeln!(file, "author: {}", yaml_string(&repo_info.owner))?;
+        }
+        writeln!(
+            file,
+            "date: {}",
+            yaml_string(&chrono::Utc::now().format("%Y-%m-%d").to_string())
+        )?;
+        writeln!(
+            file,
+            "abstract: {}",
+            yaml_string(&format!(
+                "Branch {}, {} commits, {} documents.",
+                repo_info.default_branch,
+                repo_info.total_commits,
+                self.bundle.sections.len()
+            ))
+        )?;
+        writeln!(file, "---")?;
+
+        for section in &self.bundle.sections {
+            writeln!(file)?;
+            writeln!(file, "# {}", section.title)?;
+            writeln!(file)?;
+            writeln!(file, "_Source: `{}`_", section.path)?;
+            writeln!(file)?;
+            writeln!(file, "{}", section.content.trim_end())?;
+        }
+
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Write the source to `metadata_dir` and convert it to `documentation.pdf`
+    /// in `output_dir`. A missing or failing converter is not an error: the
+    /// outcome says why, and the source stays for converting by hand.
+    pub fn render(
+        &self,
+        repo_info: &RepositoryInfo,
+        output_dir: &Path,
+        metadata_dir: &Path,
+        converter: Option<&str>,
+    ) -> Result<PdfOutcome> {
+        let source = metadata_dir.join(PDF_SOURCE_FILE);
+        self.write_source(repo_info, &source)?;
+        let output = output_dir.join(PDF_FILE);
+
+        let converter = converter.unwrap_or(DEFAULT_PDF_CONVERTER);
+        let fallback = |reason: String| PdfOutcome::Fallback {
+            source: source.clone(),
+            reason,
+        };
+        let args: Vec<String> = converter
+            .split_whitespace()
+            .map(|arg| {
+                arg.replace("{input}", &source.to_string_lossy())
+                    .replace("{output}", &output.to_string_lossy())
+            })
+            .collect();
+        let Some((program, args)) = args.split_first() else {
+            return Ok(fallback("no PDF converter is configured".to_string()));
+        };
+
+        match Command::new(program)
+            .args(args)
+            .current_dir(output_dir)
+            .output()
+        {
+            Ok(result) if result.status.success() && output.is_file() => {
+                Ok(PdfOutcome::Rendered(output))
+            }
+            Ok(result) => {
+                let stderr = String::from_utf8_lossy(&result.stderr);
+                let detail = stderr.lines().rev().find(|line| !line.trim().is_empty());
+                Ok(fallback(match detail {
+                    Some(detail) => format!("{} failed: {}", program, detail.trim()),
+                    None => format!("{} failed ({})", program, result.status),
+                }))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                Ok(fallback(format!("{} is not installed", program)))
+            }
+            Err(e) => Ok(fallback(format!("{} could not be run: {}", program, e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cloner::GitProvider;
+    use crate::scanner::write_test_documents;
+    use tempfile::TempDir;
+
+    fn repo_info() -> RepositoryInfo {
+        RepositoryInfo {
+            name: "repo".to_string(),
+            owner: "owner".to_string(),
+            default_branch: "main".to_string(),
+            is_empty: false,
+            total_commits: 42,
+            commit: None,
+            url: "https://github.com/owner/repo".to_string(),
+            provider: GitProvider::GitHub,
+        }
+    }
+
+    fn create_pdf_document(temp_dir: &Path) -> PdfDocument {
+        let files = [
+            ("README.md", "# Project\n\nIntroduction.\n"),
+            ("docs/guide.md", "---\ntitle: Guide\n---\n## Setup\n"),
+        ];
+        let documents = write_test_documents(temp_dir, &files);
+        PdfDocument::build(&documents, SortOrder::Path, "repo Documentation")
+    }
+
+    #[test]
+    fn test_pdf_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let pdf = create_pdf_document(temp_dir.path());
+        let source = temp_dir.path().join("out/documentation.md");
+        pdf.write_source(&repo_info(), &source).unwrap();
+
+        let markdown = fs::read_to_string(&source).unwrap();
+        assert!(markdown.starts_with("---\ntitle: \"repo Documentation\"\n"));
+        assert!(markdown.contains("subtitle: \"https://github.com/owner/repo\"\n"));
+        assert!(markdown.contains("Branch main, 42 commits, 2 documents."));
+        assert!(markdown.contains("\n# Guide\n\n_Source: `docs/guide.md`_\n\n### Setup\n"));
+        assert!(markdown.contains("\n# README.md\n\n_Source: `README.md`_\n\n## Project\n"));
+    }
+
+    #[test]
+    fn test_render_falls_back_without_converter() {
+        let temp_dir = TempDir::new().unwrap();
+        let pdf = create_pdf_document(temp_dir.path());
+        let output_dir = temp_dir.path().join("out");
+        let metadata_dir = output_dir.join(".repodocs");
+        fs::create_dir_all(&metadata_dir).unwrap();
+
+        let outcome = pdf
+            .render(
+                &repo_info(),
+                &output_dir,
+                &metadata_dir,
+                Some("repodocs-missing-converter {input} {output}"),
+            )
+            .unwrap();
+        assert_eq!(
+            outcome,
+            PdfOutcome::Fallback {
+                source: metadata_dir.join(PDF_SOURCE_FILE),
+                reason: "repodocs-missing-converter is not installed".to_string(),
+            }
+        );
+        assert!(metadata_dir.join(PDF_SOURCE_FILE).is_file());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_render_with_converter() {
+        let temp_dir = TempDir::new().unwrap();
+        let pdf = create_pdf_document(temp_dir.path());
+        let output_dir = temp_dir.path().join("out");
+        let metadata_dir = output_dir.join(".repodocs");
+        fs::create_dir_all(&metadata_dir).unwrap();
+
+        let outcome = pdf
+            .render(
+                &repo_info(),
+                &output_dir,
+                &metadata_dir,
+                Some("cp {input} {output}"),
+            )
+            .unwrap();
+        assert_eq!(outcome, PdfOutcome::Rendered(output_dir.join(PDF_FILE)));
+        assert!(output_dir.join(PDF_FILE).is_file());
+    }
+}
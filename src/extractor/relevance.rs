@@ -0,0 +1,147 @@
+use crate::extractor::graph;
+use crate::scanner::DocumentFile;
+use std::collections::HashMap;
+
+/// Score of a document at the repository root, halved one level down,
+/// divided by three two levels down, and so on
+const DEPTH_WEIGHT: f64 = 2.0;
+
+/// Score per doubling of the number of documents linking to a document
+const LINK_WEIGHT: f64 = 1.5;
+
+/// Score per doubling of a document's size in kilobytes
+const SIZE_WEIGHT: f64 = 0.5;
+
+/// Doublings of the size that count; larger documents score as 64 KB
+const SIZE_CAP: f64 = 6.0;
+
+/// Set the relevance score of every document from its depth, file name,
+/// the number of other documents linking to it, and its size. Scores are
+/// rounded to two decimals so that reports list them stably.
+pub fn score(documents: &mut [DocumentFile]) {
+    let mut inbound: HashMap<String, usize> = HashMap::new();
+    for (_, target) in graph::link_edges(documents) {
+        *inbound.entry(target).or_default() += 1;
+    }
+
+    for document in documents.iter_mut() {
+        let path = document.relative_path.to_string_lossy().replace('\\', "/");
+        let links = inbound.get(&path).copied().unwrap_or(0);
+        document.score = document_score(document, links);
+    }
+}
+
+/// Keep the `n` documents with the highest scores, ties going to the first
+/// by path, and return the others. The kept documents stay in their order.
+pub fn keep_top(documents: &mut Vec<DocumentFile>, n: usize) -> Vec<DocumentFile> {
+    let mut ranked: Vec<usize> = (0..documents.len()).collect();
+    ranked.sort_by(|&a, &b| {
+        documents[b]
+            .score
+            .total_cmp(&documents[a].score)
+            .then_with(|| documents[a].relative_path.cmp(&documents[b].relative_path))
+    });
+    let mut kept = vec![false; documents.len()];
+    for &index in ranked.iter().take(n) {
+        kept[index] = true;
+    }
+
+    let (top, rest): (Vec<_>, Vec<_>) = documents.drain(..).zip(kept).partition(|(_, kept)| *kept);
+    *documents = top.into_iter().map(|(document, _)| document).collect();
+    rest.into_iter().map(|(document, _)| document).collect()
+}
+
+fn document_score(document: &DocumentFile, inbound_links: usize) -> f64 {
+    let depth = document
+        .relative_path
+        .components()
+        .count()
+        .saturating_sub(1);
+    let kilobytes = document.size as f64 / 1024.0;
+
+    let score = name_weight(document)
+        + DEPTH_WEIGHT / (1 + depth) as f64
+        + LINK_WEIGHT * (1.0 + inbound_links as f64).log2()
+        + SIZE_WEIGHT * (1.0 + kilobytes).log2().min(SIZE_CAP);
+    (score * 100.0).round() / 100.0
+}
+
+/// Landing pages and guides score above ordinary pages, and project
+/// boilerplate such as licenses and changelogs below them
+fn name_weight(document: &DocumentFile) -> f64 {
+    let stem = document
+        .relative_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_lowercase().replace('_', "-"))
+        .unwrap_or_default();
+
+    match stem.as_str() {
+        "readme" | "index" => 3.0,
+        "getting-started" | "gettingstarted" | "quickstart" | "quick-start" | "introduction"
+        | "intro" | "overview" | "install" | "installation" | "usage" => 2.0,
+        "changelog" | "changes" | "history" | "news" | "license" | "licence" | "copying"
+        | "notice" | "authors" | "contributors" | "maintainers" | "code-of-conduct"
+        | "security" => 0.0,
+        _ if stem.contains("guide") || stem.contains("tutorial") => 2.0,
+        _ => 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::write_test_documents;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_relevance_scores() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut documents = write_test_documents(
+            temp_dir.path(),
+            &[
+                (
+                    "README.md",
+                    "# Project\n\nSee the [guide](docs/user_guide.md).",
+                ),
+                ("CHANGELOG.md", "# Changes"),
+                (
+                    "docs/user_guide.md",
+                    "# Guide\n\nSee the [API](api/endpoints.md).",
+                ),
+                ("docs/api/endpoints.md", "# Endpoints"),
+                ("docs/api/internals.md", "# Internals"),
+            ],
+        );
+
+        score(&mut documents);
+        let scores: HashMap<&str, f64> = documents
+            .iter()
+            .map(|doc| (doc.relative_path.to_str().unwrap(), doc.score))
+            .collect();
+
+        assert!(scores["README.md"] > scores["docs/user_guide.md"]);
+        assert!(scores["docs/user_guide.md"] > scores["CHANGELOG.md"]);
+        // Linked documents rank above their unlinked siblings
+        assert!(scores["docs/api/endpoints.md"] > scores["docs/api/internals.md"]);
+        assert_eq!(scores["docs/api/internals.md"], 1.67);
+
+        let mut large = documents[4].clone();
+        large.size = 64 * 1024;
+        assert!(document_score(&large, 0) > scores["docs/api/internals.md"]);
+        large.size = 1024 * 1024;
+        // Size stops counting at the cap
+        assert_eq!(document_score(&large, 0), 4.67);
+
+        let left_out = keep_top(&mut documents, 3);
+        let paths = |documents: &[DocumentFile]| -> Vec<String> {
+            documents.iter().map(|doc| doc.display_path()).collect()
+        };
+        assert_eq!(
+            paths(&documents),
+            ["README.md", "docs/user_guide.md", "docs/api/endpoints.md"]
+        );
+        assert_eq!(paths(&left_out), ["CHANGELOG.md", "docs/api/internals.md"]);
+        assert!(keep_top(&mut documents, 10).is_empty());
+        assert_eq!(documents.len(), 3);
+    }
+}
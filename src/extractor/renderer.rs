@@ -0,0 +1,758 @@
+use crate::config::{HtmlConfig, HtmlTheme};
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::accessibility::{self, AccessibilityFinding};
+use crate::extractor::manifest::Manifest;
+use crate::extractor::markdown;
+use crate::extractor::search::{self, SearchIndex};
+use crate::extractor::sitemap::{self, Sitemap};
+use crate::scanner::front_matter;
+use crate::scanner::DocumentFile;
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory of the output that rendered pages are written to
+pub const HTML_DIR: &str = "html";
+
+/// Stylesheet the pages link to after the built-in one with `custom_css`
+const CUSTOM_STYLESHEET: &str = "custom.css";
+
+const LIGHT_COLORS: &str = "  color-scheme: light;
+  --text: #1f2328;
+  --background: #ffffff;
+  --muted: #59636e;
+  --border: #d0d7de;
+  --surface: #f6f8fa;
+  --link: #0969da;
+";
+
+const DARK_COLORS: &str = "  color-scheme: dark;
+  --text: #e6edf3;
+  --background: #0d1117;
+  --muted: #9198a1;
+  --border: #3d444d;
+  --surface: #151b23;
+  --link: #4493f8;
+";
+
+const STYLESHEET: &str = r#"body {
+  margin: 0;
+  font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Helvetica, Arial, sans-serif;
+  line-height: 1.6;
+  color: var(--text);
+  background: var(--background);
+}
+nav {
+  padding: 0.75rem 1.5rem;
+  border-bottom: 1px solid var(--border);
+  background: var(--surface);
+}
+main {
+  max-width: 52rem;
+  margin: 0 auto;
+  padding: 1.5rem;
+}
+a { color: var(--link); }
+code, pre { font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }
+pre {
+  padding: 1rem;
+  overflow: auto;
+  background: var(--surface);
+  border-radius: 6px;
+}
+table { border-collapse: collapse; }
+th, td { padding: 0.4rem 0.8rem; border: 1px solid var(--border); }
+blockquote { margin: 0; padding: 0 1rem; color: var(--muted); border-left: 0.25rem solid var(--border); }
+img { max-width: 100%; }
+nav {
+  display: flex;
+  flex-wrap: wrap;
+  align-items: center;
+  justify-content: space-between;
+  gap: 0.5rem 1rem;
+}
+form.search { position: relative; }
+form.search input {
+  padding: 0.25rem 0.5rem;
+  font: inherit;
+  color: inherit;
+  background: var(--background);
+  border: 1px solid var(--border);
+  border-radius: 6px;
+}
+.search-results {
+  position: absolute;
+  right: 0;
+  z-index: 1;
+  width: min(28rem, 90vw);
+  max-height: 70vh;
+  margin: 0.25rem 0 0;
+  padding: 0.5rem 1rem;
+  overflow: auto;
+  list-style: none;
+  background: var(--background);
+  border: 1px solid var(--border);
+  border-radius: 6px;
+}
+.search-results p { margin: 0 0 0.5rem; font-size: 0.875rem; color: var(--muted); }
+nav.pager {
+  display: flex;
+  justify-content: space-between;
+  gap: 1rem;
+  margin-top: 2rem;
+  padding: 1rem 0 0;
+  border-top: 1px solid var(--border);
+  border-bottom: none;
+  background: none;
+}
+nav.pager a[rel="next"] { margin-left: auto; text-align: right; }
+"#;
+
+/// A rendered document
+#[derive(Debug, Clone)]
+pub struct RenderedPage {
+    /// Path relative to the repository root
+    pub source: String,
+    /// Path relative to the `html/` directory
+    pub page: PathBuf,
+    pub title: String,
+}
+
+/// Static HTML pages of the extracted documents (`--render html`), written
+/// to `html/` in the output directory with an `index.html` navigation page.
+/// Markdown is converted to HTML with links between documents pointing at
+/// their pages; other formats, such as reStructuredText, are shown as
+/// preformatted text. Every page has breadcrumbs of its directories and
+/// links to the previous and next page in index order, and is styled with
+/// the configured theme and custom stylesheet. A search box finds pages
+/// through an index embedded in the site, so no server is needed. The
+/// content of every page is checked for images without alternative text,
+/// skipped heading levels and links without text.
+#[derive(Debug, Clone, Default)]
+pub struct HtmlSite {
+    /// Pages in the order of the index
+    pub pages: Vec<RenderedPage>,
+    /// Accessibility problems found in the content of the pages
+    pub accessibility: Vec<AccessibilityFinding>,
+    /// Words of the pages, empty without `search`
+    pub search_index: SearchIndex,
+}
+
+impl HtmlSite {
+    /// Render the extracted files listed in `manifest`, which live in
+    /// `output_dir`. Binary files are skipped.
+    pub fn render(
+        documents: &[DocumentFile],
+        manifest: &Manifest,
+        output_dir: &Path,
+        style: &HtmlConfig,
+    ) -> Result<Self> {
+        Self::render_to(
+            documents,
+            manifest,
+            output_dir,
+            &output_dir.join(HTML_DIR),
+            style,
+        )
+    }
+
+    /// Like `render`, writing the pages to `html_dir` instead of the
+    /// output directory
+    pub fn render_to(
+        documents: &[DocumentFile],
+        manifest: &Manifest,
+        output_dir: &Path,
+        html_dir: &Path,
+        style: &HtmlConfig,
+    ) -> Result<Self> {
+        let titles: HashMap<String, &str> = documents
+            .iter()
+            .map(|doc| {
+                (
+                    doc.relative_path.to_string_lossy().replace('\\', "/"),
+                    doc.title(),
+                )
+            })
+            .collect();
+
+        // Pages by repository path, so links between documents can be rewritten
+        let pages: HashMap<PathBuf, PathBuf> = manifest
+            .entries
+            .iter()
+            .map(|entry| (PathBuf::from(&entry.path), page_path(&entry.extracted)))
+            .collect();
+
+        fs::create_dir_all(html_dir).map_err(RepoDocsError::Io)?;
+        fs::write(html_dir.join("style.css"), stylesheet(style.theme))
+            .map_err(RepoDocsError::Io)?;
+        if let Some(custom_css) = &style.custom_css {
+            fs::copy(custom_css, html_dir.join(CUSTOM_STYLESHEET)).map_err(|e| {
+                RepoDocsError::Config {
+                    message: format!(
+                        "Failed to read custom stylesheet {}: {}",
+                        custom_css.display(),
+                        e
+                    ),
+                }
+            })?;
+        }
+        let layout = Layout {
+            custom_css: style.custom_css.is_some(),
+            search: style.search,
+        };
+
+        let mut site = HtmlSite::default();
+        let mut bodies: HashMap<PathBuf, String> = HashMap::new();
+        for entry in &manifest.entries {
+            let Ok(content) = fs::read_to_string(output_dir.join(&entry.extracted)) else {
+                continue;
+            };
+            let source = PathBuf::from(&entry.path);
+            let page = page_path(&entry.extracted);
+            let title = titles
+                .get(&entry.path)
+                .map_or_else(|| file_name(&entry.path), |title| title.to_string());
+
+            let (body, text) = if markdown::is_markdown_path(&entry.extracted) {
+                let content = front_matter::strip(&content);
+                (
+                    render_markdown(content, &source, &page, &pages),
+                    search::markdown_text(content),
+                )
+            } else {
+                (format!("<pre>{}</pre>\n", escape_html(&content)), content)
+            };
+            if layout.search {
+                site.search_index.add(&title, &href(&page), &text);
+            }
+
+            site.accessibility
+                .extend(accessibility::check(&href(&page), &entry.path, &body));
+            bodies.insert(page.clone(), body);
+            site.pages.push(RenderedPage {
+                source: entry.path.clone(),
+                page,
+                title,
+            });
+        }
+
+        // Pages are written once their order, and so their neighbours, is known
+        site.pages.sort_by_cached_key(|page| index_key(&page.page));
+        for (position, page) in site.pages.iter().enumerate() {
+            let previous = position
+                .checked_sub(1)
+                .map(|previous| &site.pages[previous]);
+            let next = site.pages.get(position + 1);
+
+            let destination = html_dir.join(&page.page);
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent).map_err(RepoDocsError::Io)?;
+            }
+            let body = format!(
+                "{}{}",
+                bodies[&page.page],
+                pager(&page.page, previous, next)
+            );
+            fs::write(
+                &destination,
+                page_html(&page.title, &body, &page.page, &breadcrumbs(page), layout),
+            )
+            .map_err(RepoDocsError::Io)?;
+        }
+
+        if layout.search {
+            site.search_index.save(html_dir)?;
+        }
+        site.save_index(html_dir, layout)?;
+        site.save_crawler_files(documents, manifest, html_dir, style)?;
+        Ok(site)
+    }
+
+    /// Write `sitemap.xml` when the site has a public URL, and `robots.txt`
+    /// when it has one or crawlers are kept out of some paths
+    fn save_crawler_files(
+        &self,
+        documents: &[DocumentFile],
+        manifest: &Manifest,
+        html_dir: &Path,
+        style: &HtmlConfig,
+    ) -> Result<()> {
+        let mut sitemap_location = None;
+        if let Some(site_url) = &style.site_url {
+            let modified = sitemap::modification_times(documents, manifest);
+            let mut sitemap = Sitemap::new(site_url)?;
+            sitemap.add("index.html", modified.values().max().copied());
+            for page in &self.pages {
+                sitemap.add(&href(&page.page), modified.get(&page.source).copied());
+            }
+            sitemap.save(html_dir)?;
+            sitemap_location = Some(sitemap.location());
+        }
+
+        if sitemap_location.is_some() || !style.robots_disallow.is_empty() {
+            fs::write(
+                html_dir.join(sitemap::ROBOTS),
+                sitemap::robots_txt(&style.robots_disallow, sitemap_location.as_ref()),
+            )
+            .map_err(RepoDocsError::Io)?;
+        }
+        Ok(())
+    }
+
+    /// Write `index.html`, listing the pages nested by directory in
+    /// collapsible sections
+    fn save_index(&self, html_dir: &Path, layout: Layout) -> Result<()> {
+        #[derive(Default)]
+        struct Directory<'a> {
+            /// Path relative to the `html/` directory, the anchor of its section
+            path: String,
+            pages: Vec<&'a RenderedPage>,
+            directories: BTreeMap<String, Directory<'a>>,
+        }
+
+        fn write_list(directory: &Directory, out: &mut String) {
+            out.push_str("<ul>\n");
+            for page in &directory.pages {
+                out.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a></li>\n",
+                    escape_html(&href(&page.page)),
+                    escape_html(&page.title)
+                ));
+            }
+            for (name, subdirectory) in &directory.directories {
+                out.push_str(&format!(
+                    "<li><details open id=\"{}\">\n<summary><strong>{}/</strong></summary>\n",
+                    escape_html(&subdirectory.path),
+                    escape_html(name)
+                ));
+                write_list(subdirectory, out);
+                out.push_str("</details></li>\n");
+            }
+            out.push_str("</ul>\n");
+        }
+
+        let mut root = Directory::default();
+        for page in &self.pages {
+            let mut directory = &mut root;
+            if let Some(parent) = page.page.parent() {
+                for component in parent.components() {
+                    let name = component.as_os_str().to_string_lossy().to_string();
+                    let path = match directory.path.is_empty() {
+                        true => name.clone(),
+                        false => format!("{}/{}", directory.path, name),
+                    };
+                    directory = directory
+                        .directories
+                        .entry(name)
+                        .or_insert_with(|| Directory {
+                            path,
+                            ..Directory::default()
+                        });
+                }
+            }
+            directory.pages.push(page);
+        }
+
+        let mut body = String::from("<h1>Documentation Index</h1>\n");
+        write_list(&root, &mut body);
+        fs::write(
+            html_dir.join("index.html"),
+            page_html(
+                "Documentation Index",
+                &body,
+                Path::new("index.html"),
+                "<a href=\"index.html\">Documentation Index</a>",
+                layout,
+            ),
+        )
+        .map_err(RepoDocsError::Io)?;
+        Ok(())
+    }
+}
+
+/// Page of an extracted file: Markdown files get an `.html` extension,
+/// other files have it appended so `guide.md` and `guide.rst` stay apart
+fn page_path(extracted: &str) -> PathBuf {
+    if markdown::is_markdown_path(extracted) {
+        Path::new(extracted).with_extension("html")
+    } else {
+        PathBuf::from(format!("{}.html", extracted))
+    }
+}
+
+/// Sort key putting pages in the order of the index: the pages of a
+/// directory by name, then its subdirectories by name
+fn index_key(page: &Path) -> Vec<(bool, String)> {
+    let mut components: Vec<(bool, String)> = page
+        .components()
+        .map(|component| (true, component.as_os_str().to_string_lossy().to_string()))
+        .collect();
+    if let Some(last) = components.last_mut() {
+        last.0 = false;
+    }
+    components
+}
+
+/// Links to the index, and to the sections of the index holding the
+/// directories above `page`, followed by its title
+fn breadcrumbs(page: &RenderedPage) -> String {
+    let root = "../".repeat(page.page.components().count().saturating_sub(1));
+    let mut crumbs = vec![format!(
+        "<a href=\"{}index.html\">Documentation Index</a>",
+        root
+    )];
+    let mut directory = String::new();
+    for component in page.page.parent().into_iter().flat_map(Path::components) {
+        let name = component.as_os_str().to_string_lossy();
+        if !directory.is_empty() {
+            directory.push('/');
+        }
+        directory.push_str(&name);
+        crumbs.push(format!(
+            "<a href=\"{}index.html#{}\">{}</a>",
+            root,
+            escape_html(&directory),
+            escape_html(&name)
+        ));
+    }
+    crumbs.push(format!(
+        "<span aria-current=\"page\">{}</span>",
+        escape_html(&page.title)
+    ));
+    crumbs.join(" / ")
+}
+
+/// Links from `page` to the pages before and after it
+fn pager(page: &Path, previous: Option<&RenderedPage>, next: Option<&RenderedPage>) -> String {
+    if previous.is_none() && next.is_none() {
+        return String::new();
+    }
+    let link = |other: &RenderedPage, rel: &str, label: String| {
+        format!(
+            "<a rel=\"{}\" href=\"{}\">{}</a>\n",
+            rel,
+            escape_html(&markdown::relative_href(page, &other.page)),
+            label
+        )
+    };
+
+    let mut out = String::from("<nav class=\"pager\">\n");
+    if let Some(previous) = previous {
+        out.push_str(&link(
+            previous,
+            "prev",
+            format!("&larr; {}", escape_html(&previous.title)),
+        ));
+    }
+    if let Some(next) = next {
+        out.push_str(&link(
+            next,
+            "next",
+            format!("{} &rarr;", escape_html(&next.title)),
+        ));
+    }
+    out.push_str("</nav>\n");
+    out
+}
+
+fn file_name(path: &str) -> String {
+    path.rsplit('/').next().unwrap_or(path).to_string()
+}
+
+fn render_markdown<'a>(
+    content: &'a str,
+    source: &Path,
+    page: &Path,
+    pages: &HashMap<PathBuf, PathBuf>,
+) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let rewrite = |target: CowStr<'a>| -> CowStr<'a> {
+        match markdown::relink(&target, source, page, pages) {
+            Some(rewritten) => CowStr::from(rewritten),
+            None => target,
+        }
+    };
+    let events = Parser::new_ext(content, options).map(|event| match event {
+        Event::Start(Tag::Link(kind, target, title)) => {
+            Event::Start(Tag::Link(kind, rewrite(target), title))
+        }
+        Event::End(Tag::Link(kind, target, title)) => {
+            Event::End(Tag::Link(kind, rewrite(target), title))
+        }
+        event => event,
+    });
+
+    let mut body = String::new();
+    html::push_html(&mut body, events);
+    body
+}
+
+fn href(page: &Path) -> String {
+    page.to_string_lossy().replace('\\', "/")
+}
+
+/// The built-in stylesheet with the colors of `theme`
+fn stylesheet(theme: HtmlTheme) -> String {
+    let colors = match theme {
+        HtmlTheme::Light => format!(":root {{\n{}}}\n", LIGHT_COLORS),
+        HtmlTheme::Dark => format!(":root {{\n{}}}\n", DARK_COLORS),
+        HtmlTheme::Auto => format!(
+            ":root {{\n{}}}\n@media (prefers-color-scheme: dark) {{\n:root {{\n{}}}\n}}\n",
+            LIGHT_COLORS, DARK_COLORS
+        ),
+    };
+    format!("{}{}", colors, STYLESHEET)
+}
+
+/// What every page of a site links to besides the built-in stylesheet
+#[derive(Debug, Clone, Copy)]
+struct Layout {
+    custom_css: bool,
+    search: bool,
+}
+
+fn page_html(title: &str, body: &str, page: &Path, breadcrumbs: &str, layout: Layout) -> String {
+    let root = "../".repeat(page.components().count().saturating_sub(1));
+    let custom_css = match layout.custom_css {
+        true => format!(
+            "<link rel=\"stylesheet\" href=\"{}{}\">\n",
+            root, CUSTOM_STYLESHEET
+        ),
+        false => String::new(),
+    };
+    let (search_box, scripts) = match layout.search {
+        true => (
+            format!(
+                "\n<form class=\"search\" role=\"search\" data-root=\"{root}\">\
+                 <input type=\"search\" placeholder=\"Search\" aria-label=\"Search the documentation\" autocomplete=\"off\">\
+                 <ul class=\"search-results\" hidden></ul></form>",
+                root = root
+            ),
+            format!(
+                "<script src=\"{root}{}\"></script>\n<script src=\"{root}{}\"></script>\n",
+                search::INDEX_SCRIPT,
+                search::SEARCH_SCRIPT,
+                root = root
+            ),
+        ),
+        false => (String::new(), String::new()),
+    };
+    format!(
+        "<!DOCTYPE html>\n\
+         <html lang=\"en\">\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n\
+         <link rel=\"stylesheet\" href=\"{root}style.css\">\n\
+         {custom_css}\
+         </head>\n\
+         <body>\n\
+         <nav><span>{breadcrumbs}</span>{search_box}</nav>\n\
+         <main>\n\
+         {body}\
+         </main>\n\
+         {scripts}\
+         </body>\n\
+         </html>\n",
+        title = escape_html(title),
+        root = root,
+        custom_css = custom_css,
+        breadcrumbs = breadcrumbs,
+        search_box = search_box,
+        body = body,
+        scripts = scripts,
+    )
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::manifest::ManifestEntry;
+    use crate::scanner::write_test_documents;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_render_html_site() {
+        let output_dir = TempDir::new().unwrap();
+        let files = [
+            (
+                "README.md",
+                "---\ntitle: Project\n---\n# Project\n\nSee the [guide](docs/guide.md#setup) and [site](https://example.com).\n",
+            ),
+            ("docs/guide.md", "# Guide\n\nBack to [readme](../README.md).\n"),
+            ("docs/notes.rst", "Notes\n=====\n\n<b>raw</b>\n"),
+        ];
+
+        let documents = write_test_documents(output_dir.path(), &files);
+        let manifest = Manifest::for_test_documents(&documents);
+
+        let site = HtmlSite::render(
+            &documents,
+            &manifest,
+            output_dir.path(),
+            &HtmlConfig::default(),
+        )
+        .unwrap();
+        let pages: Vec<&Path> = site.pages.iter().map(|page| page.page.as_path()).collect();
+        assert_eq!(
+            pages,
+            [
+                Path::new("README.html"),
+                Path::new("docs/guide.html"),
+                Path::new("docs/notes.rst.html"),
+            ]
+        );
+
+        let html_dir = output_dir.path().join(HTML_DIR);
+        let readme = fs::read_to_string(html_dir.join("README.html")).unwrap();
+        assert!(readme.contains("<title>Project</title>"));
+        assert!(readme.contains("href=\"docs/guide.html#setup\""));
+        assert!(readme.contains("href=\"https://example.com\""));
+        assert!(!readme.contains("title: Project"));
+
+        let guide = fs::read_to_string(html_dir.join("docs/guide.html")).unwrap();
+        assert!(guide.contains("href=\"../README.html\""));
+        assert!(guide.contains("href=\"../style.css\""));
+        assert!(guide.contains(
+            "<nav><span><a href=\"../index.html\">Documentation Index</a> / \
+             <a href=\"../index.html#docs\">docs</a> / \
+             <span aria-current=\"page\">guide.md</span></span>\n\
+             <form class=\"search\" role=\"search\" data-root=\"../\">"
+        ));
+        assert!(guide.contains("<script src=\"../search-index.js\"></script>"));
+        assert!(guide.contains("<a rel=\"prev\" href=\"../README.html\">&larr; Project</a>"));
+        assert!(guide.contains("<a rel=\"next\" href=\"notes.rst.html\">notes.rst &rarr;</a>"));
+        assert!(!readme.contains("rel=\"prev\""));
+
+        let notes = fs::read_to_string(html_dir.join("docs/notes.rst.html")).unwrap();
+        assert!(notes.contains("<pre>Notes\n=====\n\n&lt;b&gt;raw&lt;/b&gt;\n</pre>"));
+
+        let index = fs::read_to_string(html_dir.join("index.html")).unwrap();
+        assert!(index.contains("<a href=\"README.html\">Project</a>"));
+        assert!(index.contains(
+            "<details open id=\"docs\">\n<summary><strong>docs/</strong></summary>\n<ul>\n<li><a href=\"docs/guide.html\">"
+        ));
+        assert!(html_dir.join("style.css").exists());
+        assert!(!index.contains("custom.css"));
+        assert!(index.contains("<form class=\"search\" role=\"search\" data-root=\"\">"));
+
+        let search_index = fs::read_to_string(html_dir.join(search::INDEX_SCRIPT)).unwrap();
+        assert!(search_index.starts_with("window.repodocsSearch = {\"documents\":[{\"title\":\"Project\",\"page\":\"README.html\""));
+        assert!(search_index.contains("\"guide\":[[0,"));
+        assert!(html_dir.join(search::SEARCH_SCRIPT).exists());
+        assert!(!html_dir.join(sitemap::SITEMAP).exists());
+        assert!(!html_dir.join(sitemap::ROBOTS).exists());
+
+        HtmlSite::render(
+            &documents,
+            &manifest,
+            output_dir.path(),
+            &HtmlConfig {
+                site_url: Some("https://owner.github.io/project/".to_string()),
+                ..HtmlConfig::default()
+            },
+        )
+        .unwrap();
+        let sitemap = fs::read_to_string(html_dir.join(sitemap::SITEMAP)).unwrap();
+        assert!(sitemap.contains("<loc>https://owner.github.io/project/index.html</loc>"));
+        assert!(sitemap.contains("<loc>https://owner.github.io/project/docs/guide.html</loc>"));
+        assert_eq!(
+            fs::read_to_string(html_dir.join(sitemap::ROBOTS)).unwrap(),
+            "User-agent: *\nDisallow:\n\nSitemap: https://owner.github.io/project/sitemap.xml\n"
+        );
+    }
+
+    #[test]
+    fn test_theme_and_custom_css() {
+        let output_dir = TempDir::new().unwrap();
+        fs::create_dir_all(output_dir.path().join("docs")).unwrap();
+        fs::write(output_dir.path().join("docs/guide.md"), "# Guide\n").unwrap();
+        let document = DocumentFile::new(
+            output_dir.path().join("docs/guide.md"),
+            PathBuf::from("docs/guide.md"),
+            8,
+            SystemTime::UNIX_EPOCH,
+        );
+        let manifest = Manifest {
+            commit: None,
+            settings: None,
+            entries: vec![ManifestEntry {
+                path: "docs/guide.md".to_string(),
+                extracted: "docs/guide.md".to_string(),
+                size: 8,
+                modified: None,
+                hash: String::new(),
+                sha256: None,
+            }],
+        };
+        let brand = output_dir.path().join("brand.css");
+        fs::write(&brand, "nav { background: #ff6600; }\n").unwrap();
+        let render = |style: &HtmlConfig| {
+            HtmlSite::render(
+                std::slice::from_ref(&document),
+                &manifest,
+                output_dir.path(),
+                style,
+            )
+        };
+
+        render(&HtmlConfig {
+            theme: HtmlTheme::Dark,
+            custom_css: Some(brand.clone()),
+            search: false,
+            ..HtmlConfig::default()
+        })
+        .unwrap();
+        let html_dir = output_dir.path().join(HTML_DIR);
+        let style = fs::read_to_string(html_dir.join("style.css")).unwrap();
+        assert!(style.contains("--background: #0d1117;"));
+        assert!(!style.contains("#ffffff"));
+        assert_eq!(
+            fs::read_to_string(html_dir.join("custom.css")).unwrap(),
+            "nav { background: #ff6600; }\n"
+        );
+        let guide = fs::read_to_string(html_dir.join("docs/guide.html")).unwrap();
+        assert!(guide.contains(
+            "<link rel=\"stylesheet\" href=\"../style.css\">\n\
+             <link rel=\"stylesheet\" href=\"../custom.css\">\n"
+        ));
+        assert!(!guide.contains("<script"));
+        assert!(!html_dir.join(search::INDEX_SCRIPT).exists());
+        let index = fs::read_to_string(html_dir.join("index.html")).unwrap();
+        assert!(index.contains("<link rel=\"stylesheet\" href=\"custom.css\">"));
+
+        render(&HtmlConfig {
+            theme: HtmlTheme::Auto,
+            ..HtmlConfig::default()
+        })
+        .unwrap();
+        let style = fs::read_to_string(html_dir.join("style.css")).unwrap();
+        assert!(style.contains("--background: #ffffff;"));
+        assert!(style.contains("@media (prefers-color-scheme: dark)"));
+
+        let missing = HtmlConfig {
+            theme: HtmlTheme::Light,
+            custom_css: Some(output_dir.path().join("missing.css")),
+            ..HtmlConfig::default()
+        };
+        assert!(matches!(
+            render(&missing),
+            Err(RepoDocsError::Config { .. })
+        ));
+    }
+}
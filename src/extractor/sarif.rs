@@ -0,0 +1,142 @@
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::accessibility::{AccessibilityFinding, AccessibilityRule};
+use crate::extractor::links::UnresolvedLink;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// SARIF log of the problems found in an extraction, written to the
+/// `.repodocs` directory
+pub const SARIF_FILE: &str = "findings.sarif";
+
+/// Rule of relative links to files the repository does not have
+const BROKEN_LINK: &str = "broken-link";
+
+/// SARIF 2.1.0 log of the accessibility problems of the rendered pages and
+/// the links to missing files, located at the documents they were found in
+/// so code scanning shows them on the repository
+pub fn log(accessibility: &[AccessibilityFinding], unresolved_links: &[UnresolvedLink]) -> Value {
+    let mut rules: Vec<Value> = AccessibilityRule::ALL
+        .iter()
+        .map(|rule| rule_json(&rule.to_string(), rule.description()))
+        .collect();
+    rules.push(rule_json(
+        BROKEN_LINK,
+        "Relative links must point at files of the repository",
+    ));
+
+    let results: Vec<Value> = accessibility
+        .iter()
+        .map(|finding| {
+            result_json(
+                &finding.rule.to_string(),
+                &format!(
+                    "{}: {} (rendered page {})",
+                    finding.rule.description(),
+                    finding.element,
+                    finding.page
+                ),
+                &finding.source,
+            )
+        })
+        .chain(unresolved_links.iter().map(|link| {
+            result_json(
+                BROKEN_LINK,
+                &format!("Link to a file that does not exist: {}", link.target),
+                &link.document,
+            )
+        }))
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "repodocs",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// Write the SARIF log of the findings into the given directory
+pub fn save(
+    accessibility: &[AccessibilityFinding],
+    unresolved_links: &[UnresolvedLink],
+    dir: &Path,
+) -> Result<PathBuf> {
+    let json =
+        serde_json::to_string_pretty(&log(accessibility, unresolved_links)).map_err(|e| {
+            RepoDocsError::Config {
+                message: format!("Failed to serialize findings: {}", e),
+            }
+        })?;
+    let path = dir.join(SARIF_FILE);
+    fs::write(&path, json).map_err(RepoDocsError::Io)?;
+    Ok(path)
+}
+
+fn rule_json(id: &str, description: &str) -> Value {
+    json!({
+        "id": id,
+        "shortDescription": { "text": description },
+    })
+}
+
+fn result_json(rule: &str, message: &str, path: &str) -> Value {
+    json!({
+        "ruleId": rule,
+        "level": "warning",
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": {
+                    "uri": path,
+                    "uriBaseId": "%SRCROOT%",
+                },
+            },
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::accessibility;
+
+    #[test]
+    fn test_sarif_log() {
+        let findings = accessibility::check(
+            "docs/guide.html",
+            "docs/guide.md",
+            "<h1>Guide</h1>\n<h3>Deep</h3>\n",
+        );
+        let links = [UnresolvedLink {
+            document: "README.md".to_string(),
+            target: "missing.md#setup".to_string(),
+        }];
+
+        let log = log(&findings, &links);
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 4);
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        let location = |result: &Value| {
+            result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"].clone()
+        };
+        assert_eq!(results[0]["ruleId"], "heading-level-skip");
+        assert_eq!(location(&results[0]), "docs/guide.md");
+        assert_eq!(results[1]["ruleId"], "broken-link");
+        assert_eq!(location(&results[1]), "README.md");
+        assert!(results[1]["message"]["text"]
+            .as_str()
+            .unwrap()
+            .ends_with("missing.md#setup"));
+    }
+}
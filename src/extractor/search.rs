@@ -0,0 +1,288 @@
+use crate::error::{RepoDocsError, Result};
+use pulldown_cmark::{Event, Parser};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// Script of a rendered site holding its search index
+pub const INDEX_SCRIPT: &str = "search-index.js";
+
+/// Script searching the index from the search box of every page
+pub const SEARCH_SCRIPT: &str = "search.js";
+
+/// Longest excerpt shown with a search result, in characters
+const EXCERPT_CHARS: usize = 160;
+
+/// Occurrences a word of a title counts as, so pages named after a
+/// search rank above pages merely mentioning it
+const TITLE_WEIGHT: usize = 5;
+
+const SCRIPT: &str = r#"(function () {
+  var index = window.repodocsSearch;
+  var form = document.querySelector("form.search");
+  if (!index || !form) {
+    return;
+  }
+  var input = form.querySelector("input");
+  var results = form.querySelector(".search-results");
+  var root = form.getAttribute("data-root") || "";
+  var terms = Object.keys(index.terms);
+
+  function words(text) {
+    return text.toLowerCase().split(/[^\p{L}\p{N}]+/u).filter(function (word) {
+      return Array.from(word).length > 1;
+    });
+  }
+
+  // Pages holding every word of the query, as a whole word or a prefix,
+  // ranked by how often and how rarely across the site the words occur
+  function search(query) {
+    var scores = null;
+    words(query).forEach(function (word) {
+      var matched = {};
+      terms.forEach(function (term) {
+        if (term.lastIndexOf(word, 0) !== 0) {
+          return;
+        }
+        var postings = index.terms[term];
+        var weight = Math.log(1 + index.documents.length / postings.length);
+        if (term !== word) {
+          weight /= 2;
+        }
+        postings.forEach(function (posting) {
+          matched[posting[0]] = (matched[posting[0]] || 0) + posting[1] * weight;
+        });
+      });
+      if (scores === null) {
+        scores = matched;
+        return;
+      }
+      Object.keys(scores).forEach(function (document) {
+        if (document in matched) {
+          scores[document] += matched[document];
+        } else {
+          delete scores[document];
+        }
+      });
+    });
+    return Object.keys(scores || {})
+      .sort(function (a, b) {
+        return scores[b] - scores[a] || a - b;
+      })
+      .slice(0, 20)
+      .map(function (document) {
+        return index.documents[document];
+      });
+  }
+
+  function show(query) {
+    results.textContent = "";
+    results.hidden = query.trim() === "";
+    var found = search(query);
+    if (found.length === 0) {
+      var empty = document.createElement("li");
+      empty.textContent = "No results";
+      results.appendChild(empty);
+    }
+    found.forEach(function (result) {
+      var item = document.createElement("li");
+      var link = document.createElement("a");
+      link.href = root + result.page;
+      link.textContent = result.title;
+      var excerpt = document.createElement("p");
+      excerpt.textContent = result.excerpt;
+      item.appendChild(link);
+      item.appendChild(excerpt);
+      results.appendChild(item);
+    });
+  }
+
+  input.addEventListener("input", function () {
+    show(input.value);
+  });
+  form.addEventListener("submit", function (event) {
+    event.preventDefault();
+    var first = results.querySelector("a");
+    if (first) {
+      window.location.href = first.href;
+    }
+  });
+})();
+"#;
+
+/// A page that search can find
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchDocument {
+    pub title: String,
+    /// Path relative to the `html/` directory
+    pub page: String,
+    /// Beginning of the page's text
+    pub excerpt: String,
+}
+
+/// Inverted index of the words of a rendered site, searched in the browser
+/// without a server. It is written as a script rather than JSON, since
+/// browsers do not let pages opened from disk fetch files.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchIndex {
+    pub documents: Vec<SearchDocument>,
+    /// Pages holding each word, as pairs of the page's position in
+    /// `documents` and the word's weighted number of occurrences
+    pub terms: BTreeMap<String, Vec<(usize, usize)>>,
+}
+
+impl SearchIndex {
+    /// Add the page at `page` with its `title` and plain `text`
+    pub fn add(&mut self, title: &str, page: &str, text: &str) {
+        let position = self.documents.len();
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for word in words(title) {
+            *counts.entry(word).or_default() += TITLE_WEIGHT;
+        }
+        for word in words(text) {
+            *counts.entry(word).or_default() += 1;
+        }
+        for (word, count) in counts {
+            self.terms.entry(word).or_default().push((position, count));
+        }
+
+        self.documents.push(SearchDocument {
+            title: title.to_string(),
+            page: page.to_string(),
+            excerpt: excerpt(text),
+        });
+    }
+
+    /// The `limit` best pages holding every word of `query`, as a whole word
+    /// or a prefix, ranked as the search box of the site ranks them
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&SearchDocument> {
+        let mut scores: Option<HashMap<usize, f64>> = None;
+        for word in words(query) {
+            let mut matched: HashMap<usize, f64> = HashMap::new();
+            let terms = self
+                .terms
+                .range(word.clone()..)
+                .take_while(|(term, _)| term.starts_with(&word));
+            for (term, postings) in terms {
+                let mut weight = (1.0 + self.documents.len() as f64 / postings.len() as f64).ln();
+                if *term != word {
+                    weight /= 2.0;
+                }
+                for &(document, count) in postings {
+                    *matched.entry(document).or_default() += count as f64 * weight;
+                }
+            }
+            scores = Some(match scores {
+                None => matched,
+                Some(mut scores) => {
+                    scores.retain(|document, _| matched.contains_key(document));
+                    for (document, score) in scores.iter_mut() {
+                        *score += matched[document];
+                    }
+                    scores
+                }
+            });
+        }
+
+        let mut ranked: Vec<(usize, f64)> = scores.unwrap_or_default().into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|(document, _)| &self.documents[document])
+            .collect()
+    }
+
+    /// Write the index and the script searching it to `html_dir`
+    pub fn save(&self, html_dir: &Path) -> Result<()> {
+        let json = serde_json::to_string(self).map_err(|e| RepoDocsError::Config {
+            message: format!("Failed to serialize search index: {}", e),
+        })?;
+        fs::write(
+            html_dir.join(INDEX_SCRIPT),
+            format!("window.repodocsSearch = {};\n", json),
+        )
+        .map_err(RepoDocsError::Io)?;
+        fs::write(html_dir.join(SEARCH_SCRIPT), SCRIPT).map_err(RepoDocsError::Io)?;
+        Ok(())
+    }
+}
+
+/// Text of Markdown `content` without its markup
+pub fn markdown_text(content: &str) -> String {
+    let mut text = String::new();
+    for event in Parser::new(content) {
+        match event {
+            Event::Text(part) | Event::Code(part) => {
+                text.push_str(&part);
+                text.push(' ');
+            }
+            Event::SoftBreak | Event::HardBreak => text.push(' '),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Lowercase words of at least two letters or digits
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().nth(1).is_some())
+        .map(str::to_lowercase)
+}
+
+fn excerpt(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match text.char_indices().nth(EXCERPT_CHARS) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_index() {
+        let mut index = SearchIndex::default();
+        index.add(
+            "Install",
+            "docs/install.html",
+            &markdown_text("# Install\n\nRun `cargo install repodocs`, then install\nthe hooks."),
+        );
+        index.add(
+            "Guide",
+            "docs/guide.html",
+            "Read the *install* page first. A b",
+        );
+
+        assert_eq!(index.terms["install"], [(0, 8), (1, 1)]);
+        assert_eq!(index.terms["cargo"], [(0, 1)]);
+        assert_eq!(index.terms["guide"], [(1, 5)]);
+        assert!(!index.terms.contains_key("a"));
+        assert_eq!(
+            index.documents[0].excerpt,
+            "Install Run cargo install repodocs , then install the hooks."
+        );
+
+        let found = |query: &str| -> Vec<&str> {
+            index
+                .search(query, 20)
+                .into_iter()
+                .map(|document| document.title.as_str())
+                .collect()
+        };
+        assert_eq!(found("install"), ["Install", "Guide"]);
+        assert_eq!(found("INST"), ["Install", "Guide"]);
+        assert_eq!(found("install hooks"), ["Install"]);
+        assert_eq!(found("install missing"), Vec::<&str>::new());
+        assert_eq!(found(""), Vec::<&str>::new());
+        assert_eq!(index.search("install", 1).len(), 1);
+
+        let long = "word ".repeat(100);
+        assert_eq!(excerpt(&long).chars().count(), EXCERPT_CHARS);
+        assert!(excerpt(&long).ends_with("word…"));
+    }
+}
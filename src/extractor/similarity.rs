@@ -0,0 +1,180 @@
+use crate::scanner::DocumentFile;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Documents shorter than this many words are too small for a meaningful
+/// fingerprint and are left out of near-duplicate detection.
+const MIN_WORDS: usize = 8;
+const SHINGLE_SIZE: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NearDuplicateGroup {
+    pub files: Vec<String>,
+    /// Lowest pairwise similarity that joined this group
+    pub min_similarity: f64,
+}
+
+/// 64-bit SimHash fingerprint of a document's word shingles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimHash(pub u64);
+
+impl SimHash {
+    pub fn from_text(text: &str) -> Option<Self> {
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_lowercase())
+            .collect();
+
+        if words.len() < MIN_WORDS {
+            return None;
+        }
+
+        let mut weights = [0i64; 64];
+        for shingle in words.windows(SHINGLE_SIZE) {
+            let hash = fnv1a(&shingle.join(" "));
+            for (bit, weight) in weights.iter_mut().enumerate() {
+                if hash & (1 << bit) != 0 {
+                    *weight += 1;
+                } else {
+                    *weight -= 1;
+                }
+            }
+        }
+
+        let fingerprint = weights
+            .iter()
+            .enumerate()
+            .filter(|(_, weight)| **weight > 0)
+            .fold(0u64, |acc, (bit, _)| acc | (1 << bit));
+
+        Some(SimHash(fingerprint))
+    }
+
+    /// Similarity in `[0.0, 1.0]` derived from the Hamming distance
+    pub fn similarity(&self, other: &SimHash) -> f64 {
+        let distance = (self.0 ^ other.0).count_ones();
+        1.0 - distance as f64 / 64.0
+    }
+}
+
+/// Group documents whose fingerprints are at least `threshold` similar
+pub fn find_near_duplicates(documents: &[DocumentFile], threshold: f64) -> Vec<NearDuplicateGroup> {
+    let fingerprints: Vec<(String, SimHash)> = documents
+        .iter()
+        .filter_map(|doc| {
+            let bytes = fs::read(&doc.source_path).ok()?;
+            let hash = SimHash::from_text(&String::from_utf8_lossy(&bytes))?;
+            Some((doc.relative_path.to_string_lossy().replace('\\', "/"), hash))
+        })
+        .collect();
+
+    // Union-find over all pairs above the threshold
+    let mut parent: Vec<usize> = (0..fingerprints.len()).collect();
+    let mut min_similarity = vec![1.0f64; fingerprints.len()];
+
+    for i in 0..fingerprints.len() {
+        for j in (i + 1)..fingerprints.len() {
+            let similarity = fingerprints[i].1.similarity(&fingerprints[j].1);
+            if similarity >= threshold {
+                let root_i = find_root(&mut parent, i);
+                let root_j = find_root(&mut parent, j);
+                let merged = min_similarity[root_i]
+                    .min(min_similarity[root_j])
+                    .min(similarity);
+                if root_i != root_j {
+                    parent[root_j] = root_i;
+                }
+                min_similarity[root_i] = merged;
+            }
+        }
+    }
+
+    let mut groups: std::collections::BTreeMap<usize, Vec<String>> =
+        std::collections::BTreeMap::new();
+    for (index, (path, _)) in fingerprints.iter().enumerate() {
+        let root = find_root(&mut parent, index);
+        groups.entry(root).or_default().push(path.clone());
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, files)| files.len() > 1)
+        .map(|(root, mut files)| {
+            files.sort();
+            NearDuplicateGroup {
+                files,
+                min_similarity: (min_similarity[root] * 1000.0).round() / 1000.0,
+            }
+        })
+        .collect()
+}
+
+fn find_root(parent: &mut [usize], mut index: usize) -> usize {
+    while parent[index] != index {
+        parent[index] = parent[parent[index]];
+        index = parent[index];
+    }
+    index
+}
+
+fn fnv1a(text: &str) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    text.bytes().fold(OFFSET, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::write_test_documents;
+    use tempfile::TempDir;
+
+    const BASE: &str = "Install the package with cargo and then run the binary \
+                        against any public repository to extract its documentation \
+                        files into a local directory for offline reading";
+
+    #[test]
+    fn test_simhash_similarity() {
+        let a = SimHash::from_text(BASE).unwrap();
+        let b = SimHash::from_text(&format!("{} today", BASE)).unwrap();
+        let c = SimHash::from_text(
+            "Completely unrelated text about compilers, parsers, tokens and \
+             abstract syntax trees being lowered into machine code",
+        )
+        .unwrap();
+
+        assert_eq!(a.similarity(&a), 1.0);
+        assert!(a.similarity(&b) > a.similarity(&c));
+        assert!(SimHash::from_text("too short").is_none());
+    }
+
+    #[test]
+    fn test_find_near_duplicates() {
+        let dir = TempDir::new().unwrap();
+        let documents = write_test_documents(
+            dir.path(),
+            &[
+                ("a.md", BASE.to_string()),
+                ("b.md", format!("{} today", BASE)),
+                (
+                    "c.md",
+                    "Completely unrelated text about compilers, parsers, tokens and \
+                     abstract syntax trees being lowered into machine code"
+                        .to_string(),
+                ),
+            ],
+        );
+
+        let groups = find_near_duplicates(&documents, 0.85);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            groups[0].files,
+            vec!["a.md".to_string(), "b.md".to_string()]
+        );
+        assert!(groups[0].min_similarity >= 0.85);
+    }
+}
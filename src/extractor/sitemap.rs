@@ -0,0 +1,281 @@
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::links;
+use crate::extractor::manifest::Manifest;
+use crate::extractor::renderer::escape_html;
+use crate::scanner::DocumentFile;
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use git2::Repository;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Sitemap of a rendered site, listing every page for search engines
+pub const SITEMAP: &str = "sitemap.xml";
+
+/// Rules for crawlers of a rendered site
+pub const ROBOTS: &str = "robots.txt";
+
+/// A page of the sitemap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitemapUrl {
+    pub location: Url,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// Sitemap of a site published at `site_url`, whose pages are given
+/// relative to the `html/` directory
+#[derive(Debug, Clone)]
+pub struct Sitemap {
+    root: Url,
+    pub urls: Vec<SitemapUrl>,
+}
+
+impl Sitemap {
+    pub fn new(site_url: &str) -> Result<Self> {
+        // The root must end with a slash for pages to be resolved below it
+        let root = Url::parse(&format!("{}/", site_url.trim_end_matches('/'))).map_err(|e| {
+            RepoDocsError::Config {
+                message: format!("Invalid site URL '{}': {}", site_url, e),
+            }
+        })?;
+        Ok(Self {
+            root,
+            urls: Vec::new(),
+        })
+    }
+
+    /// Add the page at `page`, last modified at `modified`
+    pub fn add(&mut self, page: &str, modified: Option<DateTime<Utc>>) {
+        if let Ok(location) = self.root.join(page) {
+            self.urls.push(SitemapUrl { location, modified });
+        }
+    }
+
+    /// URL the sitemap itself is published at
+    pub fn location(&self) -> Url {
+        self.root
+            .join(SITEMAP)
+            .unwrap_or_else(|_| self.root.clone())
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for url in &self.urls {
+            xml.push_str(&format!(
+                "  <url>\n    <loc>{}</loc>\n",
+                escape_html(url.location.as_str())
+            ));
+            if let Some(modified) = url.modified {
+                xml.push_str(&format!(
+                    "    <lastmod>{}</lastmod>\n",
+                    modified.to_rfc3339_opts(SecondsFormat::Secs, true)
+                ));
+            }
+            xml.push_str("  </url>\n");
+        }
+        xml.push_str("</urlset>\n");
+        xml
+    }
+
+    pub fn save(&self, html_dir: &Path) -> Result<()> {
+        fs::write(html_dir.join(SITEMAP), self.to_xml()).map_err(RepoDocsError::Io)
+    }
+}
+
+/// `robots.txt` letting every crawler in except for the `disallow`ed
+/// paths, and pointing them at the `sitemap` when there is one
+pub fn robots_txt(disallow: &[String], sitemap: Option<&Url>) -> String {
+    let mut robots = String::from("User-agent: *\n");
+    if disallow.is_empty() {
+        robots.push_str("Disallow:\n");
+    }
+    for path in disallow {
+        robots.push_str(&format!("Disallow: {}\n", path));
+    }
+    if let Some(sitemap) = sitemap {
+        robots.push_str(&format!("\nSitemap: {}\n", sitemap));
+    }
+    robots
+}
+
+/// When each document of `manifest` last changed, by repository path: the
+/// time of the last commit touching it, or its modification time for files
+/// the history does not have, such as those of local directories outside git
+pub fn modification_times(
+    documents: &[DocumentFile],
+    manifest: &Manifest,
+) -> HashMap<String, DateTime<Utc>> {
+    let paths: Vec<&str> = manifest
+        .entries
+        .iter()
+        .map(|entry| entry.path.as_str())
+        .collect();
+    let mut times = documents
+        .first()
+        .and_then(links::repository_root)
+        .map(|root| last_commit_times(root, &paths))
+        .unwrap_or_default();
+
+    for entry in &manifest.entries {
+        if let Some(modified) = entry
+            .modified
+            .and_then(|secs| Utc.timestamp_opt(secs as i64, 0).single())
+        {
+            times.entry(entry.path.clone()).or_insert(modified);
+        }
+    }
+    times
+}
+
+/// Time of the last commit reachable from HEAD that changed each of
+/// `paths`, which are relative to `root`, following first parents. A
+/// shallow clone attributes files unchanged since its oldest commit to that
+/// commit. Paths are left out when `root` is not in a git repository.
+pub fn last_commit_times(root: &Path, paths: &[&str]) -> HashMap<String, DateTime<Utc>> {
+    let mut times = HashMap::new();
+    let Ok(repository) = Repository::discover(root) else {
+        return times;
+    };
+    let Some(workdir) = repository.workdir() else {
+        return times;
+    };
+
+    // `root` can be a directory of the repository rather than its top
+    let prefix = root
+        .canonicalize()
+        .ok()
+        .zip(workdir.canonicalize().ok())
+        .and_then(|(root, workdir)| root.strip_prefix(workdir).ok().map(Path::to_path_buf))
+        .unwrap_or_default();
+    let mut pending: HashMap<PathBuf, &str> = paths
+        .iter()
+        .map(|path| (prefix.join(path), *path))
+        .collect();
+
+    let Ok(mut revwalk) = repository.revwalk() else {
+        return times;
+    };
+    if revwalk.push_head().is_err() || revwalk.set_sorting(git2::Sort::TIME).is_err() {
+        return times;
+    }
+
+    for oid in revwalk.flatten() {
+        if pending.is_empty() {
+            break;
+        }
+        let Ok(commit) = repository.find_commit(oid) else {
+            continue;
+        };
+        let Some(time) = Utc.timestamp_opt(commit.time().seconds(), 0).single() else {
+            continue;
+        };
+        let tree = commit.tree().ok();
+        let parent = commit.parent(0).and_then(|parent| parent.tree()).ok();
+        let Ok(diff) = repository.diff_tree_to_tree(parent.as_ref(), tree.as_ref(), None) else {
+            continue;
+        };
+        for delta in diff.deltas() {
+            if let Some(path) = delta
+                .new_file()
+                .path()
+                .and_then(|path| pending.remove(path))
+            {
+                times.insert(path.to_string(), time);
+            }
+        }
+    }
+    times
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Signature, Time};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_last_commit_times() {
+        let repo_dir = TempDir::new().unwrap();
+        let repository = Repository::init(repo_dir.path()).unwrap();
+        let commit = |files: &[(&str, &str)], seconds: i64| {
+            for (path, content) in files {
+                let path = repo_dir.path().join(path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, content).unwrap();
+            }
+            let mut index = repository.index().unwrap();
+            index
+                .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.write().unwrap();
+            let tree = repository.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature =
+                Signature::new("Test", "test@example.com", &Time::new(seconds, 0)).unwrap();
+            let parent = repository
+                .head()
+                .ok()
+                .map(|head| head.peel_to_commit().unwrap());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repository
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    "commit",
+                    &tree,
+                    &parents,
+                )
+                .unwrap();
+        };
+        commit(
+            &[("docs/guide.md", "# Guide"), ("docs/api.md", "# API")],
+            1_700_000_000,
+        );
+        commit(&[("docs/guide.md", "# Guide\n\nMore.")], 1_700_086_400);
+
+        let times = last_commit_times(repo_dir.path(), &["docs/guide.md", "docs/api.md", "new.md"]);
+        assert_eq!(times["docs/guide.md"].timestamp(), 1_700_086_400);
+        assert_eq!(times["docs/api.md"].timestamp(), 1_700_000_000);
+        assert!(!times.contains_key("new.md"));
+
+        // Paths relative to a directory of the repository
+        let times = last_commit_times(&repo_dir.path().join("docs"), &["api.md"]);
+        assert_eq!(times["api.md"].timestamp(), 1_700_000_000);
+
+        let plain = TempDir::new().unwrap();
+        assert!(last_commit_times(plain.path(), &["docs/api.md"]).is_empty());
+    }
+
+    #[test]
+    fn test_sitemap_and_robots() {
+        let mut sitemap = Sitemap::new("https://example.com/docs").unwrap();
+        let modified = Utc.timestamp_opt(1_700_000_000, 0).single();
+        sitemap.add("index.html", modified);
+        sitemap.add("guides/Q&A notes.html", None);
+
+        assert_eq!(
+            sitemap.to_xml(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n  \
+             <url>\n    <loc>https://example.com/docs/index.html</loc>\n    \
+             <lastmod>2023-11-14T22:13:20Z</lastmod>\n  </url>\n  \
+             <url>\n    <loc>https://example.com/docs/guides/Q&amp;A%20notes.html</loc>\n  </url>\n\
+             </urlset>\n"
+        );
+        assert!(Sitemap::new("not a url").is_err());
+
+        assert_eq!(robots_txt(&[], None), "User-agent: *\nDisallow:\n");
+        assert_eq!(
+            robots_txt(
+                &["/drafts/".to_string(), "/internal/".to_string()],
+                Some(&sitemap.location())
+            ),
+            "User-agent: *\nDisallow: /drafts/\nDisallow: /internal/\n\n\
+             Sitemap: https://example.com/docs/sitemap.xml\n"
+        );
+    }
+}
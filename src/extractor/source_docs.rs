@@ -0,0 +1,473 @@
+use crate::config::FilterConfig;
+use crate::error::{RepoDocsError, Result};
+use crate::scanner::FileFilter;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Directory of the output holding the extracted doc comments
+pub const SOURCE_DOCS_DIR: &str = "source_docs";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceLanguage {
+    Rust,
+    Python,
+    Go,
+    JavaScript,
+}
+
+impl SourceLanguage {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "rs" => Some(SourceLanguage::Rust),
+            "py" | "pyi" => Some(SourceLanguage::Python),
+            "go" => Some(SourceLanguage::Go),
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => {
+                Some(SourceLanguage::JavaScript)
+            }
+            _ => None,
+        }
+    }
+
+    /// Fence language of the item signatures
+    fn fence(&self) -> &'static str {
+        match self {
+            SourceLanguage::Rust => "rust",
+            SourceLanguage::Python => "python",
+            SourceLanguage::Go => "go",
+            SourceLanguage::JavaScript => "js",
+        }
+    }
+}
+
+/// One doc comment: module-level when `item` is `None`, otherwise the
+/// first line of the documented declaration
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocBlock {
+    pub item: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct SourceDocFile {
+    pub path: String,
+    pub language: SourceLanguage,
+    pub blocks: Vec<DocBlock>,
+}
+
+/// Doc comments pulled out of source files, written as one Markdown file
+/// per source file under `source_docs/`
+#[derive(Debug, Clone, Default)]
+pub struct SourceDocs {
+    pub files: Vec<SourceDocFile>,
+}
+
+impl SourceDocs {
+    /// Walk the tree with the scan's directory and size rules and collect
+    /// the doc comments of every supported source file
+    pub fn collect(root: &Path, config: &FilterConfig) -> Self {
+        let filter = FileFilter::new(config);
+        let mut docs = SourceDocs::default();
+
+        let walker = WalkDir::new(root)
+            .max_depth(config.max_depth)
+            .follow_links(false)
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.depth() == 0
+                    || !entry.file_type().is_dir()
+                    || entry
+                        .path()
+                        .strip_prefix(root)
+                        .is_ok_and(|relative| filter.should_traverse_directory(relative))
+            });
+
+        for entry in walker.filter_map(|entry| entry.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Some(language) = entry
+                .path()
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(SourceLanguage::from_extension)
+            else {
+                continue;
+            };
+            let Ok(relative) = entry.path().strip_prefix(root) else {
+                continue;
+            };
+            let too_large = entry
+                .metadata()
+                .map_or(true, |metadata| !filter.is_size_allowed(metadata.len()));
+            if too_large || filter.is_excluded(relative, false) {
+                continue;
+            }
+
+            let content = match fs::read(entry.path()) {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(_) => continue,
+            };
+            let blocks = extract_doc_comments(language, &content);
+            if !blocks.is_empty() {
+                docs.files.push(SourceDocFile {
+                    path: relative.to_string_lossy().replace('\\', "/"),
+                    language,
+                    blocks,
+                });
+            }
+        }
+
+        docs.files.sort_by(|a, b| a.path.cmp(&b.path));
+        docs
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.files.iter().map(|file| file.blocks.len()).sum()
+    }
+
+    /// Write `source_docs/<path>.md` for every file, replacing the output
+    /// of a previous run
+    pub fn save(&self, output_dir: &Path) -> Result<()> {
+        let dir = output_dir.join(SOURCE_DOCS_DIR);
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(RepoDocsError::Io)?;
+        }
+
+        for source in &self.files {
+            let path = dir.join(format!("{}.md", source.path));
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(RepoDocsError::Io)?;
+            }
+            let mut file = fs::File::create(&path).map_err(RepoDocsError::Io)?;
+
+            writeln!(file, "# `{}`", source.path)?;
+            writeln!(file)?;
+            writeln!(file, "Doc comments extracted by RepoDocs.")?;
+            writeln!(file)?;
+
+            for block in &source.blocks {
+                if let Some(item) = &block.item {
+                    writeln!(file, "```{}", source.language.fence())?;
+                    writeln!(file, "{}", item)?;
+                    writeln!(file, "```")?;
+                    writeln!(file)?;
+                }
+                writeln!(file, "{}", block.text)?;
+                writeln!(file)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn extract_doc_comments(language: SourceLanguage, content: &str) -> Vec<DocBlock> {
+    let lines: Vec<&str> = content.lines().collect();
+    match language {
+        SourceLanguage::Rust => rust_doc_comments(&lines),
+        SourceLanguage::Python => python_docstrings(&lines),
+        SourceLanguage::Go => go_doc_comments(&lines),
+        SourceLanguage::JavaScript => jsdoc_blocks(&lines),
+    }
+}
+
+/// Strip a comment marker and the single space that usually follows it
+fn strip_marker<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = line.trim_start().strip_prefix(marker)?;
+    Some(rest.strip_prefix(' ').unwrap_or(rest))
+}
+
+/// The first non-blank line at or after `start`, trimmed
+fn next_code_line(lines: &[&str], start: usize) -> Option<String> {
+    lines[start.min(lines.len())..]
+        .iter()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty() && !line.starts_with("#[") && !line.starts_with('@'))
+        .map(|line| line.trim_end_matches('{').trim_end().to_string())
+}
+
+fn block(item: Option<String>, text: Vec<&str>) -> Option<DocBlock> {
+    let text = text.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(DocBlock { item, text })
+}
+
+/// `//!` module docs and `///` item docs
+fn rust_doc_comments(lines: &[&str]) -> Vec<DocBlock> {
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let is_inner = if strip_marker(lines[i], "//!").is_some() {
+            true
+        } else if rust_outer_doc(lines[i]).is_some() {
+            false
+        } else {
+            i += 1;
+            continue;
+        };
+
+        let mut text = Vec::new();
+        while let Some(line) = lines.get(i).and_then(|line| {
+            if is_inner {
+                strip_marker(line, "//!")
+            } else {
+                rust_outer_doc(line)
+            }
+        }) {
+            text.push(line);
+            i += 1;
+        }
+
+        let item = (!is_inner).then(|| next_code_line(lines, i)).flatten();
+        blocks.extend(block(item, text));
+    }
+    blocks
+}
+
+/// Text of a `///` line; `////` starts an ordinary comment
+fn rust_outer_doc(line: &str) -> Option<&str> {
+    strip_marker(line, "///").filter(|_| !line.trim_start().starts_with("////"))
+}
+
+/// Module docstrings and the docstrings of `def` and `class` statements
+fn python_docstrings(lines: &[&str]) -> Vec<DocBlock> {
+    let mut blocks = Vec::new();
+    let mut owner: Option<String> = None;
+    let mut at_module_start = true;
+    let mut i = 0;
+
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+        if trimmed.is_empty() || (at_module_start && trimmed.starts_with('#')) {
+            i += 1;
+            continue;
+        }
+
+        let quote = ["\"\"\"", "'''"]
+            .into_iter()
+            .find(|quote| trimmed.trim_start_matches(['r', 'R']).starts_with(quote));
+        if let (Some(quote), true) = (quote, at_module_start || owner.is_some()) {
+            let body = &trimmed[trimmed.find(quote).unwrap_or(0) + quote.len()..];
+            let mut text = Vec::new();
+            if let Some(end) = body.find(quote) {
+                text.push(&body[..end]);
+                i += 1;
+            } else {
+                text.push(body);
+                i += 1;
+                while i < lines.len() {
+                    if let Some(end) = lines[i].find(quote) {
+                        text.push(&lines[i][..end]);
+                        i += 1;
+                        break;
+                    }
+                    text.push(lines[i]);
+                    i += 1;
+                }
+            }
+            blocks.extend(block(owner.take(), dedent(text)));
+            at_module_start = false;
+            continue;
+        }
+
+        at_module_start = false;
+        owner = None;
+        let statement = trimmed.trim_start_matches("async ");
+        if (statement.starts_with("def ") || statement.starts_with("class "))
+            && trimmed.ends_with(':')
+        {
+            owner = Some(trimmed.trim_end_matches(':').to_string());
+        }
+        i += 1;
+    }
+    blocks
+}
+
+/// Remove the indentation shared by all lines after the first
+fn dedent(lines: Vec<&str>) -> Vec<&str> {
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(n, line)| {
+            if n == 0 || line.len() < indent {
+                line.trim_start()
+            } else {
+                &line[indent..]
+            }
+        })
+        .collect()
+}
+
+/// `//` comment groups directly above `package` and top-level declarations
+fn go_doc_comments(lines: &[&str]) -> Vec<DocBlock> {
+    const DECLARATIONS: [&str; 5] = ["package ", "func ", "type ", "var ", "const "];
+
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if strip_marker(lines[i], "//").is_none() || lines[i].starts_with(char::is_whitespace) {
+            i += 1;
+            continue;
+        }
+
+        let mut text = Vec::new();
+        while let Some(line) = lines.get(i).and_then(|line| strip_marker(line, "//")) {
+            text.push(line);
+            i += 1;
+        }
+
+        let Some(declaration) = lines
+            .get(i)
+            .filter(|line| DECLARATIONS.iter().any(|keyword| line.starts_with(keyword)))
+        else {
+            continue;
+        };
+        // Directives such as `//go:build` are not documentation
+        text.retain(|line| !line.starts_with("go:") && !line.starts_with("+build"));
+
+        let item = (!declaration.starts_with("package "))
+            .then(|| declaration.trim_end_matches('{').trim_end().to_string());
+        blocks.extend(block(item, text));
+    }
+    blocks
+}
+
+/// `/** ... */` blocks with their leading asterisks removed
+fn jsdoc_blocks(lines: &[&str]) -> Vec<DocBlock> {
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+        if !trimmed.starts_with("/**") || trimmed.starts_with("/***") || trimmed.starts_with("/**/")
+        {
+            i += 1;
+            continue;
+        }
+
+        let mut text = Vec::new();
+        let mut line = &trimmed[3..];
+        loop {
+            let (content, closed) = match line.find("*/") {
+                Some(end) => (&line[..end], true),
+                None => (line, false),
+            };
+            let content = content.trim();
+            let content = content.strip_prefix('*').unwrap_or(content);
+            text.push(content.strip_prefix(' ').unwrap_or(content));
+            i += 1;
+            if closed || i >= lines.len() {
+                break;
+            }
+            line = lines[i].trim();
+        }
+
+        let item = next_code_line(lines, i);
+        blocks.extend(block(item, text));
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn texts(blocks: &[DocBlock]) -> Vec<(Option<&str>, &str)> {
+        blocks
+            .iter()
+            .map(|block| (block.item.as_deref(), block.text.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_doc_comments() {
+        let source = "//! Crate docs.\n//!\n//! More.\n\nuse std::fs;\n\n/// Adds one.\n#[inline]\npub fn add_one(x: u32) -> u32 {\n    //// not a doc\n    x + 1\n}\n";
+        let blocks = extract_doc_comments(SourceLanguage::Rust, source);
+        assert_eq!(
+            texts(&blocks),
+            vec![
+                (None, "Crate docs.\n\nMore."),
+                (Some("pub fn add_one(x: u32) -> u32"), "Adds one."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_python_docstrings() {
+        let source = "#!/usr/bin/env python\n\"\"\"Module docs.\"\"\"\n\nclass Parser:\n    \"\"\"Parses input.\n\n    Details here.\n    \"\"\"\n\n    def run(self):\n        x = \"\"\"not a docstring\"\"\"\n";
+        let blocks = extract_doc_comments(SourceLanguage::Python, source);
+        assert_eq!(
+            texts(&blocks),
+            vec![
+                (None, "Module docs."),
+                (Some("class Parser"), "Parses input.\n\nDetails here."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_go_doc_comments() {
+        let source = "//go:build linux\n\n// Package server serves docs.\npackage server\n\n// Serve starts the server.\nfunc Serve() error {\n\t// inner comment\n\treturn nil\n}\n";
+        let blocks = extract_doc_comments(SourceLanguage::Go, source);
+        assert_eq!(
+            texts(&blocks),
+            vec![
+                (None, "Package server serves docs."),
+                (Some("func Serve() error"), "Serve starts the server."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_jsdoc_blocks() {
+        let source = "/**\n * Formats a date.\n * @param {Date} date\n */\nexport function format(date) {\n  /* plain */\n}\n/** One-liner. */\nconst x = 1;\n";
+        let blocks = extract_doc_comments(SourceLanguage::JavaScript, source);
+        assert_eq!(
+            texts(&blocks),
+            vec![
+                (
+                    Some("export function format(date)"),
+                    "Formats a date.\n@param {Date} date"
+                ),
+                (Some("const x = 1;"), "One-liner."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_and_save() {
+        let repo = TempDir::new().unwrap();
+        fs::create_dir_all(repo.path().join("src")).unwrap();
+        fs::create_dir_all(repo.path().join("node_modules/dep")).unwrap();
+        fs::write(repo.path().join("src/lib.rs"), "//! Library.\n").unwrap();
+        fs::write(repo.path().join("src/plain.rs"), "fn main() {}\n").unwrap();
+        fs::write(
+            repo.path().join("node_modules/dep/index.js"),
+            "/** Dep. */\n",
+        )
+        .unwrap();
+
+        let docs = SourceDocs::collect(repo.path(), &FilterConfig::default());
+        assert_eq!(docs.files.len(), 1);
+        assert_eq!(docs.files[0].path, "src/lib.rs");
+
+        let output = TempDir::new().unwrap();
+        docs.save(output.path()).unwrap();
+        let written =
+            fs::read_to_string(output.path().join(SOURCE_DOCS_DIR).join("src/lib.rs.md")).unwrap();
+        assert!(written.starts_with("# `src/lib.rs`"));
+        assert!(written.contains("Library."));
+    }
+}
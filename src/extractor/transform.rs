@@ -0,0 +1,414 @@
+use crate::config::{SourceBanner, TransformConfig};
+use crate::scanner::front_matter::{self, yaml_string};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Image hosts and paths that mark an image as a status badge
+const BADGE_MARKERS: &[&str] = &[
+    "badge",
+    "shields.io",
+    "travis-ci.",
+    "circleci.com",
+    "codecov.io",
+    "coveralls.io",
+];
+
+fn linked_image_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"\[\s*!\[[^\]]*\]\(\s*<?([^)\s>]*)>?[^)]*\)\s*\]\([^)]*\)")
+            .expect("valid linked image regex")
+    })
+}
+
+fn image_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"!\[[^\]]*\]\(\s*<?([^)\s>]*)>?[^)]*\)").expect("valid image regex")
+    })
+}
+
+fn html_image_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"(?i)<img\b[^>]*?\bsrc\s*=\s*["']?([^"'\s>]*)[^>]*>"#)
+            .expect("valid HTML image regex")
+    })
+}
+
+fn atx_heading_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^(\s{0,3})(#{1,6})(\s+.*|\s*)$").expect("valid ATX heading regex")
+    })
+}
+
+/// Normalize extracted Markdown as configured in `[transform]`. Front
+/// matter is kept as it is, and fenced code blocks are only touched by
+/// line-ending normalization.
+pub fn apply(content: &str, config: &TransformConfig) -> String {
+    let normalized;
+    let content = if config.normalize_line_endings {
+        normalized = content.replace("\r\n", "\n").replace('\r', "\n");
+        normalized.as_str()
+    } else {
+        content
+    };
+
+    let body = front_matter::strip(content);
+    let (front_matter, body) = content.split_at(content.len() - body.len());
+
+    let mut out = String::with_capacity(content.len());
+    out.push_str(front_matter);
+    out.push_str(&transform_body(body, config));
+    out
+}
+
+/// Mark `content`, extracted from the file at repository path `path`, as a
+/// copy of the file at `url`, taken at `commit` when it is known: with a
+/// note above the body, or with keys added to the front matter, which is
+/// created when there is none
+pub fn add_source_banner(
+    content: &str,
+    style: SourceBanner,
+    path: &str,
+    url: &str,
+    commit: Option<&str>,
+) -> String {
+    let body = front_matter::strip(content);
+    let (header, body) = content.split_at(content.len() - body.len());
+    let header = header.trim_start_matches('\u{feff}');
+
+    match style {
+        SourceBanner::Banner => {
+            let revision = commit
+                .map(|commit| format!(" at commit `{}`", &commit[..commit.len().min(12)]))
+                .unwrap_or_default();
+            format!(
+                "{}> **Note:** This is an extracted copy. The canonical source is [`{}`]({}){}.\n\n{}",
+                header,
+                path,
+                url.replace(' ', "%20"),
+                revision,
+                body
+            )
+        }
+        SourceBanner::FrontMatter => {
+            let separator = if header.starts_with("+++") { " =" } else { ":" };
+            let mut keys = format!("source_url{} {}\n", separator, yaml_string(url));
+            if let Some(commit) = commit {
+                keys.push_str(&format!(
+                    "source_commit{} {}\n",
+                    separator,
+                    yaml_string(commit)
+                ));
+            }
+            if header.is_empty() {
+                return format!("---\n{}---\n{}", keys, body);
+            }
+            // Keys go before the closing delimiter
+            let closing = header
+                .trim_end_matches(['\n', '\r'])
+                .rfind('\n')
+                .map_or(0, |newline| newline + 1);
+            format!(
+                "{}{}{}{}",
+                &header[..closing],
+                keys,
+                &header[closing..],
+                body
+            )
+        }
+    }
+}
+
+fn transform_body(body: &str, config: &TransformConfig) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_fence = false;
+    let mut in_comment = false;
+    // Original levels of the enclosing headings, for renumbering
+    let mut heading_stack: Vec<usize> = Vec::new();
+    // Whether the last line pushed could be the text of a setext heading
+    let mut paragraph_line = false;
+
+    for raw in body.split_inclusive('\n') {
+        let (line, ending) = split_ending(raw);
+        let trimmed = line.trim();
+
+        if !in_comment && (trimmed.starts_with("```") || trimmed.starts_with("~~~")) {
+            in_fence = !in_fence;
+            paragraph_line = false;
+            lines.push(raw.to_string());
+            continue;
+        }
+        if in_fence {
+            lines.push(raw.to_string());
+            continue;
+        }
+
+        let mut text = line.to_string();
+        if config.strip_html_comments {
+            text = strip_comments(&text, &mut in_comment);
+        }
+        if config.strip_badges || config.strip_images {
+            text = strip_images(&text, config.strip_images);
+        }
+        if config.expand_tabs {
+            text = expand_tabs(&text, config.tab_width);
+        }
+        // Lines that held only removed content go away with them
+        if text.trim().is_empty() && !line.trim().is_empty() {
+            continue;
+        }
+
+        if config.normalize_headings {
+            if let Some(caps) = atx_heading_regex().captures(&text) {
+                let level = renumber(&mut heading_stack, caps[2].len());
+                text = format!("{}{}{}", &caps[1], "#".repeat(level), &caps[3]);
+                paragraph_line = false;
+                lines.push(format!("{}{}", text, ending));
+                continue;
+            }
+
+            let underline = !trimmed.is_empty()
+                && (trimmed.chars().all(|c| c == '=') || trimmed.chars().all(|c| c == '-'));
+            if paragraph_line && underline {
+                // Setext headings become ATX headings, which can go deeper than level 2
+                let previous = lines.pop().unwrap_or_default();
+                let (heading, heading_ending) = split_ending(&previous);
+                let level = renumber(
+                    &mut heading_stack,
+                    if trimmed.starts_with('=') { 1 } else { 2 },
+                );
+                lines.push(format!(
+                    "{} {}{}",
+                    "#".repeat(level),
+                    heading.trim(),
+                    heading_ending
+                ));
+                paragraph_line = false;
+                continue;
+            }
+        }
+
+        paragraph_line = !text.trim().is_empty() && !text.starts_with([' ', '\t']);
+        lines.push(format!("{}{}", text, ending));
+    }
+
+    lines.concat()
+}
+
+fn split_ending(line: &str) -> (&str, &str) {
+    let content = line.trim_end_matches(['\n', '\r']);
+    line.split_at(content.len())
+}
+
+/// Level of a heading at original `level`: one below the enclosing heading,
+/// so documents start at level 1 and never skip a level
+fn renumber(stack: &mut Vec<usize>, level: usize) -> usize {
+    while stack.last().is_some_and(|&enclosing| enclosing >= level) {
+        stack.pop();
+    }
+    stack.push(level);
+    stack.len().min(6)
+}
+
+/// Remove `<!-- ... -->` comments from a line; `in_comment` carries a
+/// comment that is still open over to the next line
+fn strip_comments(line: &str, in_comment: &mut bool) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    loop {
+        if *in_comment {
+            match rest.find("-->") {
+                Some(end) => {
+                    rest = &rest[end + 3..];
+                    *in_comment = false;
+                }
+                None => return out,
+            }
+        } else {
+            match rest.find("<!--") {
+                Some(start) => {
+                    out.push_str(&rest[..start]);
+                    rest = &rest[start + 4..];
+                    *in_comment = true;
+                }
+                None => {
+                    out.push_str(rest);
+                    return out;
+                }
+            }
+        }
+    }
+}
+
+/// Remove badge images, linked ones included, or every image with `all`
+fn strip_images(line: &str, all: bool) -> String {
+    if !line.contains("![") && !line.to_lowercase().contains("<img") {
+        return line.to_string();
+    }
+    let strip = |url: &str| all || is_badge_url(url);
+    let mut text = line.to_string();
+    for regex in [linked_image_regex(), image_regex(), html_image_regex()] {
+        text = regex
+            .replace_all(&text, |caps: &regex::Captures| {
+                if strip(&caps[1]) {
+                    String::new()
+                } else {
+                    caps[0].to_string()
+                }
+            })
+            .into_owned();
+    }
+    text
+}
+
+fn is_badge_url(url: &str) -> bool {
+    let url = url.to_lowercase();
+    BADGE_MARKERS.iter().any(|marker| url.contains(marker))
+}
+
+/// Replace tabs with spaces up to the next multiple of `width`
+fn expand_tabs(line: &str, width: usize) -> String {
+    if !line.contains('\t') {
+        return line.to_string();
+    }
+    let mut out = String::with_capacity(line.len());
+    let mut column = 0;
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = width - column % width;
+            out.push_str(&" ".repeat(spaces));
+            column += spaces;
+        } else {
+            out.push(c);
+            column += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> TransformConfig {
+        TransformConfig {
+            normalize_headings: true,
+            normalize_line_endings: true,
+            expand_tabs: true,
+            tab_width: 4,
+            strip_badges: true,
+            strip_images: false,
+            strip_html_comments: true,
+        }
+    }
+
+    #[test]
+    fn test_source_banner() {
+        let url = "https://github.com/owner/repo/blob/0123456789abcdef/docs/guide.md";
+        let commit = Some("0123456789abcdef");
+        assert_eq!(
+            add_source_banner(
+                "# Guide\n",
+                SourceBanner::Banner,
+                "docs/guide.md",
+                url,
+                commit
+            ),
+            format!(
+                "> **Note:** This is an extracted copy. The canonical source is \
+                 [`docs/guide.md`]({}) at commit `0123456789ab`.\n\n# Guide\n",
+                url
+            )
+        );
+        assert_eq!(
+            add_source_banner(
+                "---\ntitle: Guide\n---\n# Guide\n",
+                SourceBanner::Banner,
+                "docs/guide.md",
+                url,
+                None
+            ),
+            format!(
+                "---\ntitle: Guide\n---\n> **Note:** This is an extracted copy. The canonical \
+                 source is [`docs/guide.md`]({}).\n\n# Guide\n",
+                url
+            )
+        );
+
+        let front_matter = |content| {
+            add_source_banner(
+                content,
+                SourceBanner::FrontMatter,
+                "docs/guide.md",
+                url,
+                commit,
+            )
+        };
+        let keys = format!(
+            "source_url: \"{}\"\nsource_commit: \"0123456789abcdef\"\n",
+            url
+        );
+        assert_eq!(
+            front_matter("# Guide\n"),
+            format!("---\n{}---\n# Guide\n", keys)
+        );
+        assert_eq!(
+            front_matter("---\ntitle: Guide\n---\n# Guide\n"),
+            format!("---\ntitle: Guide\n{}---\n# Guide\n", keys)
+        );
+        assert_eq!(
+            front_matter("+++\ntitle = \"Guide\"\n+++\n"),
+            format!(
+                "+++\ntitle = \"Guide\"\nsource_url = \"{}\"\nsource_commit = \"0123456789abcdef\"\n+++\n",
+                url
+            )
+        );
+    }
+
+    #[test]
+    fn test_normalize_headings() {
+        let content =
+            "### Project\n\n##### Install\n\nUsage\n-----\n\n```\n# comment\n```\n#### Deep ###\n";
+        assert_eq!(
+            apply(content, &config()),
+            "# Project\n\n## Install\n\n# Usage\n\n```\n# comment\n```\n## Deep ###\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_badges_and_comments() {
+        let content = "---\ntitle: <!-- kept -->\n---\r\n\
+                       # Project\r\n\
+                       [![CI](https://github.com/o/r/actions/workflows/ci.yml/badge.svg)](https://github.com/o/r/actions) ![Crates](https://img.shields.io/crates/v/r.svg)\r\n\
+                       ![Diagram](docs/diagram.png)<!-- TODO: update -->\r\n\
+                       <!--\nhidden\n-->\n\
+                       \tindented\n";
+        assert_eq!(
+            apply(content, &config()),
+            "---\ntitle: <!-- kept -->\n---\n# Project\n![Diagram](docs/diagram.png)\n    indented\n"
+        );
+
+        let strip_all = TransformConfig {
+            strip_images: true,
+            ..TransformConfig::default()
+        };
+        assert_eq!(
+            apply(
+                "See ![Diagram](docs/diagram.png) <img src=\"logo.png\"> here\n",
+                &strip_all
+            ),
+            "See   here\n"
+        );
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        assert_eq!(expand_tabs("a\tb", 4), "a   b");
+        assert_eq!(expand_tabs("\t\tx", 2), "    x");
+        let content = "```make\nall:\n\tcargo build\n```\n";
+        assert_eq!(apply(content, &config()), content);
+    }
+}
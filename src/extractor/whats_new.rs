@@ -0,0 +1,256 @@
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::manifest::{ChangeSummary, Manifest};
+use crate::extractor::markdown::{self, Heading};
+use crate::scanner::front_matter;
+use crate::scanner::DocumentFile;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// File of the output the digest is written to
+pub const WHATS_NEW_FILE: &str = "WHATS_NEW.md";
+
+/// An added or changed document
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedDocument {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Front matter title or first level-1 heading
+    pub title: Option<String>,
+    /// Sections that are new in a changed document
+    pub added_sections: Vec<Heading>,
+    /// Sections that are no longer in a changed document
+    pub removed_sections: Vec<Heading>,
+}
+
+/// What changed since the previous incremental extraction (`--whats-new`),
+/// written as `WHATS_NEW.md` for release announcements. Sections are
+/// compared by heading, so a renamed section shows up as one removed and
+/// one added.
+#[derive(Debug, Clone, Default)]
+pub struct WhatsNew {
+    pub added: Vec<ChangedDocument>,
+    pub changed: Vec<ChangedDocument>,
+    pub removed: Vec<String>,
+}
+
+impl WhatsNew {
+    /// Build the digest from the changes of an extraction whose files, as
+    /// listed in `manifest`, live in `output_dir`
+    pub fn build(
+        changes: &ChangeSummary,
+        documents: &[DocumentFile],
+        manifest: &Manifest,
+        output_dir: &Path,
+    ) -> Self {
+        let front_matter_titles: HashMap<String, &str> = documents
+            .iter()
+            .filter_map(|doc| {
+                let title = doc.front_matter.title.as_deref()?;
+                Some((
+                    doc.relative_path.to_string_lossy().replace('\\', "/"),
+                    title,
+                ))
+            })
+            .collect();
+        let entries = manifest.by_path();
+
+        // Headings of the extracted Markdown file, and the document's title:
+        // the front matter title, else the first level-1 heading
+        let document = |path: &String| {
+            let headings = entries
+                .get(path.as_str())
+                .filter(|entry| markdown::is_markdown_path(&entry.extracted))
+                .and_then(|entry| fs::read_to_string(output_dir.join(&entry.extracted)).ok())
+                .map(|content| markdown::extract_headings(front_matter::strip(&content)));
+            let title = front_matter_titles
+                .get(path)
+                .map(|title| title.to_string())
+                .or_else(|| {
+                    headings.as_ref().and_then(|headings| {
+                        headings
+                            .iter()
+                            .find(|heading| heading.level == 1)
+                            .map(|heading| heading.text.clone())
+                    })
+                });
+            let document = ChangedDocument {
+                path: path.clone(),
+                title,
+                added_sections: Vec::new(),
+                removed_sections: Vec::new(),
+            };
+            (document, headings)
+        };
+
+        let changed = changes
+            .modified
+            .iter()
+            .map(|path| {
+                let (mut changed, current) = document(path);
+                if let (Some(previous), Some(current)) =
+                    (changes.previous_headings.get(path), current)
+                {
+                    changed.added_sections = difference(&current, previous);
+                    changed.removed_sections = difference(previous, &current);
+                }
+                changed
+            })
+            .collect();
+
+        Self {
+            added: changes.added.iter().map(|path| document(path).0).collect(),
+            changed,
+            removed: changes.removed.clone(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# What's New\n\n");
+        if self.is_empty() {
+            out.push_str("No documentation changes since the previous extraction.\n");
+            return out;
+        }
+
+        out.push_str(&format!(
+            "{} added, {} changed, {} removed since the previous extraction.\n",
+            self.added.len(),
+            self.changed.len(),
+            self.removed.len()
+        ));
+
+        if !self.added.is_empty() {
+            out.push_str("\n## Added\n\n");
+            for doc in &self.added {
+                out.push_str(&document_line(doc));
+            }
+        }
+
+        if !self.changed.is_empty() {
+            out.push_str("\n## Changed\n\n");
+            for doc in &self.changed {
+                out.push_str(&document_line(doc));
+                for heading in &doc.added_sections {
+                    out.push_str(&format!("  - New section: {}\n", heading.text));
+                }
+                for heading in &doc.removed_sections {
+                    out.push_str(&format!("  - Removed section: {}\n", heading.text));
+                }
+            }
+        }
+
+        if !self.removed.is_empty() {
+            out.push_str("\n## Removed\n\n");
+            for path in &self.removed {
+                out.push_str(&format!("- `{}`\n", path));
+            }
+        }
+
+        out
+    }
+
+    /// Write `WHATS_NEW.md` into `output_dir`
+    pub fn save(&self, output_dir: &Path) -> Result<()> {
+        fs::write(output_dir.join(WHATS_NEW_FILE), self.to_markdown()).map_err(RepoDocsError::Io)
+    }
+}
+
+fn document_line(doc: &ChangedDocument) -> String {
+    match &doc.title {
+        Some(title) => format!("- **{}** (`{}`)\n", title, doc.path),
+        None => format!("- `{}`\n", doc.path),
+    }
+}
+
+/// Headings of `headings` that `other` does not have, counting repeated
+/// headings so a second "Example" section is still noticed
+fn difference(headings: &[Heading], other: &[Heading]) -> Vec<Heading> {
+    let mut remaining: HashMap<&Heading, usize> = HashMap::new();
+    for heading in other {
+        *remaining.entry(heading).or_default() += 1;
+    }
+    headings
+        .iter()
+        .filter(|heading| match remaining.get_mut(heading) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::write_test_documents;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_whats_new_digest() {
+        let output_dir = TempDir::new().unwrap();
+        let files = [
+            (
+                "docs/guide.md",
+                "# Guide\n\n## Install\n\n## Configuration\n\n## Example\n\n## Example\n",
+            ),
+            ("docs/faq.md", "# FAQ\n"),
+        ];
+
+        let documents = write_test_documents(output_dir.path(), &files);
+        let manifest = Manifest::for_test_documents(&documents);
+
+        let heading = |level, text: &str| Heading {
+            level,
+            text: text.to_string(),
+        };
+        let mut changes = ChangeSummary {
+            added: vec!["docs/faq.md".to_string()],
+            modified: vec!["docs/guide.md".to_string()],
+            removed: vec!["docs/old.md".to_string()],
+            ..ChangeSummary::default()
+        };
+        changes.previous_headings.insert(
+            "docs/guide.md".to_string(),
+            vec![
+                heading(1, "Guide"),
+                heading(2, "Setup"),
+                heading(2, "Configuration"),
+                heading(2, "Example"),
+            ],
+        );
+
+        let digest = WhatsNew::build(&changes, &documents, &manifest, output_dir.path());
+        assert_eq!(
+            digest.changed[0].added_sections,
+            vec![heading(2, "Install"), heading(2, "Example")]
+        );
+        assert_eq!(
+            digest.changed[0].removed_sections,
+            vec![heading(2, "Setup")]
+        );
+
+        digest.save(output_dir.path()).unwrap();
+        let markdown = fs::read_to_string(output_dir.path().join(WHATS_NEW_FILE)).unwrap();
+        assert!(markdown.contains("1 added, 1 changed, 1 removed"));
+        assert!(markdown.contains("## Added\n\n- **FAQ** (`docs/faq.md`)\n"));
+        assert!(markdown.contains(
+            "- **Guide** (`docs/guide.md`)\n  - New section: Install\n  - New section: Example\n  - Removed section: Setup\n"
+        ));
+        assert!(markdown.contains("## Removed\n\n- `docs/old.md`\n"));
+
+        let unchanged = WhatsNew::build(
+            &ChangeSummary::default(),
+            &documents,
+            &manifest,
+            output_dir.path(),
+        );
+        assert!(unchanged.to_markdown().contains("No documentation changes"));
+    }
+}
@@ -1,27 +1,62 @@
+pub mod batch;
 pub mod cli;
 pub mod cloner;
 pub mod config;
+pub mod deadline;
+pub mod environment;
 pub mod error;
 pub mod extractor;
+pub mod politeness;
+pub mod priority;
+pub mod sample_config;
 pub mod scanner;
+pub mod server;
+pub mod status;
 pub mod ui;
+pub mod watchdog;
 
 // Public API re-exports
+pub use batch::{BatchEntry, BatchReport, BatchStatus};
 pub use cli::{Cli, OutputFormat};
-pub use config::{CliOverrides, Config, FilterConfig, GitConfig, OutputConfig};
+pub use config::{
+    CliOverrides, ClonePreset, Config, ConfigSource, DedupeMode, EffectiveConfig, ExportFormat,
+    ExtractionPreset, FetchMode, FilterConfig, GitConfig, HtmlConfig, HtmlTheme, NotebookMode,
+    OutputConfig, PolitenessConfig, ReadmePromotion, RenderFormat, SortOrder, SourceBanner,
+    TransformConfig, UnicodeNormalization,
+};
+pub use deadline::Deadline;
+pub use environment::EnvironmentInfo;
 pub use error::{RepoDocsError, Result, UserFriendlyError};
+pub use status::{RunStatus, Stage, StatusTracker};
 
 // Core functionality re-exports
-pub use cloner::{CloneProgress, RepositoryInfo, SafeCloner};
+pub use cloner::{
+    CloneCache, CloneProgress, GitProvider, LfsFetcher, RepositoryInfo, SafeCloner, TarballFetcher,
+};
 pub use extractor::{
-    ConfigSnapshot, ExtractionProgress, ExtractionReport, FileOperations, OutputManager,
+    Bundle, ConfigSnapshot, DocumentGraph, ExtractionProgress, ExtractionReport, FileOperations,
+    Glossary, HtmlSite, LlmPack, Manifest, Outline, OutputManager, PdfDocument, SiteExport,
+    SourceDocs, WhatsNew,
 };
-pub use scanner::{DocumentFile, DocumentScanner, FileFilter};
+pub use politeness::HostThrottle;
+pub use scanner::{DocumentFile, DocumentScanner, FileFilter, InventoryEntry, SkipStatistics};
 pub use ui::{GracefulShutdown, OutputFormatter, OutputMode, ProgressManager};
+pub use watchdog::{Stall, Watchdog};
 
+use extractor::output_manager::format_bytes;
+use extractor::pdf::PdfOutcome;
+use extractor::{
+    archive, budget, duplicates, markdown, relevance, renderer, sarif, similarity, transform,
+    whats_new,
+};
+use scanner::SkipReason;
+use server::{Credentials, DocsServer};
+use std::net::{SocketAddr, TcpListener};
 use std::path::Path;
-use std::time::Instant;
-use tokio::task;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::{self, JoinSet};
 
 /// Main library interface for RepoDocs functionality
 pub struct RepoDocs {
@@ -29,6 +64,16 @@ pub struct RepoDocs {
     output_formatter: OutputFormatter,
     progress_manager: ProgressManager,
     shutdown: GracefulShutdown,
+    status: Option<StatusTracker>,
+    deadline: Option<Deadline>,
+    throttle: HostThrottle,
+}
+
+/// Source tree ready to be extracted, with the temporary clone it lives in
+struct PreparedSource {
+    tree: std::path::PathBuf,
+    repo_info: RepositoryInfo,
+    _temp_dir: Option<tempfile::TempDir>,
 }
 
 impl RepoDocs {
@@ -37,12 +82,18 @@ impl RepoDocs {
         let output_formatter = OutputFormatter::new(output_mode, verbose, quiet);
         let progress_manager = ProgressManager::new(!quiet);
         let shutdown = GracefulShutdown::new()?;
+        let status = Self::status_tracker(&config);
+        let deadline = Self::deadline(&config);
+        let throttle = HostThrottle::new(&config.politeness);
 
         Ok(Self {
             config,
             output_formatter,
             progress_manager,
             shutdown,
+            status,
+            deadline,
+            throttle,
         })
     }
 
@@ -51,44 +102,712 @@ impl RepoDocs {
         let output_formatter = OutputFormatter::new(output_mode, verbose, quiet);
         let progress_manager = ProgressManager::new(!quiet);
         let shutdown = GracefulShutdown::new_for_test();
+        let status = Self::status_tracker(&config);
+        let deadline = Self::deadline(&config);
+        let throttle = HostThrottle::new(&config.politeness);
 
         Self {
             config,
             output_formatter,
             progress_manager,
             shutdown,
+            status,
+            deadline,
+            throttle,
+        }
+    }
+
+    fn status_tracker(config: &Config) -> Option<StatusTracker> {
+        config
+            .output
+            .status_file
+            .then(|| StatusTracker::new(&config.output.base_directory))
+    }
+
+    /// The `--max-runtime` limit, counted from when RepoDocs is created
+    fn deadline(config: &Config) -> Option<Deadline> {
+        config
+            .output
+            .max_runtime
+            .map(|seconds| Deadline::after(Duration::from_secs(seconds)))
+    }
+
+    fn check_deadline(&self, stage: Stage) -> Result<()> {
+        match &self.deadline {
+            Some(deadline) => deadline.check(stage),
+            None => Ok(()),
         }
     }
 
+    /// The clone timeout, shortened to what is left of `--max-runtime`
+    fn clone_timeout(&self) -> Duration {
+        let timeout = self.config.git_timeout_duration();
+        self.deadline
+            .map_or(timeout, |deadline| timeout.min(deadline.remaining()))
+    }
+
+    /// Watch `stage` for stalls when `--stall-timeout` is set
+    fn start_watchdog(&self, stage: Stage) -> Option<Watchdog> {
+        let seconds = self.config.output.stall_timeout?;
+        let abort = self.config.output.abort_on_stall;
+        let formatter = self.output_formatter.clone();
+
+        Some(Watchdog::spawn(
+            stage,
+            Duration::from_secs(seconds),
+            abort,
+            move |stall| {
+                let outcome = if abort {
+                    "stopping it"
+                } else {
+                    "still waiting"
+                };
+                formatter.warning(&format!("{}; {}", stall, outcome));
+            },
+        ))
+    }
+
+    /// Update `status.json` when it is enabled. The status file is
+    /// informational, so failing to write it does not fail the run.
+    fn update_status(&self, update: impl FnOnce(&StatusTracker) -> Result<()>) {
+        if let Some(status) = &self.status {
+            if let Err(e) = update(status) {
+                self.output_formatter
+                    .debug(&format!("Failed to update status file: {}", e));
+            }
+        }
+    }
+
+    fn report_stage(&self, source: &str, stage: Stage) {
+        self.update_status(|status| status.set_stage(source, stage));
+    }
+
     /// Create RepoDocs instance from CLI arguments
     pub fn from_cli(cli_args: &Cli) -> Result<Self> {
         let config = cli_args.load_config()?;
         let output_mode = match cli_args.output_format {
             crate::cli::OutputFormat::Human => OutputMode::Human,
             crate::cli::OutputFormat::Json => OutputMode::Json,
-            crate::cli::OutputFormat::Plain => OutputMode::Plain,
+            crate::cli::OutputFormat::Plain | crate::cli::OutputFormat::Csv => OutputMode::Plain,
         };
 
-        Self::new(config, output_mode, cli_args.verbose, cli_args.quiet)
+        // Progress messages would mix with the listing on stdout
+        let quiet = cli_args.quiet
+            || cli_args.list
+            || cli_args.cat.is_some()
+            || (cli_args.get.is_some() && cli_args.get_output.is_none());
+        Self::new(config, output_mode, cli_args.verbose, quiet)
     }
 
     /// Extract documentation from a repository URL
     pub async fn extract_documentation(&self, repository_url: &str) -> Result<ExtractionReport> {
-        let _start_time = Instant::now();
+        let prepared = self.prepare_repository(repository_url).await?;
+        self.extract_prepared(repository_url, prepared)
+    }
 
+    /// Extract documentation from an existing local checkout or directory,
+    /// skipping the clone step
+    pub async fn extract_from_local(&self, path: &Path) -> Result<ExtractionReport> {
+        let prepared = self.prepare_local(path)?;
+        self.extract_prepared(&path.display().to_string(), prepared)
+    }
+
+    /// Step 1 for a repository URL: clone or download it
+    async fn prepare_repository(&self, repository_url: &str) -> Result<PreparedSource> {
         // Validate the operation can proceed
         self.shutdown.check_shutdown()?;
+        validate_repository_url_with_hosts(repository_url, &self.config.git.allowed_hosts)?;
 
         self.output_formatter
             .start_operation("Starting documentation extraction");
 
         // Step 1: Clone repository
-        let (_repo, temp_dir, repo_info) = self.clone_repository(repository_url).await?;
+        self.report_stage(repository_url, Stage::Cloning);
+        self.check_deadline(Stage::Cloning)?;
+        let fetched = self.fetch_repository(repository_url).await;
+        // A clone cut short by the deadline fails with its own error
+        let (temp_dir, repo_info) = match (fetched, &self.deadline) {
+            (Err(_), Some(deadline)) if deadline.is_expired() => {
+                return Err(deadline.timeout(Stage::Cloning))
+            }
+            (fetched, _) => fetched?,
+        };
+        self.shutdown.check_shutdown()?;
+
+        Ok(PreparedSource {
+            tree: temp_dir.path().to_path_buf(),
+            repo_info,
+            _temp_dir: Some(temp_dir),
+        })
+    }
+
+    /// Step 1 for a local directory: check it and describe it
+    fn prepare_local(&self, path: &Path) -> Result<PreparedSource> {
+        self.shutdown.check_shutdown()?;
+
+        if !path.is_dir() {
+            return Err(RepoDocsError::InvalidPath {
+                path: path.display().to_string(),
+            });
+        }
+
+        self.output_formatter
+            .start_operation("Starting documentation extraction from local directory");
+
+        let repo_info = RepositoryInfo::from_local(path, self.config.git.count_commits)?;
+        self.output_formatter.debug(&repo_info.display_summary());
+
+        Ok(PreparedSource {
+            tree: path.to_path_buf(),
+            repo_info,
+            _temp_dir: None,
+        })
+    }
+
+    /// List the documentation files a run would extract from `source`, a
+    /// repository URL or local directory, without writing any output
+    pub async fn list_documents(
+        &self,
+        source: &str,
+        force_local: bool,
+    ) -> Result<Vec<InventoryEntry>> {
         self.shutdown.check_shutdown()?;
 
+        let documents = if force_local || cli::is_local_path(source) {
+            let path = Path::new(source);
+            if !path.is_dir() {
+                return Err(RepoDocsError::InvalidPath {
+                    path: path.display().to_string(),
+                });
+            }
+            self.scan_documentation(path)?.0
+        } else {
+            validate_repository_url_with_hosts(source, &self.config.git.allowed_hosts)?;
+            let (temp_dir, _) = self.fetch_repository(source).await?;
+            self.shutdown.check_shutdown()?;
+            self.scan_documentation(temp_dir.path())?.0
+        };
+
+        Ok(scanner::inventory::inventory(&documents))
+    }
+
+    /// Read the file at `path`, relative to the root of `source`, a
+    /// repository URL or local directory, without writing any output
+    pub async fn read_document(
+        &self,
+        source: &str,
+        path: &str,
+        force_local: bool,
+    ) -> Result<Vec<u8>> {
+        self.shutdown.check_shutdown()?;
+
+        if force_local || cli::is_local_path(source) {
+            return read_file_in(Path::new(source), path, source);
+        }
+
+        validate_repository_url_with_hosts(source, &self.config.git.allowed_hosts)?;
+        let (temp_dir, _) = self.fetch_repository(source).await?;
+        self.shutdown.check_shutdown()?;
+        read_file_in(temp_dir.path(), path, source)
+    }
+
+    /// Fetch the file at `path`, relative to the root of `source`, with a
+    /// single HTTPS request where the host serves raw files, and from a
+    /// clone (the clone cache when it is enabled) otherwise. Markdown files
+    /// are marked as copies of their source as `output.source_banner` asks.
+    pub async fn get_document(
+        &self,
+        source: &str,
+        path: &str,
+        force_local: bool,
+    ) -> Result<Vec<u8>> {
+        self.shutdown.check_shutdown()?;
+
+        let (content, repo_info) = if force_local || cli::is_local_path(source) {
+            let root = Path::new(source);
+            let content = read_file_in(root, path, source)?;
+            (content, RepositoryInfo::from_local(root, false)?)
+        } else {
+            validate_repository_url_with_hosts(source, &self.config.git.allowed_hosts)?;
+            check_relative_path(path)?;
+            match self.download_file(source, path).await {
+                Ok(content) => (
+                    content,
+                    RepositoryInfo::from_archive(source, self.config.git.branch.as_deref())?,
+                ),
+                Err(RepoDocsError::Cancelled) => return Err(RepoDocsError::Cancelled),
+                Err(e) => {
+                    self.output_formatter.info(&format!(
+                        "Could not download {} directly ({}); cloning instead",
+                        path, e
+                    ));
+                    let (temp_dir, repo_info) = self.fetch_repository(source).await?;
+                    self.shutdown.check_shutdown()?;
+                    (read_file_in(temp_dir.path(), path, source)?, repo_info)
+                }
+            }
+        };
+
+        Ok(self.mark_source(content, path, &repo_info))
+    }
+
+    /// Extract several repositories one after another, each into its own
+    /// output directory, and write a combined `batch_report.json` to the base
+    /// output directory. Failures are recorded and do not stop the batch;
+    /// cancellation does.
+    pub async fn extract_batch(
+        &self,
+        sources: &[String],
+        force_local: bool,
+    ) -> Result<BatchReport> {
+        let start_time = Instant::now();
+        let mut batch = BatchReport::new();
+        self.update_status(|status| status.start(sources.len()));
+
+        for (index, source) in sources.iter().enumerate() {
+            self.shutdown.check_shutdown()?;
+            self.output_formatter.print_header(&format!(
+                "[{}/{}] {}",
+                index + 1,
+                sources.len(),
+                source
+            ));
+
+            let repo_start = Instant::now();
+            let result = batch::catch_panic(self.extract_source(source, force_local)).await;
+            if !matches!(result, Ok(Err(RepoDocsError::Cancelled))) {
+                self.update_status(|status| status.complete(source, !matches!(result, Ok(Ok(_)))));
+            }
+
+            match result {
+                Ok(Ok(report)) => {
+                    batch.entries.push(BatchEntry::succeeded(
+                        source,
+                        &report,
+                        repo_start.elapsed(),
+                    ));
+                }
+                Ok(Err(RepoDocsError::Cancelled)) => return Err(RepoDocsError::Cancelled),
+                Ok(Err(e)) => {
+                    self.handle_error(&e);
+                    batch
+                        .entries
+                        .push(BatchEntry::failed(source, &e, repo_start.elapsed()));
+                }
+                Err(message) => {
+                    self.output_formatter
+                        .error(&format!("Extraction of {} panicked: {}", source, message));
+                    batch.entries.push(BatchEntry::panicked(
+                        source,
+                        &message,
+                        repo_start.elapsed(),
+                    ));
+                }
+            }
+        }
+
+        batch.duration = start_time.elapsed();
+        self.update_status(StatusTracker::finish);
+        let path = batch.save(&self.config.output.base_directory)?;
+        self.output_formatter
+            .debug(&format!("Batch report written to {}", path.display()));
+
+        Ok(batch)
+    }
+
+    /// Like [`RepoDocs::extract_batch`], but extracts up to `jobs` repositories
+    /// at once on separate tokio tasks, with one progress bar per in-flight
+    /// repository. Entries keep the order of `sources`.
+    pub async fn extract_batch_concurrent(
+        self: Arc<Self>,
+        sources: Vec<String>,
+        force_local: bool,
+        jobs: usize,
+    ) -> Result<BatchReport> {
+        let start_time = Instant::now();
+        self.update_status(|status| status.start(sources.len()));
+        let semaphore = Arc::new(Semaphore::new(jobs.max(1)));
+        let mut tasks = JoinSet::new();
+
+        for (index, source) in sources.into_iter().enumerate() {
+            let repodocs = Arc::clone(&self);
+            let semaphore = Arc::clone(&semaphore);
+
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await.ok();
+                if let Err(e) = repodocs.shutdown.check_shutdown() {
+                    return (index, Err(e));
+                }
+
+                let repo_progress = repodocs.progress_manager.create_repo_progress(&source);
+                let repo_start = Instant::now();
+                // Cloning and downloading wait on their own blocking tasks;
+                // the extraction itself is blocking work too and must not
+                // hold up the runtime threads the other jobs share
+                let result = match batch::catch_panic(repodocs.prepare_source(&source, force_local))
+                    .await
+                {
+                    Ok(Ok(prepared)) => {
+                        let repodocs = Arc::clone(&repodocs);
+                        let source = source.clone();
+                        task::spawn_blocking(move || repodocs.extract_prepared(&source, prepared))
+                            .await
+                            .map_err(batch::join_error_message)
+                    }
+                    Ok(Err(e)) => Ok(Err(e)),
+                    Err(message) => Err(message),
+                };
+                if !matches!(result, Ok(Err(RepoDocsError::Cancelled))) {
+                    repodocs.update_status(|status| {
+                        status.complete(&source, !matches!(result, Ok(Ok(_))))
+                    });
+                }
+
+                let entry = match result {
+                    Ok(Ok(report)) => {
+                        let entry = BatchEntry::succeeded(&source, &report, repo_start.elapsed());
+                        repo_progress
+                            .finish_with_message(format!("done, {} files", entry.files_extracted));
+                        Ok(entry)
+                    }
+                    Ok(Err(RepoDocsError::Cancelled)) => {
+                        repo_progress.abandon_with_message("cancelled");
+                        Err(RepoDocsError::Cancelled)
+                    }
+                    Ok(Err(e)) => {
+                        repo_progress.abandon_with_message(format!("failed: {}", e));
+                        Ok(BatchEntry::failed(&source, &e, repo_start.elapsed()))
+                    }
+                    Err(message) => {
+                        repo_progress.abandon_with_message(format!("panicked: {}", message));
+                        Ok(BatchEntry::panicked(
+                            &source,
+                            &message,
+                            repo_start.elapsed(),
+                        ))
+                    }
+                };
+                (index, entry)
+            });
+        }
+
+        let mut entries = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            let (index, entry) = joined.map_err(|e| RepoDocsError::Config {
+                message: format!("Extraction task failed: {}", e),
+            })?;
+
+            match entry {
+                Ok(entry) => entries.push((index, entry)),
+                Err(e) => {
+                    tasks.abort_all();
+                    return Err(e);
+                }
+            }
+        }
+
+        entries.sort_by_key(|(index, _)| *index);
+
+        let mut batch = BatchReport::new();
+        batch.entries = entries.into_iter().map(|(_, entry)| entry).collect();
+        batch.duration = start_time.elapsed();
+        self.update_status(StatusTracker::finish);
+        let path = batch.save(&self.config.output.base_directory)?;
+        self.output_formatter
+            .debug(&format!("Batch report written to {}", path.display()));
+
+        Ok(batch)
+    }
+
+    /// Extract `source` every `interval` until shutdown is requested, updating
+    /// the output in place (the configuration should enable incremental
+    /// updates) and announcing the documents each run added, changed or
+    /// removed. A failed run is reported and retried at the next interval.
+    pub async fn watch(&self, source: &str, force_local: bool, interval: Duration) -> Result<()> {
+        self.watch_serving(source, force_local, interval, None)
+            .await
+    }
+
+    /// Like [`RepoDocs::watch`], but also serve the extraction on `host` and
+    /// `port` as [`RepoDocs::serve`] does, from the first successful run on.
+    /// Runs that change documents render the site again and reload the pages
+    /// open in browsers.
+    pub async fn watch_and_serve(
+        &self,
+        source: &str,
+        force_local: bool,
+        interval: Duration,
+        host: &str,
+        port: u16,
+        credentials: Credentials,
+    ) -> Result<()> {
+        let listener = self.listen(host, port, &credentials)?;
+        let address = listener.local_addr().map_err(RepoDocsError::Io)?;
+        let live = LiveSite::Waiting(listener, address, credentials);
+        self.watch_serving(source, force_local, interval, Some(live))
+            .await
+    }
+
+    async fn watch_serving(
+        &self,
+        source: &str,
+        force_local: bool,
+        interval: Duration,
+        mut live: Option<LiveSite>,
+    ) -> Result<()> {
+        let mut run = 0;
+        loop {
+            run += 1;
+            let started = Instant::now();
+            match self.extract_source(source, force_local).await {
+                Ok(report) => {
+                    self.announce_changes(run, &report);
+                    if let Some(site) = live.take() {
+                        live = Some(self.refresh_live_site(site, &report));
+                    }
+                }
+                Err(RepoDocsError::Cancelled) => break,
+                Err(e) => self.output_formatter.error(&format!(
+                    "Watch run {} failed: {}",
+                    run,
+                    e.user_message()
+                )),
+            }
+
+            // Sleep in short steps so that Ctrl+C ends the watch promptly
+            let next_run = started + interval;
+            while Instant::now() < next_run && self.shutdown.is_running() {
+                let remaining = next_run.saturating_duration_since(Instant::now());
+                tokio::time::sleep(remaining.min(WATCH_POLL)).await;
+            }
+            if !self.shutdown.is_running() {
+                break;
+            }
+        }
+
+        match live {
+            Some(LiveSite::Serving(_, running)) => self.finish_serving(running).await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Start serving the extraction of `report` after the first successful
+    /// watch run, or render it again after a run that changed documents. A
+    /// site that cannot be rendered is reported; the previous rendering, if
+    /// any, stays up.
+    fn refresh_live_site(&self, site: LiveSite, report: &ExtractionReport) -> LiveSite {
+        match site {
+            LiveSite::Waiting(listener, address, credentials) => {
+                let server = match DocsServer::render(
+                    &report.output_directory,
+                    &self.config.output.html,
+                    credentials.clone(),
+                ) {
+                    Ok(server) => Arc::new(server.with_live_reload()),
+                    Err(e) => {
+                        self.output_formatter.warning(&format!(
+                            "Cannot serve the extraction: {}",
+                            e.user_message()
+                        ));
+                        return LiveSite::Waiting(listener, address, credentials);
+                    }
+                };
+                self.output_formatter.success(&format!(
+                    "Serving {} pages of {} at http://{}/ with live reload",
+                    server.pages(),
+                    report.output_directory.display(),
+                    address
+                ));
+
+                let shutdown = self.shutdown.clone();
+                let running = {
+                    let server = Arc::clone(&server);
+                    task::spawn_blocking(move || server.run(listener, &shutdown))
+                };
+                LiveSite::Serving(server, running)
+            }
+            LiveSite::Serving(server, running) => {
+                if report
+                    .changes
+                    .as_ref()
+                    .is_some_and(|changes| changes.has_changes())
+                {
+                    match server.reload() {
+                        Ok(pages) => self.output_formatter.info(&format!(
+                            "Rendered {} pages again, reloading {} browser(s)",
+                            pages,
+                            server.live_subscribers()
+                        )),
+                        Err(e) => self.output_formatter.warning(&format!(
+                            "Cannot render the extraction again, still serving the previous pages: {}",
+                            e.user_message()
+                        )),
+                    }
+                }
+                LiveSite::Serving(server, running)
+            }
+        }
+    }
+
+    /// Listen on `host` and `port` for [`RepoDocs::serve`]. Without
+    /// `credentials`, only loopback addresses are accepted.
+    fn listen(&self, host: &str, port: u16, credentials: &Credentials) -> Result<TcpListener> {
+        let listener = TcpListener::bind((host, port)).map_err(|e| RepoDocsError::Config {
+            message: format!("Cannot listen on {} port {}: {}", host, port, e),
+        })?;
+        let address = listener.local_addr().map_err(RepoDocsError::Io)?;
+        if credentials.is_empty() && !address.ip().is_loopback() {
+            return Err(RepoDocsError::Config {
+                message: format!(
+                    "Refusing to serve on {} without authentication; set --serve-token or --serve-user, or listen on a loopback address such as 127.0.0.1",
+                    address.ip()
+                ),
+            });
+        }
+        Ok(listener)
+    }
+
+    /// Wait for a server started on a blocking task to stop
+    async fn finish_serving(&self, running: task::JoinHandle<Result<usize>>) -> Result<()> {
+        let unfinished = running.await.map_err(|e| RepoDocsError::Config {
+            message: format!("Server task failed: {}", e),
+        })??;
+        if unfinished > 0 {
+            self.output_formatter.warning(&format!(
+                "Closed {} request(s) still in progress after {}s",
+                unfinished,
+                server::DRAIN_TIMEOUT.as_secs()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Render the extraction in `output_dir` to HTML and serve it on `host`
+    /// and `port` until shutdown is requested, then let the requests in
+    /// progress finish. Without `credentials`, only loopback addresses are
+    /// served, so internal documentation is not exposed on shared networks.
+    pub async fn serve(
+        &self,
+        output_dir: &Path,
+        host: &str,
+        port: u16,
+        credentials: Credentials,
+    ) -> Result<()> {
+        let listener = self.listen(host, port, &credentials)?;
+        let address = listener.local_addr().map_err(RepoDocsError::Io)?;
+
+        let server = Arc::new(DocsServer::render(
+            output_dir,
+            &self.config.output.html,
+            credentials,
+        )?);
+        self.output_formatter.debug(&format!(
+            "Rendered {} to {}",
+            output_dir.display(),
+            server.site_dir().display()
+        ));
+        self.output_formatter.success(&format!(
+            "Serving {} pages of {} at http://{}/; press Ctrl+C to stop",
+            server.pages(),
+            output_dir.display(),
+            address
+        ));
+
+        let shutdown = self.shutdown.clone();
+        let running = task::spawn_blocking(move || server.run(listener, &shutdown));
+        self.finish_serving(running).await
+    }
+
+    fn announce_changes(&self, run: usize, report: &ExtractionReport) {
+        let time = report.extraction_time.format("%Y-%m-%d %H:%M:%S UTC");
+        let changes = match &report.changes {
+            Some(changes) if run > 1 || changes.has_changes() => changes,
+            _ => {
+                self.output_formatter.success(&format!(
+                    "[{}] Extracted {} documentation files",
+                    time,
+                    report.files.len()
+                ));
+                return;
+            }
+        };
+
+        if !changes.has_changes() {
+            self.output_formatter
+                .info(&format!("[{}] No documentation changes", time));
+            return;
+        }
+
+        self.output_formatter.success(&format!(
+            "[{}] Documentation changed: {} added, {} modified, {} removed",
+            time,
+            changes.added.len(),
+            changes.modified.len(),
+            changes.removed.len()
+        ));
+        for (label, paths) in [
+            ("added", &changes.added),
+            ("modified", &changes.modified),
+            ("removed", &changes.removed),
+        ] {
+            for path in paths {
+                self.output_formatter
+                    .info(&format!("  {}: {}", label, path));
+            }
+        }
+    }
+
+    /// Extract one batch source, either a local directory or a repository URL
+    async fn extract_source(&self, source: &str, force_local: bool) -> Result<ExtractionReport> {
+        let prepared = self.prepare_source(source, force_local).await?;
+        self.extract_prepared(source, prepared)
+    }
+
+    /// Step 1 for one batch source, either a local directory or a repository URL
+    async fn prepare_source(&self, source: &str, force_local: bool) -> Result<PreparedSource> {
+        if force_local || cli::is_local_path(source) {
+            self.prepare_local(Path::new(source))
+        } else {
+            self.prepare_repository(source).await
+        }
+    }
+
+    /// Extract a prepared source. This is blocking work; the temporary
+    /// clone is removed once it is done.
+    fn extract_prepared(&self, source: &str, prepared: PreparedSource) -> Result<ExtractionReport> {
+        self.extract_from_tree(source, &prepared.tree, prepared.repo_info)
+    }
+
+    /// Steps shared by all sources: scan, extract, report and analyze
+    fn extract_from_tree(
+        &self,
+        source: &str,
+        tree: &Path,
+        repo_info: RepositoryInfo,
+    ) -> Result<ExtractionReport> {
         // Step 2: Scan for documentation files
-        let documents = self.scan_documentation(temp_dir.path())?;
+        self.report_stage(source, Stage::Scanning);
+        let (mut documents, mut skipped) = self.scan_documentation(tree)?;
         self.shutdown.check_shutdown()?;
+        relevance::score(&mut documents);
+
+        if let Some(top_n) = self.config.filters.top_n {
+            let left_out = relevance::keep_top(&mut documents, top_n);
+            if !left_out.is_empty() {
+                self.output_formatter.info(&format!(
+                    "Keeping the {} most relevant of {} documentation files",
+                    documents.len(),
+                    documents.len() + left_out.len()
+                ));
+            }
+            for document in &left_out {
+                skipped.record(
+                    document.display_path().replace('\\', "/"),
+                    SkipReason::BelowTopN,
+                );
+            }
+            skipped.paths.sort_by(|a, b| a.path.cmp(&b.path));
+        }
 
         if documents.is_empty() {
             return Err(RepoDocsError::NoDocumentationFound {
@@ -98,30 +817,429 @@ impl RepoDocs {
 
         self.output_formatter
             .info(&format!("Found {} documentation files", documents.len()));
+        if self.config.output.print_budget {
+            self.output_formatter
+                .print_budget(&budget::directory_budgets(&documents));
+        }
 
         // Step 3: Setup output directory
-        let output_manager = self.setup_output_directory(&repo_info)?;
+        let mut output_manager = self.setup_output_directory(&repo_info)?;
+
+        // The output only replaces the output directory once it is complete
+        let result = self
+            .write_output(
+                source,
+                tree,
+                &documents,
+                skipped,
+                &repo_info,
+                &output_manager,
+            )
+            .and_then(|report| {
+                // Only the archive is kept; an earlier extraction stays
+                if self.config.output.archive_only {
+                    output_manager.discard_staging()?;
+                }
+                output_manager.commit()?;
+                Ok(report)
+            });
+        if let Err(e) = &result {
+            self.handle_partial_output(&output_manager, e);
+        }
+        result
+    }
+
+    /// Remove the half-written output of a failed or cancelled run, unless
+    /// `--keep-partial` asks to keep it for inspection
+    fn handle_partial_output(&self, output_manager: &OutputManager, error: &RepoDocsError) {
+        let run = match error {
+            RepoDocsError::Cancelled => "Run cancelled",
+            _ => "Run failed",
+        };
+        let output_dir = output_manager.get_output_directory();
+        if self.config.output.keep_partial {
+            self.output_formatter.warning(&format!(
+                "{}, partial output kept in {}",
+                run,
+                output_dir.display()
+            ));
+            return;
+        }
+
+        match output_manager.rollback() {
+            Ok(Some(removed)) => self
+                .output_formatter
+                .info(&format!("{}, removed partial output {}", run, removed)),
+            Ok(None) => self.output_formatter.warning(&format!(
+                "{}, {} was being updated in place and may be incomplete",
+                run,
+                output_dir.display()
+            )),
+            Err(e) => self.output_formatter.warning(&format!(
+                "Failed to remove partial output {}: {}",
+                output_dir.display(),
+                e
+            )),
+        }
+    }
+
+    /// Steps 4 to 10, everything that writes into the output directory
+    fn write_output(
+        &self,
+        source: &str,
+        tree: &Path,
+        documents: &[DocumentFile],
+        skipped: SkipStatistics,
+        repo_info: &RepositoryInfo,
+        output_manager: &OutputManager,
+    ) -> Result<ExtractionReport> {
         self.shutdown.check_shutdown()?;
+        self.check_deadline(Stage::Extracting)?;
 
         // Step 4: Extract files
-        let extraction_progress =
-            self.extract_files(&documents, output_manager.get_output_directory())?;
+        self.report_stage(source, Stage::Extracting);
+        let deduplicated;
+        let mut skipped_duplicates = Vec::new();
+        let documents = if self.config.output.dedupe == DedupeMode::Skip {
+            (deduplicated, skipped_duplicates) = duplicates::remove(documents.to_vec())?;
+            if !skipped_duplicates.is_empty() {
+                self.output_formatter.info(&format!(
+                    "Skipping {} byte-identical duplicate document(s)",
+                    skipped_duplicates
+                        .iter()
+                        .map(|group| group.duplicates.len())
+                        .sum::<usize>()
+                ));
+            }
+            deduplicated.as_slice()
+        } else {
+            documents
+        };
+        let previous_manifest = if self.config.output.incremental || self.config.output.merge {
+            Manifest::load(&output_manager.get_metadata_dir())?
+        } else {
+            None
+        };
+        let mut extraction_progress = self.extract_files(
+            documents,
+            output_manager.get_output_directory(),
+            previous_manifest,
+            repo_info,
+        )?;
+        extraction_progress.manifest.commit = repo_info.commit.clone();
+        extraction_progress
+            .manifest
+            .save(&output_manager.get_metadata_dir())?;
         self.shutdown.check_shutdown()?;
 
+        if self.config.output.dedupe == DedupeMode::Hardlink {
+            let saved = duplicates::hardlink(
+                &duplicates::find(&extraction_progress.manifest.entries),
+                &extraction_progress.manifest,
+                output_manager.get_output_directory(),
+            )?;
+            if saved > 0 {
+                self.output_formatter.info(&format!(
+                    "Hard-linked duplicate documents, saving {}",
+                    format_bytes(saved)
+                ));
+            }
+        }
+
+        if let Some(changes) = &extraction_progress.changes {
+            self.output_formatter.info(&format!(
+                "Changes since previous extraction: {} added, {} modified, {} removed",
+                changes.added.len(),
+                changes.modified.len(),
+                changes.removed.len()
+            ));
+        }
+
+        if let Some(merge) = &extraction_progress.merge {
+            self.output_formatter.info(&format!(
+                "Merged into existing output: {} written, {} unchanged",
+                merge.written.len(),
+                merge.unchanged
+            ));
+            if !merge.conflicts.is_empty() {
+                self.output_formatter.warning(&format!(
+                    "Kept {} file(s) added or edited by hand instead of overwriting them; see the extraction report",
+                    merge.conflicts.len()
+                ));
+            }
+        }
+
+        if !extraction_progress.normalized_paths.is_empty() {
+            self.output_formatter.warning(&format!(
+                "Unicode normalization changed {} output path(s); see the extraction report",
+                extraction_progress.normalized_paths.len()
+            ));
+        }
+
+        if !extraction_progress.unresolved_links.is_empty() {
+            self.output_formatter.warning(&format!(
+                "Found {} link(s) to files that do not exist; see the extraction report or .repodocs/{}",
+                extraction_progress.unresolved_links.len(),
+                sarif::SARIF_FILE
+            ));
+        }
+
+        if !extraction_progress.oversized_assets.is_empty() {
+            self.output_formatter.warning(&format!(
+                "Skipped {} asset(s) larger than {}; see the extraction report",
+                extraction_progress.oversized_assets.len(),
+                format_bytes(self.config.output.max_asset_size)
+            ));
+        }
+
         // Step 5: Generate reports
+        self.report_stage(source, Stage::Reporting);
+        self.check_deadline(Stage::Reporting)?;
         let config_snapshot = self.create_config_snapshot();
-        let report = output_manager.create_extraction_report(
-            &repo_info,
-            &documents,
+        let mut report = output_manager.build_extraction_report(
+            repo_info,
+            documents,
             &extraction_progress,
             &config_snapshot,
-        )?;
+        );
+
+        report.skipped = skipped;
+        report.budget = budget::directory_budgets(documents);
+        if !skipped_duplicates.is_empty() {
+            report.duplicates.extend(skipped_duplicates);
+            report.duplicates.sort_by(|a, b| a.kept.cmp(&b.kept));
+        }
+        if self.config.output.capture_env {
+            report.environment = Some(EnvironmentInfo::capture(
+                output_manager.get_output_directory(),
+            ));
+        }
+
+        if let Some(threshold) = self.config.output.similarity_threshold {
+            report.near_duplicates = similarity::find_near_duplicates(documents, threshold);
+            if !report.near_duplicates.is_empty() {
+                self.output_formatter.warning(&format!(
+                    "Found {} group(s) of near-duplicate documents",
+                    report.near_duplicates.len()
+                ));
+            }
+        }
+
+        output_manager.save_reports(&report)?;
 
         // Step 6: Create index file if requested
         if self.config.output.create_index {
-            let file_ops = FileOperations::new()
-                .with_preserve_structure(self.config.output.preserve_structure);
-            file_ops.create_index_file(&documents, output_manager.get_output_directory())?;
+            self.file_operations()
+                .create_index_file(documents, output_manager.get_output_directory())?;
+        }
+
+        // Step 7: Export document graph if requested
+        if self.config.output.generate_graph {
+            let graph = DocumentGraph::build(documents);
+            graph.save(&output_manager.get_metadata_dir())?;
+            self.output_formatter.debug(&format!(
+                "Document graph: {} nodes, {} links, {} orphans",
+                graph.nodes.len(),
+                graph.link_count(),
+                graph.orphans.len()
+            ));
+        }
+
+        // Step 8: Export heading outline if requested
+        if self.config.output.generate_outline {
+            let outline = Outline::build(documents);
+            outline.save(&output_manager.get_metadata_dir())?;
+            self.output_formatter.debug(&format!(
+                "Outline: {} headings across {} documents",
+                outline.heading_count(),
+                outline.documents.len()
+            ));
+        }
+
+        // Step 9: Extract glossary if requested
+        if self.config.output.generate_glossary {
+            let glossary = Glossary::build(documents);
+            if glossary.is_empty() {
+                self.output_formatter
+                    .debug("No glossary terms found, skipping GLOSSARY.md");
+            } else {
+                glossary.save(output_manager.get_output_directory())?;
+                self.output_formatter.debug(&format!(
+                    "Glossary: {} definitions, {} frequent terms",
+                    glossary.definitions.len(),
+                    glossary.frequent_terms.len()
+                ));
+            }
+        }
+
+        // Step 10: Extract doc comments from source files if requested
+        if self.config.output.source_docs {
+            let source_docs = SourceDocs::collect(tree, &self.config.filters);
+            source_docs.save(output_manager.get_output_directory())?;
+            self.output_formatter.info(&format!(
+                "Extracted {} doc comments from {} source files",
+                source_docs.block_count(),
+                source_docs.files.len()
+            ));
+        }
+
+        // Step 11: Concatenate all documents into one file if requested
+        if let Some(bundle_path) = &self.config.output.bundle {
+            let bundle_path = output_manager.get_output_directory().join(bundle_path);
+            let bundle = Bundle::build(documents, self.config.output.sort_order);
+            bundle.save(&bundle_path)?;
+            self.output_formatter.info(&format!(
+                "Bundled {} documents into {}",
+                bundle.sections.len(),
+                bundle_path.display()
+            ));
+        }
+
+        // Step 12: Pack the documents for an LLM context window if requested
+        if self.config.output.llm_pack {
+            let pack = LlmPack::build(documents, self.config.output.max_tokens);
+            let files = pack.save(
+                output_manager.get_output_directory(),
+                self.config.output.llm_chunk_tokens,
+            )?;
+            self.output_formatter.info(&format!(
+                "Packed {} documents (about {} tokens) into {} file(s)",
+                pack.documents.len(),
+                pack.total_tokens(),
+                files.len()
+            ));
+            if !pack.omitted.is_empty() {
+                self.output_formatter.warning(&format!(
+                    "Left {} document(s) out of the LLM pack to stay within {} tokens",
+                    pack.omitted.len(),
+                    self.config.output.max_tokens.unwrap_or_default()
+                ));
+            }
+        }
+
+        // Step 13: Render the documents as static pages or a PDF if requested
+        match self.config.output.render {
+            Some(RenderFormat::Html) => {
+                let site = HtmlSite::render(
+                    documents,
+                    &extraction_progress.manifest,
+                    output_manager.get_output_directory(),
+                    &self.config.output.html,
+                )?;
+                self.output_formatter.info(&format!(
+                    "Rendered {} HTML pages into {}/",
+                    site.pages.len(),
+                    renderer::HTML_DIR
+                ));
+                // The reports were saved before rendering and are saved
+                // again with the problems found in the pages
+                if !site.accessibility.is_empty() {
+                    self.output_formatter.warning(&format!(
+                        "Found {} accessibility problem(s) in the rendered pages; see the extraction report or .repodocs/{}",
+                        site.accessibility.len(),
+                        sarif::SARIF_FILE
+                    ));
+                    report.accessibility = site.accessibility;
+                    output_manager.save_reports(&report)?;
+                }
+            }
+            Some(RenderFormat::Pdf) => {
+                let pdf = PdfDocument::build(
+                    documents,
+                    self.config.output.sort_order,
+                    &format!("{} Documentation", repo_info.name),
+                );
+                match pdf.render(
+                    repo_info,
+                    output_manager.get_output_directory(),
+                    &output_manager.get_metadata_dir(),
+                    self.config.output.pdf_converter.as_deref(),
+                )? {
+                    PdfOutcome::Rendered(path) => self.output_formatter.info(&format!(
+                        "Rendered {} documents into {}",
+                        pdf.bundle.sections.len(),
+                        path.display()
+                    )),
+                    PdfOutcome::Fallback { source, reason } => {
+                        self.output_formatter.warning(&format!(
+                            "Could not render the PDF, {}; its Markdown source is in {}",
+                            reason,
+                            source.display()
+                        ))
+                    }
+                }
+            }
+            None => {}
+        }
+
+        // Written even without findings, so code scanning closes the alerts
+        // of fixed problems
+        if self.config.output.rewrites_links()
+            || self.config.output.render == Some(RenderFormat::Html)
+        {
+            sarif::save(
+                &report.accessibility,
+                &report.unresolved_links,
+                &output_manager.get_metadata_dir(),
+            )?;
+        }
+
+        // Step 14: Lay out the documents for a static site generator if requested
+        if let Some(format) = self.config.output.export {
+            let export = SiteExport::build(
+                format,
+                documents,
+                &extraction_progress.manifest,
+                output_manager.get_output_directory(),
+            );
+            let directory = export.save(
+                output_manager.get_output_directory(),
+                &format!("{} Documentation", repo_info.name),
+            )?;
+            self.output_formatter.info(&format!(
+                "Exported {} chapters to {}",
+                export.chapters.len(),
+                directory.display()
+            ));
+        }
+
+        // Step 15: Summarize the changes since the previous extraction if requested
+        if let (true, Some(changes)) = (self.config.output.whats_new, &extraction_progress.changes)
+        {
+            let digest = WhatsNew::build(
+                changes,
+                documents,
+                &extraction_progress.manifest,
+                output_manager.get_output_directory(),
+            );
+            digest.save(output_manager.get_output_directory())?;
+            self.output_formatter.info(&format!(
+                "Summarized {} added, {} changed and {} removed documents in {}",
+                digest.added.len(),
+                digest.changed.len(),
+                digest.removed.len(),
+                whats_new::WHATS_NEW_FILE
+            ));
+        }
+
+        // Step 16: Write the output to an archive if requested
+        if let Some(template) = &self.config.output.archive {
+            let name = output_manager
+                .destination()
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let archive = archive::write_archive(
+                output_manager.get_output_directory(),
+                &name,
+                &archive::archive_path(template, &repo_info.name),
+            )?;
+            self.output_formatter.info(&format!(
+                "Archived {} files ({}) to {}",
+                archive.files,
+                format_bytes(archive.bytes),
+                archive.path.display()
+            ));
         }
 
         // Display summary
@@ -131,23 +1249,113 @@ impl RepoDocs {
         Ok(report)
     }
 
+    /// Clone or download a repository, then fetch its Git LFS objects when
+    /// `git.lfs` is set
+    async fn fetch_repository(&self, url: &str) -> Result<(tempfile::TempDir, RepositoryInfo)> {
+        let (temp_dir, repo_info) = self.fetch_politely(url).await?;
+        if self.config.git.lfs {
+            self.fetch_lfs_objects(url, temp_dir.path()).await;
+        }
+        Ok((temp_dir, repo_info))
+    }
+
+    /// Replace the LFS pointer files of the checkout at `tree` with their
+    /// objects. Failures leave the pointers in place and only warn.
+    async fn fetch_lfs_objects(&self, url: &str, tree: &Path) {
+        let fetcher = LfsFetcher::new(self.config.filters.extensions.clone())
+            .with_max_size(self.config.filters.max_file_size)
+            .with_timeout(self.clone_timeout())
+            .with_allowed_hosts(self.config.git.allowed_hosts.clone());
+        let (url, tree) = (url.to_string(), tree.to_path_buf());
+        let fetched = task::spawn_blocking(move || fetcher.fetch(&url, &tree))
+            .await
+            .map_err(|e| RepoDocsError::Config {
+                message: format!("Git LFS task failed: {}", e),
+            })
+            .and_then(|fetched| fetched);
+
+        match fetched {
+            Ok(summary) => {
+                if summary.fetched > 0 {
+                    self.output_formatter.info(&format!(
+                        "Fetched {} Git LFS object(s) ({} bytes)",
+                        summary.fetched, summary.bytes
+                    ));
+                }
+                if !summary.left_as_pointers.is_empty() {
+                    self.output_formatter.warning(&format!(
+                        "Left {} Git LFS pointer(s) larger than the file size limit: {}",
+                        summary.left_as_pointers.len(),
+                        summary.left_as_pointers.join(", ")
+                    ));
+                }
+            }
+            Err(e) => self
+                .output_formatter
+                .warning(&format!("Could not fetch Git LFS objects: {}", e)),
+        }
+    }
+
+    /// Clone or download a repository within the `[politeness]` limits of
+    /// its host. A rate limit response slows down all fetches from the host;
+    /// clones are then retried, downloads already retry on their own.
+    async fn fetch_politely(&self, url: &str) -> Result<(tempfile::TempDir, RepositoryInfo)> {
+        let host = politeness::host_of(url);
+        let mut attempt = 0;
+        loop {
+            let permit = self.throttle.acquire(&host, &self.shutdown).await?;
+            let fetched = match self.config.git.fetch_mode {
+                FetchMode::Git => self.clone_repository(url).await,
+                FetchMode::Tarball => self.download_tarball(url).await,
+            };
+            drop(permit);
+
+            let Err(RepoDocsError::RateLimited { retry_after, .. }) = &fetched else {
+                return fetched;
+            };
+            let delay = self
+                .throttle
+                .slow_down(&host, Duration::from_secs(*retry_after));
+            let retry = self.config.git.fetch_mode == FetchMode::Git
+                && attempt < self.config.git.max_retries;
+            if !retry {
+                return fetched;
+            }
+
+            attempt += 1;
+            self.output_formatter.warning(&format!(
+                "Rate limited by {}, waiting {}s between clones (attempt {}/{})",
+                host,
+                delay.as_secs(),
+                attempt,
+                self.config.git.max_retries
+            ));
+        }
+    }
+
     /// Clone repository with progress indication
-    async fn clone_repository(
-        &self,
-        url: &str,
-    ) -> Result<(git2::Repository, tempfile::TempDir, RepositoryInfo)> {
+    async fn clone_repository(&self, url: &str) -> Result<(tempfile::TempDir, RepositoryInfo)> {
         self.output_formatter.start_operation("Cloning repository");
 
         let clone_progress = self.progress_manager.create_clone_progress();
+        let watchdog = self.start_watchdog(Stage::Cloning);
         let progress_callback = {
             let pb = clone_progress.clone();
+            let watchdog = watchdog.clone();
             move |progress: CloneProgress| {
                 ui::progress::update_clone_progress(&pb, &progress);
+                if let Some(watchdog) = &watchdog {
+                    watchdog.progress(format!(
+                        "{}/{} objects",
+                        progress.received_objects, progress.total_objects
+                    ));
+                }
             }
         };
 
         let cloner = SafeCloner::new()
-            .with_timeout(self.config.git_timeout_duration())
+            .with_timeout(self.clone_timeout())
+            .with_allowed_hosts(self.config.git.allowed_hosts.clone())
             .with_progress(progress_callback);
 
         let cloner = if let Some(ref branch) = self.config.git.branch {
@@ -156,12 +1364,33 @@ impl RepoDocs {
             cloner
         };
 
+        let cloner = if let Some(depth) = self.config.git.clone_depth {
+            cloner.with_depth(depth)
+        } else {
+            cloner
+        };
+        let cloner = cloner
+            .with_submodules(self.config.git.submodules)
+            .with_preflight(self.config.git.preflight)
+            .with_sparse_paths(self.config.git.sparse_paths.clone());
+
+        let cloner = match self.clone_cache() {
+            Some(cache) => {
+                self.output_formatter
+                    .debug(&format!("Using clone cache at {}", cache.root().display()));
+                cloner.with_cache(cache)
+            }
+            None => cloner,
+        };
+
+        let cloner = match &watchdog {
+            Some(watchdog) => cloner.with_running(watchdog.running()),
+            None => cloner,
+        };
+
         let url_clone = url.to_string();
-        let (repo, temp_dir) = task::spawn_blocking(move || cloner.clone_to_temp(&url_clone))
-            .await
-            .map_err(|e| RepoDocsError::Config {
-                message: format!("Clone task failed: {}", e),
-            })??;
+        let clone = task::spawn_blocking(move || cloner.clone_to_temp(&url_clone));
+        let (repo, temp_dir) = join_watched(clone, watchdog.as_ref(), "Clone").await?;
 
         ui::progress::finish_progress_with_summary(
             &clone_progress,
@@ -169,18 +1398,135 @@ impl RepoDocs {
             clone_progress.elapsed(),
         );
 
-        let repo_info = RepositoryInfo::from_repository(&repo, url)?;
+        let repo_info =
+            RepositoryInfo::from_repository_with(&repo, url, self.config.git.count_commits)?;
+        self.output_formatter.debug(&repo_info.display_summary());
+
+        Ok((temp_dir, repo_info))
+    }
+
+    /// The clone cache, when enabled and a location is known
+    fn clone_cache(&self) -> Option<CloneCache> {
+        if !self.config.git.cache {
+            return None;
+        }
+
+        match self
+            .config
+            .git
+            .cache_dir
+            .clone()
+            .or_else(CloneCache::default_root)
+        {
+            Some(root) => Some(CloneCache::new(root)),
+            None => {
+                self.output_formatter.warning(
+                    "No cache directory available (HOME is not set); cloning without cache",
+                );
+                None
+            }
+        }
+    }
+
+    /// Download one file of a repository within the `[politeness]` limits
+    async fn download_file(&self, url: &str, path: &str) -> Result<Vec<u8>> {
+        let _permit = self
+            .throttle
+            .acquire(&politeness::host_of(url), &self.shutdown)
+            .await?;
+
+        let fetcher = TarballFetcher::new()
+            .with_timeout(self.clone_timeout())
+            .with_retries(
+                self.config.git.max_retries,
+                Duration::from_secs(self.config.git.max_retry_wait),
+            )
+            .with_shutdown(&self.shutdown);
+        let fetcher = if let Some(ref branch) = self.config.git.branch {
+            fetcher.with_branch(branch)
+        } else {
+            fetcher
+        };
+
+        let (url, path) = (url.to_string(), path.to_string());
+        task::spawn_blocking(move || fetcher.fetch_file(&url, &path))
+            .await
+            .map_err(|e| RepoDocsError::Config {
+                message: format!("Download task failed: {}", e),
+            })?
+    }
+
+    /// Mark a Markdown file fetched on its own as a copy of its source, in
+    /// the `output.source_banner` style; other files are left as they are
+    fn mark_source(&self, content: Vec<u8>, path: &str, repo_info: &RepositoryInfo) -> Vec<u8> {
+        let Some(style) = self.config.output.source_banner else {
+            return content;
+        };
+        if !markdown::is_markdown_path(path) {
+            return content;
+        }
+        match (std::str::from_utf8(&content), repo_info.permalink(path)) {
+            (Ok(text), Some(url)) => {
+                transform::add_source_banner(text, style, path, &url, repo_info.commit.as_deref())
+                    .into_bytes()
+            }
+            _ => content,
+        }
+    }
+
+    /// Download a repository snapshot archive instead of cloning
+    async fn download_tarball(&self, url: &str) -> Result<(tempfile::TempDir, RepositoryInfo)> {
+        self.output_formatter
+            .start_operation("Downloading repository archive");
+
+        let download_progress = self.progress_manager.create_clone_progress();
+        download_progress.set_message("Downloading archive...");
+        let status_callback = {
+            let pb = download_progress.clone();
+            move |status: &str| pb.set_message(status.to_string())
+        };
+
+        let fetcher = TarballFetcher::new()
+            .with_timeout(self.clone_timeout())
+            .with_retries(
+                self.config.git.max_retries,
+                Duration::from_secs(self.config.git.max_retry_wait),
+            )
+            .with_shutdown(&self.shutdown)
+            .with_status(status_callback);
+        let fetcher = if let Some(ref branch) = self.config.git.branch {
+            fetcher.with_branch(branch)
+        } else {
+            fetcher
+        };
+
+        let url_clone = url.to_string();
+        let temp_dir = task::spawn_blocking(move || fetcher.fetch_to_temp(&url_clone))
+            .await
+            .map_err(|e| RepoDocsError::Config {
+                message: format!("Download task failed: {}", e),
+            })??;
+
+        ui::progress::finish_progress_with_summary(
+            &download_progress,
+            "Repository archive downloaded",
+            download_progress.elapsed(),
+        );
+
+        let repo_info = RepositoryInfo::from_archive(url, self.config.git.branch.as_deref())?;
         self.output_formatter.debug(&repo_info.display_summary());
 
-        Ok((repo, temp_dir, repo_info))
+        Ok((temp_dir, repo_info))
     }
 
     /// Scan for documentation files
-    fn scan_documentation(&self, repo_path: &Path) -> Result<Vec<DocumentFile>> {
+    fn scan_documentation(&self, repo_path: &Path) -> Result<(Vec<DocumentFile>, SkipStatistics)> {
         self.output_formatter
             .start_operation("Scanning for documentation files");
 
-        let scanner = DocumentScanner::new(&self.config.filters).with_repo_root(repo_path);
+        let scanner = DocumentScanner::new(&self.config.filters)
+            .with_repo_root(repo_path)
+            .with_deadline(self.deadline);
 
         let documents = scanner.scan_directory(repo_path)?;
 
@@ -188,7 +1534,7 @@ impl RepoDocs {
         let stats = scanner.get_statistics(&documents);
         self.output_formatter.debug(&stats.display_summary());
 
-        Ok(documents)
+        Ok((documents, scanner.skip_statistics()))
     }
 
     /// Setup output directory management
@@ -196,26 +1542,53 @@ impl RepoDocs {
         let output_manager = OutputManager::new(
             self.config.output.base_directory.clone(),
             repo_info.name.clone(),
-        )?;
+        )?
+        .with_name_template(
+            &self.config.output.name_template,
+            &repo_info.owner,
+            &repo_info.default_branch,
+        );
 
-        // Configure force overwrite based on CLI arguments (would need to be passed through)
-        let manager = output_manager; // .with_force_overwrite(force);
+        let mut manager = output_manager
+            .with_force_overwrite(self.config.output.force)
+            // Merges write into the existing directory like incremental updates
+            .with_incremental(self.config.output.incremental || self.config.output.merge)
+            .with_sort_order(self.config.output.sort_order)
+            .with_snippets(self.config.output.report_snippets);
 
         manager.initialize()?;
 
         self.output_formatter.success(&format!(
             "Initialized output directory: {}",
-            manager.get_output_directory().display()
+            manager.destination().display()
         ));
 
         Ok(manager)
     }
 
+    /// File operations configured from the output settings; extraction and
+    /// the index must agree on where each document ends up
+    fn file_operations(&self) -> FileOperations {
+        FileOperations::new()
+            .with_preserve_structure(self.config.output.preserve_structure)
+            .with_max_path_length(self.config.output.max_path_length)
+            .with_unicode_normalization(self.config.output.unicode_normalization)
+            .with_parallel(self.config.output.parallel_extraction)
+            .with_transliteration(self.config.output.ascii_filenames)
+            .with_readme_promotion(self.config.output.readme_promotion)
+            .with_sort_order(self.config.output.sort_order)
+            .with_index_page_size(self.config.output.index_page_size)
+            .with_notebook_mode(self.config.filters.notebook_mode)
+            .with_transform(self.config.transform.clone())
+    }
+
     /// Extract files with progress tracking
     fn extract_files(
         &self,
         documents: &[DocumentFile],
         output_dir: &Path,
+        previous_manifest: Option<Manifest>,
+        repo_info: &RepositoryInfo,
     ) -> Result<ExtractionProgress> {
         self.output_formatter
             .start_operation("Extracting documentation files");
@@ -223,18 +1596,50 @@ impl RepoDocs {
         let file_progress = self
             .progress_manager
             .create_file_progress(documents.len() as u64);
+        let watchdog = self.start_watchdog(Stage::Extracting);
         let progress_callback = {
             let pb = file_progress.clone();
+            let watchdog = watchdog.clone();
             move |progress: &ExtractionProgress| {
                 ui::progress::update_file_progress(&pb, progress);
+                if let (Some(watchdog), Some(file)) = (&watchdog, &progress.current_file) {
+                    watchdog.progress(file.clone());
+                }
             }
         };
 
-        let file_ops =
-            FileOperations::new().with_preserve_structure(self.config.output.preserve_structure);
+        if self.config.output.parallel_extraction && !cfg!(feature = "parallel") {
+            self.output_formatter.warning(
+                "Parallel extraction requested, but RepoDocs was built without the `parallel` feature; copying sequentially",
+            );
+        }
 
-        let extraction_progress =
-            file_ops.extract_files(documents, output_dir, Some(&progress_callback))?;
+        let file_ops = self
+            .file_operations()
+            .with_previous_manifest(previous_manifest)
+            .with_source_banner(self.config.output.source_banner, repo_info)
+            .with_merge(self.config.output.merge)
+            .with_link_rewriting(
+                self.config
+                    .output
+                    .rewrites_links()
+                    .then(|| repo_info.clone()),
+            )
+            .with_assets(
+                self.config
+                    .output
+                    .with_assets
+                    .then_some(self.config.output.max_asset_size),
+            )
+            .with_deadline(self.deadline)
+            .with_running(watchdog.as_ref().map(Watchdog::running));
+
+        let extracted = file_ops.extract_files(documents, output_dir, Some(&progress_callback));
+        // A stall stops the extraction, which then reports it as cancelled
+        if let Some(watchdog) = &watchdog {
+            watchdog.check()?;
+        }
+        let extraction_progress = extracted?;
 
         ui::progress::finish_progress_with_summary(
             &file_progress,
@@ -252,6 +1657,7 @@ impl RepoDocs {
             max_file_size: self.config.filters.max_file_size,
             exclude_dirs: self.config.filters.exclude_dirs.clone(),
             preserve_structure: self.config.output.preserve_structure,
+            scan_roots: self.config.filters.scan_roots.clone(),
         }
     }
 
@@ -293,6 +1699,72 @@ impl RepoDocs {
     }
 }
 
+/// Longest pause between checks for shutdown while waiting for the next
+/// run of `watch`
+const WATCH_POLL: Duration = Duration::from_millis(250);
+
+/// The server of a watch with live reload: listening from the start, serving
+/// from the first successful run on
+enum LiveSite {
+    Waiting(TcpListener, SocketAddr, Credentials),
+    Serving(Arc<DocsServer>, task::JoinHandle<Result<usize>>),
+}
+
+/// Wait for the blocking `task` of a stage, unless `watchdog` aborts the
+/// stage first. The task was then told to stop through the watchdog's flag,
+/// but a hung network or disk may never let it notice, so it is not waited
+/// for.
+async fn join_watched<T>(
+    mut task: task::JoinHandle<Result<T>>,
+    watchdog: Option<&Watchdog>,
+    name: &str,
+) -> Result<T> {
+    loop {
+        tokio::select! {
+            joined = &mut task => {
+                let result = joined.map_err(|e| RepoDocsError::Config {
+                    message: format!("{} task failed: {}", name, e),
+                })?;
+                if let Some(watchdog) = watchdog {
+                    watchdog.check()?;
+                }
+                return result;
+            }
+            () = tokio::time::sleep(WATCH_POLL), if watchdog.is_some() => {
+                if let Some(watchdog) = watchdog {
+                    watchdog.check()?;
+                }
+            }
+        }
+    }
+}
+
+/// Read the file at `path` below `root`, refusing paths that lead outside it,
+/// including through symbolic links
+fn read_file_in(root: &Path, path: &str, source: &str) -> Result<Vec<u8>> {
+    let not_found = || RepoDocsError::InvalidPath {
+        path: format!("{} not found in {}", path, source),
+    };
+    check_relative_path(path)?;
+
+    let root = root.canonicalize().map_err(|_| not_found())?;
+    let file = root.join(path).canonicalize().map_err(|_| not_found())?;
+    if !file.starts_with(&root) || !file.is_file() {
+        return Err(not_found());
+    }
+    std::fs::read(&file).map_err(RepoDocsError::Io)
+}
+
+/// Reject paths that are absolute or climb out of the repository root
+fn check_relative_path(path: &str) -> Result<()> {
+    if Path::new(path).is_absolute() || path.split(['/', '\\']).any(|part| part == "..") {
+        return Err(RepoDocsError::InvalidPath {
+            path: format!("{} is not a path relative to the repository root", path),
+        });
+    }
+    Ok(())
+}
+
 /// Convenience function to extract documentation with minimal setup
 pub async fn extract_docs_simple(
     repository_url: &str,
@@ -315,13 +1787,22 @@ pub async fn extract_docs_simple(
     repodocs.extract_documentation(repository_url).await
 }
 
-/// Validate a GitHub repository URL
+/// Validate a GitHub or GitLab repository URL
 pub fn validate_repository_url(url: &str) -> Result<String> {
-    cli::validate_github_url(url).map_err(|msg| RepoDocsError::InvalidUrl {
+    cli::validate_repository_url(url).map_err(|msg| RepoDocsError::InvalidUrl {
         url: msg.to_string(),
     })
 }
 
+/// Validate a repository URL, additionally accepting the given hosts
+pub fn validate_repository_url_with_hosts(url: &str, allowed_hosts: &[String]) -> Result<String> {
+    cli::validate_repository_url_with_hosts(url, allowed_hosts).map_err(|msg| {
+        RepoDocsError::InvalidUrl {
+            url: msg.to_string(),
+        }
+    })
+}
+
 /// Get version information
 pub fn version_info() -> &'static str {
     env!("CARGO_PKG_VERSION")
@@ -399,10 +1880,212 @@ mod tests {
     #[test]
     fn test_url_validation() {
         assert!(validate_repository_url("https://github.com/microsoft/vscode").is_ok());
-        assert!(validate_repository_url("https://gitlab.com/owner/repo").is_err());
+        assert!(validate_repository_url("https://gitlab.com/owner/repo").is_ok());
+        assert!(validate_repository_url("https://bitbucket.org/owner/repo").is_err());
         assert!(validate_repository_url("not-a-url").is_err());
     }
 
+    #[tokio::test]
+    async fn test_extract_from_local_directory() {
+        let source = TempDir::new().unwrap();
+        std::fs::write(source.path().join("README.md"), "# Hello").unwrap();
+        std::fs::create_dir_all(source.path().join("docs")).unwrap();
+        std::fs::write(source.path().join("docs/guide.md"), "# Guide").unwrap();
+
+        let output = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.output.base_directory = output.path().to_path_buf();
+
+        let repodocs = RepoDocs::new_for_test(config, OutputMode::Human, 0, true);
+        let report = repodocs.extract_from_local(source.path()).await.unwrap();
+
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.repository_info.owner, "local");
+    }
+
+    #[tokio::test]
+    async fn test_watch_updates_output_until_shutdown() {
+        let source = TempDir::new().unwrap();
+        std::fs::write(source.path().join("README.md"), "# Hello").unwrap();
+
+        let output = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.output.base_directory = output.path().to_path_buf();
+        config.output.incremental = true;
+        let repodocs = RepoDocs::new_for_test(config, OutputMode::Human, 0, true);
+        let extracted = |name: &str| {
+            std::fs::read_dir(output.path())
+                .unwrap()
+                .flatten()
+                .any(|entry| entry.path().join(name).exists())
+        };
+
+        let edit_then_stop = async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            std::fs::write(source.path().join("guide.md"), "# Guide").unwrap();
+            for _ in 0..50 {
+                if extracted("guide.md") {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            repodocs.request_shutdown();
+        };
+        let (watched, ()) = tokio::join!(
+            repodocs.watch(
+                source.path().to_str().unwrap(),
+                true,
+                Duration::from_millis(100)
+            ),
+            edit_then_stop
+        );
+
+        watched.unwrap();
+        assert!(extracted("README.md"));
+        assert!(extracted("guide.md"));
+    }
+
+    #[tokio::test]
+    async fn test_read_document() {
+        let source = TempDir::new().unwrap();
+        std::fs::create_dir_all(source.path().join("docs")).unwrap();
+        std::fs::write(source.path().join("docs/guide.md"), "# Guide").unwrap();
+        let outside = TempDir::new().unwrap();
+        std::fs::write(outside.path().join("secret.md"), "secret").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(
+            outside.path().join("secret.md"),
+            source.path().join("docs/link.md"),
+        )
+        .unwrap();
+
+        let repodocs = RepoDocs::new_for_test(Config::default(), OutputMode::Human, 0, true);
+        let source_dir = source.path().to_str().unwrap();
+        let read = |path: &'static str| repodocs.read_document(source_dir, path, true);
+
+        assert_eq!(read("docs/guide.md").await.unwrap(), b"# Guide");
+        assert!(read("docs/missing.md").await.is_err());
+        assert!(read("docs").await.is_err());
+        assert!(read("../secret.md").await.is_err());
+        #[cfg(unix)]
+        assert!(read("docs/link.md").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_document_marks_source() {
+        let source = TempDir::new().unwrap();
+        let repository = git2::Repository::init(source.path()).unwrap();
+        repository
+            .remote("origin", "https://github.com/owner/project")
+            .unwrap();
+        std::fs::create_dir_all(source.path().join("docs")).unwrap();
+        std::fs::write(source.path().join("docs/guide.md"), "# Guide\n").unwrap();
+        std::fs::write(source.path().join("docs/notes.txt"), "Notes\n").unwrap();
+
+        let mut config = Config::default();
+        config.output.source_banner = Some(SourceBanner::FrontMatter);
+        let repodocs = RepoDocs::new_for_test(config, OutputMode::Human, 0, true);
+        let source_dir = source.path().to_str().unwrap();
+
+        let guide = repodocs
+            .get_document(source_dir, "docs/guide.md", true)
+            .await
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(guide).unwrap(),
+            "---\nsource_url: \"https://github.com/owner/project/blob/HEAD/docs/guide.md\"\n---\n# Guide\n"
+        );
+        let notes = repodocs
+            .get_document(source_dir, "docs/notes.txt", true)
+            .await
+            .unwrap();
+        assert_eq!(notes, b"Notes\n");
+        assert!(repodocs
+            .get_document(source_dir, "../guide.md", true)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_extraction_removes_output() {
+        let source = TempDir::new().unwrap();
+        std::fs::write(source.path().join("README.md"), "# Hello").unwrap();
+
+        // Rendering fails after the documents were extracted
+        let output = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.output.base_directory = output.path().to_path_buf();
+        config.output.render = Some(RenderFormat::Html);
+        config.output.html.custom_css = Some(source.path().join("missing.css"));
+
+        let repodocs = RepoDocs::new_for_test(config.clone(), OutputMode::Human, 0, true);
+        assert!(repodocs.extract_from_local(source.path()).await.is_err());
+        assert_eq!(std::fs::read_dir(output.path()).unwrap().count(), 0);
+
+        config.output.keep_partial = true;
+        let repodocs = RepoDocs::new_for_test(config, OutputMode::Human, 0, true);
+        assert!(repodocs.extract_from_local(source.path()).await.is_err());
+        let kept: Vec<std::path::PathBuf> = std::fs::read_dir(output.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(kept.len(), 1);
+        assert!(kept[0].join("README.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_extract_batch_records_failures() {
+        let source = TempDir::new().unwrap();
+        std::fs::write(source.path().join("README.md"), "# Hello").unwrap();
+
+        let output = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.output.base_directory = output.path().to_path_buf();
+
+        let repodocs = RepoDocs::new_for_test(config, OutputMode::Human, 0, true);
+        let sources = vec![
+            source.path().display().to_string(),
+            "https://bitbucket.org/owner/repo".to_string(),
+        ];
+        let batch = repodocs.extract_batch(&sources, false).await.unwrap();
+
+        assert_eq!(batch.succeeded(), 1);
+        assert_eq!(batch.failed(), 1);
+        assert_eq!(batch.entries[0].files_extracted, 1);
+        assert!(batch.entries[0].output_directory.as_ref().unwrap().is_dir());
+        assert!(output.path().join(batch::BATCH_REPORT_FILE).is_file());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_extract_batch_concurrent_keeps_order() {
+        let sources: Vec<TempDir> = (0..3).map(|_| TempDir::new().unwrap()).collect();
+        for (i, source) in sources.iter().enumerate() {
+            for n in 0..=i {
+                std::fs::write(source.path().join(format!("doc{}.md", n)), "# Doc").unwrap();
+            }
+        }
+
+        let output = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.output.base_directory = output.path().to_path_buf();
+
+        let repodocs = Arc::new(RepoDocs::new_for_test(config, OutputMode::Human, 0, true));
+        let paths: Vec<String> = sources
+            .iter()
+            .map(|s| s.path().display().to_string())
+            .collect();
+        let batch = repodocs
+            .extract_batch_concurrent(paths.clone(), false, 2)
+            .await
+            .unwrap();
+
+        assert_eq!(batch.succeeded(), 3);
+        for (i, entry) in batch.entries.iter().enumerate() {
+            assert_eq!(entry.source, paths[i]);
+            assert_eq!(entry.files_extracted, i + 1);
+        }
+    }
+
     #[test]
     fn test_version_info() {
         let version = version_info();
@@ -1,22 +1,58 @@
 use clap::Parser;
-use repodocs::{Cli, OutputFormatter, OutputMode, RepoDocs, RepoDocsError, UserFriendlyError};
+use repodocs::scanner::inventory;
+use repodocs::server::Credentials;
+use repodocs::{
+    Cli, EffectiveConfig, Manifest, OutputFormat, OutputFormatter, OutputMode, RepoDocs,
+    RepoDocsError, UserFriendlyError,
+};
+use std::io::Write;
+use std::path::Path;
 use std::process;
+use std::sync::Arc;
+use std::time::Duration;
 
-#[tokio::main]
-async fn main() {
-    let exit_code = run().await;
-    process::exit(exit_code);
-}
-
-async fn run() -> i32 {
+fn main() {
     // Parse CLI arguments
     let cli = Cli::parse();
 
+    // Lowered before the runtime starts its worker threads, which inherit
+    // the priority
+    if cli.background {
+        if let Err(e) = repodocs::priority::enter_background_mode() {
+            eprintln!("Warning: could not lower process priority: {}", e);
+        }
+    }
+
+    let exit_code = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => {
+            let exit_code = runtime.block_on(run(cli));
+            // A clone abandoned on a stall may still be blocked on the
+            // network; it must not hold up the exit
+            runtime.shutdown_background();
+            exit_code
+        }
+        Err(e) => {
+            print_startup_error(&RepoDocsError::Io(e));
+            1
+        }
+    };
+    process::exit(exit_code);
+}
+
+async fn run(cli: Cli) -> i32 {
     // Handle special commands first
     if cli.generate_config {
         return handle_generate_config(&cli);
     }
 
+    if cli.print_config {
+        return handle_print_config(&cli);
+    }
+
+    if let Some(output_dir) = &cli.verify {
+        return handle_verify(&cli, output_dir);
+    }
+
     // Create RepoDocs instance
     let repodocs = match RepoDocs::from_cli(&cli) {
         Ok(repodocs) => repodocs,
@@ -26,13 +62,72 @@ async fn run() -> i32 {
         }
     };
 
+    if let Some(output_dir) = &cli.serve {
+        return handle_serve(&cli, &repodocs, output_dir).await;
+    }
+
+    let sources = match cli.repository_sources() {
+        Ok(sources) => sources,
+        Err(e) => {
+            print_startup_error(&e);
+            return 1;
+        }
+    };
+
+    if cli.list {
+        return handle_list(&cli, &repodocs, &sources).await;
+    }
+
+    if let Some(path) = &cli.cat {
+        return handle_cat(&cli, &repodocs, &sources, path).await;
+    }
+
+    if let Some(path) = &cli.get {
+        return handle_get(&cli, &repodocs, &sources, path).await;
+    }
+
+    if let Some(interval) = cli.watch {
+        return handle_watch(&cli, &repodocs, &sources, interval).await;
+    }
+
+    // A batch writing every repository to the same archive would keep only the last
+    if let Some(archive) = &repodocs.config().output.archive {
+        if cli.is_batch()
+            && !archive
+                .to_string_lossy()
+                .contains(repodocs::extractor::archive::REPO_PLACEHOLDER)
+        {
+            print_startup_error(&RepoDocsError::Config {
+                message: format!(
+                    "Archive path {} needs a {} placeholder to write one archive per repository",
+                    archive.display(),
+                    repodocs::extractor::archive::REPO_PLACEHOLDER
+                ),
+            });
+            return 1;
+        }
+    }
+
     // Handle dry run mode
     if cli.dry_run {
-        return handle_dry_run(&cli, &repodocs);
+        return handle_dry_run(&cli, &repodocs, &sources);
+    }
+
+    if cli.is_batch() {
+        return run_batch(&cli, repodocs, sources).await;
     }
 
     // Execute main extraction workflow
-    match repodocs.extract_documentation(&cli.repository_url).await {
+    let source = &sources[0];
+    let result = if cli.is_local_source(source) {
+        repodocs
+            .extract_from_local(std::path::Path::new(source))
+            .await
+    } else {
+        repodocs.extract_documentation(source).await
+    };
+
+    match result {
         Ok(report) => {
             // Display final report based on output format
             repodocs.output_formatter().print_extraction_report(&report);
@@ -46,21 +141,321 @@ async fn run() -> i32 {
         }
         Err(e) => {
             repodocs.handle_error(&e);
+            exit_code_for(&e)
+        }
+    }
+}
+
+async fn run_batch(cli: &Cli, repodocs: RepoDocs, sources: Vec<String>) -> i32 {
+    let repodocs = Arc::new(repodocs);
+    // Background runs extract one repository at a time
+    let jobs = if cli.background { 1 } else { cli.jobs };
+    let result = if jobs > 1 {
+        Arc::clone(&repodocs)
+            .extract_batch_concurrent(sources, cli.local, jobs as usize)
+            .await
+    } else {
+        repodocs.extract_batch(&sources, cli.local).await
+    };
+
+    match result {
+        Ok(batch) => {
+            repodocs.output_formatter().print_batch_report(&batch);
+
+            if batch.failed() == batch.entries.len() {
+                1 // Every repository failed
+            } else if batch.failed() > 0 || batch.entries.iter().any(|e| e.warnings > 0) {
+                2 // Partial success
+            } else {
+                0
+            }
+        }
+        Err(e) => {
+            repodocs.handle_error(&e);
+            exit_code_for(&e)
+        }
+    }
+}
+
+/// Map error types to appropriate exit codes
+fn exit_code_for(error: &RepoDocsError) -> i32 {
+    match error {
+        RepoDocsError::Cancelled => 130, // Interrupted (SIGINT)
+        RepoDocsError::InvalidUrl { .. } => 2,
+        RepoDocsError::RepositoryNotFound { .. }
+        | RepoDocsError::RepositoryNotFoundOrPrivate { .. } => 3,
+        RepoDocsError::AuthenticationFailed { .. } => 4,
+        RepoDocsError::NetworkError { .. }
+        | RepoDocsError::DnsResolutionFailed { .. }
+        | RepoDocsError::ConnectionFailed { .. }
+        | RepoDocsError::ProxyError { .. }
+        | RepoDocsError::TlsError { .. }
+        | RepoDocsError::RateLimited { .. } => 5,
+        RepoDocsError::NoDocumentationFound { .. } => 6,
+        RepoDocsError::Permission { .. } => 7,
+        RepoDocsError::OutputDirectoryExists { .. } => 8,
+        RepoDocsError::Timeout { .. } | RepoDocsError::Stalled { .. } => 9,
+        _ => 1, // General error
+    }
+}
+
+fn handle_print_config(cli: &Cli) -> i32 {
+    let effective = EffectiveConfig::resolve(cli.config.as_deref(), &cli.create_cli_overrides())
+        .and_then(|effective| effective.to_annotated_toml());
+
+    match effective {
+        Ok(toml) => {
+            print!("{}", toml);
+            0
+        }
+        Err(e) => {
+            print_startup_error(&e);
+            1
+        }
+    }
+}
 
-            // Map error types to appropriate exit codes
-            match e {
-                RepoDocsError::Cancelled => 130, // Interrupted (SIGINT)
-                RepoDocsError::InvalidUrl { .. } => 2,
-                RepoDocsError::RepositoryNotFound { .. } => 3,
-                RepoDocsError::AuthenticationFailed { .. } => 4,
-                RepoDocsError::NetworkError { .. } => 5,
-                RepoDocsError::NoDocumentationFound { .. } => 6,
-                RepoDocsError::Permission { .. } => 7,
-                RepoDocsError::OutputDirectoryExists { .. } => 8,
-                RepoDocsError::Timeout { .. } => 9,
-                _ => 1, // General error
+/// Check an extraction against the checksums in its manifest; exits with 10
+/// when files are missing or changed
+fn handle_verify(cli: &Cli, output_dir: &Path) -> i32 {
+    let metadata_dir = output_dir.join(".repodocs");
+    let manifest = match Manifest::load(&metadata_dir) {
+        Ok(Some(manifest)) => manifest,
+        Ok(None) => {
+            print_startup_error(&RepoDocsError::InvalidPath {
+                path: format!(
+                    "{} has no extraction manifest; it is not an extraction or the extraction did not finish",
+                    output_dir.display()
+                ),
+            });
+            return 1;
+        }
+        Err(e) => {
+            print_startup_error(&e);
+            return 1;
+        }
+    };
+    let verification = match manifest.verify(output_dir) {
+        Ok(verification) => verification,
+        Err(e) => {
+            print_startup_error(&e);
+            return 1;
+        }
+    };
+
+    if matches!(cli.output_format, OutputFormat::Json) {
+        let json = serde_json::json!({
+            "commit": manifest.commit,
+            "intact": verification.is_intact(),
+            "verification": verification,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&json).unwrap_or_default()
+        );
+    } else {
+        if let Some(commit) = &manifest.commit {
+            println!("Extracted from commit {}", commit);
+        }
+        println!("Verified: {} file(s)", verification.verified);
+        for (label, paths) in [
+            ("Missing", &verification.missing),
+            ("Modified", &verification.modified),
+            (
+                "Not checked (no checksum recorded)",
+                &verification.unchecked,
+            ),
+        ] {
+            if !paths.is_empty() {
+                println!("{}: {}", label, paths.len());
+                for path in paths {
+                    println!("  {}", path);
+                }
             }
         }
+        if verification.is_intact() {
+            println!("Extraction is intact");
+        } else {
+            println!("Extraction does not match its manifest");
+        }
+    }
+
+    if verification.is_intact() {
+        0
+    } else {
+        10
+    }
+}
+
+/// Print the documentation files of one repository without extracting them
+async fn handle_list(cli: &Cli, repodocs: &RepoDocs, sources: &[String]) -> i32 {
+    if cli.is_batch() {
+        print_startup_error(&RepoDocsError::Config {
+            message: "--list takes a single repository".to_string(),
+        });
+        return 1;
+    }
+
+    let entries = match repodocs.list_documents(&sources[0], cli.local).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            print_startup_error(&e);
+            return exit_code_for(&e);
+        }
+    };
+
+    let listing = match cli.output_format {
+        OutputFormat::Json => format!(
+            "{}\n",
+            serde_json::to_string_pretty(&entries).unwrap_or_default()
+        ),
+        OutputFormat::Csv => inventory::to_csv(&entries),
+        OutputFormat::Human | OutputFormat::Plain => inventory::to_text(&entries),
+    };
+    write_stdout(listing.as_bytes())
+}
+
+/// Write `content` to standard output, returning the exit code
+fn write_stdout(content: &[u8]) -> i32 {
+    match std::io::stdout().lock().write_all(content) {
+        Ok(()) => 0,
+        // A reader like `head` closing the pipe early is not a failure
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => 0,
+        Err(e) => {
+            let error = RepoDocsError::Io(e);
+            print_startup_error(&error);
+            exit_code_for(&error)
+        }
+    }
+}
+
+/// Print one file of one repository without extracting anything
+async fn handle_cat(cli: &Cli, repodocs: &RepoDocs, sources: &[String], path: &str) -> i32 {
+    if cli.is_batch() {
+        print_startup_error(&RepoDocsError::Config {
+            message: "--cat takes a single repository".to_string(),
+        });
+        return 1;
+    }
+
+    match repodocs.read_document(&sources[0], path, cli.local).await {
+        Ok(content) => write_stdout(&content),
+        Err(e) => {
+            print_startup_error(&e);
+            exit_code_for(&e)
+        }
+    }
+}
+
+/// Fetch one file of a repository and print or save it
+async fn handle_get(cli: &Cli, repodocs: &RepoDocs, sources: &[String], path: &str) -> i32 {
+    if cli.is_batch() {
+        print_startup_error(&RepoDocsError::Config {
+            message: "--get takes a single repository".to_string(),
+        });
+        return 1;
+    }
+
+    let content = match repodocs.get_document(&sources[0], path, cli.local).await {
+        Ok(content) => content,
+        Err(e) => {
+            print_startup_error(&e);
+            return exit_code_for(&e);
+        }
+    };
+
+    let Some(destination) = &cli.get_output else {
+        return write_stdout(&content);
+    };
+    match std::fs::write(destination, &content) {
+        Ok(()) => {
+            repodocs.output_formatter().success(&format!(
+                "Saved {} to {}",
+                path,
+                destination.display()
+            ));
+            0
+        }
+        Err(e) => {
+            let e = RepoDocsError::Io(e);
+            print_startup_error(&e);
+            exit_code_for(&e)
+        }
+    }
+}
+
+/// Re-extract one repository every `interval` seconds until interrupted
+async fn handle_watch(cli: &Cli, repodocs: &RepoDocs, sources: &[String], interval: u64) -> i32 {
+    if cli.is_batch() || interval == 0 {
+        print_startup_error(&RepoDocsError::Config {
+            message: "--watch takes a single repository and an interval greater than 0".to_string(),
+        });
+        return 1;
+    }
+
+    let formatter = repodocs.output_formatter();
+    formatter.info(&format!(
+        "Watching {} every {}s; press Ctrl+C to stop",
+        sources[0], interval
+    ));
+    let interval = Duration::from_secs(interval);
+    let watched = if cli.live_reload {
+        let credentials =
+            match Credentials::new(cli.serve_token.as_deref(), cli.serve_user.as_deref()) {
+                Ok(credentials) => credentials,
+                Err(e) => {
+                    print_startup_error(&e);
+                    return 1;
+                }
+            };
+        repodocs
+            .watch_and_serve(
+                &sources[0],
+                cli.local,
+                interval,
+                &cli.bind,
+                cli.port,
+                credentials,
+            )
+            .await
+    } else {
+        repodocs.watch(&sources[0], cli.local, interval).await
+    };
+    match watched {
+        Ok(()) => {
+            formatter.info("Stopped watching");
+            0
+        }
+        Err(e) => {
+            repodocs.handle_error(&e);
+            exit_code_for(&e)
+        }
+    }
+}
+
+/// Serve an extraction as HTML until interrupted
+async fn handle_serve(cli: &Cli, repodocs: &RepoDocs, output_dir: &Path) -> i32 {
+    let credentials = match Credentials::new(cli.serve_token.as_deref(), cli.serve_user.as_deref())
+    {
+        Ok(credentials) => credentials,
+        Err(e) => {
+            print_startup_error(&e);
+            return 1;
+        }
+    };
+
+    match repodocs
+        .serve(output_dir, &cli.bind, cli.port, credentials)
+        .await
+    {
+        Ok(()) => {
+            repodocs.output_formatter().info("Stopped serving");
+            0
+        }
+        Err(e) => {
+            repodocs.handle_error(&e);
+            exit_code_for(&e)
+        }
     }
 }
 
@@ -92,21 +487,32 @@ fn handle_generate_config(cli: &Cli) -> i32 {
     }
 }
 
-fn handle_dry_run(cli: &Cli, repodocs: &RepoDocs) -> i32 {
+fn handle_dry_run(cli: &Cli, repodocs: &RepoDocs, sources: &[String]) -> i32 {
     let formatter = repodocs.output_formatter();
 
     formatter.info("DRY RUN MODE - No files will be extracted");
     formatter.print_separator();
 
-    // Validate repository URL
-    match repodocs::validate_repository_url(&cli.repository_url) {
-        Ok(_) => formatter.success(&format!(
-            "✓ Repository URL is valid: {}",
-            cli.repository_url
-        )),
-        Err(e) => {
-            formatter.error(&format!("✗ Invalid repository URL: {}", e.user_message()));
-            return 1;
+    // Validate repository URLs or local directories
+    for source in sources {
+        if cli.is_local_source(source) {
+            if std::path::Path::new(source).is_dir() {
+                formatter.success(&format!("✓ Local directory exists: {}", source));
+            } else {
+                formatter.error(&format!("✗ Local directory not found: {}", source));
+                return 1;
+            }
+        } else {
+            match repodocs::validate_repository_url_with_hosts(
+                source,
+                &repodocs.config().git.allowed_hosts,
+            ) {
+                Ok(_) => formatter.success(&format!("✓ Repository URL is valid: {}", source)),
+                Err(e) => {
+                    formatter.error(&format!("✗ Invalid repository URL: {}", e.user_message()));
+                    return 1;
+                }
+            }
         }
     }
 
@@ -120,7 +526,16 @@ fn handle_dry_run(cli: &Cli, repodocs: &RepoDocs) -> i32 {
         "  Exclude directories: {}",
         config.filters.exclude_dirs.join(", ")
     );
+    if !config.filters.scan_roots.is_empty() {
+        println!("  Scan roots: {}", config.filters.scan_roots.join(", "));
+    }
     println!("  Preserve structure: {}", config.output.preserve_structure);
+    if let Some(max_length) = config.output.max_path_length {
+        println!(
+            "  Max path length: {} (longer paths are shortened)",
+            max_length
+        );
+    }
     println!(
         "  Base directory: {}",
         config.output.base_directory.display()
@@ -130,35 +545,74 @@ fn handle_dry_run(cli: &Cli, repodocs: &RepoDocs) -> i32 {
         println!("  Git branch: {}", branch);
     }
     println!("  Git timeout: {} seconds", config.git.timeout);
+    if let Some(depth) = config.git.clone_depth {
+        println!("  Clone depth: {}", depth);
+    }
+    println!("  Fetch mode: {:?}", config.git.fetch_mode);
+    println!("  Submodules: {}", config.git.submodules);
+    println!("  Git LFS: {}", config.git.lfs);
+    if config.git.cache {
+        let cache_dir = config
+            .git
+            .cache_dir
+            .clone()
+            .or_else(repodocs::CloneCache::default_root);
+        match cache_dir {
+            Some(dir) => println!("  Clone cache: {}", dir.display()),
+            None => println!("  Clone cache: unavailable"),
+        }
+    }
+    if !config.git.sparse_paths.is_empty() {
+        println!("  Sparse paths: {}", config.git.sparse_paths.join(", "));
+    }
 
     formatter.print_separator();
 
-    // Extract repository information
-    let (owner, repo_name) = match cli.extract_repo_info() {
-        Ok(info) => info,
-        Err(e) => {
-            formatter.error(&format!(
-                "Failed to parse repository info: {}",
-                e.user_message()
-            ));
-            return 1;
-        }
-    };
+    formatter.info("Extraction plan:");
+    for source in sources {
+        // Extract repository information
+        let (owner, repo_name) = match cli.extract_repo_info(source) {
+            Ok(info) => info,
+            Err(e) => {
+                formatter.error(&format!(
+                    "Failed to parse repository info: {}",
+                    e.user_message()
+                ));
+                return 1;
+            }
+        };
 
-    let output_dir = match cli.get_output_directory_name() {
-        Ok(name) => name,
-        Err(e) => {
-            formatter.error(&format!(
-                "Failed to determine output directory: {}",
-                e.user_message()
-            ));
-            return 1;
+        let output_dir = match cli.get_output_directory_name(source, config) {
+            Ok(name) => name,
+            Err(e) => {
+                formatter.error(&format!(
+                    "Failed to determine output directory: {}",
+                    e.user_message()
+                ));
+                return 1;
+            }
+        };
+
+        println!("  Repository: {}/{}", owner, repo_name);
+        println!("  Output directory: {}", output_dir);
+        if cli.branch_is_placeholder(source, config) {
+            println!(
+                "    (HEAD stands for the default branch, resolved once the repository is fetched)"
+            );
         }
-    };
+    }
 
-    formatter.info("Extraction plan:");
-    println!("  Repository: {}/{}", owner, repo_name);
-    println!("  Output directory: {}", output_dir);
+    if cli.is_batch() {
+        println!(
+            "  Batch report: {}",
+            repodocs
+                .config()
+                .output
+                .base_directory
+                .join(repodocs::batch::BATCH_REPORT_FILE)
+                .display()
+        );
+    }
 
     if cli.force {
         formatter.warning("Force mode enabled - would overwrite existing directory");
@@ -200,7 +654,8 @@ mod tests {
         let config_path = temp_dir.path().join("test.toml");
 
         let cli = Cli {
-            repository_url: "https://github.com/test/repo".to_string(),
+            repository_urls: vec!["https://github.com/test/repo".to_string()],
+            from_file: None,
             output: None,
             formats: None,
             exclude: None,
@@ -215,6 +670,85 @@ mod tests {
             force: false,
             dry_run: false,
             generate_config: true,
+            graph: false,
+            similarity_threshold: None,
+            glossary: false,
+            outline: false,
+            allow_host: vec![],
+            depth: None,
+            clone_preset: None,
+            no_preflight: false,
+            sparse: None,
+            fetch_mode: None,
+            local: false,
+            max_path_length: None,
+            jobs: 1,
+            unicode_normalization: None,
+            parallel: false,
+            ascii_filenames: false,
+            include_hidden: None,
+            incremental: false,
+            paths: None,
+            cache: false,
+            cache_dir: None,
+            preset: None,
+            respect_gitignore: false,
+            include: None,
+            sniff_content: false,
+            readme_promotion: None,
+            sort_order: None,
+            notebook_mode: None,
+            source_docs: false,
+            api_specs: false,
+            status_file: false,
+            keep_partial: false,
+            exclude_glob: None,
+            max_runtime: None,
+            stall_timeout: None,
+            abort_on_stall: false,
+            background: false,
+            skip_empty: false,
+            bundle: None,
+            llm_pack: false,
+            max_tokens: None,
+            chunk_tokens: None,
+            render: None,
+            export: None,
+            whats_new: false,
+            archive: None,
+            archive_only: false,
+            pdf_converter: None,
+            print_config: false,
+            capture_env: false,
+            rewrite_links: None,
+            with_assets: false,
+            max_asset_size: None,
+            dedupe: None,
+            report_snippets: None,
+            verify: None,
+            index_page_size: None,
+            html_theme: None,
+            custom_css: None,
+            no_search: false,
+            name_template: None,
+            site_url: None,
+            robots_disallow: None,
+            merge: false,
+            source_banner: None,
+            include_vendored: false,
+            list: false,
+            cat: None,
+            top_n: None,
+            watch: None,
+            live_reload: false,
+            print_budget: false,
+            get: None,
+            get_output: None,
+            serve: None,
+            port: 8080,
+            bind: "127.0.0.1".to_string(),
+            serve_token: None,
+            serve_user: None,
         };
 
         let exit_code = handle_generate_config(&cli);
@@ -231,7 +765,8 @@ mod tests {
         let repodocs = RepoDocs::new_for_test(config, OutputMode::Plain, 0, true);
 
         let cli = Cli {
-            repository_url: "https://github.com/microsoft/vscode".to_string(),
+            repository_urls: vec!["https://github.com/microsoft/vscode".to_string()],
+            from_file: None,
             output: None,
             formats: None,
             exclude: None,
@@ -246,9 +781,88 @@ mod tests {
             force: false,
             dry_run: true,
             generate_config: false,
+            graph: false,
+            similarity_threshold: None,
+            glossary: false,
+            outline: false,
+            allow_host: vec![],
+            depth: None,
+            clone_preset: None,
+            no_preflight: false,
+            sparse: None,
+            fetch_mode: None,
+            local: false,
+            max_path_length: None,
+            jobs: 1,
+            unicode_normalization: None,
+            parallel: false,
+            ascii_filenames: false,
+            include_hidden: None,
+            incremental: false,
+            paths: None,
+            cache: false,
+            cache_dir: None,
+            preset: None,
+            respect_gitignore: false,
+            include: None,
+            sniff_content: false,
+            readme_promotion: None,
+            sort_order: None,
+            notebook_mode: None,
+            source_docs: false,
+            api_specs: false,
+            status_file: false,
+            keep_partial: false,
+            exclude_glob: None,
+            max_runtime: None,
+            stall_timeout: None,
+            abort_on_stall: false,
+            background: false,
+            skip_empty: false,
+            bundle: None,
+            llm_pack: false,
+            max_tokens: None,
+            chunk_tokens: None,
+            render: None,
+            export: None,
+            whats_new: false,
+            archive: None,
+            archive_only: false,
+            pdf_converter: None,
+            print_config: false,
+            capture_env: false,
+            rewrite_links: None,
+            with_assets: false,
+            max_asset_size: None,
+            dedupe: None,
+            report_snippets: None,
+            verify: None,
+            index_page_size: None,
+            html_theme: None,
+            custom_css: None,
+            no_search: false,
+            name_template: None,
+            site_url: None,
+            robots_disallow: None,
+            merge: false,
+            source_banner: None,
+            include_vendored: false,
+            list: false,
+            cat: None,
+            top_n: None,
+            watch: None,
+            live_reload: false,
+            print_budget: false,
+            get: None,
+            get_output: None,
+            serve: None,
+            port: 8080,
+            bind: "127.0.0.1".to_string(),
+            serve_token: None,
+            serve_user: None,
         };
 
-        let exit_code = handle_dry_run(&cli, &repodocs);
+        let exit_code = handle_dry_run(&cli, &repodocs, &cli.repository_urls);
         assert_eq!(exit_code, 0);
     }
 
@@ -258,7 +872,8 @@ mod tests {
         let repodocs = RepoDocs::new_for_test(config, OutputMode::Plain, 0, true);
 
         let cli = Cli {
-            repository_url: "invalid-url".to_string(),
+            repository_urls: vec!["invalid-url".to_string()],
+            from_file: None,
             output: None,
             formats: None,
             exclude: None,
@@ -273,9 +888,88 @@ mod tests {
             force: false,
             dry_run: true,
             generate_config: false,
+            graph: false,
+            similarity_threshold: None,
+            glossary: false,
+            outline: false,
+            allow_host: vec![],
+            depth: None,
+            clone_preset: None,
+            no_preflight: false,
+            sparse: None,
+            fetch_mode: None,
+            local: false,
+            max_path_length: None,
+            jobs: 1,
+            unicode_normalization: None,
+            parallel: false,
+            ascii_filenames: false,
+            include_hidden: None,
+            incremental: false,
+            paths: None,
+            cache: false,
+            cache_dir: None,
+            preset: None,
+            respect_gitignore: false,
+            include: None,
+            sniff_content: false,
+            readme_promotion: None,
+            sort_order: None,
+            notebook_mode: None,
+            source_docs: false,
+            api_specs: false,
+            status_file: false,
+            keep_partial: false,
+            exclude_glob: None,
+            max_runtime: None,
+            stall_timeout: None,
+            abort_on_stall: false,
+            background: false,
+            skip_empty: false,
+            bundle: None,
+            llm_pack: false,
+            max_tokens: None,
+            chunk_tokens: None,
+            render: None,
+            export: None,
+            whats_new: false,
+            archive: None,
+            archive_only: false,
+            pdf_converter: None,
+            print_config: false,
+            capture_env: false,
+            rewrite_links: None,
+            with_assets: false,
+            max_asset_size: None,
+            dedupe: None,
+            report_snippets: None,
+            verify: None,
+            index_page_size: None,
+            html_theme: None,
+            custom_css: None,
+            no_search: false,
+            name_template: None,
+            site_url: None,
+            robots_disallow: None,
+            merge: false,
+            source_banner: None,
+            include_vendored: false,
+            list: false,
+            cat: None,
+            top_n: None,
+            watch: None,
+            live_reload: false,
+            print_budget: false,
+            get: None,
+            get_output: None,
+            serve: None,
+            port: 8080,
+            bind: "127.0.0.1".to_string(),
+            serve_token: None,
+            serve_user: None,
         };
 
-        let exit_code = handle_dry_run(&cli, &repodocs);
+        let exit_code = handle_dry_run(&cli, &repodocs, &cli.repository_urls);
         assert_eq!(exit_code, 1);
     }
 }
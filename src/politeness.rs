@@ -0,0 +1,196 @@
+use crate::config::PolitenessConfig;
+use crate::error::Result;
+use crate::ui::GracefulShutdown;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use url::Url;
+
+/// Shortest pause after a rate limit response, when the host gave no hint
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+
+/// How long to sleep at most before checking for cancellation again
+const SLEEP_SLICE: Duration = Duration::from_millis(200);
+
+/// Clone and download limits per host (`[politeness]`), so extracting a
+/// whole organization does not trip abuse detection: at most
+/// `max_clones_per_host` fetches at once, `clone_delay` seconds between
+/// their starts, and a doubling delay after every rate limit response.
+#[derive(Debug)]
+pub struct HostThrottle {
+    max_clones_per_host: Option<usize>,
+    clone_delay: Duration,
+    max_backoff: Duration,
+    hosts: Mutex<HashMap<String, HostState>>,
+}
+
+#[derive(Debug)]
+struct HostState {
+    permits: Option<Arc<Semaphore>>,
+    /// Earliest time the next fetch may start
+    next_start: Instant,
+    /// Current pause between fetch starts, raised by rate limits
+    delay: Duration,
+}
+
+/// A fetch slot on a host, released when dropped
+#[derive(Debug)]
+pub struct HostPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl HostThrottle {
+    pub fn new(config: &PolitenessConfig) -> Self {
+        Self {
+            max_clones_per_host: config.max_clones_per_host,
+            clone_delay: Duration::from_secs(config.clone_delay),
+            max_backoff: Duration::from_secs(config.max_backoff),
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn with_host<R>(&self, host: &str, f: impl FnOnce(&mut HostState) -> R) -> R {
+        let mut hosts = self.hosts.lock().unwrap_or_else(|e| e.into_inner());
+        let state = hosts.entry(host.to_string()).or_insert_with(|| HostState {
+            permits: self
+                .max_clones_per_host
+                .map(|permits| Arc::new(Semaphore::new(permits.max(1)))),
+            next_start: Instant::now(),
+            delay: self.clone_delay,
+        });
+        f(state)
+    }
+
+    /// Wait for a fetch slot on `host` and for its delay to pass. Returns
+    /// a cancellation error if the run is stopped while waiting.
+    pub async fn acquire(&self, host: &str, shutdown: &GracefulShutdown) -> Result<HostPermit> {
+        let permit = match self.with_host(host, |state| state.permits.clone()) {
+            Some(permits) => permits.acquire_owned().await.ok(),
+            None => None,
+        };
+
+        let start = self.with_host(host, |state| {
+            let start = state.next_start.max(Instant::now());
+            state.next_start = start + state.delay;
+            start
+        });
+        while Instant::now() < start {
+            shutdown.check_shutdown()?;
+            let remaining = start.saturating_duration_since(Instant::now());
+            tokio::time::sleep(remaining.min(SLEEP_SLICE)).await;
+        }
+        shutdown.check_shutdown()?;
+
+        Ok(HostPermit { _permit: permit })
+    }
+
+    /// Double the delay between fetches from `host` after a rate limit
+    /// response, to at least `retry_after` and at most `max_backoff`, and
+    /// hold off the next fetch for that long. Returns the new delay.
+    pub fn slow_down(&self, host: &str, retry_after: Duration) -> Duration {
+        let max_backoff = self.max_backoff;
+        self.with_host(host, |state| {
+            state.delay = (state.delay * 2)
+                .max(retry_after)
+                .max(MIN_BACKOFF)
+                .min(max_backoff);
+            state.next_start = state.next_start.max(Instant::now() + state.delay);
+            state.delay
+        })
+    }
+
+    /// Current delay between fetch starts on `host`
+    pub fn delay(&self, host: &str) -> Duration {
+        self.with_host(host, |state| state.delay)
+    }
+}
+
+/// Host a repository is fetched from, lowercased; scp-like remotes
+/// (`git@github.com:owner/repo`) included
+pub fn host_of(url: &str) -> String {
+    if let Some(host) = Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+    {
+        return host.to_lowercase();
+    }
+    let host = url.split_once(':').map_or(url, |(host, _)| host);
+    host.rsplit('@').next().unwrap_or(host).to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_clones_per_host: Option<usize>, clone_delay: u64) -> PolitenessConfig {
+        PolitenessConfig {
+            max_clones_per_host,
+            clone_delay,
+            max_backoff: 8,
+        }
+    }
+
+    #[test]
+    fn test_host_of() {
+        assert_eq!(host_of("https://GitHub.com/owner/repo"), "github.com");
+        assert_eq!(host_of("git@gitlab.com:owner/repo.git"), "gitlab.com");
+    }
+
+    #[test]
+    fn test_slow_down_doubles_up_to_max_backoff() {
+        let throttle = HostThrottle::new(&config(None, 0));
+        assert_eq!(throttle.delay("github.com"), Duration::ZERO);
+        assert_eq!(
+            throttle.slow_down("github.com", Duration::ZERO),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            throttle.slow_down("github.com", Duration::from_secs(3)),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            throttle.slow_down("github.com", Duration::ZERO),
+            Duration::from_secs(6)
+        );
+        assert_eq!(
+            throttle.slow_down("github.com", Duration::from_secs(60)),
+            Duration::from_secs(8)
+        );
+        assert_eq!(throttle.delay("gitlab.com"), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_limits_fetches_per_host() {
+        let throttle = HostThrottle::new(&config(Some(1), 0));
+        let shutdown = GracefulShutdown::new_for_test();
+
+        let permit = throttle.acquire("github.com", &shutdown).await.unwrap();
+        let other_host = throttle.acquire("gitlab.com", &shutdown).await;
+        assert!(other_host.is_ok());
+
+        let second = tokio::time::timeout(
+            Duration::from_millis(50),
+            throttle.acquire("github.com", &shutdown),
+        )
+        .await;
+        assert!(second.is_err(), "second fetch should wait for the first");
+
+        drop(permit);
+        assert!(throttle.acquire("github.com", &shutdown).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_spaces_fetch_starts() {
+        let throttle = HostThrottle::new(&config(None, 0));
+        let shutdown = GracefulShutdown::new_for_test();
+        throttle.slow_down("github.com", Duration::ZERO);
+
+        let start = Instant::now();
+        throttle.acquire("github.com", &shutdown).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(900));
+
+        shutdown.request_shutdown();
+        assert!(throttle.acquire("github.com", &shutdown).await.is_err());
+    }
+}
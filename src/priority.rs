@@ -0,0 +1,78 @@
+use std::io;
+
+/// Niceness of background runs on Unix, the default of `nice`
+#[cfg(unix)]
+const BACKGROUND_NICENESS: libc::c_int = 10;
+
+/// Lower the CPU and IO priority of the process for `--background` runs,
+/// so scheduled extractions yield to interactive work.
+///
+/// On Linux both priorities are per thread and inherited by threads created
+/// afterwards, so this must run before the async runtime and thread pools
+/// start.
+pub fn enter_background_mode() -> io::Result<()> {
+    lower_priority()
+}
+
+#[cfg(unix)]
+fn lower_priority() -> io::Result<()> {
+    // SAFETY: getpriority and setpriority only read and change the
+    // scheduling priority of the calling process. getpriority returns -1 on
+    // error as well, which at worst leads to setting the default niceness.
+    let current = unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) };
+    if current < BACKGROUND_NICENESS
+        && unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, BACKGROUND_NICENESS) } != 0
+    {
+        return Err(io::Error::last_os_error());
+    }
+
+    #[cfg(target_os = "linux")]
+    set_idle_io_class()?;
+
+    Ok(())
+}
+
+/// The equivalent of `ionice -c 3`: disk access only when no other process
+/// needs the disk
+#[cfg(target_os = "linux")]
+fn set_idle_io_class() -> io::Result<()> {
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+    // SAFETY: ioprio_set only changes the IO priority of the calling thread
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            0,
+            IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Background processing mode lowers both CPU and IO priority
+#[cfg(windows)]
+fn lower_priority() -> io::Result<()> {
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, SetPriorityClass, PROCESS_MODE_BACKGROUND_BEGIN,
+    };
+
+    // SAFETY: GetCurrentProcess returns a pseudo handle that needs no closing
+    if unsafe { SetPriorityClass(GetCurrentProcess(), PROCESS_MODE_BACKGROUND_BEGIN) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn lower_priority() -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "background priority is not supported on this platform",
+    ))
+}
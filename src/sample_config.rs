@@ -0,0 +1,540 @@
+use crate::config::{self, Config};
+
+/// Documentation of one setting in the sample configuration
+struct Setting {
+    /// Dotted key, e.g. `output.bundle`
+    key: &'static str,
+    /// What the setting does and its unit, one comment line per `\n`
+    description: &'static str,
+    /// Example value; settings without a default are written commented
+    /// out with it
+    example: Option<&'static str>,
+}
+
+const fn setting(key: &'static str, description: &'static str) -> Setting {
+    Setting {
+        key,
+        description,
+        example: None,
+    }
+}
+
+const fn example(key: &'static str, description: &'static str, example: &'static str) -> Setting {
+    Setting {
+        key,
+        description,
+        example: Some(example),
+    }
+}
+
+/// Introductions of the configuration sections, in the order they are written
+const SECTIONS: &[(&str, &str)] = &[
+    ("filters", "Which files of the repository are extracted."),
+    ("output", "What is written to the output directory."),
+    ("git", "How repositories are cloned or downloaded."),
+    (
+        "politeness",
+        "Limits on clones and downloads from one host, for batches of many repositories.",
+    ),
+    (
+        "transform",
+        "Normalization of extracted Markdown files, rendered notebooks included.\nEvery step is off by default; front matter and fenced code blocks are left alone.",
+    ),
+];
+
+/// Every setting, in the order it is written within its section
+const SETTINGS: &[Setting] = &[
+    // [filters]
+    setting("filters.extensions", "File extensions to extract, without the dot."),
+    setting(
+        "filters.max_file_size",
+        "Largest file to extract, in bytes (10485760 is 10 MB).",
+    ),
+    setting(
+        "filters.min_file_size",
+        "Files smaller than this many bytes are skipped. 0 keeps every file.",
+    ),
+    setting(
+        "filters.skip_empty",
+        "If true, skips files that are empty or contain only whitespace.",
+    ),
+    example(
+        "filters.exclude_dirs",
+        "Directory names, or paths relative to the repository root when they contain a `/`,\nto leave out. Rules are evaluated like .gitignore and the last matching rule wins,\nso a later `!` entry re-includes a path an earlier rule excluded.",
+        r#"["node_modules", "examples", "!docs/examples"]"#,
+    ),
+    setting(
+        "filters.exclude_patterns",
+        "Regular expressions, matched against paths relative to the repository root, of\nfiles and directories to leave out. A leading `!` re-includes matching paths.",
+    ),
+    setting("filters.max_depth", "Deepest directory level to scan."),
+    setting(
+        "filters.hidden_allowlist",
+        "Hidden directories to scan and hidden files to treat as documentation, by name.\nOther dot-directories are skipped; exclude_dirs still takes precedence.",
+    ),
+    example(
+        "filters.scan_roots",
+        "Directories to scan, relative to the repository root. Empty scans the whole tree.",
+        r#"["docs", "guides"]"#,
+    ),
+    setting(
+        "filters.respect_gitignore",
+        "If true, skips paths ignored by the repository's .gitignore files.\n.repodocsignore files (gitignore syntax) are honored either way.",
+    ),
+    example(
+        "filters.include_patterns",
+        "Glob patterns a file's path must match to be extracted. Empty extracts every\ndocumentation file.",
+        r#"["docs/**/*.md"]"#,
+    ),
+    example(
+        "filters.exclude_globs",
+        "Glob patterns of files to leave out, matched against the file's path.",
+        r#"["**/CHANGELOG*.md"]"#,
+    ),
+    setting(
+        "filters.sniff_content",
+        "If true, extensionless files not known by name are kept when their content reads\nas Markdown or reStructuredText.",
+    ),
+    example(
+        "filters.notebook_mode",
+        "Extract Jupyter notebooks: \"copy\" keeps the .ipynb file, \"markdown\" writes its\nmarkdown cells to a .md file. Notebooks are skipped when unset.",
+        r#""markdown""#,
+    ),
+    setting(
+        "filters.include_api_specs",
+        "If true, extracts OpenAPI/Swagger/AsyncAPI descriptions and JSON Schemas.",
+    ),
+    example(
+        "filters.top_n",
+        "Extract only this many documents, the most relevant by depth, file name, inbound\nlinks and size. Every document is extracted when unset.",
+        "50",
+    ),
+    setting(
+        "filters.include_vendored",
+        "If true, scans vendored and third-party directories: vendor/, third_party/, extern/\nand nested checkouts of other repositories (with their own .git, LICENSE and README).",
+    ),
+    // [output]
+    setting(
+        "output.base_directory",
+        "Directory the output folder is created in. Defaults to the current directory.",
+    ),
+    setting(
+        "output.name_template",
+        "Name of the output folder. {owner}, {repo} and {branch} are replaced with the\nrepository's, {date} with the date of the extraction (YYYY-MM-DD), so batch\nextractions of several owners or branches do not collide.",
+    ),
+    setting(
+        "output.preserve_structure",
+        "If true, mirrors the repository's directory structure; otherwise all files are\nwritten to one directory.",
+    ),
+    example(
+        "output.rewrite_links",
+        "If true, relative links of extracted Markdown point at where their targets were\nwritten, links to files that were not extracted at the repository's GitHub or\nGitLab page, and links to missing files are listed in the report. When unset,\nlinks are rewritten if preserve_structure is false.",
+        "true",
+    ),
+    example(
+        "output.source_banner",
+        "Marks every extracted Markdown document as a copy of its file at the extracted\ncommit on GitHub or GitLab: \"banner\" adds a note at the top, \"front-matter\"\nadds source_url and source_commit keys. Unset leaves documents as they are.",
+        r#""banner""#,
+    ),
+    setting(
+        "output.with_assets",
+        "If true, also copies the images and other assets (png, svg, pdf, ...) that\nextracted Markdown links to, so the documents render with them.",
+    ),
+    setting(
+        "output.max_asset_size",
+        "Largest asset copied with with_assets, in bytes. Larger ones are listed in the\nreport instead.",
+    ),
+    setting(
+        "output.create_index",
+        "If true, writes `_index.md`, a table of contents of all extracted files.",
+    ),
+    example(
+        "output.index_page_size",
+        "Splits the index into pages of at most this many files. Directories that do not\nfit in `_index.md` get pages of their own under `_index/`. Unset writes a single\n`_index.md`.",
+        "1000",
+    ),
+    setting(
+        "output.generate_report",
+        "If true, writes `extraction_report.json` with repository details and statistics.",
+    ),
+    setting(
+        "output.generate_graph",
+        "If true, exports the document link graph to `.repodocs/graph.json` and\n`graph.graphml`.",
+    ),
+    example(
+        "output.similarity_threshold",
+        "Report near-duplicate documents at or above this similarity, from 0.0 to 1.0.\nDisabled when unset.",
+        "0.9",
+    ),
+    setting(
+        "output.generate_glossary",
+        "If true, collects definition lists and frequent terms into `GLOSSARY.md`.",
+    ),
+    setting(
+        "output.generate_outline",
+        "If true, exports the heading tree of every document to `.repodocs/outline.json`.",
+    ),
+    example(
+        "output.max_path_length",
+        "Shorten directory names of output paths longer than this many characters (at\nleast 64). Over-long paths are an error when unset.",
+        "260",
+    ),
+    setting(
+        "output.unicode_normalization",
+        "Unicode normalization of output paths: \"nfc\", \"nfd\", or \"none\".",
+    ),
+    setting(
+        "output.ascii_filenames",
+        "If true, transliterates non-ASCII file and directory names to ASCII.",
+    ),
+    setting(
+        "output.readme_promotion",
+        "Naming of subdirectory READMEs when preserve_structure is false: \"none\",\n\"rename\", or \"directory\".",
+    ),
+    setting(
+        "output.sort_order",
+        "Order of file listings in reports and the index: \"path\", \"size\", \"modified\",\n\"extension\", or \"relevance\".",
+    ),
+    setting(
+        "output.dedupe",
+        "Byte-identical documents: \"keep\" writes every copy, \"hardlink\" hard-links\ncopies to the first one and \"skip\" writes only the first. Duplicates are\nlisted in the report.",
+    ),
+    setting(
+        "output.source_docs",
+        "If true, extracts doc comments from Rust, Python, Go, and JavaScript/TypeScript\nsources into `source_docs/`.",
+    ),
+    example(
+        "output.bundle",
+        "Also concatenate all documents into this Markdown file, relative to the output\ndirectory.",
+        r#""merged.md""#,
+    ),
+    setting(
+        "output.llm_pack",
+        "If true, writes the documents as plain text for an LLM context window to\n`llm_pack.txt`.",
+    ),
+    example(
+        "output.max_tokens",
+        "Estimated token limit of the LLM pack; the longest documents are shortened first.",
+        "100000",
+    ),
+    example(
+        "output.llm_chunk_tokens",
+        "Split the LLM pack into files of at most this many estimated tokens each.",
+        "32000",
+    ),
+    example(
+        "output.render",
+        "Also render the documents: \"html\" writes static pages to `html/`, \"pdf\" writes\n`documentation.pdf` with a chapter per file.",
+        r#""html""#,
+    ),
+    example(
+        "output.pdf_converter",
+        "Command converting the Markdown source to PDF, with {input} and {output}\nplaceholders. pandoc is used when unset.",
+        r#""pandoc {input} --output {output} --toc --standalone""#,
+    ),
+    setting(
+        "output.html.theme",
+        "Color scheme of the HTML pages: \"light\", \"dark\", or \"auto\" to follow the\nreader's system setting.",
+    ),
+    example(
+        "output.html.custom_css",
+        "Stylesheet copied to `html/custom.css` and linked from every page after the\nbuilt-in one, so its rules take precedence.",
+        r#""branding.css""#,
+    ),
+    setting(
+        "output.html.search",
+        "If true, adds a search box to every HTML page, backed by an index embedded in\nthe site so it works without a server.",
+    ),
+    example(
+        "output.html.site_url",
+        "URL the HTML pages are published at. Writes sitemap.xml, dated from the last\ncommit of each document, and robots.txt pointing at it.",
+        r#""https://owner.github.io/repo""#,
+    ),
+    setting(
+        "output.html.robots_disallow",
+        "Paths of the published site crawlers are asked to skip in robots.txt, e.g.\n[\"/drafts/\"]; [\"/\"] keeps the whole site out of search engines.",
+    ),
+    example(
+        "output.export",
+        "Also lay out the documents for a static site generator: \"mdbook\", \"mkdocs\", or\n\"docusaurus\".",
+        r#""mdbook""#,
+    ),
+    example(
+        "output.archive",
+        "Also write the output directory to a .zip or .tar.gz archive; {repo} is replaced\nby the repository name.",
+        r#""dist/{repo}-docs.zip""#,
+    ),
+    setting(
+        "output.archive_only",
+        "If true, keeps only the archive: the extraction is removed once archived and an existing output directory is left as it is.",
+    ),
+    setting(
+        "output.incremental",
+        "If true, updates an existing output directory, copying only files whose content\nchanged.",
+    ),
+    setting(
+        "output.merge",
+        "If true, writes into an existing output directory without removing anything:\noutputs of changed documents are replaced, files added or edited by hand are\nkept and listed as conflicts in the report. Cannot be combined with incremental.",
+    ),
+    setting(
+        "output.force",
+        "If true, replaces an existing output directory once the new extraction is\ncomplete instead of refusing to run.",
+    ),
+    setting(
+        "output.whats_new",
+        "If true, incremental updates write `WHATS_NEW.md` summarizing what changed.",
+    ),
+    setting(
+        "output.parallel_extraction",
+        "If true, copies files in parallel (requires the `parallel` build feature).",
+    ),
+    setting(
+        "output.status_file",
+        "If true, keeps `status.json` in the base directory updated with the run's progress.",
+    ),
+    setting(
+        "output.capture_env",
+        "If true, records the OS, CPU count, filesystem type and free disk space in the\nextraction report. No host, user or path names are recorded.",
+    ),
+    setting(
+        "output.print_budget",
+        "If true, prints the files, words and estimated tokens under each directory after scanning.",
+    ),
+    example(
+        "output.report_snippets",
+        "Records the first heading and paragraph of each file, cut to this many\ncharacters, as `snippet` in the JSON report. Unset records no snippets.",
+        "200",
+    ),
+    setting(
+        "output.keep_partial",
+        "If true, keeps the half-written staging directory of a failed or cancelled run\ninstead of removing it. Complete extractions are renamed into place.",
+    ),
+    example(
+        "output.max_runtime",
+        "Time limit for the whole run, in seconds. No limit when unset.",
+        "600",
+    ),
+    example(
+        "output.stall_timeout",
+        "Warn when cloning or extraction makes no progress for this many seconds.\nDisabled when unset.",
+        "60",
+    ),
+    setting(
+        "output.abort_on_stall",
+        "If true, a stall fails the run instead of only producing a warning.",
+    ),
+    // [git]
+    example(
+        "git.clone_depth",
+        "Number of commits to clone. Full history when unset.",
+        "1",
+    ),
+    setting("git.timeout", "Time limit for cloning, in seconds."),
+    example(
+        "git.branch",
+        "Branch to clone. The repository's default branch when unset.",
+        r#""main""#,
+    ),
+    example(
+        "git.allowed_hosts",
+        "Hosts to accept besides github.com and gitlab.com, matched exactly. Private\nrepositories authenticate with GIT_TOKEN.",
+        r#"["bitbucket.org", "git.example.com"]"#,
+    ),
+    setting(
+        "git.submodules",
+        "If true, also checks out submodules hosted on allowed hosts.",
+    ),
+    setting(
+        "git.lfs",
+        "If true, replaces Git LFS pointer files among the extracted formats with their\nobjects, up to filters.max_file_size.",
+    ),
+    setting(
+        "git.count_commits",
+        "If false, skips counting commits for the report, which is faster for very large\nrepositories.",
+    ),
+    setting(
+        "git.preflight",
+        "If true, checks DNS and TCP reachability of the host (or HTTPS proxy) before\ncloning.",
+    ),
+    example(
+        "git.sparse_paths",
+        "Sparse checkout: only these paths or patterns are written to the working tree.\nEmpty checks out everything.",
+        r#"["docs/", "*.md"]"#,
+    ),
+    setting(
+        "git.fetch_mode",
+        "How to fetch the repository: \"git\" clones it, \"tarball\" downloads an HTTPS archive.",
+    ),
+    setting(
+        "git.max_retries",
+        "Retries of rate-limited (HTTP 403/429) downloads and clones.",
+    ),
+    setting(
+        "git.max_retry_wait",
+        "Longest Retry-After wait to honor, in seconds; longer waits fail immediately.",
+    ),
+    setting(
+        "git.cache",
+        "If true, keeps bare clones in cache_dir and only fetches changes on later runs.",
+    ),
+    example(
+        "git.cache_dir",
+        "Location of cached clones. ~/.cache/repodocs when unset.",
+        r#""/var/cache/repodocs""#,
+    ),
+    // [politeness]
+    example(
+        "politeness.max_clones_per_host",
+        "Most clones or downloads from one host at once. Unlimited when unset.",
+        "2",
+    ),
+    setting(
+        "politeness.clone_delay",
+        "Pause between the starts of clones from one host, in seconds.",
+    ),
+    setting(
+        "politeness.max_backoff",
+        "Rate limit responses double the pause between clones from a host up to this many\nseconds.",
+    ),
+    // [transform]
+    setting(
+        "transform.normalize_headings",
+        "If true, renumbers headings so each document starts at level 1 and never skips\na level.",
+    ),
+    setting(
+        "transform.normalize_line_endings",
+        "If true, converts CRLF and CR line endings to LF.",
+    ),
+    setting(
+        "transform.expand_tabs",
+        "If true, replaces tabs outside code blocks with spaces.",
+    ),
+    setting(
+        "transform.tab_width",
+        "Columns between tab stops for expand_tabs.",
+    ),
+    setting(
+        "transform.strip_badges",
+        "If true, removes status badges (shields.io and similar images, linked or not).",
+    ),
+    setting(
+        "transform.strip_images",
+        "If true, removes all images, badges included.",
+    ),
+    setting(
+        "transform.strip_html_comments",
+        "If true, removes <!-- ... --> comments.",
+    ),
+];
+
+/// `config` as TOML with every setting described: a comment with what it
+/// does and its unit, an example where the value alone does not show the
+/// syntax, and settings without a default commented out
+pub fn generate(config: &Config) -> String {
+    let values = config::flatten(config).unwrap_or_default();
+    let mut out = String::from(
+        "# repodocs configuration\n\
+         #\n\
+         # Settings left out or commented out keep their defaults; command-line options\n\
+         # override this file.\n",
+    );
+
+    for (section, introduction) in SECTIONS {
+        out.push_str(&format!("\n[{}]\n", section));
+        push_comment(&mut out, introduction);
+
+        let prefix = format!("{}.", section);
+        let documented = SETTINGS.iter().filter(|s| s.key.starts_with(&prefix));
+        for setting in documented {
+            let name = &setting.key[prefix.len()..];
+            out.push('\n');
+            push_comment(&mut out, setting.description);
+            match (values.get(setting.key), setting.example) {
+                (Some(value), Some(example)) => {
+                    out.push_str(&format!("# Example: {} = {}\n", name, example));
+                    out.push_str(&format!("{} = {}\n", name, value));
+                }
+                (Some(value), None) => out.push_str(&format!("{} = {}\n", name, value)),
+                (None, Some(example)) => out.push_str(&format!("# {} = {}\n", name, example)),
+                (None, None) => {}
+            }
+        }
+
+        // Settings added without documentation are still written
+        for (key, value) in values.range(prefix.clone()..) {
+            let Some(name) = key.strip_prefix(&prefix) else {
+                break;
+            };
+            if !SETTINGS.iter().any(|s| s.key == key) {
+                out.push_str(&format!("\n{} = {}\n", name, value));
+            }
+        }
+    }
+
+    out
+}
+
+fn push_comment(out: &mut String, text: &str) {
+    for line in text.lines() {
+        out.push_str(&format!("# {}\n", line));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_setting_documented() {
+        let values = config::flatten(&Config::default()).unwrap();
+        for key in values.keys() {
+            assert!(
+                SETTINGS.iter().any(|s| s.key == key),
+                "{} has no description",
+                key
+            );
+        }
+        for setting in SETTINGS {
+            let section = setting.key.split_once('.').unwrap().0;
+            assert!(SECTIONS.iter().any(|(name, _)| *name == section));
+            assert!(
+                values.contains_key(setting.key) || setting.example.is_some(),
+                "{} has neither a default nor an example",
+                setting.key
+            );
+        }
+    }
+
+    #[test]
+    fn test_sample_round_trips() {
+        let config = Config::default();
+        let sample = generate(&config);
+        assert!(sample.contains(
+            "\n# Largest file to extract, in bytes (10485760 is 10 MB).\nmax_file_size = 10485760\n"
+        ));
+        assert!(sample.contains("\n# bundle = \"merged.md\"\n"));
+        assert!(sample
+            .contains("# Example: exclude_globs = [\"**/CHANGELOG*.md\"]\nexclude_globs = []\n"));
+
+        let parsed: Config = toml::from_str(&sample).unwrap();
+        assert_eq!(
+            config::flatten(&parsed).unwrap(),
+            config::flatten(&config).unwrap()
+        );
+
+        // Every commented-out example is valid configuration too
+        let uncommented: String = sample
+            .lines()
+            .map(|line| match line.strip_prefix("# ") {
+                Some(setting) if setting.contains(" = ") && !setting.starts_with("Example:") => {
+                    setting
+                }
+                _ => line,
+            })
+            .map(|line| format!("{}\n", line))
+            .collect();
+        let examples: Config = toml::from_str(&uncommented).unwrap();
+        assert_eq!(examples.output.bundle, Some("merged.md".into()));
+        assert_eq!(examples.git.clone_depth, Some(1));
+    }
+}
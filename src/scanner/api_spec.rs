@@ -0,0 +1,108 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// How much of a file is inspected for spec markers
+const SNIFF_BYTES: u64 = 4 * 1024;
+
+const SPEC_EXTENSIONS: &[&str] = &["yaml", "yml", "json"];
+
+/// File names (without extension) that are API specs by convention
+const SPEC_NAMES: &[&str] = &["openapi", "swagger", "asyncapi"];
+
+/// Whether a YAML or JSON file is an OpenAPI/Swagger/AsyncAPI description
+/// or a JSON Schema: by its conventional name (`openapi.yaml`,
+/// `swagger.json`, `*.schema.json`), or by a top-level `openapi`,
+/// `swagger` or `asyncapi` key or a json-schema.org `$schema` near the
+/// start of the file.
+pub fn is_api_spec(path: &Path) -> bool {
+    let Some(extension) = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+    else {
+        return false;
+    };
+    if !SPEC_EXTENSIONS.contains(&extension.as_str()) {
+        return false;
+    }
+
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    if SPEC_NAMES.contains(&stem.as_str()) || stem.ends_with(".schema") {
+        return true;
+    }
+
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let mut bytes = Vec::new();
+    if file.take(SNIFF_BYTES).read_to_end(&mut bytes).is_err() {
+        return false;
+    }
+
+    has_spec_marker(&String::from_utf8_lossy(&bytes))
+}
+
+fn has_spec_marker(head: &str) -> bool {
+    head.lines().any(|line| {
+        let trimmed = line.trim_start();
+        let indented = trimmed.len() != line.len();
+        let key = trimmed
+            .trim_start_matches(['{', '"', '\''])
+            .split(['"', '\'', ':'])
+            .next()
+            .unwrap_or_default();
+
+        match key {
+            // YAML keys must be top-level; JSON documents are often indented
+            "openapi" | "swagger" | "asyncapi" => {
+                (!indented || trimmed.starts_with('"')) && trimmed.contains(':')
+            }
+            "$schema" => trimmed.contains("json-schema.org"),
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_spec_markers() {
+        assert!(has_spec_marker("openapi: 3.0.3\ninfo:\n  title: API\n"));
+        assert!(has_spec_marker(
+            "{\n  \"swagger\": \"2.0\",\n  \"info\": {}\n}"
+        ));
+        assert!(has_spec_marker(
+            "{\"$schema\": \"https://json-schema.org/draft/2020-12/schema\"}"
+        ));
+
+        assert!(!has_spec_marker("name: build\non: push\n"));
+        assert!(!has_spec_marker("components:\n  openapi: nested\n"));
+        assert!(!has_spec_marker("{\"$schema\": \"./local.json\"}"));
+    }
+
+    #[test]
+    fn test_is_api_spec() {
+        let temp_dir = TempDir::new().unwrap();
+        let by_name = temp_dir.path().join("swagger.json");
+        fs::write(&by_name, "{}").unwrap();
+        let by_content = temp_dir.path().join("petstore.yml");
+        fs::write(&by_content, "openapi: 3.1.0\n").unwrap();
+        let config = temp_dir.path().join("ci.yaml");
+        fs::write(&config, "jobs:\n  test: {}\n").unwrap();
+
+        assert!(is_api_spec(&by_name));
+        assert!(is_api_spec(&by_content));
+        assert!(is_api_spec(Path::new("user.schema.json")));
+        assert!(!is_api_spec(&config));
+        assert!(!is_api_spec(Path::new("openapi.md")));
+    }
+}
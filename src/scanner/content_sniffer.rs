@@ -0,0 +1,170 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// How much of a file is inspected
+const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Extensionless files that are build or tooling inputs, whose `#` comments
+/// would otherwise pass for Markdown headings
+const BUILD_FILES: &[&str] = &[
+    "makefile",
+    "gnumakefile",
+    "dockerfile",
+    "containerfile",
+    "jenkinsfile",
+    "procfile",
+    "gemfile",
+    "rakefile",
+    "vagrantfile",
+    "brewfile",
+    "justfile",
+    "podfile",
+    "cartfile",
+    "pipfile",
+    "snakefile",
+    "tiltfile",
+    "caddyfile",
+    "codeowners",
+];
+
+/// Read the start of a file and decide whether it is text documentation:
+/// valid UTF-8 without NUL bytes that carries Markdown headings or
+/// reStructuredText section underlines or directives, and does not read
+/// like a script or source file.
+pub fn looks_like_documentation(path: &Path) -> bool {
+    let name_lower = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_lowercase())
+        .unwrap_or_default();
+    if BUILD_FILES.contains(&name_lower.as_str()) {
+        return false;
+    }
+
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let mut buffer = Vec::with_capacity(SNIFF_BYTES);
+    if file
+        .take(SNIFF_BYTES as u64)
+        .read_to_end(&mut buffer)
+        .is_err()
+    {
+        return false;
+    }
+
+    classify(&buffer)
+}
+
+fn classify(bytes: &[u8]) -> bool {
+    if bytes.is_empty() || bytes.contains(&0) {
+        return false;
+    }
+
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        // The read may have stopped in the middle of a character
+        Err(e) if e.error_len().is_none() => {
+            std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return false,
+    };
+    if text.starts_with("#!") {
+        return false;
+    }
+
+    let mut markers = 0;
+    let mut code_lines = 0;
+    let mut lines = 0;
+    let mut previous = "";
+    for line in text.lines() {
+        let trimmed = line.trim_end();
+        if trimmed.is_empty() {
+            previous = trimmed;
+            continue;
+        }
+        lines += 1;
+
+        if is_markdown_heading(trimmed)
+            || is_rst_directive(trimmed)
+            || is_rst_underline(trimmed, previous)
+        {
+            markers += 1;
+        } else if is_code_line(line) {
+            code_lines += 1;
+        }
+        previous = trimmed;
+    }
+
+    markers > 0 && code_lines * 5 < lines
+}
+
+fn is_markdown_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    (1..=6).contains(&hashes)
+        && line[hashes..].starts_with(' ')
+        && line[hashes..]
+            .trim()
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphanumeric())
+}
+
+fn is_rst_directive(line: &str) -> bool {
+    line.strip_prefix(".. ").is_some_and(|rest| {
+        rest.split_once("::")
+            .is_some_and(|(name, _)| !name.is_empty() && !name.contains(' '))
+    })
+}
+
+fn is_rst_underline(line: &str, previous: &str) -> bool {
+    let Some(first) = line.chars().next() else {
+        return false;
+    };
+    matches!(first, '=' | '-' | '~' | '^' | '*' | '+')
+        && line.len() >= 3
+        && line.chars().all(|c| c == first)
+        && !previous.is_empty()
+        && previous.chars().count() <= line.len()
+}
+
+fn is_code_line(line: &str) -> bool {
+    let trimmed = line.trim();
+    line.starts_with('\t') || trimmed.ends_with(';') || trimmed.ends_with('{') || trimmed == "}"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_classify() {
+        assert!(classify(b"# Guide\n\nSome prose about the project.\n"));
+        assert!(classify(b"Overview\n========\n\nText.\n"));
+        assert!(classify(b".. note:: Read this first.\n"));
+
+        assert!(!classify(b"plain notes without structure\n"));
+        assert!(!classify(b"#!/bin/sh\n# Setup\necho hi\n"));
+        assert!(!classify(b"# Heading\n\x00\x01\x02"));
+        assert!(!classify(&[0xff, 0xfe, b'#', b' ', b'a']));
+        assert!(!classify(
+            b"# Build\nall:\n\tcc -o app main.c\n\trm -f *.o\n\tstrip app\n"
+        ));
+    }
+
+    #[test]
+    fn test_looks_like_documentation() {
+        let temp_dir = TempDir::new().unwrap();
+        let guide = temp_dir.path().join("GUIDE");
+        fs::write(&guide, "# Guide\n\nHow to use the tool.\n").unwrap();
+        let makefile = temp_dir.path().join("Makefile");
+        fs::write(&makefile, "# Build everything\nall: app\n").unwrap();
+
+        assert!(looks_like_documentation(&guide));
+        assert!(!looks_like_documentation(&makefile));
+        assert!(!looks_like_documentation(&temp_dir.path().join("MISSING")));
+    }
+}
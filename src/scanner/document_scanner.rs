@@ -1,6 +1,12 @@
 use crate::config::FilterConfig;
+use crate::deadline::Deadline;
 use crate::error::{RepoDocsError, Result};
-use crate::scanner::file_filter::FileFilter;
+use crate::scanner::file_filter::{FileFilter, VENDORED_DIRS};
+use crate::scanner::front_matter::FrontMatter;
+use crate::scanner::ignore_rules::IgnoreRules;
+use crate::status::Stage;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use walkdir::{DirEntry, WalkDir};
@@ -13,6 +19,13 @@ pub struct DocumentFile {
     pub extension: String,
     pub size: u64,
     pub modified: SystemTime,
+    /// Scan root the file was found under, when scanning selected directories
+    pub scan_root: Option<String>,
+    /// Title, tags and date from Markdown front matter
+    pub front_matter: FrontMatter,
+    /// How important the document is among those scanned, set by
+    /// `extractor::relevance::score`; zero until then
+    pub score: f64,
 }
 
 impl DocumentFile {
@@ -41,9 +54,17 @@ impl DocumentFile {
             extension,
             size,
             modified,
+            scan_root: None,
+            front_matter: FrontMatter::default(),
+            score: 0.0,
         }
     }
 
+    /// The front matter title, or the file name for files without one
+    pub fn title(&self) -> &str {
+        self.front_matter.title.as_deref().unwrap_or(&self.filename)
+    }
+
     pub fn is_extensionless_doc(&self) -> bool {
         if !self.extension.is_empty() {
             return false;
@@ -80,10 +101,37 @@ impl DocumentFile {
     }
 }
 
+/// Write `files`, given as paths relative to `root` with their content, and
+/// describe them as a scan would, front matter included
+#[cfg(test)]
+pub fn write_test_documents<C: AsRef<[u8]>>(root: &Path, files: &[(&str, C)]) -> Vec<DocumentFile> {
+    files
+        .iter()
+        .map(|(name, content)| {
+            let path = root.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, content).unwrap();
+            let mut document = DocumentFile::new(
+                path.clone(),
+                PathBuf::from(name),
+                content.as_ref().len() as u64,
+                SystemTime::UNIX_EPOCH,
+            );
+            document.front_matter = FrontMatter::read(&path);
+            document
+        })
+        .collect()
+}
+
 pub struct DocumentScanner {
     filter: FileFilter,
     max_depth: usize,
     repo_root: Option<PathBuf>,
+    scan_roots: Vec<String>,
+    respect_gitignore: bool,
+    include_vendored: bool,
+    deadline: Option<Deadline>,
+    skipped: RefCell<SkipStatistics>,
 }
 
 impl DocumentScanner {
@@ -92,6 +140,11 @@ impl DocumentScanner {
             filter: FileFilter::new(config),
             max_depth: config.max_depth,
             repo_root: None,
+            scan_roots: config.scan_roots.clone(),
+            respect_gitignore: config.respect_gitignore,
+            include_vendored: config.include_vendored,
+            deadline: None,
+            skipped: RefCell::new(SkipStatistics::default()),
         }
     }
 
@@ -100,6 +153,12 @@ impl DocumentScanner {
         self
     }
 
+    /// Stop walking and fail with a timeout once the deadline passes
+    pub fn with_deadline(mut self, deadline: Option<Deadline>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
     pub fn scan_directory<P: AsRef<Path>>(&self, root: P) -> Result<Vec<DocumentFile>> {
         let root_path = root.as_ref();
 
@@ -117,14 +176,94 @@ impl DocumentScanner {
 
         let mut documents = Vec::new();
         let mut scan_errors = Vec::new();
+        self.skipped.replace(SkipStatistics::default());
+        let ignore_rules = IgnoreRules::new(root_path, self.respect_gitignore);
+
+        if self.scan_roots.is_empty() {
+            self.walk(
+                root_path,
+                root_path,
+                &ignore_rules,
+                &mut documents,
+                &mut scan_errors,
+            );
+        } else {
+            for scan_root in &self.scan_roots {
+                let dir = root_path.join(scan_root);
+                if !dir.is_dir() {
+                    return Err(RepoDocsError::InvalidPath {
+                        path: format!(
+                            "Scan root {} is not a directory in {}",
+                            scan_root,
+                            root_path.display()
+                        ),
+                    });
+                }
+
+                let start = documents.len();
+                self.walk(
+                    &dir,
+                    root_path,
+                    &ignore_rules,
+                    &mut documents,
+                    &mut scan_errors,
+                );
+                for document in &mut documents[start..] {
+                    document.scan_root = Some(scan_root.clone());
+                }
+            }
+        }
+
+        if let Some(deadline) = &self.deadline {
+            deadline.check(Stage::Scanning)?;
+        }
+
+        self.skipped
+            .borrow_mut()
+            .paths
+            .sort_by(|a, b| a.path.cmp(&b.path));
+
+        // Log errors but don't fail the entire scan
+        if !scan_errors.is_empty() && documents.is_empty() {
+            return Err(RepoDocsError::Permission {
+                path: format!("Multiple scan errors: {}", scan_errors.join(", ")),
+            });
+        }
 
-        let walker = WalkDir::new(root_path)
+        if documents.is_empty() {
+            return Err(RepoDocsError::NoDocumentationFound {
+                searched_extensions: self.filter.get_extensions().clone(),
+            });
+        }
+
+        // Sort by relative path for consistent output. The sort is stable, so
+        // files under overlapping roots are attributed to the first root.
+        documents.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        documents.dedup_by(|a, b| a.relative_path == b.relative_path);
+
+        Ok(documents)
+    }
+
+    /// Collect documentation files under `dir`, with paths relative to `root_path`
+    fn walk(
+        &self,
+        dir: &Path,
+        root_path: &Path,
+        ignore_rules: &IgnoreRules,
+        documents: &mut Vec<DocumentFile>,
+        scan_errors: &mut Vec<String>,
+    ) {
+        let walker = WalkDir::new(dir)
             .max_depth(self.max_depth)
             .follow_links(false) // Security: don't follow symlinks
             .into_iter()
-            .filter_entry(|e| self.should_traverse(e));
+            .filter_entry(|e| self.should_traverse(e, root_path, ignore_rules));
 
         for entry in walker {
+            if self.deadline.is_some_and(|deadline| deadline.is_expired()) {
+                break;
+            }
+
             let entry = match entry {
                 Ok(entry) => entry,
                 Err(err) => {
@@ -142,6 +281,11 @@ impl DocumentScanner {
             };
 
             if entry.file_type().is_file() {
+                if ignore_rules.is_ignored(entry.path(), false) {
+                    self.record_skip(entry.path(), root_path, SkipReason::Ignored);
+                    continue;
+                }
+
                 match self.process_file(&entry, root_path) {
                     Ok(Some(doc_file)) => documents.push(doc_file),
                     Ok(None) => {} // File filtered out
@@ -155,27 +299,14 @@ impl DocumentScanner {
                 }
             }
         }
-
-        // Log errors but don't fail the entire scan
-        if !scan_errors.is_empty() && documents.is_empty() {
-            return Err(RepoDocsError::Permission {
-                path: format!("Multiple scan errors: {}", scan_errors.join(", ")),
-            });
-        }
-
-        if documents.is_empty() {
-            return Err(RepoDocsError::NoDocumentationFound {
-                searched_extensions: self.filter.get_extensions().clone(),
-            });
-        }
-
-        // Sort by relative path for consistent output
-        documents.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
-
-        Ok(documents)
     }
 
-    fn should_traverse(&self, entry: &DirEntry) -> bool {
+    fn should_traverse(
+        &self,
+        entry: &DirEntry,
+        root_path: &Path,
+        ignore_rules: &IgnoreRules,
+    ) -> bool {
         let path = entry.path();
 
         // Security: Check depth limit
@@ -193,9 +324,28 @@ impl DocumentScanner {
             return true;
         }
 
-        // For other directories, check against exclude patterns
+        // For other directories, check against the exclude rules
         if entry.file_type().is_dir() {
-            return self.filter.should_traverse_directory(path);
+            if ignore_rules.is_ignored(path, true) {
+                self.record_skip(path, root_path, SkipReason::Ignored);
+                return false;
+            }
+
+            let relative = path.strip_prefix(root_path).unwrap_or(path);
+            if !self.filter.should_traverse_directory(relative) {
+                let reason = if self.filter.is_vendored_directory(relative) {
+                    SkipReason::Vendored
+                } else {
+                    SkipReason::ExcludedDirectory
+                };
+                self.record_skip(path, root_path, reason);
+                return false;
+            }
+
+            if !self.include_vendored && is_bundled_repository(path, relative) {
+                self.record_skip(path, root_path, SkipReason::Vendored);
+                return false;
+            }
         }
 
         true
@@ -206,6 +356,7 @@ impl DocumentScanner {
 
         // Check if it's a documentation file
         if !self.filter.is_documentation_file(path) {
+            self.record_skip(path, root_path, SkipReason::NotDocumentation);
             return Ok(None);
         }
 
@@ -214,17 +365,41 @@ impl DocumentScanner {
 
         // Check file size limits
         if !self.filter.is_size_allowed(metadata.len()) {
+            self.record_skip(path, root_path, SkipReason::TooLarge);
+            return Ok(None);
+        }
+
+        if self.filter.is_below_min_size(metadata.len()) {
+            self.record_skip(path, root_path, SkipReason::TooSmall);
+            return Ok(None);
+        }
+
+        if self.filter.is_empty_file(path, metadata.len()) {
+            self.record_skip(path, root_path, SkipReason::Empty);
             return Ok(None);
         }
 
         // Calculate relative path
         let relative_path = self.calculate_relative_path(path, root_path)?;
 
+        if !self.filter.is_included(&relative_path) {
+            self.record_skip(path, root_path, SkipReason::NotIncluded);
+            return Ok(None);
+        }
+
+        // Apply the exclude rules to the file itself; files under excluded
+        // directories are only reached through re-inclusion rules
+        if self.filter.is_excluded(&relative_path, false) {
+            self.record_skip(path, root_path, SkipReason::ExcludedPattern);
+            return Ok(None);
+        }
+
         // Get modification time
         let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
 
-        let doc_file =
+        let mut doc_file =
             DocumentFile::new(path.to_path_buf(), relative_path, metadata.len(), modified);
+        doc_file.front_matter = FrontMatter::read(path);
 
         Ok(Some(doc_file))
     }
@@ -257,12 +432,24 @@ impl DocumentScanner {
         Ok(relative.to_path_buf())
     }
 
+    /// What the last scan skipped, and why
+    pub fn skip_statistics(&self) -> SkipStatistics {
+        self.skipped.borrow().clone()
+    }
+
+    fn record_skip(&self, path: &Path, root_path: &Path, reason: SkipReason) {
+        let relative = path.strip_prefix(root_path).unwrap_or(path);
+        self.skipped
+            .borrow_mut()
+            .record(relative.to_string_lossy().replace('\\', "/"), reason);
+    }
+
     pub fn get_statistics(&self, documents: &[DocumentFile]) -> ScanStatistics {
         let total_files = documents.len();
         let total_size = documents.iter().map(|d| d.size).sum();
 
         // Group by extension
-        let mut files_by_extension = std::collections::HashMap::new();
+        let mut files_by_extension = std::collections::BTreeMap::new();
         for doc in documents {
             let ext = if doc.extension.is_empty() {
                 "no_extension".to_string()
@@ -293,7 +480,7 @@ impl DocumentScanner {
 pub struct ScanStatistics {
     pub total_files: usize,
     pub total_size: u64,
-    pub files_by_extension: std::collections::HashMap<String, usize>,
+    pub files_by_extension: std::collections::BTreeMap<String, usize>,
     pub largest_file_size: u64,
     pub largest_file_path: PathBuf,
 }
@@ -328,6 +515,187 @@ impl ScanStatistics {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    TooLarge,
+    /// Smaller than `min_file_size`
+    TooSmall,
+    /// Empty or whitespace only, with `skip_empty`
+    Empty,
+    /// A directory pruned by `exclude_dirs`, a pattern or a built-in exclusion
+    ExcludedDirectory,
+    /// A file matched by an exclude rule
+    ExcludedPattern,
+    /// Matched by `.gitignore` or `.repodocsignore`
+    Ignored,
+    /// Did not match the include patterns
+    NotIncluded,
+    /// A vendored or third-party directory, without `include_vendored`
+    Vendored,
+    /// Scanned, but not among the `top_n` most relevant documents
+    BelowTopN,
+    NotDocumentation,
+}
+
+impl SkipReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SkipReason::TooLarge => "size",
+            SkipReason::TooSmall => "too small",
+            SkipReason::Empty => "empty",
+            SkipReason::ExcludedDirectory => "excluded dirs",
+            SkipReason::ExcludedPattern => "patterns",
+            SkipReason::Ignored => "ignored",
+            SkipReason::NotIncluded => "not included",
+            SkipReason::Vendored => "vendored",
+            SkipReason::BelowTopN => "below top-n",
+            SkipReason::NotDocumentation => "not documentation",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkippedPath {
+    pub path: String,
+    pub reason: SkipReason,
+}
+
+/// Files and directories left out of a scan, counted by reason. Pruned
+/// directories count once, however many files they contain.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SkipStatistics {
+    pub too_large: usize,
+    #[serde(default)]
+    pub too_small: usize,
+    #[serde(default)]
+    pub empty: usize,
+    pub excluded_dirs: usize,
+    pub excluded_patterns: usize,
+    pub ignored: usize,
+    pub not_included: usize,
+    #[serde(default)]
+    pub vendored: usize,
+    #[serde(default)]
+    pub below_top_n: usize,
+    pub not_documentation: usize,
+    /// Every skipped path except non-documentation files, which are the
+    /// bulk of most repositories
+    pub paths: Vec<SkippedPath>,
+}
+
+impl SkipStatistics {
+    pub fn total(&self) -> usize {
+        self.too_large
+            + self.too_small
+            + self.empty
+            + self.excluded_dirs
+            + self.excluded_patterns
+            + self.ignored
+            + self.not_included
+            + self.vendored
+            + self.below_top_n
+            + self.not_documentation
+    }
+
+    /// Counts by reason, largest first, leaving out reasons that never applied
+    pub fn by_reason(&self) -> Vec<(SkipReason, usize)> {
+        let mut counts: Vec<(SkipReason, usize)> = [
+            (SkipReason::TooLarge, self.too_large),
+            (SkipReason::TooSmall, self.too_small),
+            (SkipReason::Empty, self.empty),
+            (SkipReason::ExcludedDirectory, self.excluded_dirs),
+            (SkipReason::ExcludedPattern, self.excluded_patterns),
+            (SkipReason::Ignored, self.ignored),
+            (SkipReason::NotIncluded, self.not_included),
+            (SkipReason::Vendored, self.vendored),
+            (SkipReason::BelowTopN, self.below_top_n),
+            (SkipReason::NotDocumentation, self.not_documentation),
+        ]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .collect();
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        counts
+    }
+
+    /// One-line summary, e.g. `1,204 (excluded dirs: 1,122, patterns: 79, size: 3)`
+    pub fn summary(&self) -> String {
+        let reasons: Vec<String> = self
+            .by_reason()
+            .iter()
+            .map(|(reason, count)| format!("{}: {}", reason.label(), format_count(*count)))
+            .collect();
+        format!("{} ({})", format_count(self.total()), reasons.join(", "))
+    }
+
+    pub(crate) fn record(&mut self, path: String, reason: SkipReason) {
+        let counter = match reason {
+            SkipReason::TooLarge => &mut self.too_large,
+            SkipReason::TooSmall => &mut self.too_small,
+            SkipReason::Empty => &mut self.empty,
+            SkipReason::ExcludedDirectory => &mut self.excluded_dirs,
+            SkipReason::ExcludedPattern => &mut self.excluded_patterns,
+            SkipReason::Ignored => &mut self.ignored,
+            SkipReason::NotIncluded => &mut self.not_included,
+            SkipReason::Vendored => &mut self.vendored,
+            SkipReason::BelowTopN => &mut self.below_top_n,
+            SkipReason::NotDocumentation => &mut self.not_documentation,
+        };
+        *counter += 1;
+
+        if reason != SkipReason::NotDocumentation {
+            self.paths.push(SkippedPath { path, reason });
+        }
+    }
+}
+
+/// Whether `dir` (at `relative` below the scan root) holds a copy of
+/// another repository, recognized by a license file next to a README. A
+/// monorepo package has those too, so the directory must also be a nested
+/// checkout (with its own `.git`) or sit below a vendor directory
+/// re-included by `exclude_dirs`.
+fn is_bundled_repository(dir: &Path, relative: &Path) -> bool {
+    let vendored = relative.parent().is_some_and(|parent| {
+        parent.components().any(|component| {
+            let name = component.as_os_str().to_string_lossy().to_lowercase();
+            VENDORED_DIRS.contains(&name.as_str())
+        })
+    });
+    if !vendored && !dir.join(".git").exists() {
+        return false;
+    }
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    let (mut license, mut readme) = (false, false);
+    for entry in entries.flatten() {
+        if !entry.file_type().is_ok_and(|file_type| file_type.is_file()) {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        license |= ["license", "licence", "copying"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix));
+        readme |= name.starts_with("readme");
+    }
+    license && readme
+}
+
+/// Group digits in thousands: 1204 becomes `1,204`
+fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
 fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
     let mut size = bytes as f64;
@@ -358,6 +726,18 @@ mod tests {
             exclude_dirs: vec![".git".to_string(), "node_modules".to_string()],
             exclude_patterns: vec![],
             max_depth: 5,
+            hidden_allowlist: vec![".github".to_string()],
+            scan_roots: vec![],
+            respect_gitignore: false,
+            include_patterns: vec![],
+            sniff_content: false,
+            notebook_mode: None,
+            include_api_specs: false,
+            include_vendored: false,
+            top_n: None,
+            exclude_globs: vec![],
+            min_file_size: 0,
+            skip_empty: false,
         }
     }
 
@@ -435,6 +815,171 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_scan_roots() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for dir in ["docs/api", "guides", "src"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("README.md"), "# Project").unwrap();
+        fs::write(root.join("docs/index.md"), "# Docs").unwrap();
+        fs::write(root.join("docs/api/client.md"), "# Client").unwrap();
+        fs::write(root.join("guides/setup.md"), "# Setup").unwrap();
+        fs::write(root.join("src/notes.md"), "# Notes").unwrap();
+
+        let mut config = create_test_config();
+        config.scan_roots = vec![
+            "guides".to_string(),
+            "docs".to_string(),
+            "docs/api".to_string(),
+        ];
+        let documents = DocumentScanner::new(&config).scan_directory(root).unwrap();
+
+        let found: Vec<(String, Option<&str>)> = documents
+            .iter()
+            .map(|d| {
+                (
+                    d.relative_path.to_string_lossy().replace('\\', "/"),
+                    d.scan_root.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("docs/api/client.md".to_string(), Some("docs")),
+                ("docs/index.md".to_string(), Some("docs")),
+                ("guides/setup.md".to_string(), Some("guides")),
+            ]
+        );
+
+        config.scan_roots = vec!["missing".to_string()];
+        assert!(DocumentScanner::new(&config).scan_directory(root).is_err());
+    }
+
+    #[test]
+    fn test_skip_statistics() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        fs::write(root.join("docs/guide.md"), "# Guide").unwrap();
+        fs::write(root.join("docs/huge.md"), "x".repeat(2048)).unwrap();
+        fs::write(root.join("docs/app.js"), "// code").unwrap();
+        fs::write(root.join("node_modules/pkg/README.md"), "# Pkg").unwrap();
+
+        let mut config = create_test_config();
+        config.max_file_size = 1024;
+        let scanner = DocumentScanner::new(&config);
+        let documents = scanner.scan_directory(root).unwrap();
+        assert_eq!(documents.len(), 1);
+
+        let skipped = scanner.skip_statistics();
+        assert_eq!(skipped.too_large, 1);
+        assert_eq!(skipped.excluded_dirs, 1);
+        assert_eq!(skipped.not_documentation, 1);
+        assert_eq!(skipped.total(), 3);
+        assert_eq!(
+            skipped.paths,
+            vec![
+                SkippedPath {
+                    path: "docs/huge.md".to_string(),
+                    reason: SkipReason::TooLarge
+                },
+                SkippedPath {
+                    path: "node_modules".to_string(),
+                    reason: SkipReason::ExcludedDirectory
+                },
+            ]
+        );
+        assert_eq!(format_count(1204), "1,204");
+        assert_eq!(format_count(999), "999");
+    }
+
+    #[test]
+    fn test_vendored_directories_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for (path, content) in [
+            ("README.md", "# Project"),
+            ("LICENSE", "MIT"),
+            ("docs/guide.md", "# Guide"),
+            ("third_party/zlib/README.md", "# zlib"),
+            ("libs/json/README.md", "# json"),
+            ("libs/json/LICENSE.txt", "MIT"),
+            ("libs/json/docs/api.md", "# API"),
+            ("libs/json/.git", "gitdir: ../../.git/modules/json"),
+            ("packages/core/README.md", "# Core"),
+            ("packages/core/LICENSE", "MIT"),
+        ] {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let mut config = create_test_config();
+        let scanner = DocumentScanner::new(&config);
+        let documents = scanner.scan_directory(root).unwrap();
+        let paths: Vec<String> = documents.iter().map(|d| d.display_path()).collect();
+        // The root's own LICENSE and README do not make it vendored
+        assert_eq!(
+            paths,
+            [
+                "LICENSE",
+                "README.md",
+                "docs/guide.md",
+                "packages/core/LICENSE",
+                "packages/core/README.md"
+            ]
+        );
+
+        let skipped = scanner.skip_statistics();
+        assert_eq!(skipped.vendored, 2);
+        assert_eq!(
+            skipped.paths,
+            vec![
+                SkippedPath {
+                    path: "libs/json".to_string(),
+                    reason: SkipReason::Vendored
+                },
+                SkippedPath {
+                    path: "third_party".to_string(),
+                    reason: SkipReason::Vendored
+                },
+            ]
+        );
+
+        config.include_vendored = true;
+        let scanner = DocumentScanner::new(&config);
+        let documents = scanner.scan_directory(root).unwrap();
+        assert_eq!(documents.len(), 9);
+        assert_eq!(scanner.skip_statistics().vendored, 0);
+    }
+
+    #[test]
+    fn test_bundled_repository_in_reincluded_vendor_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for (path, content) in [
+            ("vendor/NOTES.md", "# Notes"),
+            ("vendor/zlib/README.md", "# zlib"),
+            ("vendor/zlib/LICENSE", "zlib"),
+        ] {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let mut config = create_test_config();
+        config.exclude_dirs.push("!vendor".to_string());
+        let scanner = DocumentScanner::new(&config);
+        let documents = scanner.scan_directory(root).unwrap();
+        let paths: Vec<String> = documents.iter().map(|d| d.display_path()).collect();
+        assert_eq!(paths, ["vendor/NOTES.md"]);
+        assert_eq!(scanner.skip_statistics().vendored, 1);
+    }
+
     #[test]
     fn test_scan_statistics() {
         let documents = vec![
@@ -1,12 +1,115 @@
+use super::{api_spec, content_sniffer, notebook};
 use crate::config::FilterConfig;
+use crate::config::NotebookMode;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use regex::Regex;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Files up to this size are read to tell whether they hold only
+/// whitespace; larger files are never considered empty
+const EMPTY_CHECK_BYTES: u64 = 4 * 1024;
+
+/// Names of directories holding vendored or third-party code, skipped
+/// unless `include_vendored` is set
+pub const VENDORED_DIRS: [&str; 6] = [
+    "vendor",
+    "vendored",
+    "third_party",
+    "third-party",
+    "thirdparty",
+    "extern",
+];
+
+/// One entry of `exclude_dirs` or `exclude_patterns`. A leading `!` negates
+/// the rule, re-including what earlier rules excluded.
+#[derive(Debug, Clone)]
+struct ExcludeRule {
+    negated: bool,
+    matcher: RuleMatcher,
+}
+
+#[derive(Debug, Clone)]
+enum RuleMatcher {
+    /// Directory name, matched case-insensitively at any depth
+    Name(String),
+    /// File or directory path relative to the scan root
+    Path(String),
+    /// Regex matched against the relative path
+    Pattern(Regex),
+}
+
+impl ExcludeRule {
+    fn parse_dir(entry: &str) -> Option<Self> {
+        let (negated, entry) = split_negation(entry);
+        let entry = entry.trim_matches('/');
+        if entry.is_empty() {
+            return None;
+        }
+
+        let matcher = if entry.contains('/') {
+            RuleMatcher::Path(entry.to_string())
+        } else {
+            RuleMatcher::Name(entry.to_lowercase())
+        };
+        Some(Self { negated, matcher })
+    }
+
+    fn parse_pattern(entry: &str) -> Option<Self> {
+        let (negated, pattern) = split_negation(entry);
+        Regex::new(pattern).ok().map(|regex| Self {
+            negated,
+            matcher: RuleMatcher::Pattern(regex),
+        })
+    }
+
+    fn matches(&self, relative: &str, name_lower: &str, is_dir: bool) -> bool {
+        match &self.matcher {
+            RuleMatcher::Name(name) => is_dir && name == name_lower,
+            RuleMatcher::Path(path) => path == relative,
+            RuleMatcher::Pattern(regex) => regex.is_match(relative),
+        }
+    }
+}
+
+/// `*` and `?` stay within one path component, `**` spans directories.
+/// Invalid patterns are rejected by config validation and skipped here.
+fn build_glob_set(patterns: &[String]) -> Option<GlobSet> {
+    if patterns.is_empty() {
+        return None;
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        if let Ok(glob) = GlobBuilder::new(pattern).literal_separator(true).build() {
+            builder.add(glob);
+        }
+    }
+    builder.build().ok()
+}
+
+fn split_negation(entry: &str) -> (bool, &str) {
+    match entry.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, entry),
+    }
+}
 
 pub struct FileFilter {
     doc_extensions: Vec<String>,
     max_file_size: u64,
+    min_file_size: u64,
+    skip_empty: bool,
     exclude_dirs: Vec<String>,
     exclude_patterns: Vec<Regex>,
+    hidden_allowlist: Vec<String>,
+    rules: Vec<ExcludeRule>,
+    include_patterns: Option<GlobSet>,
+    exclude_globs: Option<GlobSet>,
+    sniff_content: bool,
+    notebook_mode: Option<NotebookMode>,
+    include_api_specs: bool,
+    include_vendored: bool,
 }
 
 impl FileFilter {
@@ -14,17 +117,54 @@ impl FileFilter {
         let exclude_patterns = config
             .exclude_patterns
             .iter()
+            .filter(|pattern| !pattern.starts_with('!'))
             .filter_map(|pattern| Regex::new(pattern).ok())
             .collect();
 
+        // Directory rules come before patterns; within each list the
+        // configured order is kept, since the last matching rule wins
+        let rules = config
+            .exclude_dirs
+            .iter()
+            .filter_map(|entry| ExcludeRule::parse_dir(entry))
+            .chain(
+                config
+                    .exclude_patterns
+                    .iter()
+                    .filter_map(|entry| ExcludeRule::parse_pattern(entry)),
+            )
+            .collect();
+
         Self {
             doc_extensions: config.extensions.clone(),
             max_file_size: config.max_file_size,
+            min_file_size: config.min_file_size,
+            skip_empty: config.skip_empty,
             exclude_dirs: config.exclude_dirs.clone(),
             exclude_patterns,
+            hidden_allowlist: config
+                .hidden_allowlist
+                .iter()
+                .map(|name| name.to_lowercase())
+                .collect(),
+            rules,
+            include_patterns: build_glob_set(&config.include_patterns),
+            exclude_globs: build_glob_set(&config.exclude_globs),
+            sniff_content: config.sniff_content,
+            notebook_mode: config.notebook_mode,
+            include_api_specs: config.include_api_specs,
+            include_vendored: config.include_vendored,
         }
     }
 
+    /// Whether a file, given by its path relative to the repository root,
+    /// matches the include patterns. Without patterns every file matches.
+    pub fn is_included(&self, path: &Path) -> bool {
+        self.include_patterns
+            .as_ref()
+            .is_none_or(|patterns| patterns.is_match(path))
+    }
+
     pub fn is_documentation_file(&self, path: &Path) -> bool {
         // Check by extension first
         if let Some(extension) = path.extension().and_then(|s| s.to_str()) {
@@ -34,10 +174,28 @@ impl FileFilter {
             }
         }
 
-        // Check for extensionless documentation files
+        if self.notebook_mode.is_some() && notebook::is_notebook(path) {
+            return true;
+        }
+
+        if self.include_api_specs && api_spec::is_api_spec(path) {
+            return true;
+        }
+
+        // Check for extensionless documentation files and allow-listed dotfiles
         if let Some(filename) = path.file_name().and_then(|s| s.to_str()) {
             let filename_lower = filename.to_lowercase();
-            return self.is_extensionless_doc(&filename_lower);
+            if self.is_extensionless_doc(&filename_lower)
+                || (filename.starts_with('.') && self.is_hidden_allowed(&filename_lower))
+            {
+                return true;
+            }
+
+            // Unknown extensionless files are classified by their content
+            return self.sniff_content
+                && path.extension().is_none()
+                && !filename.starts_with('.')
+                && content_sniffer::looks_like_documentation(path);
         }
 
         false
@@ -71,71 +229,151 @@ impl FileFilter {
         )
     }
 
+    /// Whether to descend into a directory, given by its path relative to
+    /// the scan root. Excluded directories are still entered when a negated
+    /// path rule re-includes something beneath them.
     pub fn should_traverse_directory(&self, path: &Path) -> bool {
-        if let Some(dir_name) = path.file_name().and_then(|s| s.to_str()) {
-            let dir_name_lower = dir_name.to_lowercase();
+        !self.is_excluded(path, true) || self.has_reinclusion_below(path)
+    }
 
-            // Check against excluded directories
-            if self
-                .exclude_dirs
-                .iter()
-                .any(|exclude| exclude.to_lowercase() == dir_name_lower)
-            {
-                return false;
-            }
+    /// Whether a directory is skipped as vendored code by its name, rather
+    /// than by an exclude rule
+    pub fn is_vendored_directory(&self, path: &Path) -> bool {
+        !self.include_vendored
+            && path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| VENDORED_DIRS.contains(&name.to_lowercase().as_str()))
+    }
 
-            // Check against exclude patterns
-            let path_str = path.to_string_lossy();
-            for pattern in &self.exclude_patterns {
-                if pattern.is_match(&path_str) {
-                    return false;
-                }
-            }
+    /// Evaluate the exclude rules for a path relative to the scan root, in
+    /// the style of `.gitignore`: each ancestor directory is evaluated from
+    /// the top down and a path inherits the state of its parent unless a rule
+    /// matches it. Built-in exclusions (build output, hidden directories,
+    /// vendored code) come first, then `exclude_dirs`, then `exclude_patterns`; the last
+    /// matching rule wins, so `!docs/vendor/important.md` re-includes one
+    /// file of an excluded `vendor` directory.
+    ///
+    /// Absolute paths carry no scan root, so only the path itself is
+    /// evaluated for them.
+    ///
+    /// Files matching an `exclude_globs` pattern are excluded regardless of
+    /// the rules; negations do not re-include them.
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        if !is_dir
+            && self
+                .exclude_globs
+                .as_ref()
+                .is_some_and(|globs| globs.is_match(path))
+        {
+            return true;
+        }
 
-            // Skip hidden directories (starting with .)
-            if dir_name.starts_with('.') && dir_name != "." && dir_name != ".." {
-                // Allow some common documentation directories
-                if !matches!(
-                    dir_name_lower.as_str(),
-                    ".github" | ".vscode" | ".devcontainer"
-                ) {
-                    return false;
-                }
+        let components: Vec<_> = path.components().collect();
+        let first = if path.is_absolute() {
+            components.len().saturating_sub(1)
+        } else {
+            0
+        };
+        let mut prefix: PathBuf = components[..first].iter().collect();
+        let mut excluded = false;
+
+        for (index, component) in components.iter().enumerate().skip(first) {
+            prefix.push(component);
+            let prefix_is_dir = is_dir || index + 1 < components.len();
+            if let Some(state) = self.evaluate(&prefix, prefix_is_dir) {
+                excluded = state;
             }
+        }
+
+        excluded
+    }
 
-            // Skip common build/output directories
-            if matches!(
-                dir_name_lower.as_str(),
-                "target"
-                    | "build"
-                    | "dist"
-                    | "out"
-                    | "output"
-                    | "bin"
-                    | "obj"
-                    | "node_modules"
-                    | "vendor"
-                    | ".cache"
-                    | "tmp"
-                    | "temp"
-                    | "__pycache__"
-                    | ".pytest_cache"
-                    | ".mypy_cache"
-                    | "coverage"
-                    | ".coverage"
-                    | "htmlcov"
-            ) {
-                return false;
+    /// The verdict of the last rule matching this exact path, if any
+    fn evaluate(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let name = path.file_name()?.to_str()?;
+        let name_lower = name.to_lowercase();
+        let relative = path.to_string_lossy().replace('\\', "/");
+
+        let mut verdict =
+            (is_dir && self.is_builtin_excluded_dir(name, &name_lower)).then_some(true);
+        for rule in &self.rules {
+            if rule.matches(&relative, &name_lower, is_dir) {
+                verdict = Some(!rule.negated);
             }
         }
+        verdict
+    }
+
+    fn has_reinclusion_below(&self, path: &Path) -> bool {
+        let prefix = format!("{}/", path.to_string_lossy().replace('\\', "/"));
+        self.rules.iter().any(|rule| {
+            rule.negated
+                && matches!(&rule.matcher, RuleMatcher::Path(path) if path.starts_with(&prefix))
+        })
+    }
+
+    fn is_builtin_excluded_dir(&self, dir_name: &str, dir_name_lower: &str) -> bool {
+        // Skip hidden directories (starting with .) unless allow-listed
+        if dir_name.starts_with('.')
+            && dir_name != "."
+            && dir_name != ".."
+            && !self.is_hidden_allowed(dir_name_lower)
+        {
+            return true;
+        }
+
+        if !self.include_vendored && VENDORED_DIRS.contains(&dir_name_lower) {
+            return true;
+        }
 
-        true
+        // Skip common build/output directories
+        matches!(
+            dir_name_lower,
+            "target"
+                | "build"
+                | "dist"
+                | "out"
+                | "output"
+                | "bin"
+                | "obj"
+                | "node_modules"
+                | ".cache"
+                | "tmp"
+                | "temp"
+                | "__pycache__"
+                | ".pytest_cache"
+                | ".mypy_cache"
+                | "coverage"
+                | ".coverage"
+                | "htmlcov"
+        )
+    }
+
+    fn is_hidden_allowed(&self, name_lower: &str) -> bool {
+        self.hidden_allowlist
+            .iter()
+            .any(|allowed| allowed == name_lower)
     }
 
     pub fn is_size_allowed(&self, size: u64) -> bool {
         size <= self.max_file_size
     }
 
+    pub fn is_below_min_size(&self, size: u64) -> bool {
+        size < self.min_file_size
+    }
+
+    /// Whether `skip_empty` leaves out this file of `size` bytes: zero-byte
+    /// files and small files holding only whitespace, such as placeholder
+    /// changelogs
+    pub fn is_empty_file(&self, path: &Path, size: u64) -> bool {
+        if !self.skip_empty || size > EMPTY_CHECK_BYTES {
+            return false;
+        }
+        size == 0 || fs::read(path).is_ok_and(|bytes| bytes.iter().all(|b| b.is_ascii_whitespace()))
+    }
+
     pub fn get_extensions(&self) -> &Vec<String> {
         &self.doc_extensions
     }
@@ -161,6 +399,7 @@ impl FileFilter {
     pub fn add_exclude_directory<S: Into<String>>(&mut self, directory: S) {
         let dir = directory.into();
         if !self.exclude_dirs.contains(&dir) {
+            self.rules.extend(ExcludeRule::parse_dir(&dir));
             self.exclude_dirs.push(dir);
         }
     }
@@ -205,6 +444,18 @@ mod tests {
             ],
             exclude_patterns: vec![r".*\.min\..*".to_string(), r".*\.lock".to_string()],
             max_depth: 10,
+            hidden_allowlist: vec![".github".to_string(), ".vscode".to_string()],
+            scan_roots: vec![],
+            respect_gitignore: false,
+            include_patterns: vec![],
+            sniff_content: false,
+            notebook_mode: None,
+            include_api_specs: false,
+            include_vendored: false,
+            top_n: None,
+            exclude_globs: vec![],
+            min_file_size: 0,
+            skip_empty: false,
         }
     }
 
@@ -265,6 +516,148 @@ mod tests {
         assert!(filter.should_traverse_directory(Path::new(".vscode")));
     }
 
+    #[test]
+    fn test_hidden_allowlist() {
+        let mut config = create_test_config();
+        config.hidden_allowlist = vec![".Gitea".to_string(), ".env.example".to_string()];
+        let filter = FileFilter::new(&config);
+
+        assert!(filter.should_traverse_directory(Path::new(".gitea")));
+        assert!(!filter.should_traverse_directory(Path::new(".github")));
+
+        assert!(filter.is_documentation_file(Path::new(".env.example")));
+        assert!(!filter.is_documentation_file(Path::new(".env")));
+        // Only dotfiles are matched by name
+        assert!(!filter.is_documentation_file(Path::new("env.example")));
+    }
+
+    #[test]
+    fn test_vendored_directories() {
+        let mut config = create_test_config();
+        let filter = FileFilter::new(&config);
+        for dir in ["vendor", "third_party", "lib/Third-Party", "extern"] {
+            assert!(!filter.should_traverse_directory(Path::new(dir)), "{}", dir);
+            assert!(filter.is_vendored_directory(Path::new(dir)), "{}", dir);
+        }
+        assert!(!filter.is_vendored_directory(Path::new("target")));
+        assert!(filter.should_traverse_directory(Path::new("external")));
+
+        config.include_vendored = true;
+        let filter = FileFilter::new(&config);
+        assert!(filter.should_traverse_directory(Path::new("third_party")));
+        assert!(!filter.is_vendored_directory(Path::new("third_party")));
+
+        // A negated rule re-includes a vendored directory
+        config.include_vendored = false;
+        config.exclude_dirs = vec!["!vendor".to_string()];
+        let filter = FileFilter::new(&config);
+        assert!(filter.should_traverse_directory(Path::new("vendor")));
+        assert!(!filter.should_traverse_directory(Path::new("extern")));
+    }
+
+    #[test]
+    fn test_negated_rules_last_match_wins() {
+        let mut config = create_test_config();
+        config.exclude_dirs = vec![
+            "docs/internal".to_string(),
+            "!docs/vendor/important.md".to_string(),
+            "!docs/internal/public".to_string(),
+        ];
+        config.exclude_patterns = vec![r"^drafts/".to_string(), r"!^drafts/ready\.md$".to_string()];
+        let filter = FileFilter::new(&config);
+
+        // Built-in `vendor` exclusion, re-included for one file only
+        assert!(filter.is_excluded(Path::new("docs/vendor"), true));
+        assert!(filter.should_traverse_directory(Path::new("docs/vendor")));
+        assert!(!filter.is_excluded(Path::new("docs/vendor/important.md"), false));
+        assert!(filter.is_excluded(Path::new("docs/vendor/other.md"), false));
+        assert!(!filter.should_traverse_directory(Path::new("lib/vendor")));
+
+        // A later rule re-includes a subtree of an excluded path
+        assert!(filter.is_excluded(Path::new("docs/internal/notes.md"), false));
+        assert!(!filter.is_excluded(Path::new("docs/internal/public/faq.md"), false));
+
+        // Patterns come after directory rules and apply to files too
+        assert!(filter.is_excluded(Path::new("drafts/wip.md"), false));
+        assert!(!filter.is_excluded(Path::new("drafts/ready.md"), false));
+
+        // Without negation, the default behaviour is unchanged
+        assert!(!filter.is_excluded(Path::new("docs/guide.md"), false));
+        assert!(filter.is_excluded(Path::new("node_modules/pkg/README.md"), false));
+    }
+
+    #[test]
+    fn test_include_patterns() {
+        let config = create_test_config();
+        assert!(FileFilter::new(&config).is_included(Path::new("src/notes.md")));
+
+        let mut config = create_test_config();
+        config.include_patterns = vec!["docs/**/*.md".to_string(), "**/README*".to_string()];
+        let filter = FileFilter::new(&config);
+
+        assert!(filter.is_included(Path::new("docs/guide.md")));
+        assert!(filter.is_included(Path::new("docs/api/v2/client.md")));
+        assert!(filter.is_included(Path::new("README.md")));
+        assert!(filter.is_included(Path::new("crates/core/README")));
+        assert!(!filter.is_included(Path::new("docs/guide.rst")));
+        assert!(!filter.is_included(Path::new("src/notes.md")));
+    }
+
+    #[test]
+    fn test_exclude_globs() {
+        let mut config = create_test_config();
+        config.exclude_globs = vec![
+            "**/CHANGELOG*.md".to_string(),
+            "docs/internal/**".to_string(),
+        ];
+        let filter = FileFilter::new(&config);
+
+        assert!(filter.is_excluded(Path::new("CHANGELOG.md"), false));
+        assert!(filter.is_excluded(Path::new("crates/core/CHANGELOG-1.x.md"), false));
+        assert!(filter.is_excluded(Path::new("docs/internal/notes.md"), false));
+        assert!(!filter.is_excluded(Path::new("docs/guide.md"), false));
+        assert!(!filter.is_excluded(Path::new("CHANGELOG.rst"), false));
+        // Directories are still entered; the globs apply to files
+        assert!(filter.should_traverse_directory(Path::new("docs/internal")));
+    }
+
+    #[test]
+    fn test_min_size_and_empty_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let empty = temp_dir.path().join("CHANGELOG.md");
+        std::fs::write(&empty, "").unwrap();
+        let blank = temp_dir.path().join("NOTES.md");
+        std::fs::write(&blank, "\n  \n").unwrap();
+        let content = temp_dir.path().join("README.md");
+        std::fs::write(&content, "# Project\n").unwrap();
+
+        let mut config = create_test_config();
+        config.min_file_size = 8;
+        let filter = FileFilter::new(&config);
+        assert!(filter.is_below_min_size(4));
+        assert!(!filter.is_below_min_size(8));
+        assert!(!filter.is_empty_file(&empty, 0));
+
+        config.skip_empty = true;
+        let filter = FileFilter::new(&config);
+        assert!(filter.is_empty_file(&empty, 0));
+        assert!(filter.is_empty_file(&blank, 4));
+        assert!(!filter.is_empty_file(&content, 10));
+    }
+
+    #[test]
+    fn test_sniff_content() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let guide = temp_dir.path().join("GUIDE");
+        std::fs::write(&guide, "Getting Started\n===============\n\nInstall it.\n").unwrap();
+
+        let mut config = create_test_config();
+        assert!(!FileFilter::new(&config).is_documentation_file(&guide));
+
+        config.sniff_content = true;
+        assert!(FileFilter::new(&config).is_documentation_file(&guide));
+    }
+
     #[test]
     fn test_size_limits() {
         let config = create_test_config();
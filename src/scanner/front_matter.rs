@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// How much of a file is read looking for front matter
+const READ_BYTES: u64 = 16 * 1024;
+
+/// Extensions of the files whose front matter is read
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown", "mdx"];
+
+/// Metadata from the front matter of a Markdown file: YAML between `---`
+/// lines or TOML between `+++` lines at the very start of the file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FrontMatter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// As written in the file, e.g. `2024-05-01`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
+impl FrontMatter {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.tags.is_empty() && self.date.is_none()
+    }
+
+    /// Front matter of a Markdown file; empty for other files and for
+    /// files without any
+    pub fn read(path: &Path) -> Self {
+        let is_markdown = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                MARKDOWN_EXTENSIONS
+                    .iter()
+                    .any(|markdown| ext.eq_ignore_ascii_case(markdown))
+            });
+        if !is_markdown {
+            return Self::default();
+        }
+
+        let Ok(file) = File::open(path) else {
+            return Self::default();
+        };
+        let mut bytes = Vec::new();
+        if file.take(READ_BYTES).read_to_end(&mut bytes).is_err() {
+            return Self::default();
+        }
+
+        Self::parse(&String::from_utf8_lossy(&bytes))
+    }
+
+    /// The fields as a YAML front matter block, delimiters included
+    pub fn to_yaml(&self) -> String {
+        let mut yaml = String::from("---\n");
+        if let Some(title) = &self.title {
+            yaml.push_str(&format!("title: {}\n", yaml_string(title)));
+        }
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|tag| yaml_string(tag)).collect();
+            yaml.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+        }
+        if let Some(date) = &self.date {
+            yaml.push_str(&format!("date: {}\n", yaml_string(date)));
+        }
+        yaml.push_str("---\n");
+        yaml
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+        let mut lines = content.lines();
+        let delimiter = match lines.next().map(str::trim_end) {
+            Some("---") => "---",
+            Some("+++") => "+++",
+            _ => return Self::default(),
+        };
+
+        let mut block = Vec::new();
+        let mut closed = false;
+        for line in lines {
+            let trimmed = line.trim_end();
+            if trimmed == delimiter || (delimiter == "---" && trimmed == "...") {
+                closed = true;
+                break;
+            }
+            block.push(line);
+        }
+        if !closed {
+            return Self::default();
+        }
+
+        if delimiter == "+++" {
+            parse_toml(&block.join("\n"))
+        } else {
+            parse_yaml(&block)
+        }
+    }
+}
+
+/// `content` without its front matter block, if it has one
+pub fn strip(content: &str) -> &str {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let mut lines = content.split_inclusive('\n');
+    let Some(first) = lines.next() else {
+        return content;
+    };
+    let delimiter = match first.trim_end() {
+        "---" => "---",
+        "+++" => "+++",
+        _ => return content,
+    };
+
+    let mut offset = first.len();
+    for line in lines {
+        offset += line.len();
+        let trimmed = line.trim_end();
+        if trimmed == delimiter || (delimiter == "---" && trimmed == "...") {
+            return &content[offset..];
+        }
+    }
+    content
+}
+
+/// `text` as a YAML double-quoted scalar, which JSON strings are valid as
+pub(crate) fn yaml_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+fn parse_toml(block: &str) -> FrontMatter {
+    let Ok(table) = block.parse::<toml::Table>() else {
+        return FrontMatter::default();
+    };
+
+    FrontMatter {
+        title: table
+            .get("title")
+            .and_then(|value| value.as_str())
+            .map(str::to_string),
+        tags: table
+            .get("tags")
+            .and_then(|value| value.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|tag| tag.as_str())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        date: table.get("date").and_then(|value| match value {
+            toml::Value::String(date) => Some(date.clone()),
+            toml::Value::Datetime(date) => Some(date.to_string()),
+            _ => None,
+        }),
+    }
+}
+
+/// The subset of YAML that front matter uses in practice: top-level `key: value`
+/// pairs, with tags given as a flow list (`[a, b]`), a block list of
+/// `- item` lines, or a comma or space separated string
+fn parse_yaml(block: &[&str]) -> FrontMatter {
+    let mut front_matter = FrontMatter::default();
+    let mut in_tags = false;
+
+    for line in block {
+        let indented = line.starts_with([' ', '\t']);
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if indented || trimmed.starts_with("- ") {
+            if in_tags {
+                if let Some(tag) = trimmed.strip_prefix("- ") {
+                    front_matter.tags.push(unquote(tag).to_string());
+                }
+            }
+            continue;
+        }
+
+        in_tags = false;
+        let Some((key, value)) = trimmed.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "title" if !value.is_empty() => front_matter.title = Some(unquote(value).to_string()),
+            "date" if !value.is_empty() => front_matter.date = Some(unquote(value).to_string()),
+            "tags" if value.is_empty() => in_tags = true,
+            "tags" => front_matter.tags = split_tags(value),
+            _ => {}
+        }
+    }
+
+    front_matter
+}
+
+fn split_tags(value: &str) -> Vec<String> {
+    let list = value
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'));
+    let value = list.unwrap_or(value);
+    let separator: &[char] = if list.is_some() || value.contains(',') {
+        &[',']
+    } else {
+        &[' ', '\t']
+    };
+
+    value
+        .split(separator)
+        .map(|tag| unquote(tag.trim()))
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_front_matter() {
+        let content = "---\ntitle: \"Getting Started\"\ndate: 2024-05-01\ntags:\n  - intro\n  - 'setup'\nlayout: page\n---\n\n# Getting Started\n";
+        let front_matter = FrontMatter::parse(content);
+        assert_eq!(front_matter.title.as_deref(), Some("Getting Started"));
+        assert_eq!(front_matter.date.as_deref(), Some("2024-05-01"));
+        assert_eq!(front_matter.tags, vec!["intro", "setup"]);
+
+        let flow = FrontMatter::parse("---\ntags: [api, \"v2\"]\n---\n");
+        assert_eq!(flow.tags, vec!["api", "v2"]);
+        let spaced = FrontMatter::parse("---\ntags: api reference\n---\n");
+        assert_eq!(spaced.tags, vec!["api", "reference"]);
+    }
+
+    #[test]
+    fn test_toml_front_matter() {
+        let content =
+            "+++\ntitle = \"Configuration\"\ndate = 2023-11-02\ntags = [\"config\"]\n+++\nBody\n";
+        let front_matter = FrontMatter::parse(content);
+        assert_eq!(front_matter.title.as_deref(), Some("Configuration"));
+        assert_eq!(front_matter.date.as_deref(), Some("2023-11-02"));
+        assert_eq!(front_matter.tags, vec!["config"]);
+    }
+
+    #[test]
+    fn test_no_front_matter() {
+        assert!(FrontMatter::parse("# Title\n\n---\ntitle: no\n---\n").is_empty());
+        assert!(FrontMatter::parse("---\ntitle: Unclosed\n").is_empty());
+        assert!(FrontMatter::parse("").is_empty());
+    }
+
+    #[test]
+    fn test_to_yaml() {
+        let front_matter =
+            FrontMatter::parse("+++\ntitle = 'Say \"hi\"'\ntags = [\"a\", \"b\"]\n+++\n");
+        assert_eq!(
+            front_matter.to_yaml(),
+            "---\ntitle: \"Say \\\"hi\\\"\"\ntags: [\"a\", \"b\"]\n---\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_front_matter() {
+        assert_eq!(strip("---\ntitle: A\n---\n# A\n"), "# A\n");
+        assert_eq!(strip("+++\r\ntitle = \"A\"\r\n+++\r\nBody"), "Body");
+        assert_eq!(strip("---\ntitle: Unclosed\n"), "---\ntitle: Unclosed\n");
+        assert_eq!(strip("# No front matter\n"), "# No front matter\n");
+    }
+}
@@ -0,0 +1,119 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Ignore file with gitignore syntax read by RepoDocs only
+pub const REPODOCS_IGNORE_FILE: &str = ".repodocsignore";
+
+/// Per-directory ignore files of one scan. `.repodocsignore` files are
+/// always honored; `.gitignore` files and `.git/info/exclude` only when
+/// requested. Files are loaded lazily as the walk reaches each directory.
+pub struct IgnoreRules {
+    root: PathBuf,
+    respect_gitignore: bool,
+    matchers: RefCell<HashMap<PathBuf, Option<Rc<Gitignore>>>>,
+}
+
+impl IgnoreRules {
+    pub fn new<P: Into<PathBuf>>(root: P, respect_gitignore: bool) -> Self {
+        Self {
+            root: root.into(),
+            respect_gitignore,
+            matchers: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Whether a path under the root is ignored. Ignore files of every
+    /// directory from the root down to the path's parent are consulted; as
+    /// in git, the deepest file with a matching pattern decides.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+
+        let mut ignored = false;
+        let mut dir = self.root.clone();
+        let mut components = relative.components().peekable();
+        while let Some(component) = components.next() {
+            if let Some(matcher) = self.matcher(&dir) {
+                match matcher.matched(path, is_dir) {
+                    Match::Ignore(_) => ignored = true,
+                    Match::Whitelist(_) => ignored = false,
+                    Match::None => {}
+                }
+            }
+
+            if components.peek().is_none() {
+                break;
+            }
+            dir.push(component);
+        }
+
+        ignored
+    }
+
+    fn matcher(&self, dir: &Path) -> Option<Rc<Gitignore>> {
+        if let Some(cached) = self.matchers.borrow().get(dir) {
+            return cached.clone();
+        }
+
+        let mut builder = GitignoreBuilder::new(dir);
+        let mut found = false;
+        let mut candidates = Vec::new();
+        if self.respect_gitignore {
+            if dir == self.root {
+                candidates.push(dir.join(".git/info/exclude"));
+            }
+            candidates.push(dir.join(".gitignore"));
+        }
+        candidates.push(dir.join(REPODOCS_IGNORE_FILE));
+
+        for file in candidates.iter().filter(|file| file.is_file()) {
+            // Unparseable lines are skipped; the rest of the file still applies
+            let _ = builder.add(file);
+            found = true;
+        }
+
+        let matcher = found
+            .then(|| builder.build().ok())
+            .flatten()
+            .filter(|matcher| !matcher.is_empty())
+            .map(Rc::new);
+        self.matchers
+            .borrow_mut()
+            .insert(dir.to_path_buf(), matcher.clone());
+        matcher
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_gitignore_and_repodocsignore() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("docs/generated")).unwrap();
+        fs::write(root.join(".gitignore"), "generated/\n*.tmp.md\n").unwrap();
+        fs::write(root.join(REPODOCS_IGNORE_FILE), "drafts.md\n").unwrap();
+        fs::write(root.join("docs/.gitignore"), "!keep.tmp.md\n").unwrap();
+
+        let rules = IgnoreRules::new(root, true);
+        assert!(rules.is_ignored(&root.join("docs/generated"), true));
+        assert!(rules.is_ignored(&root.join("docs/notes.tmp.md"), false));
+        assert!(!rules.is_ignored(&root.join("docs/keep.tmp.md"), false));
+        assert!(rules.is_ignored(&root.join("drafts.md"), false));
+        assert!(!rules.is_ignored(&root.join("docs/guide.md"), false));
+
+        // Without gitignore support only .repodocsignore applies
+        let rules = IgnoreRules::new(root, false);
+        assert!(!rules.is_ignored(&root.join("docs/generated"), true));
+        assert!(rules.is_ignored(&root.join("drafts.md"), false));
+    }
+}
@@ -0,0 +1,130 @@
+use crate::scanner::DocumentFile;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+/// A documentation file as listed by `--list`, without its content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InventoryEntry {
+    /// Path relative to the repository root
+    pub path: String,
+    pub size: u64,
+    /// Lowercase extension, empty for extensionless files
+    pub extension: String,
+    /// Modification time, RFC 3339 in UTC
+    pub modified: String,
+}
+
+impl From<&DocumentFile> for InventoryEntry {
+    fn from(document: &DocumentFile) -> Self {
+        Self {
+            path: document.relative_path.to_string_lossy().replace('\\', "/"),
+            size: document.size,
+            extension: document.extension.clone(),
+            modified: DateTime::<Utc>::from(document.modified)
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
+}
+
+/// The documentation files of a scan, in scan order
+pub fn inventory(documents: &[DocumentFile]) -> Vec<InventoryEntry> {
+    documents.iter().map(InventoryEntry::from).collect()
+}
+
+/// CSV with a header row, quoting fields as RFC 4180 requires
+pub fn to_csv(entries: &[InventoryEntry]) -> String {
+    let mut csv = String::from("path,size,extension,modified\n");
+    for entry in entries {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            csv_field(&entry.path),
+            entry.size,
+            csv_field(&entry.extension),
+            entry.modified
+        ));
+    }
+    csv
+}
+
+/// Tab-separated lines without a header, for reading or `cut`
+pub fn to_text(entries: &[InventoryEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}\t{}\t{}\t{}\n",
+                entry.path, entry.size, entry.extension, entry.modified
+            )
+        })
+        .collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_inventory_formats() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let documents = vec![
+            DocumentFile::new(
+                PathBuf::from("/tmp/repo/docs/guide.md"),
+                PathBuf::from("docs/guide.md"),
+                120,
+                modified,
+            ),
+            DocumentFile::new(
+                PathBuf::from("/tmp/repo/docs/Q, \"A\".MD"),
+                PathBuf::from("docs/Q, \"A\".MD"),
+                7,
+                modified,
+            ),
+            DocumentFile::new(
+                PathBuf::from("/tmp/repo/README"),
+                PathBuf::from("README"),
+                0,
+                modified,
+            ),
+        ];
+
+        let entries = inventory(&documents);
+        assert_eq!(
+            entries[0],
+            InventoryEntry {
+                path: "docs/guide.md".to_string(),
+                size: 120,
+                extension: "md".to_string(),
+                modified: "2023-11-14T22:13:20Z".to_string(),
+            }
+        );
+        assert_eq!(entries[2].extension, "");
+
+        assert_eq!(
+            to_csv(&entries),
+            "path,size,extension,modified\n\
+             docs/guide.md,120,md,2023-11-14T22:13:20Z\n\
+             \"docs/Q, \"\"A\"\".MD\",7,md,2023-11-14T22:13:20Z\n\
+             README,0,,2023-11-14T22:13:20Z\n"
+        );
+        assert!(to_text(&entries).starts_with("docs/guide.md\t120\tmd\t2023-11-14T22:13:20Z\n"));
+        assert_eq!(
+            serde_json::to_value(&entries[0]).unwrap(),
+            serde_json::json!({
+                "path": "docs/guide.md",
+                "size": 120,
+                "extension": "md",
+                "modified": "2023-11-14T22:13:20Z",
+            })
+        );
+    }
+}
@@ -1,5 +1,15 @@
+pub mod api_spec;
+pub mod content_sniffer;
 pub mod document_scanner;
 pub mod file_filter;
+pub mod front_matter;
+pub mod ignore_rules;
+pub mod inventory;
+pub mod notebook;
 
-pub use document_scanner::{DocumentFile, DocumentScanner};
+#[cfg(test)]
+pub use document_scanner::write_test_documents;
+pub use document_scanner::{DocumentFile, DocumentScanner, SkipReason, SkipStatistics};
 pub use file_filter::FileFilter;
+pub use front_matter::FrontMatter;
+pub use inventory::InventoryEntry;
@@ -0,0 +1,141 @@
+use crate::error::{RepoDocsError, Result};
+use serde::Deserialize;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+/// Extension of Jupyter notebooks
+pub const NOTEBOOK_EXTENSION: &str = "ipynb";
+
+/// How much of a file is inspected to recognize a notebook
+const SNIFF_BYTES: u64 = 4 * 1024;
+
+/// The parts of a Jupyter notebook (nbformat 4) RepoDocs reads
+#[derive(Debug, Deserialize)]
+pub struct Notebook {
+    pub cells: Vec<Cell>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Cell {
+    pub cell_type: String,
+    #[serde(default)]
+    pub source: CellSource,
+}
+
+/// Cell source, stored either as one string or as a list of lines
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum CellSource {
+    Text(String),
+    Lines(Vec<String>),
+}
+
+impl Default for CellSource {
+    fn default() -> Self {
+        CellSource::Text(String::new())
+    }
+}
+
+impl CellSource {
+    pub fn text(&self) -> String {
+        match self {
+            CellSource::Text(text) => text.clone(),
+            CellSource::Lines(lines) => lines.concat(),
+        }
+    }
+}
+
+impl Notebook {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(RepoDocsError::Io)?;
+        serde_json::from_str(&content).map_err(|e| RepoDocsError::InvalidPath {
+            path: format!("Not a valid notebook: {} ({})", path.display(), e),
+        })
+    }
+
+    /// The markdown cells joined into one document; code and raw cells are
+    /// left out
+    pub fn to_markdown(&self) -> String {
+        let blocks: Vec<String> = self
+            .cells
+            .iter()
+            .filter(|cell| cell.cell_type == "markdown")
+            .map(|cell| cell.source.text().trim().to_string())
+            .filter(|text| !text.is_empty())
+            .collect();
+
+        let mut markdown = blocks.join("\n\n");
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+        markdown
+    }
+}
+
+pub fn has_notebook_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(NOTEBOOK_EXTENSION))
+}
+
+/// Whether a file is a Jupyter notebook: an `.ipynb` file holding a JSON
+/// object with a `cells` key. nbformat writes keys in sorted order, so the
+/// key appears near the start of the file.
+pub fn is_notebook(path: &Path) -> bool {
+    if !has_notebook_extension(path) {
+        return false;
+    }
+
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let mut bytes = Vec::new();
+    if file.take(SNIFF_BYTES).read_to_end(&mut bytes).is_err() {
+        return false;
+    }
+
+    // Lossy, as the limit may cut a multi-byte character in half
+    let head = String::from_utf8_lossy(&bytes);
+    head.trim_start().starts_with('{') && head.contains("\"cells\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const NOTEBOOK: &str = r###"{
+ "cells": [
+  {"cell_type": "markdown", "metadata": {}, "source": ["# Analysis\n", "\n", "Loads the data."]},
+  {"cell_type": "code", "metadata": {}, "source": "import pandas as pd", "outputs": []},
+  {"cell_type": "markdown", "metadata": {}, "source": "## Results"}
+ ],
+ "metadata": {},
+ "nbformat": 4,
+ "nbformat_minor": 5
+}"###;
+
+    #[test]
+    fn test_notebook_to_markdown() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("analysis.ipynb");
+        fs::write(&path, NOTEBOOK).unwrap();
+
+        assert!(is_notebook(&path));
+        let markdown = Notebook::load(&path).unwrap().to_markdown();
+        assert_eq!(markdown, "# Analysis\n\nLoads the data.\n\n## Results\n");
+    }
+
+    #[test]
+    fn test_is_notebook() {
+        let temp_dir = TempDir::new().unwrap();
+        let not_json = temp_dir.path().join("fake.ipynb");
+        fs::write(&not_json, "not a notebook").unwrap();
+        let wrong_extension = temp_dir.path().join("notebook.json");
+        fs::write(&wrong_extension, NOTEBOOK).unwrap();
+
+        assert!(!is_notebook(&not_json));
+        assert!(!is_notebook(&wrong_extension));
+    }
+}
@@ -0,0 +1,1202 @@
+use crate::config::HtmlConfig;
+use crate::error::{RepoDocsError, Result};
+use crate::extractor::manifest::Manifest;
+use crate::extractor::markdown;
+use crate::extractor::search::SearchIndex;
+use crate::extractor::HtmlSite;
+use crate::scanner::{DocumentFile, FrontMatter};
+use crate::ui::GracefulShutdown;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Timelike, Utc};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, TrySendError};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use tempfile::TempDir;
+
+/// How long a connection may take to send its request or read the response
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest request line and header block read, in bytes
+const MAX_REQUEST_BYTES: u64 = 16 * 1024;
+
+/// Threads answering requests
+const WORKERS: usize = 8;
+
+/// Connections waiting for a worker; more are turned away with `503`
+const QUEUE: usize = 64;
+
+/// Pause between looks for new connections and shutdown
+const POLL: Duration = Duration::from_millis(50);
+
+/// How long requests in progress may take to finish after shutdown
+pub const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Event stream live-reloading pages subscribe to
+const EVENTS_PATH: &str = "/_repodocs/events";
+
+/// Index of the documents, and below it each document
+const DOCUMENTS_PATH: &str = "/_repodocs/documents";
+
+/// Search results for the `q` parameter
+const SEARCH_PATH: &str = "/_repodocs/search";
+
+/// Most search results answered
+const SEARCH_RESULTS: usize = 20;
+
+/// Browsers subscribed to reload events at once; more get `503`
+const MAX_SUBSCRIBERS: usize = 64;
+
+/// Pause between keep-alive comments on the event stream, which also let
+/// the server notice browsers that went away
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// How long one subscriber may take to accept an event
+const EVENT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Credentials a client must send to be served: a bearer token, or a user
+/// and password for HTTP basic authentication. Either is accepted when both
+/// are set; without any, every request is answered.
+#[derive(Debug, Clone, Default)]
+pub struct Credentials {
+    token: Option<String>,
+    /// `user:password`, base64-encoded as clients send it
+    basic: Option<String>,
+}
+
+impl Credentials {
+    /// Credentials from a bearer `token` and a `user:password` pair
+    pub fn new(token: Option<&str>, user: Option<&str>) -> Result<Self> {
+        if token.is_some_and(|token| token.trim().is_empty()) {
+            return Err(RepoDocsError::Config {
+                message: "The server token must not be empty".to_string(),
+            });
+        }
+        if user.is_some_and(|user| !matches!(user.split_once(':'), Some((name, password)) if !name.is_empty() && !password.is_empty()))
+        {
+            return Err(RepoDocsError::Config {
+                message: "Server users must be given as USER:PASSWORD".to_string(),
+            });
+        }
+
+        Ok(Self {
+            token: token.map(|token| token.trim().to_string()),
+            basic: user.map(|user| BASE64.encode(user)),
+        })
+    }
+
+    /// Whether requests are answered without credentials
+    pub fn is_empty(&self) -> bool {
+        self.token.is_none() && self.basic.is_none()
+    }
+
+    fn allows(&self, authorization: Option<&str>) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let Some((scheme, value)) = authorization.and_then(|value| value.trim().split_once(' '))
+        else {
+            return false;
+        };
+        let expected = match scheme.to_ascii_lowercase().as_str() {
+            "bearer" => &self.token,
+            "basic" => &self.basic,
+            _ => return false,
+        };
+        expected
+            .as_deref()
+            .is_some_and(|expected| constant_time_eq(expected.as_bytes(), value.trim().as_bytes()))
+    }
+
+    fn challenge(&self) -> &'static str {
+        match self.basic {
+            Some(_) => "Basic realm=\"repodocs\"",
+            None => "Bearer realm=\"repodocs\"",
+        }
+    }
+}
+
+/// Compare secrets in a time that does not depend on where they differ
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// One rendering of the extraction. Requests in progress keep the one they
+/// started with, so a new rendering never pulls files from under them.
+struct Site {
+    dir: TempDir,
+    root: PathBuf,
+    /// Extraction the site was rendered from
+    output_dir: PathBuf,
+    /// When the site was rendered, to the second, the `Last-Modified` of
+    /// every file
+    rendered_at: DateTime<Utc>,
+    /// Rendered documents, in the order of the index
+    documents: Vec<SiteDocument>,
+    search_index: SearchIndex,
+}
+
+/// A rendered document, as listed by `DOCUMENTS_PATH`
+#[derive(Debug, Serialize)]
+struct SiteDocument {
+    /// Path relative to the repository root
+    path: String,
+    title: String,
+    /// URL path of the rendered page
+    page: String,
+    size: u64,
+    /// Path of the extracted file relative to the extraction
+    #[serde(skip)]
+    extracted: String,
+}
+
+/// What a client asks a document to be sent as, by its `Accept` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Representation {
+    /// The extracted file as it is, such as Markdown
+    Source,
+    /// The rendered page
+    Html,
+    /// The file's content and details as JSON
+    Json,
+}
+
+impl Representation {
+    /// The representation a client prefers most, `Source` when it states no
+    /// preference, or `None` when it accepts none of them
+    fn negotiate(accept: Option<&str>) -> Option<Self> {
+        let Some(accept) = accept else {
+            return Some(Self::Source);
+        };
+        let mut best: Option<(f32, Self)> = None;
+        for range in accept.split(',') {
+            let mut parameters = range.split(';');
+            let media_type = parameters.next().unwrap_or("").trim().to_ascii_lowercase();
+            let quality = parameters
+                .find_map(|parameter| parameter.trim().strip_prefix("q=")?.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            let representation = match media_type.as_str() {
+                "text/markdown" | "text/plain" | "text/*" | "*/*" => Self::Source,
+                "text/html" => Self::Html,
+                "application/json" => Self::Json,
+                _ => continue,
+            };
+            if quality > 0.0 && best.is_none_or(|(best, _)| quality > best) {
+                best = Some((quality, representation));
+            }
+        }
+        best.map(|(_, representation)| representation)
+    }
+}
+
+impl Site {
+    /// Render the extraction in `output_dir` to a temporary directory
+    fn render(output_dir: &Path, style: &HtmlConfig) -> Result<Self> {
+        let manifest = Manifest::load(&output_dir.join(".repodocs"))?.ok_or_else(|| {
+            RepoDocsError::InvalidPath {
+                path: format!(
+                    "{} has no extraction manifest; it is not an extraction or the extraction did not finish",
+                    output_dir.display()
+                ),
+            }
+        })?;
+
+        let documents: Vec<DocumentFile> = manifest
+            .entries
+            .iter()
+            .map(|entry| {
+                let path = output_dir.join(&entry.extracted);
+                let modified = SystemTime::UNIX_EPOCH
+                    + Duration::from_secs(entry.modified.unwrap_or_default());
+                let mut document = DocumentFile::new(
+                    path.clone(),
+                    PathBuf::from(&entry.path),
+                    entry.size,
+                    modified,
+                );
+                document.front_matter = FrontMatter::read(&path);
+                document
+            })
+            .collect();
+
+        let dir = TempDir::new().map_err(RepoDocsError::Io)?;
+        let rendered = HtmlSite::render_to(&documents, &manifest, output_dir, dir.path(), style)?;
+
+        let extracted: HashMap<&str, (&str, u64)> = manifest
+            .entries
+            .iter()
+            .map(|entry| (entry.path.as_str(), (entry.extracted.as_str(), entry.size)))
+            .collect();
+        let documents = rendered
+            .pages
+            .iter()
+            .filter_map(|page| {
+                let (file, size) = extracted.get(page.source.as_str())?;
+                Some(SiteDocument {
+                    path: page.source.clone(),
+                    title: page.title.clone(),
+                    page: format!("/{}", page.page.to_string_lossy().replace('\\', "/")),
+                    size: *size,
+                    extracted: file.to_string(),
+                })
+            })
+            .collect();
+
+        let now = Utc::now();
+        Ok(Self {
+            root: dir.path().canonicalize().map_err(RepoDocsError::Io)?,
+            dir,
+            output_dir: output_dir.canonicalize().map_err(RepoDocsError::Io)?,
+            rendered_at: now.with_nanosecond(0).unwrap_or(now),
+            documents,
+            search_index: rendered.search_index,
+        })
+    }
+
+    /// Answer a request for `path` below `/_repodocs/` other than the event
+    /// stream, or `None` when there is no such endpoint
+    fn api(&self, path: &str, target: &str, headers: &HashMap<String, String>) -> Option<Response> {
+        if path == DOCUMENTS_PATH {
+            return Some(self.json_response(&json!({ "documents": self.documents }), headers));
+        }
+        if path == SEARCH_PATH {
+            let query = query_parameter(target, "q").unwrap_or_default();
+            let results: Vec<_> = self
+                .search_index
+                .search(&query, SEARCH_RESULTS)
+                .into_iter()
+                .filter_map(|result| {
+                    let page = format!("/{}", result.page);
+                    let document = self
+                        .documents
+                        .iter()
+                        .find(|document| document.page == page)?;
+                    Some(json!({
+                        "path": document.path,
+                        "title": document.title,
+                        "page": document.page,
+                        "excerpt": result.excerpt,
+                    }))
+                })
+                .collect();
+            return Some(
+                self.json_response(&json!({ "query": query, "results": results }), headers),
+            );
+        }
+
+        let path = percent_decode(path.strip_prefix(DOCUMENTS_PATH)?.strip_prefix('/')?)?;
+        let response = match self.documents.iter().find(|document| document.path == path) {
+            Some(document) => self.document_response(document, headers),
+            None => Response::not_found(),
+        };
+        Some(response.with_header("Vary", "Accept".to_string()))
+    }
+
+    /// A document in the representation the client prefers: the extracted
+    /// file, its content and details as JSON, or a redirect to its page
+    fn document_response(
+        &self,
+        document: &SiteDocument,
+        headers: &HashMap<String, String>,
+    ) -> Response {
+        let representation = Representation::negotiate(headers.get("accept").map(String::as_str));
+        let content = || {
+            // The manifest is part of the served directory, so its paths are
+            // not trusted to stay inside it
+            let file = self
+                .output_dir
+                .join(&document.extracted)
+                .canonicalize()
+                .ok()?;
+            file.starts_with(&self.output_dir)
+                .then(|| fs::read_to_string(file).ok())
+                .flatten()
+        };
+
+        match representation {
+            Some(Representation::Html) => Response::new(
+                303,
+                "See Other",
+                "text/plain; charset=utf-8",
+                format!("See {}\n", document.page).into_bytes(),
+            )
+            .with_header("Location", percent_encode(&document.page)),
+            Some(Representation::Source) => match content() {
+                Some(content) => {
+                    let content_type = if markdown::is_markdown_path(&document.extracted) {
+                        "text/markdown; charset=utf-8"
+                    } else {
+                        "text/plain; charset=utf-8"
+                    };
+                    self.file_response(content_type, content.into_bytes(), headers)
+                }
+                None => Response::not_found(),
+            },
+            Some(Representation::Json) => match content() {
+                Some(content) => {
+                    let mut details = json!(document);
+                    details["content"] = json!(content);
+                    self.json_response(&details, headers)
+                }
+                None => Response::not_found(),
+            },
+            None => Response::new(
+                406,
+                "Not Acceptable",
+                "text/plain; charset=utf-8",
+                b"Documents are available as text/markdown, text/html or application/json\n"
+                    .to_vec(),
+            ),
+        }
+    }
+
+    fn json_response(
+        &self,
+        value: &serde_json::Value,
+        headers: &HashMap<String, String>,
+    ) -> Response {
+        let body = serde_json::to_vec_pretty(value).unwrap_or_default();
+        self.file_response("application/json", body, headers)
+    }
+
+    /// A file of the site, or `304 Not Modified` when the client's copy is
+    /// current. Rendered pages also show their neighbours' titles and the
+    /// theme, so the strong `ETag` is the BLAKE3 hash of the file as served
+    /// rather than the manifest hash of its source.
+    fn file_response(
+        &self,
+        content_type: &'static str,
+        body: Vec<u8>,
+        headers: &HashMap<String, String>,
+    ) -> Response {
+        let etag = format!("\"{}\"", blake3::hash(&body).to_hex());
+        // `If-None-Match` takes precedence over `If-Modified-Since`
+        let current = match headers.get("if-none-match") {
+            Some(tags) => tags
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag),
+            None => headers
+                .get("if-modified-since")
+                .and_then(|since| DateTime::parse_from_rfc2822(since).ok())
+                .is_some_and(|since| self.rendered_at <= since),
+        };
+
+        let response = match current {
+            true => Response::new(304, "Not Modified", content_type, Vec::new()),
+            false => Response::new(200, "OK", content_type, body),
+        };
+        response.with_header("ETag", etag).with_header(
+            "Last-Modified",
+            self.rendered_at
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string(),
+        )
+    }
+}
+
+/// Browsers of live-reloading pages, kept on the event stream of
+/// `EVENTS_PATH` without holding up a worker
+#[derive(Default)]
+struct LiveReload {
+    /// Number of renderings since the server started
+    generation: AtomicU64,
+    subscribers: Mutex<Vec<TcpStream>>,
+}
+
+impl LiveReload {
+    /// Add the script reloading the page on the next rendering to an HTML
+    /// page, before `</body>`
+    fn inject(&self, body: Vec<u8>) -> Vec<u8> {
+        let script = format!(
+            "<script>new EventSource(\"{}?since={}\").addEventListener(\"reload\", function () {{ location.reload(); }});</script>\n",
+            EVENTS_PATH,
+            self.generation.load(Ordering::SeqCst)
+        );
+        let end = body
+            .windows(7)
+            .rposition(|window| window.eq_ignore_ascii_case(b"</body>"))
+            .unwrap_or(body.len());
+        [&body[..end], script.as_bytes(), &body[end..]].concat()
+    }
+
+    /// Keep `stream` open for reload events. A browser that saw an older
+    /// rendering than the current one, as told by `Last-Event-ID` on a
+    /// reconnect or the `since` of the page, is reloaded right away.
+    fn subscribe(
+        &self,
+        mut stream: TcpStream,
+        target: &str,
+        last_event_id: Option<&str>,
+    ) -> io::Result<()> {
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        if subscribers.len() >= MAX_SUBSCRIBERS {
+            drop(subscribers);
+            return turn_away(stream);
+        }
+
+        stream.set_write_timeout(Some(EVENT_TIMEOUT))?;
+        stream.write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n",
+        )?;
+        let since = last_event_id
+            .map(str::to_string)
+            .or_else(|| query_parameter(target, "since"));
+        let generation = self.generation.load(Ordering::SeqCst);
+        if since
+            .and_then(|since| since.trim().parse::<u64>().ok())
+            .is_some_and(|since| since < generation)
+        {
+            stream.write_all(reload_event(generation).as_bytes())?;
+        }
+        stream.flush()?;
+        subscribers.push(stream);
+        Ok(())
+    }
+
+    /// Send `message` to every subscriber, dropping the ones that went away
+    fn send(&self, message: &str) {
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        subscribers.retain_mut(|stream| {
+            stream
+                .write_all(message.as_bytes())
+                .and_then(|()| stream.flush())
+                .is_ok()
+        });
+    }
+
+    fn subscribers(&self) -> usize {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+}
+
+fn reload_event(generation: u64) -> String {
+    format!("id: {0}\nevent: reload\ndata: {0}\n\n", generation)
+}
+
+/// An extraction rendered to HTML and served over HTTP (`--serve`). The
+/// pages are the ones `--render html` writes, with navigation, breadcrumbs
+/// and search, rendered to a temporary directory so the extraction is left
+/// untouched. Only `GET` and `HEAD` requests for files of the site are
+/// answered, by a fixed number of worker threads; files carry an `ETag`
+/// and `Last-Modified` so that clients revalidating them get `304 Not
+/// Modified`.
+///
+/// Below `/_repodocs/`, the extraction is also served to programs: the
+/// index of its documents and search results as JSON, and each document as
+/// its extracted file, as JSON, or as a redirect to its page, by the
+/// client's `Accept` header.
+///
+/// With live reload (`--watch --live-reload`), pages subscribe to a stream
+/// of server-sent events and reload themselves when [`DocsServer::reload`]
+/// renders the extraction again.
+pub struct DocsServer {
+    output_dir: PathBuf,
+    style: HtmlConfig,
+    credentials: Credentials,
+    site: RwLock<Arc<Site>>,
+    live: Option<LiveReload>,
+}
+
+impl DocsServer {
+    /// Render the extraction in `output_dir`, a `docs_<name>` directory
+    /// with a manifest, in the `[output.html]` style, to be served to
+    /// clients presenting `credentials`. Search is always on.
+    pub fn render(output_dir: &Path, style: &HtmlConfig, credentials: Credentials) -> Result<Self> {
+        // A local server has no public URL to list in a sitemap
+        let style = HtmlConfig {
+            search: true,
+            site_url: None,
+            robots_disallow: Vec::new(),
+            ..style.clone()
+        };
+        let site = Site::render(output_dir, &style)?;
+        Ok(Self {
+            output_dir: output_dir.to_path_buf(),
+            style,
+            credentials,
+            site: RwLock::new(Arc::new(site)),
+            live: None,
+        })
+    }
+
+    /// Make served pages reload themselves after [`DocsServer::reload`]
+    pub fn with_live_reload(mut self) -> Self {
+        self.live = Some(LiveReload::default());
+        self
+    }
+
+    /// Render the extraction again, serve the new rendering from now on,
+    /// and reload the pages open in browsers. Returns the number of pages.
+    pub fn reload(&self) -> Result<usize> {
+        let site = Site::render(&self.output_dir, &self.style)?;
+        let pages = site.documents.len();
+        *self.site.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(site);
+        if let Some(live) = &self.live {
+            let generation = live.generation.fetch_add(1, Ordering::SeqCst) + 1;
+            live.send(&reload_event(generation));
+        }
+        Ok(pages)
+    }
+
+    /// The rendering requests are answered from
+    fn site(&self) -> Arc<Site> {
+        Arc::clone(&self.site.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Number of rendered pages, the index not included
+    pub fn pages(&self) -> usize {
+        self.site().documents.len()
+    }
+
+    /// Directory the site is rendered to
+    pub fn site_dir(&self) -> PathBuf {
+        self.site().dir.path().to_path_buf()
+    }
+
+    /// Number of browsers waiting for reload events
+    pub fn live_subscribers(&self) -> usize {
+        self.live.as_ref().map_or(0, LiveReload::subscribers)
+    }
+
+    /// Answer requests on `listener` until shutdown is requested, then stop
+    /// accepting connections and give the requests in progress up to
+    /// `DRAIN_TIMEOUT` to finish. Connections arriving while every worker
+    /// is busy and the queue is full get `503 Service Unavailable`. Returns
+    /// the number of workers still busy when the server gave up waiting.
+    pub fn run(
+        self: Arc<Self>,
+        listener: TcpListener,
+        shutdown: &GracefulShutdown,
+    ) -> Result<usize> {
+        listener.set_nonblocking(true).map_err(RepoDocsError::Io)?;
+
+        let (queue, connections) = mpsc::sync_channel::<TcpStream>(QUEUE);
+        let connections = Arc::new(Mutex::new(connections));
+        let workers: Vec<_> = (0..WORKERS)
+            .map(|_| {
+                let server = Arc::clone(&self);
+                let connections = Arc::clone(&connections);
+                thread::spawn(move || loop {
+                    let next = connections.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                    let Ok(stream) = next else {
+                        return;
+                    };
+                    // A client hanging up early is not the server's problem
+                    let _ = server.respond(stream);
+                })
+            })
+            .collect();
+
+        let mut last_keep_alive = Instant::now();
+        while shutdown.is_running() {
+            if let Some(live) = &self.live {
+                if last_keep_alive.elapsed() >= KEEP_ALIVE {
+                    live.send(": keep-alive\n\n");
+                    last_keep_alive = Instant::now();
+                }
+            }
+
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    if let Err(TrySendError::Full(stream)) = queue.try_send(stream) {
+                        let _ = turn_away(stream);
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(RepoDocsError::Io(e)),
+            }
+        }
+
+        // Workers finish the queued connections, then see the queue closed;
+        // event streams end at once, browsers reconnect on their own
+        if let Some(live) = &self.live {
+            live.subscribers
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clear();
+        }
+        drop(listener);
+        drop(queue);
+        let deadline = Instant::now() + DRAIN_TIMEOUT;
+        while Instant::now() < deadline && workers.iter().any(|worker| !worker.is_finished()) {
+            thread::sleep(POLL);
+        }
+        Ok(workers
+            .iter()
+            .filter(|worker| !worker.is_finished())
+            .count())
+    }
+
+    fn respond(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+        let mut reader = BufReader::new(stream.try_clone()?.take(MAX_REQUEST_BYTES));
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut headers: HashMap<String, String> = HashMap::new();
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0 && !line.trim().is_empty() {
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+            }
+            line.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+        let events = method == "GET" && target.split(['?', '#']).next() == Some(EVENTS_PATH);
+        let response = if !matches!(method, "GET" | "HEAD") {
+            Response::new(
+                405,
+                "Method Not Allowed",
+                "text/plain; charset=utf-8",
+                b"Method not allowed\n".to_vec(),
+            )
+        } else if !self
+            .credentials
+            .allows(headers.get("authorization").map(String::as_str))
+        {
+            Response::new(
+                401,
+                "Unauthorized",
+                "text/plain; charset=utf-8",
+                b"Authentication required\n".to_vec(),
+            )
+            .with_header("WWW-Authenticate", self.credentials.challenge().to_string())
+        } else if let (Some(live), true) = (&self.live, events) {
+            return live.subscribe(
+                stream,
+                target,
+                headers.get("last-event-id").map(String::as_str),
+            );
+        } else {
+            let site = self.site();
+            let path = target.split(['?', '#']).next().unwrap_or("");
+            match site.api(path, target, &headers) {
+                Some(response) => response,
+                None => self.file(&site, target, &headers),
+            }
+        };
+        response.write(stream, method == "HEAD")
+    }
+
+    /// A file of the current rendering, with the live reload script added
+    /// to pages when live reload is on
+    fn file(&self, site: &Site, target: &str, headers: &HashMap<String, String>) -> Response {
+        let Some((body, file)) =
+            resolve(&site.root, target).and_then(|file| Some((fs::read(&file).ok()?, file)))
+        else {
+            return Response::not_found();
+        };
+        let body = match &self.live {
+            Some(live) if content_type(&file).starts_with("text/html") => live.inject(body),
+            _ => body,
+        };
+        site.file_response(content_type(&file), body, headers)
+    }
+}
+
+fn turn_away(stream: TcpStream) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_write_timeout(Some(Duration::from_secs(1)))?;
+    Response::new(
+        503,
+        "Service Unavailable",
+        "text/plain; charset=utf-8",
+        b"Too many connections; try again\n".to_vec(),
+    )
+    .with_header("Retry-After", "1".to_string())
+    .write(stream, false)
+}
+
+struct Response {
+    status: u16,
+    reason: &'static str,
+    content_type: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn new(status: u16, reason: &'static str, content_type: &'static str, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            reason,
+            content_type,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    fn with_header(mut self, name: &'static str, value: String) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
+    fn not_found() -> Self {
+        Self::new(
+            404,
+            "Not Found",
+            "text/html; charset=utf-8",
+            b"<!DOCTYPE html>\n<title>Not found</title>\n<p>Page not found. \
+              <a href=\"/index.html\">Documentation Index</a></p>\n"
+                .to_vec(),
+        )
+    }
+
+    fn write(&self, mut stream: TcpStream, head_only: bool) -> io::Result<()> {
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, self.reason);
+        // A `304` describes the client's copy and has no content of its own
+        if self.status != 304 {
+            head.push_str(&format!(
+                "Content-Type: {}\r\nContent-Length: {}\r\n",
+                self.content_type,
+                self.body.len()
+            ));
+        }
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("Cache-Control: no-cache\r\nConnection: close\r\n\r\n");
+
+        stream.write_all(head.as_bytes())?;
+        if !head_only {
+            stream.write_all(&self.body)?;
+        }
+        stream.flush()
+    }
+}
+
+/// The file of the site a request target asks for: directories serve their
+/// `index.html`, and paths leading outside the site, including through
+/// symbolic links, are refused
+fn resolve(root: &Path, target: &str) -> Option<PathBuf> {
+    let path = target.split(['?', '#']).next().unwrap_or("");
+    let path = percent_decode(path.strip_prefix('/')?)?;
+
+    let mut relative = PathBuf::new();
+    for component in Path::new(&path).components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    let mut file = root.join(relative);
+    if file.is_dir() {
+        file.push("index.html");
+    }
+    let file = file.canonicalize().ok()?;
+    (file.starts_with(root) && file.is_file()).then_some(file)
+}
+
+/// The value of the parameter `name` in the query of a request target
+fn query_parameter(target: &str, name: &str) -> Option<String> {
+    let query = target.split('#').next()?.split_once('?')?.1;
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (key == name).then(|| percent_decode(&value.replace('+', " ")))?
+    })
+}
+
+/// Escape the characters of a URL path that may not appear in it as they
+/// are, such as spaces
+fn percent_encode(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "txt" => "text/plain; charset=utf-8",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extractor::manifest::ManifestEntry;
+
+    fn get(address: std::net::SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    /// Write `files` to `output_dir` and list them in a manifest, which is
+    /// only saved when `save` is set
+    fn write_extraction(output_dir: &Path, files: &[(&str, &str)], save: bool) -> Manifest {
+        let mut manifest = Manifest::default();
+        for (name, content) in files {
+            let path = output_dir.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            manifest.entries.push(ManifestEntry {
+                path: name.to_string(),
+                extracted: name.to_string(),
+                size: content.len() as u64,
+                modified: None,
+                hash: String::new(),
+                sha256: None,
+            });
+        }
+        if save {
+            let metadata_dir = output_dir.join(".repodocs");
+            fs::create_dir_all(&metadata_dir).unwrap();
+            manifest.save(&metadata_dir).unwrap();
+        }
+        manifest
+    }
+
+    #[test]
+    fn test_serve_extraction() {
+        let output_dir = TempDir::new().unwrap();
+        let manifest = write_extraction(
+            output_dir.path(),
+            &[
+                (
+                    "README.md",
+                    "# Project\n\nSee the [guide](docs/user%20guide.md).\n",
+                ),
+                ("docs/user guide.md", "# Guide\n"),
+            ],
+            false,
+        );
+
+        let credentials = Credentials::new(Some("secret"), Some("reader:pass")).unwrap();
+        assert!(DocsServer::render(
+            output_dir.path(),
+            &HtmlConfig::default(),
+            credentials.clone()
+        )
+        .is_err());
+        let metadata_dir = output_dir.path().join(".repodocs");
+        fs::create_dir_all(&metadata_dir).unwrap();
+        manifest.save(&metadata_dir).unwrap();
+        let server = Arc::new(
+            DocsServer::render(output_dir.path(), &HtmlConfig::default(), credentials).unwrap(),
+        );
+        assert_eq!(server.pages(), 2);
+        // The extraction itself is left as it was
+        assert!(!output_dir.path().join("html").exists());
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let shutdown = GracefulShutdown::new_for_test();
+        let running = {
+            let shutdown = shutdown.clone();
+            thread::spawn(move || server.run(listener, &shutdown))
+        };
+
+        let bearer = "Authorization: Bearer secret\r\n";
+        let request = |line: &str, headers: &str| {
+            get(address, &format!("{} HTTP/1.1\r\n{}\r\n", line, headers))
+        };
+        let anonymous = request("GET /", "");
+        let index = request("GET /", bearer);
+        let guide = request(
+            "GET /docs/user%20guide.html?q=1",
+            &format!("Authorization: Basic {}\r\n", BASE64.encode("reader:pass")),
+        );
+        let search = request("HEAD /search-index.js", bearer);
+        let outside = request("GET /../secret", bearer);
+        let post = request("POST /index.html", bearer);
+
+        assert!(anonymous.starts_with("HTTP/1.1 401 Unauthorized"));
+        assert!(anonymous.contains("WWW-Authenticate: Basic realm=\"repodocs\""));
+        assert!(request("GET /", "Authorization: Bearer wrong\r\n").starts_with("HTTP/1.1 401"));
+        assert!(index.starts_with("HTTP/1.1 200 OK\r\nContent-Type: text/html"));
+        assert!(index.contains("Documentation Index"));
+        assert!(index.contains("href=\"docs/user guide.html\""));
+        assert!(!index.contains("EventSource"));
+        assert!(request("GET /_repodocs/events", bearer).starts_with("HTTP/1.1 404"));
+        assert!(guide.contains("<h1>Guide</h1>"));
+        assert!(search.starts_with("HTTP/1.1 200 OK\r\nContent-Type: text/javascript"));
+        assert!(search.ends_with("\r\n\r\n"));
+        assert!(outside.starts_with("HTTP/1.1 404 Not Found"));
+        assert!(post.starts_with("HTTP/1.1 405"));
+
+        // Clients revalidating their copy do not download it again
+        let header = |response: &str, name: &str| -> String {
+            response
+                .lines()
+                .find_map(|line| line.strip_prefix(name))
+                .unwrap()
+                .to_string()
+        };
+        let etag = header(&index, "ETag: ");
+        let last_modified = header(&index, "Last-Modified: ");
+        let unchanged = request("GET /", &format!("{}If-None-Match: {}\r\n", bearer, etag));
+        assert!(unchanged.starts_with("HTTP/1.1 304 Not Modified"));
+        assert!(unchanged.ends_with("\r\n\r\n"));
+        assert!(!unchanged.contains("Content-Length"));
+        let changed = request("GET /", &format!("{}If-None-Match: \"other\"\r\n", bearer));
+        assert!(changed.starts_with("HTTP/1.1 200"));
+        let since = |date: &str| {
+            request(
+                "GET /",
+                &format!("{}If-Modified-Since: {}\r\n", bearer, date),
+            )
+        };
+        assert!(since(&last_modified).starts_with("HTTP/1.1 304"));
+        assert!(since("Mon, 01 Jan 2024 00:00:00 GMT").starts_with("HTTP/1.1 200"));
+
+        shutdown.request_shutdown();
+        assert_eq!(running.join().unwrap().unwrap(), 0);
+        assert!(TcpStream::connect(address).is_err());
+
+        assert!(Credentials::new(None, Some("reader")).is_err());
+        assert!(Credentials::new(Some(" "), None).is_err());
+        assert!(Credentials::new(None, None).unwrap().is_empty());
+        assert_eq!(percent_decode("a%20b%2Fc").as_deref(), Some("a b/c"));
+        assert_eq!(percent_decode("%zz"), None);
+    }
+
+    #[test]
+    fn test_documentation_api() {
+        let output_dir = TempDir::new().unwrap();
+        write_extraction(
+            output_dir.path(),
+            &[
+                ("README.md", "# Project\n\nInstall it first.\n"),
+                ("docs/user guide.md", "# Guide\n\nInstall the hooks.\n"),
+                ("notes.txt", "Plain notes\n"),
+            ],
+            true,
+        );
+        let server = Arc::new(
+            DocsServer::render(
+                output_dir.path(),
+                &HtmlConfig::default(),
+                Credentials::default(),
+            )
+            .unwrap(),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let shutdown = GracefulShutdown::new_for_test();
+        let running = {
+            let shutdown = shutdown.clone();
+            thread::spawn(move || server.run(listener, &shutdown))
+        };
+
+        let request = |target: &str, headers: &str| {
+            get(
+                address,
+                &format!("GET {} HTTP/1.1\r\n{}\r\n", target, headers),
+            )
+        };
+        let json = |response: &str| -> serde_json::Value {
+            assert!(response.starts_with("HTTP/1.1 200 OK\r\nContent-Type: application/json"));
+            serde_json::from_str(response.split_once("\r\n\r\n").unwrap().1).unwrap()
+        };
+
+        let index = json(&request("/_repodocs/documents", ""));
+        let guide = index["documents"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|document| document["path"] == "docs/user guide.md")
+            .unwrap();
+        assert_eq!(index["documents"].as_array().unwrap().len(), 3);
+        assert_eq!(guide["title"], "user guide.md");
+        assert_eq!(guide["page"], "/docs/user guide.html");
+        assert!(guide.get("extracted").is_none());
+
+        // Documents in the representation the client asks for
+        let target = "/_repodocs/documents/docs/user%20guide.md";
+        let source = request(target, "");
+        assert!(source.starts_with("HTTP/1.1 200 OK\r\nContent-Type: text/markdown"));
+        assert!(source.contains("Vary: Accept\r\n"));
+        assert!(source.ends_with("\r\n\r\n# Guide\n\nInstall the hooks.\n"));
+        let details = json(&request(target, "Accept: application/json\r\n"));
+        assert_eq!(details["path"], "docs/user guide.md");
+        assert_eq!(details["content"], "# Guide\n\nInstall the hooks.\n");
+        let page = request(
+            target,
+            "Accept: text/html,application/xhtml+xml,*/*;q=0.8\r\n",
+        );
+        assert!(page.starts_with("HTTP/1.1 303 See Other"));
+        assert!(page.contains("Location: /docs/user%20guide.html\r\n"));
+        assert!(request(target, "Accept: text/html;q=0, text/markdown\r\n").contains("# Guide"));
+        assert!(request(target, "Accept: image/png\r\n").starts_with("HTTP/1.1 406"));
+        assert!(request("/_repodocs/documents/notes.txt", "")
+            .starts_with("HTTP/1.1 200 OK\r\nContent-Type: text/plain"));
+        assert!(request("/_repodocs/documents/missing.md", "").starts_with("HTTP/1.1 404"));
+        assert!(request("/_repodocs/other", "").starts_with("HTTP/1.1 404"));
+
+        let etag = source
+            .lines()
+            .find_map(|line| line.strip_prefix("ETag: "))
+            .unwrap();
+        let unchanged = request(target, &format!("If-None-Match: {}\r\n", etag));
+        assert!(unchanged.starts_with("HTTP/1.1 304 Not Modified"));
+
+        let search = json(&request("/_repodocs/search?q=install", ""));
+        assert_eq!(search["query"], "install");
+        assert_eq!(search["results"].as_array().unwrap().len(), 2);
+        let search = json(&request("/_repodocs/search?q=install+hooks", ""));
+        assert_eq!(search["results"].as_array().unwrap().len(), 1);
+        assert_eq!(search["results"][0]["path"], "docs/user guide.md");
+        assert_eq!(search["results"][0]["page"], "/docs/user guide.html");
+        assert_eq!(search["results"][0]["excerpt"], "Guide Install the hooks.");
+        assert_eq!(
+            query_parameter("/s?a=1&q=a%2Bb+c#top", "q").as_deref(),
+            Some("a+b c")
+        );
+
+        shutdown.request_shutdown();
+        assert_eq!(running.join().unwrap().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_live_reload() {
+        let output_dir = TempDir::new().unwrap();
+        write_extraction(output_dir.path(), &[("README.md", "# Project\n")], true);
+        let server = Arc::new(
+            DocsServer::render(
+                output_dir.path(),
+                &HtmlConfig::default(),
+                Credentials::default(),
+            )
+            .unwrap()
+            .with_live_reload(),
+        );
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let shutdown = GracefulShutdown::new_for_test();
+        let running = {
+            let (server, shutdown) = (Arc::clone(&server), shutdown.clone());
+            thread::spawn(move || server.run(listener, &shutdown))
+        };
+
+        let index = get(address, "GET / HTTP/1.1\r\n\r\n");
+        assert!(index.contains("EventSource(\"/_repodocs/events?since=0\")"));
+        assert!(index.contains("</script>\n</body>"));
+
+        // Open an event stream and read past its response head
+        let subscribe = |target: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            stream
+                .write_all(format!("GET {} HTTP/1.1\r\n\r\n", target).as_bytes())
+                .unwrap();
+            let mut events = BufReader::new(stream);
+            let mut head = String::new();
+            while !head.ends_with("\r\n\r\n") {
+                assert!(events.read_line(&mut head).unwrap() > 0);
+            }
+            assert!(head.contains("Content-Type: text/event-stream"));
+            events
+        };
+        let next_event = |events: &mut BufReader<TcpStream>| {
+            let mut event = String::new();
+            while !event.ends_with("\n\n") {
+                assert!(events.read_line(&mut event).unwrap() > 0);
+            }
+            event
+        };
+
+        let mut events = subscribe("/_repodocs/events?since=0");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while server.live_subscribers() == 0 && Instant::now() < deadline {
+            thread::sleep(POLL);
+        }
+        assert_eq!(server.live_subscribers(), 1);
+
+        fs::write(output_dir.path().join("README.md"), "# Changed\n").unwrap();
+        assert_eq!(server.reload().unwrap(), 1);
+        assert_eq!(next_event(&mut events), "id: 1\nevent: reload\ndata: 1\n\n");
+        let index = get(address, "GET / HTTP/1.1\r\n\r\n");
+        assert!(index.contains("since=1"));
+        let page = get(address, "GET /README.html HTTP/1.1\r\n\r\n");
+        assert!(page.contains("<h1>Changed</h1>"));
+
+        // A browser that missed a reload, holding an older page or
+        // reconnecting, is reloaded at once
+        let mut stale = subscribe("/_repodocs/events?since=0");
+        assert_eq!(next_event(&mut stale), "id: 1\nevent: reload\ndata: 1\n\n");
+        let mut reconnected = {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+            stream
+                .write_all(b"GET /_repodocs/events HTTP/1.1\r\nLast-Event-ID: 0\r\n\r\n")
+                .unwrap();
+            BufReader::new(stream)
+        };
+        let mut response = String::new();
+        while !response.ends_with("data: 1\n\n") {
+            assert!(reconnected.read_line(&mut response).unwrap() > 0);
+        }
+
+        // Shutting down ends the event streams
+        shutdown.request_shutdown();
+        assert_eq!(running.join().unwrap().unwrap(), 0);
+        let mut rest = String::new();
+        events.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "");
+    }
+}
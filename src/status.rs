@@ -0,0 +1,221 @@
+use crate::error::{RepoDocsError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// File name of the run status, written to the base output directory
+pub const STATUS_FILE: &str = "status.json";
+
+/// Step a source is currently in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    Cloning,
+    Scanning,
+    Extracting,
+    Reporting,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Stage::Cloning => "cloning",
+            Stage::Scanning => "scanning",
+            Stage::Extracting => "extracting",
+            Stage::Reporting => "reporting",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A source being extracted right now
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveSource {
+    pub source: String,
+    pub stage: Stage,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Snapshot of a run, as written to `status.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunStatus {
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    /// Share of sources completed, once the total is known
+    pub percent: Option<f64>,
+    /// Estimated seconds until the run ends, from the average time per
+    /// completed source
+    pub eta_seconds: Option<u64>,
+    pub active: Vec<ActiveSource>,
+    pub finished: bool,
+}
+
+/// Keeps `status.json` up to date for dashboards polling a long run. The
+/// file is rewritten on every change of stage and replaced atomically, so
+/// readers never see a partial write.
+pub struct StatusTracker {
+    path: PathBuf,
+    started: Mutex<Instant>,
+    status: Mutex<RunStatus>,
+}
+
+impl StatusTracker {
+    pub fn new(dir: &Path) -> Self {
+        let now = Utc::now();
+        Self {
+            path: dir.join(STATUS_FILE),
+            started: Mutex::new(Instant::now()),
+            status: Mutex::new(RunStatus {
+                started_at: now,
+                updated_at: now,
+                total: 0,
+                completed: 0,
+                failed: 0,
+                percent: None,
+                eta_seconds: None,
+                active: Vec::new(),
+                finished: false,
+            }),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Begin a run of `total` sources
+    pub fn start(&self, total: usize) -> Result<()> {
+        *self.started.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+        self.update(|status| {
+            let now = Utc::now();
+            status.started_at = now;
+            status.total = total;
+            status.completed = 0;
+            status.failed = 0;
+            status.active.clear();
+            status.finished = false;
+        })
+    }
+
+    pub fn set_stage(&self, source: &str, stage: Stage) -> Result<()> {
+        self.update(|status| {
+            match status
+                .active
+                .iter_mut()
+                .find(|active| active.source == source)
+            {
+                Some(active) => active.stage = stage,
+                None => status.active.push(ActiveSource {
+                    source: source.to_string(),
+                    stage,
+                    started_at: Utc::now(),
+                }),
+            }
+        })
+    }
+
+    /// Record that a source is done, successfully or not
+    pub fn complete(&self, source: &str, failed: bool) -> Result<()> {
+        self.update(|status| {
+            status.active.retain(|active| active.source != source);
+            status.completed += 1;
+            if failed {
+                status.failed += 1;
+            }
+        })
+    }
+
+    pub fn finish(&self) -> Result<()> {
+        self.update(|status| {
+            status.active.clear();
+            status.finished = true;
+        })
+    }
+
+    fn update(&self, change: impl FnOnce(&mut RunStatus)) -> Result<()> {
+        let elapsed = self
+            .started
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .elapsed();
+        let mut status = self.status.lock().unwrap_or_else(|e| e.into_inner());
+        change(&mut status);
+
+        status.updated_at = Utc::now();
+        status.percent =
+            (status.total > 0).then(|| status.completed as f64 / status.total as f64 * 100.0);
+        status.eta_seconds = (status.completed > 0 && !status.finished).then(|| {
+            let remaining = status.total.saturating_sub(status.completed);
+            (elapsed.as_secs_f64() / status.completed as f64 * remaining as f64).round() as u64
+        });
+
+        self.write(&status)
+    }
+
+    /// Write to a temporary file next to the status file and rename it over
+    /// the old one
+    fn write(&self, status: &RunStatus) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(RepoDocsError::Io)?;
+        }
+
+        let json = serde_json::to_string_pretty(status).map_err(|e| RepoDocsError::Config {
+            message: format!("Failed to serialize run status: {}", e),
+        })?;
+        let temp_path = self.path.with_extension("json.tmp");
+        fs::write(&temp_path, json).map_err(RepoDocsError::Io)?;
+        fs::rename(&temp_path, &self.path).map_err(RepoDocsError::Io)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn read_status(tracker: &StatusTracker) -> RunStatus {
+        serde_json::from_str(&fs::read_to_string(tracker.path()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_status_file_follows_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let tracker = StatusTracker::new(temp_dir.path());
+
+        tracker.start(2).unwrap();
+        tracker
+            .set_stage("https://github.com/a/one", Stage::Cloning)
+            .unwrap();
+        tracker
+            .set_stage("https://github.com/a/one", Stage::Extracting)
+            .unwrap();
+
+        let status = read_status(&tracker);
+        assert_eq!(status.total, 2);
+        assert_eq!(status.percent, Some(0.0));
+        assert_eq!(status.active.len(), 1);
+        assert_eq!(status.active[0].stage, Stage::Extracting);
+        assert_eq!(status.eta_seconds, None);
+
+        tracker.complete("https://github.com/a/one", false).unwrap();
+        let status = read_status(&tracker);
+        assert!(status.active.is_empty());
+        assert_eq!(status.percent, Some(50.0));
+        assert!(status.eta_seconds.is_some());
+
+        tracker.complete("https://github.com/a/two", true).unwrap();
+        tracker.finish().unwrap();
+        let status = read_status(&tracker);
+        assert_eq!(status.failed, 1);
+        assert!(status.finished);
+        assert!(!temp_dir.path().join("status.json.tmp").exists());
+    }
+}
@@ -1,4 +1,6 @@
+use crate::batch::{BatchReport, BatchStatus};
 use crate::error::{RepoDocsError, UserFriendlyError};
+use crate::extractor::budget::{budget_table, DirectoryBudget};
 use crate::extractor::{ExtractionProgress, ExtractionReport};
 use console::{style, Emoji, Term};
 use serde_json;
@@ -29,6 +31,7 @@ static WARNING: Emoji = Emoji("⚠️  ", "! ");
 static ROCKET: Emoji = Emoji("🚀 ", "> ");
 static SPARKLES: Emoji = Emoji("✨ ", "* ");
 
+#[derive(Clone)]
 pub struct OutputFormatter {
     #[allow(dead_code)]
     term: Term,
@@ -182,6 +185,71 @@ impl OutputFormatter {
     }
 
     // Specialized output methods
+    pub fn print_batch_report(&self, batch: &BatchReport) {
+        match self.mode {
+            OutputMode::Human => self.print_human_batch_report(batch),
+            OutputMode::Json => {
+                let json_output =
+                    serde_json::to_string_pretty(batch).unwrap_or_else(|_| "{}".to_string());
+                println!("{}", json_output);
+            }
+            OutputMode::Plain => {
+                println!(
+                    "BATCH: {} succeeded, {} failed",
+                    batch.succeeded(),
+                    batch.failed()
+                );
+                for entry in &batch.entries {
+                    match entry.status {
+                        BatchStatus::Succeeded => {
+                            println!("OK: {} ({} files)", entry.source, entry.files_extracted)
+                        }
+                        BatchStatus::Failed => println!(
+                            "FAILED: {} ({})",
+                            entry.source,
+                            entry.error.as_deref().unwrap_or("unknown error")
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    fn print_human_batch_report(&self, batch: &BatchReport) {
+        self.print_header("Batch Report");
+
+        for entry in &batch.entries {
+            match entry.status {
+                BatchStatus::Succeeded => println!(
+                    "{}{} - {} files -> {}",
+                    CHECKMARK,
+                    entry.source,
+                    entry.files_extracted,
+                    entry
+                        .output_directory
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_default()
+                ),
+                BatchStatus::Failed => println!(
+                    "{}{} - {}",
+                    CROSS,
+                    entry.source,
+                    entry.error.as_deref().unwrap_or("unknown error")
+                ),
+            }
+        }
+        println!();
+
+        println!(
+            "Repositories: {} succeeded, {} failed",
+            batch.succeeded(),
+            batch.failed()
+        );
+        println!("Files extracted: {}", batch.total_files());
+        println!("Duration: {}", format_duration(batch.duration));
+    }
+
     pub fn print_header(&self, title: &str) {
         if self.quiet {
             return;
@@ -209,6 +277,25 @@ impl OutputFormatter {
         }
     }
 
+    pub fn print_budget(&self, budgets: &[DirectoryBudget]) {
+        if self.quiet {
+            return;
+        }
+
+        match self.mode {
+            OutputMode::Human | OutputMode::Plain => {
+                self.print_header("Token Budget");
+                print!("{}", budget_table(budgets));
+            }
+            OutputMode::Json => {
+                self.print_json_object(&serde_json::json!({
+                    "type": "budget",
+                    "directories": budgets
+                }));
+            }
+        }
+    }
+
     pub fn print_separator(&self) {
         if self.quiet {
             return;
@@ -393,12 +480,118 @@ impl OutputFormatter {
             println!();
         }
 
+        if report.skipped.total() > 0 {
+            println!("Skipped: {}", report.skipped.summary());
+            if self.should_show_message(1) {
+                for (reason, _) in report.skipped.by_reason() {
+                    let paths: Vec<&str> = report
+                        .skipped
+                        .paths
+                        .iter()
+                        .filter(|skipped| skipped.reason == reason)
+                        .map(|skipped| skipped.path.as_str())
+                        .collect();
+                    if paths.is_empty() {
+                        continue;
+                    }
+                    println!("  {}:", reason.label());
+                    for path in paths {
+                        println!("    - {}", path);
+                    }
+                }
+            }
+            println!();
+        }
+
+        if let Some(changes) = &report.changes {
+            println!(
+                "Changes: {} added, {} modified, {} removed, {} unchanged",
+                changes.added.len(),
+                changes.modified.len(),
+                changes.removed.len(),
+                changes.unchanged
+            );
+            for path in &changes.added {
+                println!("  + {}", path);
+            }
+            for path in &changes.modified {
+                println!("  ~ {}", path);
+            }
+            for path in &changes.removed {
+                println!("  - {}", path);
+            }
+            println!();
+        }
+
+        if !report.near_duplicates.is_empty() {
+            println!("Near-duplicate documents:");
+            for group in &report.near_duplicates {
+                println!(
+                    "  - {} (similarity >= {:.2})",
+                    group.files.join(", "),
+                    group.min_similarity
+                );
+            }
+            println!();
+        }
+
+        if !report.shortened_paths.is_empty() {
+            println!("Shortened paths:");
+            for mapping in &report.shortened_paths {
+                println!("  - {} -> {}", mapping.original, mapping.extracted);
+            }
+            println!();
+        }
+
+        if !report.normalized_paths.is_empty() {
+            println!("Unicode-normalized paths:");
+            for mapping in &report.normalized_paths {
+                println!("  - {} -> {}", mapping.original, mapping.extracted);
+            }
+            println!();
+        }
+
+        if !report.transliterated_paths.is_empty() {
+            println!("Transliterated paths:");
+            for mapping in &report.transliterated_paths {
+                println!("  - {} -> {}", mapping.original, mapping.extracted);
+            }
+            println!();
+        }
+
+        if !report.case_collisions.is_empty() {
+            println!("Case collisions (renamed):");
+            for mapping in &report.case_collisions {
+                println!("  - {} -> {}", mapping.original, mapping.extracted);
+            }
+            println!();
+        }
+
         if !report.errors.is_empty() {
             println!("Issues encountered:");
             for error in &report.errors {
                 println!("  - {}", error);
             }
         }
+
+        if self.should_show_message(1) && !report.files.is_empty() {
+            println!("Files:");
+            for file_info in &report.files {
+                match &file_info.front_matter.title {
+                    Some(title) => println!(
+                        "  {} - {} ({})",
+                        file_info.relative_path,
+                        title,
+                        format_bytes(file_info.size)
+                    ),
+                    None => println!(
+                        "  {} ({})",
+                        file_info.relative_path,
+                        format_bytes(file_info.size)
+                    ),
+                }
+            }
+        }
     }
 
     fn print_plain_report(&self, report: &ExtractionReport) {
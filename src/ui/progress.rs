@@ -68,6 +68,26 @@ impl ProgressManager {
         pb
     }
 
+    /// Spinner for one repository of a concurrent batch, labelled with its source
+    pub fn create_repo_progress(&self, source: &str) -> ProgressBar {
+        if !self.enabled {
+            return ProgressBar::hidden();
+        }
+
+        let pb = self.multi_progress.add(ProgressBar::new_spinner());
+        pb.enable_steady_tick(Duration::from_millis(100));
+        pb.set_style(
+            ProgressStyle::with_template(
+                "{spinner:.green} [{elapsed_precise}] {prefix:.bold} {msg}",
+            )
+            .unwrap_or_else(|_| ProgressStyle::default_spinner())
+            .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]),
+        );
+        pb.set_prefix(source.to_string());
+        pb.set_message("extracting...");
+        pb
+    }
+
     pub fn create_bytes_progress(&self, total_bytes: u64, message: &str) -> ProgressBar {
         if !self.enabled {
             return ProgressBar::hidden();
@@ -116,8 +136,8 @@ impl Default for ProgressManager {
 
 // Helper functions for updating progress bars based on application events
 pub fn update_clone_progress(pb: &ProgressBar, progress: &CloneProgress) {
-    if progress.total_objects > 0 {
-        let percentage = (progress.received_objects * 100) / progress.total_objects;
+    if let Some(percentage) = (progress.received_objects * 100).checked_div(progress.total_objects)
+    {
         pb.set_position(percentage as u64);
 
         if progress.received_objects == progress.total_objects && progress.total_deltas > 0 {
@@ -139,21 +159,26 @@ pub fn update_clone_progress(pb: &ProgressBar, progress: &CloneProgress) {
 }
 
 pub fn update_file_progress(pb: &ProgressBar, progress: &ExtractionProgress) {
+    // Assets found while extracting add to the files counted up front
+    pb.set_length(progress.total_files as u64);
     pb.set_position(progress.files_processed as u64);
 
     if let Some(ref current_file) = progress.current_file {
-        let eta = if progress.files_processed > 0 {
+        let status = if progress.files_processed > 0 {
+            let mut parts = vec![
+                format_rate(progress.bytes_per_second()),
+                format!("{:.0} files/s", progress.files_per_second()),
+            ];
             let estimated_remaining = progress.estimated_remaining();
             if estimated_remaining.as_secs() > 0 {
-                format!(" (ETA: {})", format_duration(estimated_remaining))
-            } else {
-                String::new()
+                parts.push(format!("ETA: {}", format_duration(estimated_remaining)));
             }
+            format!(" ({})", parts.join(", "))
         } else {
             String::new()
         };
 
-        pb.set_message(format!("Processing {}{}", current_file, eta));
+        pb.set_message(format!("Processing {}{}", current_file, status));
     } else {
         pb.set_message("Processing files...");
     }
@@ -164,6 +189,15 @@ pub fn finish_progress_with_summary(pb: &ProgressBar, message: &str, duration: D
     pb.finish_with_message(final_message);
 }
 
+fn format_rate(bytes_per_second: f64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes_per_second >= MB {
+        format!("{:.1} MB/s", bytes_per_second / MB)
+    } else {
+        format!("{:.1} KB/s", bytes_per_second / 1024.0)
+    }
+}
+
 fn format_duration(duration: Duration) -> String {
     let secs = duration.as_secs();
     if secs >= 60 {
@@ -344,6 +378,12 @@ mod tests {
         assert_eq!(format_duration(Duration::from_millis(500)), "500ms");
     }
 
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(512.0), "0.5 KB/s");
+        assert_eq!(format_rate(3.5 * 1024.0 * 1024.0), "3.5 MB/s");
+    }
+
     #[test]
     fn test_operation_progress() {
         let manager = ProgressManager::new(true);
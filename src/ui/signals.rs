@@ -2,6 +2,8 @@ use crate::error::{RepoDocsError, Result};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+/// Clones share the shutdown state, so threads can watch for it
+#[derive(Clone)]
 pub struct GracefulShutdown {
     running: Arc<AtomicBool>,
     shutdown_message_shown: Arc<AtomicBool>,
@@ -15,12 +17,15 @@ impl GracefulShutdown {
         let running_clone = running.clone();
         let message_shown_clone = shutdown_message_shown.clone();
 
-        // Handle Ctrl+C gracefully
+        // Handle Ctrl+C gracefully, along with SIGTERM and SIGHUP on Unix
+        // (container stops, closed terminals)
         ctrlc::set_handler(move || {
             running_clone.store(false, Ordering::SeqCst);
 
             if !message_shown_clone.swap(true, Ordering::SeqCst) {
-                eprintln!("\n🛑 Gracefully stopping... (press Ctrl+C again to force exit)");
+                eprintln!(
+                    "\n🛑 Gracefully stopping... (press Ctrl+C or send the signal again to force exit)"
+                );
             } else {
                 eprintln!("\n💀 Force stopping...");
                 std::process::exit(1);
@@ -30,6 +35,11 @@ impl GracefulShutdown {
             message: format!("Failed to set signal handler: {}", e),
         })?;
 
+        #[cfg(windows)]
+        console_events::register(running.clone()).map_err(|e| RepoDocsError::Config {
+            message: format!("Failed to set console event handler: {}", e),
+        })?;
+
         Ok(Self {
             running,
             shutdown_message_shown,
@@ -113,6 +123,49 @@ impl Default for GracefulShutdown {
     }
 }
 
+/// Console close, logoff and shutdown events on Windows. The system ends
+/// the process as soon as a handler returns from one of these, so the
+/// handler requests shutdown and then waits while the main thread cleans up,
+/// within the roughly five seconds Windows allows.
+#[cfg(windows)]
+mod console_events {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, OnceLock};
+    use std::time::Duration;
+    use windows_sys::core::BOOL;
+    use windows_sys::Win32::System::Console::{
+        SetConsoleCtrlHandler, CTRL_CLOSE_EVENT, CTRL_LOGOFF_EVENT, CTRL_SHUTDOWN_EVENT,
+    };
+
+    const CLEANUP_GRACE: Duration = Duration::from_millis(4500);
+
+    static RUNNING: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+    unsafe extern "system" fn handler(event: u32) -> BOOL {
+        match event {
+            CTRL_CLOSE_EVENT | CTRL_LOGOFF_EVENT | CTRL_SHUTDOWN_EVENT => {
+                if let Some(running) = RUNNING.get() {
+                    running.store(false, Ordering::SeqCst);
+                }
+                std::thread::sleep(CLEANUP_GRACE);
+                1
+            }
+            // Ctrl+C and Ctrl+Break fall through to the ctrlc handler
+            _ => 0,
+        }
+    }
+
+    pub fn register(running: Arc<AtomicBool>) -> std::io::Result<()> {
+        let _ = RUNNING.set(running);
+        // SAFETY: `handler` is a plain function that stays valid for the
+        // lifetime of the process
+        if unsafe { SetConsoleCtrlHandler(Some(handler), 1) } == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
 // Shutdown-aware operation wrapper
 pub struct ShutdownAwareOperation<'a> {
     shutdown: &'a GracefulShutdown,
@@ -0,0 +1,239 @@
+use crate::error::{RepoDocsError, Result};
+use crate::status::Stage;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Longest wait between two looks at the last progress event
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A stage that saw no progress for longer than the stall timeout
+#[derive(Debug, Clone)]
+pub struct Stall {
+    pub stage: Stage,
+    pub idle: Duration,
+    /// Last progress seen before the stall, such as the file being copied
+    pub detail: Option<String>,
+}
+
+impl fmt::Display for Stall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "No progress for {} seconds while {}",
+            self.idle.as_secs(),
+            self.stage
+        )?;
+        if let Some(detail) = &self.detail {
+            write!(f, " (last: {})", detail)?;
+        }
+        Ok(())
+    }
+}
+
+struct Activity {
+    last_progress: Instant,
+    detail: Option<String>,
+    /// Whether the current quiet period has been reported
+    reported: bool,
+}
+
+struct Shared {
+    stage: Stage,
+    stall_after: Duration,
+    abort: bool,
+    /// Cleared on a stall when aborting, to stop the stage
+    running: Arc<AtomicBool>,
+    activity: Mutex<Activity>,
+    stall: Mutex<Option<Stall>>,
+}
+
+impl Shared {
+    fn poll(&self) -> Option<Stall> {
+        let mut activity = self.activity.lock().unwrap_or_else(|e| e.into_inner());
+        let idle = activity.last_progress.elapsed();
+        if activity.reported || idle < self.stall_after {
+            return None;
+        }
+
+        activity.reported = true;
+        let stall = Stall {
+            stage: self.stage,
+            idle,
+            detail: activity.detail.clone(),
+        };
+        self.stall
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(|| stall.clone());
+        if self.abort {
+            self.running.store(false, Ordering::SeqCst);
+        }
+        Some(stall)
+    }
+}
+
+/// Watches a clone or extraction for progress events from a background
+/// thread. When none arrive for `stall_after`, `on_stall` is called once
+/// per quiet period, so a hung network or disk is reported instead of
+/// waiting silently. The thread ends when the last clone of the watchdog
+/// is dropped.
+#[derive(Clone)]
+pub struct Watchdog {
+    shared: Arc<Shared>,
+}
+
+impl Watchdog {
+    /// Start watching `stage`. With `abort`, a stall clears the
+    /// [`Watchdog::running`] flag the stage stops on, and
+    /// [`Watchdog::check`] fails from then on.
+    pub fn spawn(
+        stage: Stage,
+        stall_after: Duration,
+        abort: bool,
+        on_stall: impl Fn(&Stall) + Send + 'static,
+    ) -> Self {
+        let shared = Arc::new(Shared {
+            stage,
+            stall_after,
+            abort,
+            running: Arc::new(AtomicBool::new(true)),
+            activity: Mutex::new(Activity {
+                last_progress: Instant::now(),
+                detail: None,
+                reported: false,
+            }),
+            stall: Mutex::new(None),
+        });
+
+        let interval = (stall_after / 4).clamp(Duration::from_millis(10), POLL_INTERVAL);
+        let weak = Arc::downgrade(&shared);
+        thread::spawn(move || Self::run(weak, interval, on_stall));
+
+        Self { shared }
+    }
+
+    fn run(shared: Weak<Shared>, interval: Duration, on_stall: impl Fn(&Stall)) {
+        while let Some(shared) = shared.upgrade() {
+            if let Some(stall) = shared.poll() {
+                on_stall(&stall);
+            }
+            drop(shared);
+            thread::sleep(interval);
+        }
+    }
+
+    /// Record a progress event, described by `detail`
+    pub fn progress(&self, detail: impl Into<String>) {
+        let mut activity = self
+            .shared
+            .activity
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        activity.last_progress = Instant::now();
+        activity.detail = Some(detail.into());
+        activity.reported = false;
+    }
+
+    /// The first stall seen, if any
+    pub fn stall(&self) -> Option<Stall> {
+        self.shared
+            .stall
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Flag the watched stage polls to stop, cleared when it stalls and
+    /// aborting is enabled
+    pub fn running(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.shared.running)
+    }
+
+    /// Fail with a stall error if the stage stalled and aborting is enabled
+    pub fn check(&self) -> Result<()> {
+        match self.stall() {
+            Some(stall) if self.shared.abort => Err(RepoDocsError::Stalled {
+                stage: stall.stage,
+                seconds: stall.idle.as_secs(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_stall_reported_once_per_quiet_period() {
+        let (sender, receiver) = mpsc::channel();
+        let watchdog = Watchdog::spawn(
+            Stage::Extracting,
+            Duration::from_millis(40),
+            true,
+            move |stall| {
+                let _ = sender.send(stall.to_string());
+            },
+        );
+        watchdog.progress("docs/guide.md");
+        assert!(watchdog.check().is_ok());
+
+        let message = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(message.contains("while extracting"));
+        assert!(message.ends_with("(last: docs/guide.md)"));
+        assert!(receiver.recv_timeout(Duration::from_millis(150)).is_err());
+        assert!(matches!(
+            watchdog.check(),
+            Err(RepoDocsError::Stalled {
+                stage: Stage::Extracting,
+                ..
+            })
+        ));
+
+        // Progress starts a new quiet period
+        watchdog.progress("docs/api.md");
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+
+    #[test]
+    fn test_stall_without_abort() {
+        let watchdog = Watchdog::spawn(Stage::Cloning, Duration::ZERO, false, |_| {});
+        thread::sleep(Duration::from_millis(100));
+        assert!(watchdog.stall().is_some());
+        assert!(watchdog.check().is_ok());
+        assert!(watchdog.running().load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_stalled_stage_is_stopped() {
+        let watchdog = Watchdog::spawn(Stage::Cloning, Duration::from_millis(40), true, |_| {});
+        // A stage that never makes progress and only ends when told to
+        let running = watchdog.running();
+        let stage = thread::spawn(move || {
+            while running.load(Ordering::SeqCst) {
+                thread::sleep(Duration::from_millis(5));
+            }
+        });
+
+        let started = Instant::now();
+        while !stage.is_finished() {
+            assert!(
+                started.elapsed() < Duration::from_secs(5),
+                "stage not stopped"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(matches!(
+            watchdog.check(),
+            Err(RepoDocsError::Stalled {
+                stage: Stage::Cloning,
+                ..
+            })
+        ));
+    }
+}